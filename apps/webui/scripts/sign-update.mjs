@@ -0,0 +1,78 @@
+// Keys and signatures for the desktop updater (src-tauri/src/updater).
+// A signature is a base64 Ed25519 signature over the artifact's SHA-256
+// digest, which is not the minisign format of `tauri signer`.
+//
+//   node scripts/sign-update.mjs keygen updater.pem
+//     Writes a new private key and prints the public key to build with
+//     as MOBVIBE_UPDATER_PUBKEY.
+//   node scripts/sign-update.mjs sign updater.pem Mobvibe.AppImage ...
+//     Prints `<signature>  <file>` for each artifact, for the `signature`
+//     fields of the channel manifest. The key may be given as `-` to read
+//     the PEM from MOBVIBE_UPDATER_PRIVATE_KEY instead.
+import crypto from "node:crypto";
+import fs from "node:fs";
+
+const usage = `Usage:
+  node scripts/sign-update.mjs keygen <private-key.pem>
+  node scripts/sign-update.mjs sign <private-key.pem | -> <artifact>...`;
+
+function fail(message) {
+	console.error(message);
+	process.exit(1);
+}
+
+/** The raw 32-byte public key, base64, as the app parses it. */
+function encodePublicKey(publicKey) {
+	return publicKey
+		.export({ format: "der", type: "spki" })
+		.subarray(-32)
+		.toString("base64");
+}
+
+function readPrivateKey(source) {
+	const pem =
+		source === "-"
+			? process.env.MOBVIBE_UPDATER_PRIVATE_KEY
+			: fs.readFileSync(source, "utf8");
+	if (!pem) {
+		fail("MOBVIBE_UPDATER_PRIVATE_KEY is not set");
+	}
+	const key = crypto.createPrivateKey(pem);
+	if (key.asymmetricKeyType !== "ed25519") {
+		fail(`Expected an Ed25519 key, got ${key.asymmetricKeyType}`);
+	}
+	return key;
+}
+
+async function digest(file) {
+	const hash = crypto.createHash("sha256");
+	for await (const chunk of fs.createReadStream(file)) {
+		hash.update(chunk);
+	}
+	return hash.digest();
+}
+
+const [command, keyPath, ...files] = process.argv.slice(2);
+
+if (command === "keygen" && keyPath && files.length === 0) {
+	if (fs.existsSync(keyPath)) {
+		fail(`${keyPath} already exists`);
+	}
+	const { privateKey, publicKey } = crypto.generateKeyPairSync("ed25519");
+	fs.writeFileSync(
+		keyPath,
+		privateKey.export({ format: "pem", type: "pkcs8" }),
+		{ mode: 0o600, flag: "wx" },
+	);
+	console.log(encodePublicKey(publicKey));
+} else if (command === "sign" && keyPath && files.length > 0) {
+	const privateKey = readPrivateKey(keyPath);
+	const publicKey = crypto.createPublicKey(privateKey);
+	console.error(`Signing with ${encodePublicKey(publicKey)}`);
+	for (const file of files) {
+		const signature = crypto.sign(null, await digest(file), privateKey);
+		console.log(`${signature.toString("base64")}  ${file}`);
+	}
+} else {
+	fail(usage);
+}
@@ -1,3 +1,28 @@
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+
 fn main() {
+    check_updater_key();
     tauri_build::build()
 }
+
+/// Release builds of the desktop app must carry the key updates are
+/// verified against, or they would never offer one.
+fn check_updater_key() {
+    println!("cargo:rerun-if-env-changed=MOBVIBE_UPDATER_PUBKEY");
+    let target_os = std::env::var("CARGO_CFG_TARGET_OS").unwrap_or_default();
+    let release = std::env::var("PROFILE").is_ok_and(|profile| profile == "release");
+    if !release || matches!(target_os.as_str(), "android" | "ios") {
+        return;
+    }
+    let key = std::env::var("MOBVIBE_UPDATER_PUBKEY").unwrap_or_default();
+    if !STANDARD
+        .decode(key.trim())
+        .is_ok_and(|bytes| bytes.len() == 32)
+    {
+        panic!(
+            "Release builds need MOBVIBE_UPDATER_PUBKEY, the base64 Ed25519 public key \
+             updates are signed with. Create one with `node scripts/sign-update.mjs keygen`."
+        );
+    }
+}
@@ -0,0 +1,29 @@
+use tauri::ipc::Channel;
+use tauri::{AppHandle, State, Webview};
+
+use super::AppMenu;
+use crate::error::Result;
+
+/// Streams the ids of hotkey commands chosen from the menu while this
+/// webview's window is in front.
+#[tauri::command]
+pub fn app_menu_listen(webview: Webview, menu: State<'_, AppMenu>, on_command: Channel<String>) {
+    menu.listen(webview.label(), on_command);
+}
+
+/// Relabels the menu for `language` and greys out the `disabled` command
+/// ids. Ignored unless the calling window is focused, so a background
+/// window cannot overwrite the state of the one in front.
+#[tauri::command]
+pub fn app_menu_update(
+    app: AppHandle,
+    webview: Webview,
+    menu: State<'_, AppMenu>,
+    language: String,
+    disabled: Vec<String>,
+) -> Result<()> {
+    if !webview.window().is_focused()? {
+        return Ok(());
+    }
+    menu.update(&app, &language, &disabled)
+}
@@ -0,0 +1,136 @@
+//! Menu text in the languages the webui ships (`i18n/locales`).
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    Zh,
+}
+
+impl Locale {
+    /// Maps an i18next language such as `zh-CN` to the nearest locale,
+    /// falling back to English like the webui does.
+    pub fn from_language(language: &str) -> Self {
+        let primary = language.split(['-', '_']).next().unwrap_or_default();
+        if primary.eq_ignore_ascii_case("zh") {
+            Self::Zh
+        } else {
+            Self::En
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::En => "en",
+            Self::Zh => "zh",
+        }
+    }
+
+    pub fn labels(self) -> &'static Labels {
+        match self {
+            Self::En => &EN,
+            Self::Zh => &ZH,
+        }
+    }
+}
+
+pub struct Labels {
+    pub file: &'static str,
+    pub edit: &'static str,
+    pub session: &'static str,
+    pub view: &'static str,
+    pub window: &'static str,
+    pub help: &'static str,
+    pub new_session: &'static str,
+    pub settings: &'static str,
+    pub find_in_chat: &'static str,
+    pub open_in_new_window: &'static str,
+    pub toggle_sidebar: &'static str,
+    pub command_palette: &'static str,
+    pub about: &'static str,
+    pub services: &'static str,
+    pub hide: &'static str,
+    pub hide_others: &'static str,
+    pub show_all: &'static str,
+    pub quit: &'static str,
+    pub close_window: &'static str,
+    pub undo: &'static str,
+    pub redo: &'static str,
+    pub cut: &'static str,
+    pub copy: &'static str,
+    pub paste: &'static str,
+    pub select_all: &'static str,
+    pub fullscreen: &'static str,
+    pub minimize: &'static str,
+    pub maximize: &'static str,
+}
+
+const EN: Labels = Labels {
+    file: "File",
+    edit: "Edit",
+    session: "Session",
+    view: "View",
+    window: "Window",
+    help: "Help",
+    new_session: "New Session",
+    settings: "Settings…",
+    find_in_chat: "Find in Chat",
+    open_in_new_window: "Open in New Window",
+    toggle_sidebar: "Toggle Sidebar",
+    command_palette: "Command Palette…",
+    about: "About Mobvibe",
+    services: "Services",
+    hide: "Hide Mobvibe",
+    hide_others: "Hide Others",
+    show_all: "Show All",
+    quit: "Quit Mobvibe",
+    close_window: "Close Window",
+    undo: "Undo",
+    redo: "Redo",
+    cut: "Cut",
+    copy: "Copy",
+    paste: "Paste",
+    select_all: "Select All",
+    fullscreen: "Toggle Full Screen",
+    minimize: "Minimize",
+    maximize: if cfg!(target_os = "macos") {
+        "Zoom"
+    } else {
+        "Maximize"
+    },
+};
+
+const ZH: Labels = Labels {
+    file: "文件",
+    edit: "编辑",
+    session: "会话",
+    view: "显示",
+    window: "窗口",
+    help: "帮助",
+    new_session: "新建会话",
+    settings: "设置…",
+    find_in_chat: "在对话中查找",
+    open_in_new_window: "在新窗口中打开",
+    toggle_sidebar: "切换侧边栏",
+    command_palette: "命令面板…",
+    about: "关于 Mobvibe",
+    services: "服务",
+    hide: "隐藏 Mobvibe",
+    hide_others: "隐藏其他",
+    show_all: "全部显示",
+    quit: "退出 Mobvibe",
+    close_window: "关闭窗口",
+    undo: "撤销",
+    redo: "重做",
+    cut: "剪切",
+    copy: "拷贝",
+    paste: "粘贴",
+    select_all: "全选",
+    fullscreen: "切换全屏",
+    minimize: "最小化",
+    maximize: if cfg!(target_os = "macos") {
+        "缩放"
+    } else {
+        "最大化"
+    },
+};
@@ -0,0 +1,339 @@
+//! Application menu bar. Its own items carry the command ids of the webui
+//! hotkeys (`hotkeys.ts`): choosing one hands the id to the focused
+//! window's webview, which runs the same handler as the shortcut. Webviews
+//! report their language and which commands apply, so labels follow the
+//! app's locale and items grey out with the focused window's state. The
+//! last language is kept in the `menu` settings scope for the next launch,
+//! and handed on to the tray.
+
+pub mod commands;
+mod labels;
+
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard};
+
+use serde_json::Value;
+use tauri::ipc::Channel;
+use tauri::menu::{
+    AboutMetadata, IsMenuItem, Menu, MenuEvent, MenuItem, MenuItemKind, PredefinedMenuItem, Submenu,
+};
+use tauri::{AppHandle, Manager, Wry};
+
+use self::labels::Labels;
+pub use self::labels::Locale;
+use crate::error::Result;
+use crate::quick_prompt;
+use crate::settings::{self, SettingsStore};
+use crate::single_instance::focus_main_window;
+use crate::tray;
+
+/// Menu ids of hotkey commands are the command id behind this prefix.
+const ID_PREFIX: &str = "menu:";
+const LOCALE_KEY: &str = "locale";
+const MAIN_WINDOW: &str = "main";
+
+pub const NEW_SESSION: &str = "session.new";
+pub const OPEN_SETTINGS: &str = "settings.open";
+pub const FIND_IN_CHAT: &str = "chat.search";
+pub const OPEN_SESSION_WINDOW: &str = "session.openWindow";
+pub const TOGGLE_SIDEBAR: &str = "sidebar.toggle";
+pub const COMMAND_PALETTE: &str = "palette.open";
+
+/// Hotkey commands with their shortcuts, which match `hotkeys.ts`.
+const COMMANDS: [(&str, &str); 6] = [
+    (NEW_SESSION, "CmdOrCtrl+N"),
+    (OPEN_SETTINGS, "CmdOrCtrl+,"),
+    (FIND_IN_CHAT, "CmdOrCtrl+F"),
+    (OPEN_SESSION_WINDOW, "CmdOrCtrl+Shift+N"),
+    (TOGGLE_SIDEBAR, "CmdOrCtrl+B"),
+    (COMMAND_PALETTE, "CmdOrCtrl+K"),
+];
+
+#[derive(Default)]
+struct Inner {
+    locale: Locale,
+    items: BTreeMap<&'static str, MenuItem<Wry>>,
+    /// Where each webview wants commands, by label.
+    listeners: BTreeMap<String, Channel<String>>,
+}
+
+#[derive(Default)]
+pub struct AppMenu {
+    inner: Mutex<Inner>,
+}
+
+/// Builds the menu in the last language a webview reported and manages
+/// [`AppMenu`].
+pub fn init(app: &AppHandle) -> Result<()> {
+    let locale = saved_locale(&app.state::<SettingsStore>())?;
+    let items = install(app, locale)?;
+    app.manage(AppMenu {
+        inner: Mutex::new(Inner {
+            locale,
+            items,
+            ..Inner::default()
+        }),
+    });
+    app.on_menu_event(on_menu_event);
+    Ok(())
+}
+
+/// The last language a webview reported, for native text shown before any
+/// webview has loaded.
+pub fn saved_locale(settings: &SettingsStore) -> Result<Locale> {
+    Ok(match settings.get(settings::MENU, LOCALE_KEY)? {
+        Some(Value::String(language)) => Locale::from_language(&language),
+        _ => Locale::default(),
+    })
+}
+
+/// Sets a freshly built menu as the app menu and returns its command items.
+fn install(app: &AppHandle, locale: Locale) -> Result<BTreeMap<&'static str, MenuItem<Wry>>> {
+    let mut items = BTreeMap::new();
+    for (id, accelerator) in COMMANDS {
+        let item = MenuItem::with_id(
+            app,
+            format!("{ID_PREFIX}{id}"),
+            command_label(locale.labels(), id),
+            true,
+            Some(accelerator),
+        )?;
+        items.insert(id, item);
+    }
+    let menu = build(app, locale.labels(), &items)?;
+    app.set_menu(menu)?;
+    // The prompt window is a bare panel; the app menu is added back to every
+    // window without one whenever it is replaced.
+    #[cfg(not(target_os = "macos"))]
+    if let Some(window) = app.get_webview_window(quick_prompt::WINDOW_LABEL) {
+        window.remove_menu()?;
+    }
+    Ok(items)
+}
+
+fn command_label(labels: &Labels, id: &str) -> &'static str {
+    match id {
+        NEW_SESSION => labels.new_session,
+        OPEN_SETTINGS => labels.settings,
+        FIND_IN_CHAT => labels.find_in_chat,
+        OPEN_SESSION_WINDOW => labels.open_in_new_window,
+        TOGGLE_SIDEBAR => labels.toggle_sidebar,
+        _ => labels.command_palette,
+    }
+}
+
+/// File, Edit, Session, View, Window and Help, plus the application menu on
+/// macOS, where Settings, About and Quit live instead.
+fn build(
+    app: &AppHandle,
+    labels: &Labels,
+    items: &BTreeMap<&'static str, MenuItem<Wry>>,
+) -> tauri::Result<Menu<Wry>> {
+    let item = |id: &str| MenuItemKind::MenuItem(items[id].clone());
+    let separator = || PredefinedMenuItem::separator(app).map(MenuItemKind::Predefined);
+    let predefined =
+        |item: tauri::Result<PredefinedMenuItem<Wry>>| item.map(MenuItemKind::Predefined);
+    let about = || {
+        PredefinedMenuItem::about(
+            app,
+            Some(labels.about),
+            Some(AboutMetadata {
+                name: Some("Mobvibe".into()),
+                version: Some(app.package_info().version.to_string()),
+                ..AboutMetadata::default()
+            }),
+        )
+    };
+    let submenu = |text: &str, entries: Vec<MenuItemKind<Wry>>| -> tauri::Result<Submenu<Wry>> {
+        let refs: Vec<&dyn IsMenuItem<Wry>> = entries
+            .iter()
+            .map(|entry| entry as &dyn IsMenuItem<Wry>)
+            .collect();
+        Submenu::with_items(app, text, true, &refs)
+    };
+
+    let menu = Menu::new(app)?;
+    if cfg!(target_os = "macos") {
+        menu.append(&submenu(
+            "Mobvibe",
+            vec![
+                predefined(about())?,
+                separator()?,
+                item(OPEN_SETTINGS),
+                separator()?,
+                predefined(PredefinedMenuItem::services(app, Some(labels.services)))?,
+                separator()?,
+                predefined(PredefinedMenuItem::hide(app, Some(labels.hide)))?,
+                predefined(PredefinedMenuItem::hide_others(
+                    app,
+                    Some(labels.hide_others),
+                ))?,
+                predefined(PredefinedMenuItem::show_all(app, Some(labels.show_all)))?,
+                separator()?,
+                predefined(PredefinedMenuItem::quit(app, Some(labels.quit)))?,
+            ],
+        )?)?;
+        menu.append(&submenu(
+            labels.file,
+            vec![
+                item(NEW_SESSION),
+                separator()?,
+                predefined(PredefinedMenuItem::close_window(
+                    app,
+                    Some(labels.close_window),
+                ))?,
+            ],
+        )?)?;
+    } else {
+        menu.append(&submenu(
+            labels.file,
+            vec![
+                item(NEW_SESSION),
+                item(OPEN_SETTINGS),
+                separator()?,
+                predefined(PredefinedMenuItem::close_window(
+                    app,
+                    Some(labels.close_window),
+                ))?,
+                predefined(PredefinedMenuItem::quit(app, Some(labels.quit)))?,
+            ],
+        )?)?;
+    }
+    menu.append(&submenu(
+        labels.edit,
+        vec![
+            predefined(PredefinedMenuItem::undo(app, Some(labels.undo)))?,
+            predefined(PredefinedMenuItem::redo(app, Some(labels.redo)))?,
+            separator()?,
+            predefined(PredefinedMenuItem::cut(app, Some(labels.cut)))?,
+            predefined(PredefinedMenuItem::copy(app, Some(labels.copy)))?,
+            predefined(PredefinedMenuItem::paste(app, Some(labels.paste)))?,
+            predefined(PredefinedMenuItem::select_all(app, Some(labels.select_all)))?,
+        ],
+    )?)?;
+    menu.append(&submenu(
+        labels.session,
+        vec![item(FIND_IN_CHAT), item(OPEN_SESSION_WINDOW)],
+    )?)?;
+    menu.append(&submenu(
+        labels.view,
+        vec![
+            item(TOGGLE_SIDEBAR),
+            separator()?,
+            predefined(PredefinedMenuItem::fullscreen(app, Some(labels.fullscreen)))?,
+        ],
+    )?)?;
+    let window = submenu(
+        labels.window,
+        vec![
+            predefined(PredefinedMenuItem::minimize(app, Some(labels.minimize)))?,
+            predefined(PredefinedMenuItem::maximize(app, Some(labels.maximize)))?,
+        ],
+    )?;
+    menu.append(&window)?;
+    let mut help = vec![item(COMMAND_PALETTE)];
+    if !cfg!(target_os = "macos") {
+        help.extend([separator()?, predefined(about())?]);
+    }
+    let help = submenu(labels.help, help)?;
+    menu.append(&help)?;
+    #[cfg(target_os = "macos")]
+    {
+        window.set_as_windows_menu_for_nsapp()?;
+        help.set_as_help_menu_for_nsapp()?;
+    }
+    Ok(menu)
+}
+
+impl AppMenu {
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Sends the menu's commands for `webview` to `channel`, replacing any
+    /// earlier listener of that webview.
+    pub fn listen(&self, webview: &str, channel: Channel<String>) {
+        self.inner().listeners.insert(webview.to_owned(), channel);
+    }
+
+    /// Applies the language and unavailable commands a webview reported.
+    /// Webviews report again when their window comes to the front.
+    pub fn update(&self, app: &AppHandle, language: &str, disabled: &[String]) -> Result<()> {
+        let locale = Locale::from_language(language);
+        if self.inner().locale != locale {
+            // Built without the lock, which menu events on the main thread
+            // also take.
+            let items = install(app, locale)?;
+            app.state::<SettingsStore>().set(
+                settings::MENU,
+                LOCALE_KEY,
+                &Value::String(locale.as_str().to_owned()),
+            )?;
+            let mut inner = self.inner();
+            inner.locale = locale;
+            inner.items = items;
+            drop(inner);
+            tray::set_locale(app, locale);
+        }
+        let items = self.inner().items.clone();
+        for (id, item) in items {
+            item.set_enabled(!disabled.iter().any(|command| command == id))?;
+        }
+        Ok(())
+    }
+
+    /// Drops the listener of a webview that is navigating away.
+    pub fn forget_webview(&self, webview: &str) {
+        self.inner().listeners.remove(webview);
+    }
+
+    /// The focused webview with a listener, or the main window's.
+    fn target(&self, app: &AppHandle) -> Option<(String, Channel<String>)> {
+        let inner = self.inner();
+        let focused = app
+            .webview_windows()
+            .into_iter()
+            .find_map(|(label, window)| {
+                let listening = inner.listeners.contains_key(&label);
+                (listening && window.is_focused().unwrap_or(false)).then_some(label)
+            });
+        let label = focused.unwrap_or_else(|| MAIN_WINDOW.to_owned());
+        let channel = inner.listeners.get(&label)?.clone();
+        Some((label, channel))
+    }
+}
+
+fn on_menu_event(app: &AppHandle, event: MenuEvent) {
+    let Some(command) = event.id().as_ref().strip_prefix(ID_PREFIX) else {
+        return;
+    };
+    let Some((label, channel)) = app.state::<AppMenu>().target(app) else {
+        return;
+    };
+    // On macOS the menu stays up with every window hidden to the tray.
+    if label == MAIN_WINDOW {
+        focus_main_window(app);
+    }
+    let _ = channel.send(command.to_owned());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn follows_the_webui_language() {
+        assert_eq!(Locale::from_language("zh-CN"), Locale::Zh);
+        assert_eq!(Locale::from_language("zh_TW"), Locale::Zh);
+        assert_eq!(Locale::from_language("en-US"), Locale::En);
+        assert_eq!(Locale::from_language("fr"), Locale::En);
+        assert_eq!(
+            command_label(Locale::Zh.labels(), FIND_IN_CHAT),
+            "在对话中查找"
+        );
+        assert_eq!(
+            command_label(Locale::En.labels(), COMMAND_PALETTE),
+            "Command Palette…"
+        );
+    }
+}
@@ -0,0 +1,21 @@
+use tauri::{AppHandle, Manager};
+
+use super::{ClipboardContents, ClipboardOutput, ClipboardState};
+use crate::error::{Error, Result};
+
+/// Reads images and text off the clipboard. Async because another app
+/// may take a while to serve what it copied.
+#[tauri::command]
+pub async fn clipboard_read(app: AppHandle) -> Result<ClipboardContents> {
+    tauri::async_runtime::spawn_blocking(move || app.state::<ClipboardState>().read())
+        .await
+        .map_err(|err| Error::Clipboard(err.to_string()))?
+}
+
+/// Copies agent output as plain text and HTML.
+#[tauri::command]
+pub async fn clipboard_write_output(app: AppHandle, output: ClipboardOutput) -> Result<()> {
+    tauri::async_runtime::spawn_blocking(move || app.state::<ClipboardState>().write(&output))
+        .await
+        .map_err(|err| Error::Clipboard(err.to_string()))?
+}
@@ -0,0 +1,196 @@
+//! What goes on and comes off the clipboard besides plain text: agent
+//! output rendered as HTML for rich editors, and images embedded in copied
+//! HTML as `data:` URLs.
+
+use std::fmt::Write as _;
+use std::sync::LazyLock;
+
+use pulldown_cmark::{html, Event, Options, Parser};
+use regex::Regex;
+use serde::Deserialize;
+use similar::TextDiff;
+
+/// Agent output to copy. Each is written as plain text (the source) and
+/// as HTML.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ClipboardOutput {
+    /// A message; rich editors get the rendered markdown.
+    Markdown { text: String },
+    #[serde(rename_all = "camelCase")]
+    Code {
+        code: String,
+        language: Option<String>,
+    },
+    /// A unified diff, or a file's old and new text to diff.
+    #[serde(rename_all = "camelCase")]
+    Diff {
+        path: String,
+        diff: Option<String>,
+        old_text: Option<String>,
+        new_text: Option<String>,
+    },
+}
+
+/// The plain text and HTML to write for `output`.
+pub fn render(output: &ClipboardOutput) -> (String, String) {
+    match output {
+        ClipboardOutput::Markdown { text } => (text.clone(), markdown_html(text)),
+        ClipboardOutput::Code { code, language } => {
+            (code.clone(), code_html(code, language.as_deref()))
+        }
+        ClipboardOutput::Diff {
+            path,
+            diff,
+            old_text,
+            new_text,
+        } => {
+            let diff = match diff {
+                Some(diff) => diff.clone(),
+                None => unified_diff(
+                    path,
+                    old_text.as_deref().unwrap_or_default(),
+                    new_text.as_deref().unwrap_or_default(),
+                ),
+            };
+            let html = diff_html(&diff);
+            (diff, html)
+        }
+    }
+}
+
+/// Raw HTML in the markdown is kept as text, so a message cannot put
+/// markup of its own into the document it is pasted into.
+fn markdown_html(markdown: &str) -> String {
+    let options =
+        Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TASKLISTS;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        event => event,
+    });
+    let mut out = String::new();
+    html::push_html(&mut out, events);
+    out
+}
+
+fn code_html(code: &str, language: Option<&str>) -> String {
+    let class = language
+        .filter(|language| {
+            language
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '+' | '#'))
+        })
+        .map(|language| format!(" class=\"language-{language}\""))
+        .unwrap_or_default();
+    format!("<pre><code{class}>{}</code></pre>", escape(code))
+}
+
+fn unified_diff(path: &str, old: &str, new: &str) -> String {
+    TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(&format!("a/{path}"), &format!("b/{path}"))
+        .to_string()
+}
+
+/// Inline styles, since pasted HTML does not bring a stylesheet along.
+fn diff_html(diff: &str) -> String {
+    let mut out = String::from("<pre style=\"font-family:monospace\">");
+    for line in diff.lines() {
+        let style = if line.starts_with("+++") || line.starts_with("---") {
+            "font-weight:bold"
+        } else if line.starts_with('+') {
+            "color:#116329;background-color:#dafbe1"
+        } else if line.starts_with('-') {
+            "color:#82071e;background-color:#ffebe9"
+        } else if line.starts_with("@@") {
+            "color:#0550ae"
+        } else {
+            ""
+        };
+        if style.is_empty() {
+            let _ = writeln!(out, "{}", escape(line));
+        } else {
+            let _ = writeln!(out, "<span style=\"{style}\">{}</span>", escape(line));
+        }
+    }
+    out.push_str("</pre>");
+    out
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+static DATA_URL_IMAGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"(?i)<img\b[^>]*?\bsrc\s*=\s*["']data:(image/(?:png|jpeg|webp|gif));base64,([A-Za-z0-9+/=\s]+)["']"#,
+    )
+    .expect("valid regex")
+});
+
+/// `(mime type, base64 data)` of the prompt images embedded in `html`, as
+/// browsers and office apps put them when an image is copied with text.
+pub fn data_url_images(html: &str) -> Vec<(String, String)> {
+    DATA_URL_IMAGE
+        .captures_iter(html)
+        .map(|captures| {
+            let data = captures[2].split_whitespace().collect::<String>();
+            (captures[1].to_ascii_lowercase(), data)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_outputs_as_text_and_html() {
+        let (text, html) = render(&ClipboardOutput::Markdown {
+            text: "**Done** <script>x</script>".into(),
+        });
+        assert_eq!(text, "**Done** <script>x</script>");
+        assert_eq!(
+            html,
+            "<p><strong>Done</strong> &lt;script&gt;x&lt;/script&gt;</p>\n"
+        );
+
+        let (_, html) = render(&ClipboardOutput::Code {
+            code: "a < b".into(),
+            language: Some("rust\" onclick=\"x".into()),
+        });
+        assert_eq!(html, "<pre><code>a &lt; b</code></pre>");
+
+        let (diff, html) = render(&ClipboardOutput::Diff {
+            path: "src/a.rs".into(),
+            diff: None,
+            old_text: Some("one\ntwo\n".into()),
+            new_text: Some("one\n2\n".into()),
+        });
+        assert_eq!(
+            diff,
+            "--- a/src/a.rs\n+++ b/src/a.rs\n@@ -1,2 +1,2 @@\n one\n-two\n+2\n"
+        );
+        assert!(html.contains("<span style=\"color:#82071e;background-color:#ffebe9\">-two</span>"));
+    }
+
+    #[test]
+    fn finds_images_embedded_in_html() {
+        let html = r#"<p>Look</p><img alt="x" src="data:image/PNG;base64,iVBO
+            Rw0=" /><img src="https://example.com/a.png"><img src='data:image/svg+xml;base64,PHN2Zz4='>"#;
+        assert_eq!(
+            data_url_images(html),
+            vec![("image/png".to_owned(), "iVBORw0=".to_owned())]
+        );
+    }
+}
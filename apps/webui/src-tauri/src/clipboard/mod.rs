@@ -0,0 +1,152 @@
+//! The system clipboard, read and written natively. WebView paste events
+//! often carry no image (WebKitGTK drops most of them), and `writeText`
+//! can only put plain text on the clipboard; here images come back as
+//! prompt image blocks and agent output goes out as text and HTML.
+
+pub mod commands;
+mod format;
+
+use std::borrow::Cow;
+use std::io::Cursor;
+use std::sync::{Mutex, MutexGuard};
+
+use arboard::{Clipboard, ImageData};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use image::{ImageFormat, RgbaImage};
+use serde::Serialize;
+
+pub use self::format::ClipboardOutput;
+use crate::error::{Error, Result};
+
+/// Larger clipboard bitmaps are refused rather than encoded; the prompt
+/// would downscale them to a fraction of this anyway.
+const MAX_IMAGE_PIXELS: usize = 40_000_000;
+
+/// An ACP image content block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename = "image", rename_all = "camelCase")]
+pub struct ClipboardImage {
+    pub data: String,
+    pub mime_type: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ClipboardContents {
+    /// A copied bitmap, or the images embedded in copied HTML.
+    pub images: Vec<ClipboardImage>,
+    pub text: Option<String>,
+}
+
+/// Keeps one clipboard handle open. On X11 and Wayland the app serves
+/// what it copied for as long as the handle lives, so a handle dropped
+/// right after writing could take the copy with it.
+#[derive(Default)]
+pub struct ClipboardState {
+    clipboard: Mutex<Option<Clipboard>>,
+}
+
+impl ClipboardState {
+    fn clipboard(&self) -> Result<ClipboardGuard<'_>> {
+        let mut guard = self.clipboard.lock().unwrap_or_else(|e| e.into_inner());
+        if guard.is_none() {
+            *guard = Some(Clipboard::new().map_err(clipboard_error)?);
+        }
+        Ok(ClipboardGuard(guard))
+    }
+
+    pub fn read(&self) -> Result<ClipboardContents> {
+        let mut guard = self.clipboard()?;
+        let clipboard = guard.get();
+        let mut images = Vec::new();
+        // A bitmap that cannot be read (an unsupported format, say) counts
+        // as none, so the text and HTML images still come through.
+        if let Ok(image) = clipboard.get_image() {
+            images.push(encode_png(image)?);
+        }
+        if images.is_empty() {
+            if let Ok(html) = clipboard.get().html() {
+                images.extend(
+                    format::data_url_images(&html)
+                        .into_iter()
+                        .map(|(mime_type, data)| ClipboardImage { data, mime_type }),
+                );
+            }
+        }
+        let text = clipboard.get_text().ok().filter(|text| !text.is_empty());
+        Ok(ClipboardContents { images, text })
+    }
+
+    /// Writes `output` as plain text and HTML.
+    pub fn write(&self, output: &ClipboardOutput) -> Result<()> {
+        let (text, html) = format::render(output);
+        let mut guard = self.clipboard()?;
+        guard
+            .get()
+            .set_html(html, Some(text))
+            .map_err(clipboard_error)
+    }
+}
+
+struct ClipboardGuard<'a>(MutexGuard<'a, Option<Clipboard>>);
+
+impl ClipboardGuard<'_> {
+    fn get(&mut self) -> &mut Clipboard {
+        self.0.as_mut().expect("clipboard opened before use")
+    }
+}
+
+fn encode_png(image: ImageData<'_>) -> Result<ClipboardImage> {
+    if image.width.saturating_mul(image.height) > MAX_IMAGE_PIXELS {
+        return Err(Error::Clipboard(format!(
+            "The copied image is too large ({}×{})",
+            image.width, image.height
+        )));
+    }
+    let bitmap = RgbaImage::from_raw(
+        image.width as u32,
+        image.height as u32,
+        Cow::into_owned(image.bytes),
+    )
+    .ok_or_else(|| Error::Clipboard("The copied image is malformed".into()))?;
+    let mut png = Vec::new();
+    bitmap
+        .write_to(&mut Cursor::new(&mut png), ImageFormat::Png)
+        .map_err(|err| Error::Clipboard(err.to_string()))?;
+    Ok(ClipboardImage {
+        data: STANDARD.encode(png),
+        mime_type: "image/png".into(),
+    })
+}
+
+fn clipboard_error(err: arboard::Error) -> Error {
+    Error::Clipboard(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_clipboard_bitmaps_as_png_blocks() {
+        let image = ImageData {
+            width: 2,
+            height: 1,
+            bytes: Cow::Owned(vec![255, 0, 0, 255, 0, 0, 255, 128]),
+        };
+        let block = encode_png(image).unwrap();
+        let png = STANDARD.decode(&block.data).unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+        assert_eq!(
+            serde_json::to_value(&block).unwrap()["type"],
+            serde_json::json!("image")
+        );
+
+        let malformed = ImageData {
+            width: 4,
+            height: 4,
+            bytes: Cow::Owned(vec![0; 4]),
+        };
+        assert!(encode_png(malformed).is_err());
+    }
+}
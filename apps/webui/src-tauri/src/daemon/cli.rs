@@ -0,0 +1,135 @@
+//! Finding and running the `mobvibe` executable. Apps launched from the
+//! Finder or a desktop entry get a minimal `PATH`, so the usual install
+//! locations are searched too and handed to the CLI, whose `node` shim needs
+//! them to find its runtime.
+
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+use crate::error::{Error, Result};
+
+#[cfg(windows)]
+const NAMES: &[&str] = &["mobvibe.cmd", "mobvibe.exe"];
+#[cfg(not(windows))]
+const NAMES: &[&str] = &["mobvibe"];
+
+/// Lines of CLI output kept in an error message.
+const ERROR_LINES: usize = 5;
+
+/// `PATH` plus the global bin directories of npm, bun and Homebrew.
+pub fn search_path(home: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = env::var_os("PATH")
+        .map(|path| env::split_paths(&path).collect())
+        .unwrap_or_default();
+    let extra = [
+        home.join(".bun/bin"),
+        home.join(".local/bin"),
+        home.join(".npm-global/bin"),
+        PathBuf::from("/opt/homebrew/bin"),
+        PathBuf::from("/usr/local/bin"),
+    ];
+    #[cfg(windows)]
+    let extra = {
+        let mut extra = extra.to_vec();
+        if let Some(app_data) = env::var_os("APPDATA") {
+            extra.push(PathBuf::from(app_data).join("npm"));
+        }
+        extra
+    };
+    for dir in extra {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs
+}
+
+/// The configured CLI if given, else the first `mobvibe` on the search path.
+pub fn locate(configured: Option<&str>, home: &Path) -> Option<PathBuf> {
+    if let Some(path) = configured {
+        return Some(PathBuf::from(path)).filter(|path| path.is_file());
+    }
+    search_path(home)
+        .iter()
+        .flat_map(|dir| NAMES.iter().map(move |name| dir.join(name)))
+        .find(|path| path.is_file())
+}
+
+/// Runs `cli` with `args` and returns its stdout. The CLI prints pino-pretty
+/// lines, so a failure reports the last few without colours.
+pub async fn run(cli: &Path, home: &Path, args: &[&str], envs: &[(&str, &str)]) -> Result<String> {
+    let mut path = vec![cli.parent().map(Path::to_path_buf).unwrap_or_default()];
+    path.extend(search_path(home));
+    let mut command = Command::new(cli);
+    command
+        .args(args)
+        .envs(envs.iter().copied())
+        .env(
+            "PATH",
+            env::join_paths(path).unwrap_or_else(|_| OsString::new()),
+        )
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::null());
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let output = command.output().await?;
+    let stdout = String::from_utf8_lossy(&output.stdout).into_owned();
+    if output.status.success() {
+        return Ok(stdout);
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let text = if stderr.trim().is_empty() {
+        stdout
+    } else {
+        stderr.into_owned()
+    };
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let tail = lines[lines.len().saturating_sub(ERROR_LINES)..].join("\n");
+    Err(Error::Daemon(if tail.is_empty() {
+        format!("mobvibe {} failed ({})", args.join(" "), output.status)
+    } else {
+        super::logs::strip_ansi(&tail)
+    }))
+}
+
+/// The machine id the CLI registers with, computed as `config.ts` does:
+/// `MOBVIBE_MACHINE_ID`, else `<hostname>-<platform>-<arch>-<username>`
+/// with Node's names for the platform and architecture.
+pub fn machine_id() -> String {
+    if let Some(id) = env::var("MOBVIBE_MACHINE_ID")
+        .ok()
+        .filter(|id| !id.is_empty())
+    {
+        return id;
+    }
+    let platform = match env::consts::OS {
+        "macos" => "darwin",
+        "windows" => "win32",
+        os => os,
+    };
+    let arch = match env::consts::ARCH {
+        "x86_64" => "x64",
+        "aarch64" => "arm64",
+        "x86" => "ia32",
+        arch => arch,
+    };
+    #[cfg(windows)]
+    let user = env::var("USERNAME");
+    #[cfg(not(windows))]
+    let user = env::var("USER").or_else(|_| env::var("LOGNAME"));
+    format!(
+        "{}-{platform}-{arch}-{}",
+        tauri_plugin_os::hostname(),
+        user.unwrap_or_default()
+    )
+}
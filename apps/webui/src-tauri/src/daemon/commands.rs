@@ -0,0 +1,80 @@
+use std::path::PathBuf;
+
+use tauri::ipc::Channel;
+use tauri::{State, Webview};
+
+use super::{DaemonLogEvent, DaemonState, DaemonStatus};
+use crate::error::Result;
+use crate::gateway::GatewayState;
+use crate::settings::SettingsStore;
+
+const DEFAULT_LOG_LINES: usize = 50;
+
+#[tauri::command]
+pub async fn daemon_status(
+    settings: State<'_, SettingsStore>,
+    daemon: State<'_, DaemonState>,
+) -> Result<DaemonStatus> {
+    daemon.status(&settings).await
+}
+
+/// Starts the daemon, pointed at the gateway this app is connected to.
+#[tauri::command]
+pub async fn daemon_start(
+    settings: State<'_, SettingsStore>,
+    gateway: State<'_, GatewayState>,
+    daemon: State<'_, DaemonState>,
+    no_e2ee: Option<bool>,
+) -> Result<DaemonStatus> {
+    let gateway_url = gateway.config().map(|config| config.url);
+    daemon
+        .start(&settings, gateway_url.as_deref(), no_e2ee.unwrap_or(false))
+        .await
+}
+
+#[tauri::command]
+pub async fn daemon_stop(
+    settings: State<'_, SettingsStore>,
+    daemon: State<'_, DaemonState>,
+) -> Result<DaemonStatus> {
+    daemon.stop(&settings).await
+}
+
+/// Sets the `mobvibe` executable to run, or goes back to searching for it.
+#[tauri::command]
+pub async fn daemon_set_cli_path(
+    settings: State<'_, SettingsStore>,
+    daemon: State<'_, DaemonState>,
+    path: Option<PathBuf>,
+) -> Result<DaemonStatus> {
+    daemon.set_cli_path(&settings, path.as_deref())?;
+    daemon.status(&settings).await
+}
+
+/// Streams the daemon log to the calling webview until it unwatches or
+/// navigates away. Returns the watcher id.
+#[tauri::command]
+pub fn daemon_watch_logs(
+    webview: Webview,
+    daemon: State<'_, DaemonState>,
+    lines: Option<usize>,
+    on_event: Channel<DaemonLogEvent>,
+) -> u32 {
+    daemon.watch_logs(
+        webview.label(),
+        lines.unwrap_or(DEFAULT_LOG_LINES),
+        on_event,
+    )
+}
+
+#[tauri::command]
+pub fn daemon_unwatch_logs(daemon: State<'_, DaemonState>, id: u32) {
+    daemon.unwatch_logs(id);
+}
+
+/// The id this machine's CLI registers under, to tell which sessions run
+/// here.
+#[tauri::command]
+pub fn daemon_machine_id() -> String {
+    super::machine_id()
+}
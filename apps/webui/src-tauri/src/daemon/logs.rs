@@ -0,0 +1,211 @@
+//! Following the daemon log like `mobvibe logs --follow`. Every background
+//! start writes a new `<timestamp>-daemon.log`, so the newest file is polled
+//! and a restart switches to the file it created.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use serde::Serialize;
+use tauri::ipc::Channel;
+use tokio::sync::watch;
+
+const LOG_SUFFIX: &str = "-daemon.log";
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Upper bound on one read, so a burst does not stall the webview.
+const MAX_CHUNK_BYTES: u64 = 256 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum DaemonLogEvent {
+    /// Lines that follow come from this file.
+    File {
+        path: PathBuf,
+    },
+    Lines {
+        lines: Vec<String>,
+    },
+}
+
+/// The newest log file; timestamped names sort chronologically.
+pub fn latest(log_dir: &Path) -> Option<PathBuf> {
+    std::fs::read_dir(log_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.ends_with(LOG_SUFFIX))
+        })
+        .max()
+}
+
+/// Removes the colour and style sequences pino-pretty writes.
+pub fn strip_ansi(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\u{1b}' {
+            out.push(c);
+            continue;
+        }
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if ('@'..='~').contains(&c) {
+                    break;
+                }
+            }
+        }
+    }
+    out
+}
+
+struct Follower {
+    path: PathBuf,
+    offset: u64,
+    /// Bytes after the last newline, kept until the line is complete.
+    partial: Vec<u8>,
+}
+
+impl Follower {
+    /// Starts at the last `lines` complete lines of `path`.
+    fn open(path: PathBuf, lines: usize) -> (Self, Vec<String>) {
+        let content = std::fs::read(&path).unwrap_or_default();
+        let end = content
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |end| end + 1);
+        let text = String::from_utf8_lossy(&content[..end]);
+        let all: Vec<&str> = text.lines().collect();
+        let tail = all[all.len().saturating_sub(lines)..]
+            .iter()
+            .map(|line| strip_ansi(line))
+            .collect();
+        let follower = Self {
+            path,
+            offset: end as u64,
+            partial: Vec::new(),
+        };
+        (follower, tail)
+    }
+
+    fn from_start(path: PathBuf) -> Self {
+        Self {
+            path,
+            offset: 0,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Complete lines appended since the last read.
+    fn read(&mut self) -> Vec<String> {
+        let Ok(mut file) = File::open(&self.path) else {
+            return Vec::new();
+        };
+        let len = file.metadata().map_or(0, |metadata| metadata.len());
+        if len < self.offset {
+            // Truncated: start over.
+            self.offset = 0;
+            self.partial.clear();
+        }
+        let start = self.partial.len();
+        if file.seek(SeekFrom::Start(self.offset)).is_err()
+            || file
+                .take(MAX_CHUNK_BYTES)
+                .read_to_end(&mut self.partial)
+                .is_err()
+        {
+            self.partial.truncate(start);
+            return Vec::new();
+        }
+        self.offset += (self.partial.len() - start) as u64;
+        let Some(end) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Vec::new();
+        };
+        let lines = String::from_utf8_lossy(&self.partial[..end])
+            .lines()
+            .map(strip_ansi)
+            .collect();
+        self.partial.drain(..=end);
+        lines
+    }
+}
+
+/// Sends the tail of the newest log, then appended lines, until `stop`
+/// changes or the channel is gone.
+pub async fn follow(
+    log_dir: PathBuf,
+    lines: usize,
+    channel: Channel<DaemonLogEvent>,
+    mut stop: watch::Receiver<bool>,
+) {
+    let mut follower: Option<Follower> = None;
+    loop {
+        if let Some(path) = latest(&log_dir) {
+            if follower
+                .as_ref()
+                .is_none_or(|follower| follower.path != path)
+            {
+                // A new file after the first is a restart: show all of it.
+                let (next, tail) = match follower {
+                    None => Follower::open(path.clone(), lines),
+                    Some(_) => (Follower::from_start(path.clone()), Vec::new()),
+                };
+                let mut sent = channel.send(DaemonLogEvent::File { path }).is_ok();
+                if !tail.is_empty() {
+                    sent &= channel.send(DaemonLogEvent::Lines { lines: tail }).is_ok();
+                }
+                if !sent {
+                    return;
+                }
+                follower = Some(next);
+            }
+        }
+        if let Some(follower) = &mut follower {
+            let lines = follower.read();
+            if !lines.is_empty() && channel.send(DaemonLogEvent::Lines { lines }).is_err() {
+                return;
+            }
+        }
+        tokio::select! {
+            _ = tokio::time::sleep(POLL_INTERVAL) => {}
+            _ = stop.changed() => return,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_colour_sequences() {
+        assert_eq!(
+            strip_ansi("\u{1b}[32mINFO\u{1b}[39m (mobvibe-cli): \u{1b}[36mdaemon_started\u{1b}[0m"),
+            "INFO (mobvibe-cli): daemon_started"
+        );
+    }
+
+    #[test]
+    fn follows_appended_lines_across_partial_writes() {
+        let dir = std::env::temp_dir().join(format!("mobvibe-logs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("2026-01-01T00-00-00-000Z-daemon.log");
+        std::fs::write(&path, "one\ntwo\nthree\npart").unwrap();
+        assert_eq!(latest(&dir), Some(path.clone()));
+
+        let (mut follower, tail) = Follower::open(path.clone(), 2);
+        assert_eq!(tail, ["two", "three"]);
+        assert_eq!(follower.read(), Vec::<String>::new());
+
+        let mut file = std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap();
+        std::io::Write::write_all(&mut file, b"ial\nfour\n").unwrap();
+        assert_eq!(follower.read(), ["partial", "four"]);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
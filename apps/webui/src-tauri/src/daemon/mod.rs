@@ -0,0 +1,215 @@
+//! The local `mobvibe-cli` daemon, managed from the app.
+//!
+//! State lives where `DaemonManager` keeps it: `daemon.pid` and `logs/` in
+//! `MOBVIBE_HOME` (default `~/.mobvibe`). Status is read from the PID file
+//! here; starting and stopping run `mobvibe start` and `mobvibe stop`, so the
+//! CLI's preflight, PID file locking and identity checks before signalling
+//! stay the only implementation of those.
+
+mod cli;
+pub mod commands;
+mod logs;
+mod pid_file;
+mod process;
+
+pub use cli::{machine_id, search_path};
+pub use logs::DaemonLogEvent;
+pub use process::Liveness;
+
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::ipc::Channel;
+use tokio::sync::watch;
+
+use crate::error::{Error, Result};
+use crate::settings::{self, SettingsStore};
+
+/// Settings key for a CLI path chosen in the app, overriding the search.
+const CLI_PATH_KEY: &str = "cliPath";
+const PID_FILE: &str = "daemon.pid";
+const LOG_DIR: &str = "logs";
+/// How long `start` waits for the detached daemon to write its PID file.
+const START_TIMEOUT: Duration = Duration::from_secs(10);
+const START_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonStatus {
+    pub state: Liveness,
+    pub pid: Option<u32>,
+    pub home: PathBuf,
+    /// The CLI that start and stop would run; `None` if none was found.
+    pub cli_path: Option<PathBuf>,
+    /// Newest log file, which the running daemon writes to.
+    pub log_file: Option<PathBuf>,
+}
+
+struct LogWatcher {
+    id: u32,
+    webview: String,
+    stop: watch::Sender<bool>,
+}
+
+#[derive(Default)]
+struct Inner {
+    watchers: Vec<LogWatcher>,
+    next_watcher_id: u32,
+}
+
+pub struct DaemonState {
+    home: PathBuf,
+    /// The user's home, for the CLI search path.
+    user_home: PathBuf,
+    inner: Mutex<Inner>,
+}
+
+impl DaemonState {
+    /// Uses `MOBVIBE_HOME` if the app was started with it, like the CLI.
+    pub fn new(user_home: PathBuf) -> Self {
+        let home = std::env::var_os("MOBVIBE_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| user_home.join(".mobvibe"));
+        Self {
+            home,
+            user_home,
+            inner: Mutex::default(),
+        }
+    }
+
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn log_dir(&self) -> PathBuf {
+        self.home.join(LOG_DIR)
+    }
+
+    fn cli(&self, settings: &SettingsStore) -> Result<Option<PathBuf>> {
+        let configured = settings.string(settings::DAEMON, CLI_PATH_KEY)?;
+        Ok(cli::locate(configured.as_deref(), &self.user_home))
+    }
+
+    pub async fn status(&self, settings: &SettingsStore) -> Result<DaemonStatus> {
+        let pid_file = pid_file::read(&self.home.join(PID_FILE))?;
+        let state = match &pid_file {
+            Some(pid_file) => process::check(pid_file).await,
+            None => Liveness::Stopped,
+        };
+        Ok(DaemonStatus {
+            state,
+            pid: pid_file
+                .filter(|_| state != Liveness::Stopped)
+                .map(|pid_file| pid_file.pid()),
+            home: self.home.clone(),
+            cli_path: self.cli(settings)?,
+            log_file: logs::latest(&self.log_dir()),
+        })
+    }
+
+    /// Runs `mobvibe start` against `gateway_url` and waits for the daemon
+    /// to come up. An already running daemon is left as is.
+    pub async fn start(
+        &self,
+        settings: &SettingsStore,
+        gateway_url: Option<&str>,
+        no_e2ee: bool,
+    ) -> Result<DaemonStatus> {
+        let cli = self.require_cli(settings)?;
+        let mut args = vec!["start"];
+        if let Some(url) = gateway_url {
+            args.extend(["--gateway", url]);
+        }
+        if no_e2ee {
+            args.push("--no-e2ee");
+        }
+        cli::run(&cli, &self.user_home, &args, &self.envs()).await?;
+
+        let deadline = tokio::time::Instant::now() + START_TIMEOUT;
+        loop {
+            let status = self.status(settings).await?;
+            if status.state != Liveness::Stopped || tokio::time::Instant::now() >= deadline {
+                return Ok(status);
+            }
+            tokio::time::sleep(START_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Runs `mobvibe stop`, which waits for the daemon to exit.
+    pub async fn stop(&self, settings: &SettingsStore) -> Result<DaemonStatus> {
+        let cli = self.require_cli(settings)?;
+        cli::run(&cli, &self.user_home, &["stop"], &self.envs()).await?;
+        self.status(settings).await
+    }
+
+    fn require_cli(&self, settings: &SettingsStore) -> Result<PathBuf> {
+        self.cli(settings)?.ok_or_else(|| {
+            Error::Daemon(
+                "mobvibe CLI not found. Install @mobvibe/cli or choose its path.".to_owned(),
+            )
+        })
+    }
+
+    /// Keeps the CLI on the same home as this view of it.
+    fn envs(&self) -> Vec<(&str, &str)> {
+        self.home
+            .to_str()
+            .map(|home| vec![("MOBVIBE_HOME", home)])
+            .unwrap_or_default()
+    }
+
+    /// Persists or clears the CLI path override.
+    pub fn set_cli_path(&self, settings: &SettingsStore, path: Option<&Path>) -> Result<()> {
+        match path {
+            Some(path) if !path.is_file() => {
+                Err(Error::Daemon(format!("{} is not a file", path.display())))
+            }
+            Some(path) => settings.set(
+                settings::DAEMON,
+                CLI_PATH_KEY,
+                &Value::String(path.to_string_lossy().into_owned()),
+            ),
+            None => settings.delete(settings::DAEMON, CLI_PATH_KEY),
+        }
+    }
+
+    /// Streams the last `lines` log lines and everything appended after
+    /// them to a webview. Returns the watcher id.
+    pub fn watch_logs(&self, webview: &str, lines: usize, channel: Channel<DaemonLogEvent>) -> u32 {
+        let (stop, stopped) = watch::channel(false);
+        tauri::async_runtime::spawn(logs::follow(self.log_dir(), lines, channel, stopped));
+        let mut inner = self.inner();
+        let id = inner.next_watcher_id;
+        inner.next_watcher_id += 1;
+        inner.watchers.push(LogWatcher {
+            id,
+            webview: webview.to_owned(),
+            stop,
+        });
+        id
+    }
+
+    pub fn unwatch_logs(&self, id: u32) {
+        self.inner().watchers.retain(|watcher| {
+            let keep = watcher.id != id;
+            if !keep {
+                let _ = watcher.stop.send(true);
+            }
+            keep
+        });
+    }
+
+    /// Stops the watchers of a webview that is navigating away.
+    pub fn forget_webview(&self, webview: &str) {
+        self.inner().watchers.retain(|watcher| {
+            let keep = watcher.webview != webview;
+            if !keep {
+                let _ = watcher.stop.send(true);
+            }
+            keep
+        });
+    }
+}
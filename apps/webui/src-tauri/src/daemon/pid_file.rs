@@ -0,0 +1,110 @@
+//! `daemon.pid` as `DaemonManager.writePidFile` writes it: a JSON
+//! `DaemonPidRecord`, or a bare PID from CLIs that predate it.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DaemonPidRecord {
+    pub version: u8,
+    pub pid: u32,
+    pub instance_id: String,
+    pub process: ProcessIdentity,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessIdentity {
+    pub platform: String,
+    /// A numeric uid, or the owner SID on Windows.
+    pub uid: Value,
+    /// Opaque per platform: `/proc` ticks, `ps` `lstart` or a CIM date.
+    pub start_time: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PidFile {
+    Record(DaemonPidRecord),
+    Legacy(u32),
+}
+
+impl PidFile {
+    pub fn pid(&self) -> u32 {
+        match self {
+            Self::Record(record) => record.pid,
+            Self::Legacy(pid) => *pid,
+        }
+    }
+}
+
+/// Reads the PID file, or `None` when no daemon claimed one.
+pub fn read(path: &Path) -> Result<Option<PidFile>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => return Err(err.into()),
+    };
+    parse(&content)
+        .map(Some)
+        .ok_or_else(|| Error::Daemon(format!("Invalid daemon PID file: {}", path.display())))
+}
+
+/// Same checks as `isDaemonPidRecord` in the CLI.
+fn parse(content: &str) -> Option<PidFile> {
+    let trimmed = content.trim();
+    if !trimmed.is_empty() && trimmed.bytes().all(|b| b.is_ascii_digit()) {
+        return match trimmed.parse() {
+            Ok(pid) if pid > 0 && !trimmed.starts_with('0') => Some(PidFile::Legacy(pid)),
+            _ => None,
+        };
+    }
+    let record: DaemonPidRecord = serde_json::from_str(content).ok()?;
+    let identity = &record.process;
+    let valid_uid = match identity.platform.as_str() {
+        "win32" => identity
+            .uid
+            .as_str()
+            .is_some_and(|sid| !sid.is_empty() && sid.len() <= 256),
+        "linux" | "darwin" => identity.uid.is_u64(),
+        _ => false,
+    };
+    let valid = record.version == 1
+        && record.pid > 0
+        && record.instance_id.len() == 16
+        && record
+            .instance_id
+            .bytes()
+            .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        && valid_uid
+        && !identity.start_time.is_empty()
+        && identity.start_time.len() <= 256;
+    valid.then_some(PidFile::Record(record))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_records_and_legacy_pids() {
+        let record = r#"{"version":1,"pid":4242,"instanceId":"0123456789abcdef","process":{"platform":"linux","uid":1000,"startTime":"987654"}}"#;
+        let Some(PidFile::Record(parsed)) = parse(record) else {
+            panic!("expected a record");
+        };
+        assert_eq!(parsed.pid, 4242);
+        assert_eq!(parsed.process.start_time, "987654");
+
+        assert_eq!(parse("4242\n"), Some(PidFile::Legacy(4242)));
+        assert_eq!(parse("0"), None);
+        assert_eq!(parse("not a pid"), None);
+        assert_eq!(parse(&record.replace("0123456789abcdef", "XYZ")), None);
+        assert_eq!(parse(&record.replace("1000", "\"S-1-5\"")), None);
+    }
+}
@@ -0,0 +1,153 @@
+//! Whether the process a PID file names is still that daemon. The CLI wrote
+//! the record from the same sources read here, so a match means the PID was
+//! not reused. Only `mobvibe stop` signals the process; this is read-only.
+
+use serde::Serialize;
+
+use super::pid_file::PidFile;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Liveness {
+    Running,
+    Stopped,
+    /// A process with the PID exists but its identity cannot be checked
+    /// here: a legacy PID file, or Windows.
+    Unverified,
+}
+
+pub async fn check(pid_file: &PidFile) -> Liveness {
+    let PidFile::Record(record) = pid_file else {
+        return if platform::is_alive(pid_file.pid()).await {
+            Liveness::Unverified
+        } else {
+            Liveness::Stopped
+        };
+    };
+    if record.process.platform != platform::NAME {
+        return Liveness::Stopped;
+    }
+    platform::check(record).await
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use std::fs;
+    use std::os::unix::fs::MetadataExt;
+    use std::path::Path;
+
+    use super::Liveness;
+    use crate::daemon::pid_file::DaemonPidRecord;
+
+    /// `process.platform` in Node terms.
+    pub const NAME: &str = "linux";
+
+    pub async fn check(record: &DaemonPidRecord) -> Liveness {
+        let dir = Path::new("/proc").join(record.pid.to_string());
+        let (Ok(metadata), Some(start_time)) = (fs::metadata(&dir), start_time(&dir)) else {
+            return Liveness::Stopped;
+        };
+        let identity_file = format!(".mobvibe-daemon-{}.identity", record.instance_id);
+        let holds_identity = fs::read_dir(dir.join("fd"))
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|fd| fs::read_link(fd.path()).ok())
+            .any(|target| {
+                target
+                    .file_name()
+                    .is_some_and(|name| name == identity_file.as_str())
+            });
+        if record.process.uid.as_u64() == Some(u64::from(metadata.uid()))
+            && start_time == record.process.start_time
+            && holds_identity
+        {
+            Liveness::Running
+        } else {
+            Liveness::Stopped
+        }
+    }
+
+    /// Field 22 of `/proc/<pid>/stat`, counted after the command name.
+    fn start_time(dir: &Path) -> Option<String> {
+        let stat = fs::read_to_string(dir.join("stat")).ok()?;
+        let (_, fields) = stat.rsplit_once(')')?;
+        fields.split_whitespace().nth(19).map(str::to_owned)
+    }
+
+    pub async fn is_alive(pid: u32) -> bool {
+        Path::new("/proc").join(pid.to_string()).exists()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use tokio::process::Command;
+
+    use super::Liveness;
+    use crate::daemon::pid_file::DaemonPidRecord;
+
+    pub const NAME: &str = "darwin";
+
+    pub async fn check(record: &DaemonPidRecord) -> Liveness {
+        match ps(record.pid).await {
+            Some((uid, start_time))
+                if record.process.uid.as_u64() == Some(uid)
+                    && start_time == record.process.start_time =>
+            {
+                Liveness::Running
+            }
+            _ => Liveness::Stopped,
+        }
+    }
+
+    /// Owner and `lstart`, formatted as `inspectDarwinProcess` reads them.
+    async fn ps(pid: u32) -> Option<(u64, String)> {
+        let output = Command::new("ps")
+            .args(["-p", &pid.to_string(), "-o", "uid=", "-o", "lstart="])
+            .env("LC_ALL", "C")
+            .output()
+            .await
+            .ok()?;
+        let stdout = String::from_utf8(output.stdout).ok()?;
+        let (uid, start_time) = stdout.trim().split_once(char::is_whitespace)?;
+        Some((uid.parse().ok()?, start_time.trim().to_owned()))
+    }
+
+    pub async fn is_alive(pid: u32) -> bool {
+        ps(pid).await.is_some()
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use tokio::process::Command;
+
+    use super::Liveness;
+    use crate::daemon::pid_file::DaemonPidRecord;
+
+    pub const NAME: &str = "win32";
+
+    /// The CLI's identity check goes through PowerShell and CIM, which is
+    /// too slow to poll; a live PID is reported as such.
+    pub async fn check(record: &DaemonPidRecord) -> Liveness {
+        if is_alive(record.pid).await {
+            Liveness::Unverified
+        } else {
+            Liveness::Stopped
+        }
+    }
+
+    pub async fn is_alive(pid: u32) -> bool {
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        let Ok(output) = Command::new("tasklist")
+            .args(["/FI", &format!("PID eq {pid}"), "/FO", "CSV", "/NH"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .await
+        else {
+            return false;
+        };
+        String::from_utf8_lossy(&output.stdout).contains(&format!("\"{pid}\""))
+    }
+}
@@ -0,0 +1,30 @@
+use tauri::State;
+
+use super::{DeepLinkRoute, PendingDeepLinks, PendingPairings};
+use crate::e2ee::E2eeState;
+use crate::error::Result;
+use crate::vault::{SecretInfo, SecretVault};
+
+/// Drains routes that arrived before the webview was listening. After the
+/// first call, routes are delivered as `deep-link:route` events only.
+#[tauri::command]
+pub fn deep_link_take_pending(pending: State<'_, PendingDeepLinks>) -> Vec<DeepLinkRoute> {
+    pending.take()
+}
+
+/// Pairs the secret of a `pair` route once the user has confirmed its
+/// fingerprint.
+#[tauri::command]
+pub fn deep_link_accept_pair(
+    pairings: State<'_, PendingPairings>,
+    vault: State<'_, SecretVault>,
+    e2ee: State<'_, E2eeState>,
+    id: u32,
+) -> Result<SecretInfo> {
+    pairings.accept(id, &vault, &e2ee)
+}
+
+#[tauri::command]
+pub fn deep_link_reject_pair(pairings: State<'_, PendingPairings>, id: u32) {
+    pairings.reject(id);
+}
@@ -0,0 +1,227 @@
+//! Router for `mobvibe://` links. Every URL handed over by the deep-link
+//! plugin is validated in Rust and turned into a typed [`DeepLinkRoute`]
+//! before the webview sees it. Links that change who the app trusts wait for
+//! the user: a pairing secret is held here and only its fingerprint is
+//! forwarded until the webview confirms it, and a gateway link carries the
+//! host to confirm before the webview switches to it.
+
+pub mod commands;
+mod parse;
+
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_deep_link::DeepLinkExt;
+use zeroize::Zeroizing;
+
+use self::parse::DeepLink;
+use crate::e2ee::{self, E2eeState};
+use crate::error::{Error, Result};
+use crate::vault::{SecretInfo, SecretVault};
+
+pub const ROUTE_EVENT: &str = "deep-link:route";
+pub const ERROR_EVENT: &str = "deep-link:error";
+/// Pairing links left unanswered at once; more are refused rather than
+/// piling secrets up in memory.
+const MAX_PENDING_PAIRINGS: usize = 8;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(
+    tag = "type",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum DeepLinkRoute {
+    /// A pairing link waiting for [`PendingPairings::accept`].
+    Pair { id: u32, fingerprint: String },
+    Session {
+        session_id: String,
+        /// Event to scroll to, e.g. a search hit.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        seq: Option<u64>,
+    },
+    /// A gateway link; the webview switches only once the user confirms
+    /// `host`.
+    Gateway { url: String, host: String },
+    NewSession {
+        cwd: Option<String>,
+        prompt: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeepLinkError {
+    pub reason: String,
+}
+
+/// Routes received before the webview asked for them, e.g. the link that
+/// cold-started the app.
+#[derive(Default)]
+pub struct PendingDeepLinks {
+    inner: Mutex<Pending>,
+}
+
+#[derive(Default)]
+struct Pending {
+    webview_ready: bool,
+    routes: Vec<DeepLinkRoute>,
+}
+
+impl PendingDeepLinks {
+    /// Returns queued routes and switches to emitting events directly.
+    pub fn take(&self) -> Vec<DeepLinkRoute> {
+        let mut pending = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        pending.webview_ready = true;
+        std::mem::take(&mut pending.routes)
+    }
+
+    /// Queues the route unless the webview is already listening.
+    fn queue(&self, route: &DeepLinkRoute) -> bool {
+        let mut pending = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if !pending.webview_ready {
+            pending.routes.push(route.clone());
+        }
+        !pending.webview_ready
+    }
+}
+
+/// Secrets of pairing links the user has not answered yet, by route id.
+#[derive(Default)]
+pub struct PendingPairings {
+    inner: Mutex<Pairings>,
+}
+
+#[derive(Default)]
+struct Pairings {
+    next_id: u32,
+    secrets: BTreeMap<u32, Zeroizing<String>>,
+}
+
+impl PendingPairings {
+    fn hold(&self, secret: Zeroizing<String>) -> Result<u32> {
+        let mut pairings = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if pairings.secrets.len() >= MAX_PENDING_PAIRINGS {
+            return Err(Error::InvalidDeepLink(
+                "too many pairing links are waiting".into(),
+            ));
+        }
+        pairings.next_id = pairings.next_id.wrapping_add(1);
+        let id = pairings.next_id;
+        pairings.secrets.insert(id, secret);
+        Ok(id)
+    }
+
+    fn take(&self, id: u32) -> Option<Zeroizing<String>> {
+        let mut pairings = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        pairings.secrets.remove(&id)
+    }
+
+    /// Pairs the secret the user accepted. It stays held if that fails, e.g.
+    /// on a locked vault, so the user can try again.
+    pub fn accept(&self, id: u32, vault: &SecretVault, e2ee: &E2eeState) -> Result<SecretInfo> {
+        let secret = {
+            let pairings = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+            pairings.secrets.get(&id).cloned()
+        }
+        .ok_or_else(|| Error::InvalidDeepLink("pairing link expired".into()))?;
+        let info = vault.add(&secret)?;
+        e2ee.add_secret(&secret)?;
+        self.take(id);
+        Ok(info)
+    }
+
+    /// Drops the secret the user declined.
+    pub fn reject(&self, id: u32) {
+        self.take(id);
+    }
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    app.manage(PendingDeepLinks::default());
+    app.manage(PendingPairings::default());
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            open_url(&handle, url.as_str());
+        }
+    });
+
+    if let Some(urls) = app.deep_link().get_current()? {
+        for url in urls {
+            open_url(app, url.as_str());
+        }
+    }
+    Ok(())
+}
+
+pub fn is_deep_link(arg: &str) -> bool {
+    arg.strip_prefix(parse::SCHEME)
+        .is_some_and(|rest| rest.starts_with("://"))
+}
+
+/// Validates and dispatches one URL. Failures are reported to the webview
+/// without echoing the URL, which may carry a secret; links of other
+/// handlers are skipped.
+pub fn open_url<R: Runtime>(app: &AppHandle<R>, raw: &str) {
+    if !parse::is_routed(raw) {
+        return;
+    }
+    match parse::parse(raw).and_then(|link| resolve(app, link)) {
+        Ok(route) => dispatch(app, route),
+        Err(err) => {
+            let _ = app.emit(
+                ERROR_EVENT,
+                DeepLinkError {
+                    reason: err.to_string(),
+                },
+            );
+        }
+    }
+}
+
+/// Hands a route to the webview, queueing it until the webview is listening.
+/// Also used for navigation requested from native UI such as the tray.
+pub fn dispatch<R: Runtime>(app: &AppHandle<R>, route: DeepLinkRoute) {
+    if !app.state::<PendingDeepLinks>().queue(&route) {
+        let _ = app.emit(ROUTE_EVENT, route);
+    }
+}
+
+fn resolve<R: Runtime>(app: &AppHandle<R>, link: DeepLink) -> Result<DeepLinkRoute> {
+    Ok(match link {
+        DeepLink::Pair { secret } => {
+            let fingerprint = e2ee::fingerprint(&e2ee::decode_key(&secret)?);
+            let id = app.state::<PendingPairings>().hold(secret)?;
+            DeepLinkRoute::Pair { id, fingerprint }
+        }
+        DeepLink::Session { session_id, seq } => DeepLinkRoute::Session { session_id, seq },
+        DeepLink::Gateway { url, host } => DeepLinkRoute::Gateway { url, host },
+        DeepLink::NewSession { cwd, prompt } => DeepLinkRoute::NewSession { cwd, prompt },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn holds_pairing_secrets_until_answered() {
+        let pairings = PendingPairings::default();
+        let first = pairings.hold(Zeroizing::new("a".to_owned())).unwrap();
+        let ids: Vec<u32> = (1..MAX_PENDING_PAIRINGS)
+            .map(|_| pairings.hold(Zeroizing::new("b".to_owned())).unwrap())
+            .collect();
+        assert!(pairings.hold(Zeroizing::new("c".to_owned())).is_err());
+
+        pairings.reject(first);
+        assert!(pairings.take(first).is_none());
+        assert_eq!(
+            pairings.take(ids[0]).as_deref().map(String::as_str),
+            Some("b")
+        );
+        assert!(pairings.hold(Zeroizing::new("c".to_owned())).is_ok());
+    }
+}
@@ -0,0 +1,278 @@
+//! Parsing and validation for `mobvibe://` URLs. Everything here is pure so
+//! malformed links are rejected before any state is touched.
+
+use url::{Position, Url};
+use zeroize::Zeroizing;
+
+use crate::error::{Error, Result};
+
+pub const SCHEME: &str = "mobvibe";
+const MAX_URL_BYTES: usize = 16 * 1024;
+const MAX_SESSION_ID_BYTES: usize = 128;
+const MAX_GATEWAY_URL_BYTES: usize = 2048;
+const MAX_CWD_BYTES: usize = 4096;
+const MAX_PROMPT_BYTES: usize = 8 * 1024;
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum DeepLink {
+    /// `mobvibe://pair?secret=<base64url>`, as printed by `mobvibe login`.
+    Pair { secret: Zeroizing<String> },
+    /// `mobvibe://session/<id>[?seq=<n>]`
+    Session {
+        session_id: String,
+        seq: Option<u64>,
+    },
+    /// `mobvibe://gateway?url=<http(s) url>`; `host` is what the user is
+    /// asked to confirm.
+    Gateway { url: String, host: String },
+    /// `mobvibe://new?cwd=<path>&prompt=<text>`
+    NewSession {
+        cwd: Option<String>,
+        prompt: Option<String>,
+    },
+}
+
+/// Hosts routed here. Other `mobvibe://` links, such as sign-in callbacks,
+/// belong to the plugins that asked for them.
+const ROUTED_HOSTS: [&str; 4] = ["pair", "session", "gateway", "new"];
+
+/// Whether `raw` is a link this router is responsible for, valid or not.
+pub fn is_routed(raw: &str) -> bool {
+    raw.len() <= MAX_URL_BYTES
+        && Url::parse(raw).is_ok_and(|url| {
+            url.scheme() == SCHEME && url.host_str().is_some_and(|h| ROUTED_HOSTS.contains(&h))
+        })
+}
+
+fn invalid(reason: &str) -> Error {
+    Error::InvalidDeepLink(reason.to_owned())
+}
+
+pub fn parse(raw: &str) -> Result<DeepLink> {
+    if raw.len() > MAX_URL_BYTES {
+        return Err(invalid("link is too long"));
+    }
+    let url = Url::parse(raw).map_err(|_| invalid("not a valid URL"))?;
+    if url.scheme() != SCHEME {
+        return Err(invalid("unsupported scheme"));
+    }
+
+    match url.host_str() {
+        Some("pair") => parse_pair(&url),
+        Some("session") => parse_session(&url),
+        Some("gateway") => parse_gateway(&url),
+        Some("new") => parse_new_session(&url),
+        _ => Err(invalid("unknown link type")),
+    }
+}
+
+fn query_param(url: &Url, name: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.into_owned())
+}
+
+fn has_control_chars(value: &str) -> bool {
+    value.chars().any(char::is_control)
+}
+
+fn parse_pair(url: &Url) -> Result<DeepLink> {
+    let secret =
+        Zeroizing::new(query_param(url, "secret").ok_or_else(|| invalid("missing secret"))?);
+    Ok(DeepLink::Pair {
+        secret: Zeroizing::new(base64url_to_base64(&secret)),
+    })
+}
+
+/// Same conversion as `base64urlToBase64` in `E2EESettings.tsx`.
+fn base64url_to_base64(base64url: &str) -> String {
+    let mut b64: String = base64url
+        .chars()
+        .map(|c| match c {
+            '-' => '+',
+            '_' => '/',
+            c => c,
+        })
+        .collect();
+    match b64.len() % 4 {
+        2 => b64.push_str("=="),
+        3 => b64.push('='),
+        _ => {}
+    }
+    b64
+}
+
+fn parse_session(url: &Url) -> Result<DeepLink> {
+    let mut segments = url
+        .path_segments()
+        .into_iter()
+        .flatten()
+        .filter(|s| !s.is_empty());
+    let session_id = segments
+        .next()
+        .ok_or_else(|| invalid("missing session id"))?;
+    if segments.next().is_some() {
+        return Err(invalid("unexpected path after session id"));
+    }
+    if session_id.len() > MAX_SESSION_ID_BYTES
+        || !session_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+    {
+        return Err(invalid("invalid session id"));
+    }
+    let seq = query_param(url, "seq")
+        .map(|seq| seq.parse().map_err(|_| invalid("invalid seq")))
+        .transpose()?;
+    Ok(DeepLink::Session {
+        session_id: session_id.to_owned(),
+        seq,
+    })
+}
+
+fn parse_gateway(url: &Url) -> Result<DeepLink> {
+    let raw = query_param(url, "url").ok_or_else(|| invalid("missing gateway url"))?;
+    if raw.len() > MAX_GATEWAY_URL_BYTES {
+        return Err(invalid("gateway url is too long"));
+    }
+    let gateway = Url::parse(&raw).map_err(|_| invalid("invalid gateway url"))?;
+    if !matches!(gateway.scheme(), "http" | "https")
+        || gateway.host_str().is_none()
+        || !gateway.username().is_empty()
+        || gateway.password().is_some()
+    {
+        return Err(invalid("invalid gateway url"));
+    }
+    Ok(DeepLink::Gateway {
+        url: gateway.as_str().trim_end_matches('/').to_owned(),
+        host: gateway[Position::BeforeHost..Position::AfterPort].to_owned(),
+    })
+}
+
+fn parse_new_session(url: &Url) -> Result<DeepLink> {
+    let cwd = query_param(url, "cwd").filter(|cwd| !cwd.is_empty());
+    if let Some(cwd) = &cwd {
+        if cwd.len() > MAX_CWD_BYTES || has_control_chars(cwd) || !is_absolute_path(cwd) {
+            return Err(invalid("invalid cwd"));
+        }
+    }
+    let prompt = query_param(url, "prompt").filter(|prompt| !prompt.trim().is_empty());
+    if let Some(prompt) = &prompt {
+        if prompt.len() > MAX_PROMPT_BYTES || prompt.contains('\0') {
+            return Err(invalid("invalid prompt"));
+        }
+    }
+    Ok(DeepLink::NewSession { cwd, prompt })
+}
+
+/// The cwd refers to the machine running the CLI, which may not be this one,
+/// so accept both POSIX and Windows absolute paths plus `~`.
+fn is_absolute_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    path.starts_with('/')
+        || path == "~"
+        || path.starts_with("~/")
+        || (bytes.len() >= 3
+            && bytes[0].is_ascii_alphabetic()
+            && bytes[1] == b':'
+            && matches!(bytes[2], b'\\' | b'/'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pairing_links_as_standard_base64() {
+        let link =
+            parse("mobvibe://pair?secret=AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8").unwrap();
+        assert_eq!(
+            link,
+            DeepLink::Pair {
+                secret: Zeroizing::new("AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8=".to_owned())
+            }
+        );
+        assert!(parse("mobvibe://pair").is_err());
+    }
+
+    #[test]
+    fn parses_session_links() {
+        assert_eq!(
+            parse("mobvibe://session/sess_01J9-abc").unwrap(),
+            DeepLink::Session {
+                session_id: "sess_01J9-abc".to_owned(),
+                seq: None
+            }
+        );
+        assert_eq!(
+            parse("mobvibe://session/s1?seq=42").unwrap(),
+            DeepLink::Session {
+                session_id: "s1".to_owned(),
+                seq: Some(42)
+            }
+        );
+        assert!(parse("mobvibe://session/s1?seq=-1").is_err());
+        assert!(parse("mobvibe://session/").is_err());
+        assert!(parse("mobvibe://session/a/b").is_err());
+        assert!(parse("mobvibe://session/%3Cscript%3E").is_err());
+        assert!(parse(&format!("mobvibe://session/{}", "a".repeat(129))).is_err());
+    }
+
+    #[test]
+    fn parses_gateway_links() {
+        assert_eq!(
+            parse("mobvibe://gateway?url=https%3A%2F%2Fgw.example.com%2F").unwrap(),
+            DeepLink::Gateway {
+                url: "https://gw.example.com".to_owned(),
+                host: "gw.example.com".to_owned(),
+            }
+        );
+        assert_eq!(
+            parse("mobvibe://gateway?url=http%3A%2F%2F127.0.0.1%3A3005").unwrap(),
+            DeepLink::Gateway {
+                url: "http://127.0.0.1:3005".to_owned(),
+                host: "127.0.0.1:3005".to_owned(),
+            }
+        );
+        assert!(parse("mobvibe://gateway?url=javascript%3Aalert(1)").is_err());
+        assert!(parse("mobvibe://gateway?url=https%3A%2F%2Fuser%3Apw%40gw.example.com").is_err());
+    }
+
+    #[test]
+    fn parses_new_session_links() {
+        assert_eq!(
+            parse("mobvibe://new?cwd=%2Fhome%2Fme%2Frepo&prompt=fix%20the%20tests").unwrap(),
+            DeepLink::NewSession {
+                cwd: Some("/home/me/repo".to_owned()),
+                prompt: Some("fix the tests".to_owned()),
+            }
+        );
+        assert_eq!(
+            parse("mobvibe://new").unwrap(),
+            DeepLink::NewSession {
+                cwd: None,
+                prompt: None
+            }
+        );
+        assert!(parse("mobvibe://new?cwd=C%3A%5Ccode").is_ok());
+        assert!(parse("mobvibe://new?cwd=relative%2Fpath").is_err());
+        assert!(parse("mobvibe://new?cwd=%2Ftmp%0Arm").is_err());
+    }
+
+    #[test]
+    fn rejects_foreign_unknown_and_oversized_links() {
+        assert!(parse("https://pair?secret=abc").is_err());
+        assert!(parse("mobvibe://settings").is_err());
+        assert!(parse("not a url").is_err());
+        let prompt = "a".repeat(MAX_URL_BYTES);
+        assert!(parse(&format!("mobvibe://new?prompt={prompt}")).is_err());
+    }
+
+    #[test]
+    fn leaves_links_of_other_handlers_alone() {
+        assert!(is_routed("mobvibe://session/"));
+        assert!(is_routed("mobvibe://pair"));
+        assert!(!is_routed("mobvibe://auth/callback?token=abc"));
+        assert!(!is_routed("https://session/s1"));
+    }
+}
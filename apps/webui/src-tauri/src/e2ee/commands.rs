@@ -0,0 +1,76 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::State;
+
+use super::state::{DecryptedEvent, E2eeState};
+use crate::error::Result;
+use crate::protocol::SessionEvent;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WrappedSessionDek {
+    session_id: String,
+    wrapped_dek: Option<String>,
+    revision: Option<u64>,
+}
+
+/// A session revision whose DEK Rust holds, so its events can be decrypted
+/// and its prompts encrypted here.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnwrappedSession {
+    session_id: String,
+    revision: Option<u64>,
+}
+
+#[tauri::command]
+pub fn e2ee_forget_session(state: State<'_, E2eeState>, session_id: String) {
+    state.forget_session(&session_id);
+}
+
+/// Unwraps DEKs for every session that has a wrapped DEK and no matching
+/// cached DEK, and returns every session that now has one. Neither the
+/// master secrets nor the DEKs leave Rust.
+#[tauri::command]
+pub fn e2ee_unwrap_session_deks(
+    state: State<'_, E2eeState>,
+    sessions: Vec<WrappedSessionDek>,
+) -> Vec<UnwrappedSession> {
+    sessions
+        .into_iter()
+        .filter_map(|session| {
+            let wrapped_dek = session.wrapped_dek?;
+            if !state.has_session_dek(&session.session_id, session.revision) {
+                state.unwrap_session_dek(&session.session_id, &wrapped_dek, session.revision);
+            }
+            state
+                .has_session_dek(&session.session_id, session.revision)
+                .then_some(UnwrappedSession {
+                    session_id: session.session_id,
+                    revision: session.revision,
+                })
+        })
+        .collect()
+}
+
+/// Decrypts a batch of events (a live event, or one backfill page) off the
+/// main thread. Each event gets its own outcome, so one that fails to decrypt
+/// does not hold back the rest of the page.
+#[tauri::command]
+pub async fn e2ee_decrypt_events(
+    state: State<'_, E2eeState>,
+    events: Vec<SessionEvent>,
+) -> Result<Vec<DecryptedEvent>> {
+    Ok(state.decrypt_events(events))
+}
+
+#[tauri::command]
+pub fn e2ee_encrypt_payload(
+    state: State<'_, E2eeState>,
+    session_id: String,
+    payload: Value,
+    revision: Option<u64>,
+    encryption_required: Option<bool>,
+) -> Result<Value> {
+    state.encrypt_for_session(&session_id, payload, revision, encryption_required)
+}
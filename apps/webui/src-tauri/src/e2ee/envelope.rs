@@ -0,0 +1,94 @@
+//! `{ t: "encrypted", c }` payload envelopes, byte-compatible with
+//! `packages/shared/src/crypto/envelope.ts`.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use crypto_secretbox::aead::{Aead, AeadCore, KeyInit, OsRng};
+use crypto_secretbox::XSalsa20Poly1305;
+use serde_json::{json, Value};
+use zeroize::Zeroizing;
+
+use super::keys::KEY_BYTES;
+use crate::error::{Error, Result};
+
+/// `crypto_secretbox_NONCEBYTES`
+const NONCE_BYTES: usize = 24;
+
+pub fn is_encrypted_payload(payload: &Value) -> bool {
+    payload.get("t").and_then(Value::as_str) == Some("encrypted")
+        && payload.get("c").is_some_and(Value::is_string)
+}
+
+pub fn encrypt_payload(payload: &Value, dek: &[u8; KEY_BYTES]) -> Result<Value> {
+    let nonce = XSalsa20Poly1305::generate_nonce(&mut OsRng);
+    seal(payload, &nonce.into(), dek)
+}
+
+fn seal(payload: &Value, nonce: &[u8; NONCE_BYTES], dek: &[u8; KEY_BYTES]) -> Result<Value> {
+    let plaintext = Zeroizing::new(serde_json::to_vec(payload)?);
+    let ciphertext = XSalsa20Poly1305::new(dek.into())
+        .encrypt(nonce.into(), plaintext.as_slice())
+        .map_err(|_| Error::EncryptFailed)?;
+    let mut combined = Vec::with_capacity(NONCE_BYTES + ciphertext.len());
+    combined.extend_from_slice(nonce);
+    combined.extend_from_slice(&ciphertext);
+    Ok(json!({ "t": "encrypted", "c": STANDARD.encode(combined) }))
+}
+
+/// Decrypts the `c` field of an encrypted envelope back into its JSON value.
+pub fn decrypt_payload(combined_base64: &str, dek: &[u8; KEY_BYTES]) -> Result<Value> {
+    let combined = STANDARD.decode(combined_base64)?;
+    if combined.len() < NONCE_BYTES {
+        return Err(Error::DecryptFailed);
+    }
+    let (nonce, ciphertext) = combined.split_at(NONCE_BYTES);
+    let plaintext = Zeroizing::new(
+        XSalsa20Poly1305::new(dek.into())
+            .decrypt(nonce.into(), ciphertext)
+            .map_err(|_| Error::DecryptFailed)?,
+    );
+    Ok(serde_json::from_slice(&plaintext)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::e2ee::keys::decode_key;
+    use crate::e2ee::keys::tests::vectors;
+
+    #[test]
+    fn decrypts_vector_envelope() {
+        let v = vectors();
+        let dek = decode_key(&v.dek).unwrap();
+        let expected: Value = serde_json::from_str(&v.payload_json).unwrap();
+        assert_eq!(decrypt_payload(&v.encrypted_c, &dek).unwrap(), expected);
+    }
+
+    #[test]
+    fn encrypts_vector_envelope_byte_for_byte() {
+        let v = vectors();
+        let dek = decode_key(&v.dek).unwrap();
+        let nonce: [u8; NONCE_BYTES] = STANDARD
+            .decode(&v.envelope_nonce)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let payload: Value = serde_json::from_str(&v.payload_json).unwrap();
+        let sealed = seal(&payload, &nonce, &dek).unwrap();
+        assert!(is_encrypted_payload(&sealed));
+        assert_eq!(sealed["c"], v.encrypted_c);
+    }
+
+    #[test]
+    fn round_trips_and_rejects_wrong_key() {
+        let dek = [1u8; KEY_BYTES];
+        let payload = json!({ "type": "agent_message_chunk", "content": { "text": "hi" } });
+        let sealed = encrypt_payload(&payload, &dek).unwrap();
+        let c = sealed["c"].as_str().unwrap();
+        assert_eq!(decrypt_payload(c, &dek).unwrap(), payload);
+        assert!(matches!(
+            decrypt_payload(c, &[2u8; KEY_BYTES]),
+            Err(Error::DecryptFailed)
+        ));
+    }
+}
@@ -0,0 +1,182 @@
+//! Key derivation and DEK unwrapping, byte-compatible with
+//! `packages/shared/src/crypto/keys.ts`.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use blake2::digest::consts::U32;
+use blake2::digest::Mac;
+use blake2::Blake2bMac;
+use crypto_box::SecretKey;
+use ed25519_dalek::SigningKey;
+use sha2::{Digest, Sha512};
+use zeroize::Zeroizing;
+
+use crate::error::{Error, Result};
+
+pub const KEY_BYTES: usize = 32;
+/// `crypto_box_SEALBYTES`: ephemeral public key + Poly1305 tag.
+const SEAL_OVERHEAD: usize = 48;
+
+const AUTH_SUBKEY_ID: u64 = 1;
+const AUTH_CONTEXT: &[u8; 8] = b"mobvauth";
+const CONTENT_SUBKEY_ID: u64 = 2;
+const CONTENT_CONTEXT: &[u8; 8] = b"mobvcont";
+
+pub type Key = Zeroizing<[u8; KEY_BYTES]>;
+
+/// Decodes a standard base64 string into a 32-byte key.
+pub fn decode_key(base64_key: &str) -> Result<Key> {
+    let bytes = Zeroizing::new(STANDARD.decode(base64_key)?);
+    if bytes.len() != KEY_BYTES {
+        return Err(Error::InvalidKeyLength {
+            expected: KEY_BYTES,
+            actual: bytes.len(),
+        });
+    }
+    let mut key = Zeroizing::new([0u8; KEY_BYTES]);
+    key.copy_from_slice(&bytes);
+    Ok(key)
+}
+
+/// Matches libsodium's `crypto_kdf_derive_from_key` for 32-byte subkeys:
+///   - salt = subkeyId as LE uint64 + 8 zero bytes
+///   - personalization = ctx (8 ASCII bytes) + 8 zero bytes
+///   - BLAKE2b keyed hash with empty message
+fn kdf_derive_from_key(subkey_id: u64, ctx: &[u8; 8], key: &[u8; KEY_BYTES]) -> Key {
+    let mut salt = [0u8; 16];
+    salt[..8].copy_from_slice(&subkey_id.to_le_bytes());
+    let mut personal = [0u8; 16];
+    personal[..8].copy_from_slice(ctx);
+
+    let mac = Blake2bMac::<U32>::new_with_salt_and_personal(key, &salt, &personal)
+        .expect("32-byte key, 16-byte salt and personalization are valid BLAKE2b parameters");
+    let mut subkey = Zeroizing::new([0u8; KEY_BYTES]);
+    subkey.copy_from_slice(&mac.finalize().into_bytes());
+    subkey
+}
+
+/// Public half of the Ed25519 auth key pair derived from a master secret.
+pub fn derive_auth_public_key(master_secret: &[u8; KEY_BYTES]) -> [u8; KEY_BYTES] {
+    let seed = kdf_derive_from_key(AUTH_SUBKEY_ID, AUTH_CONTEXT, master_secret);
+    SigningKey::from_bytes(&seed).verifying_key().to_bytes()
+}
+
+/// Short, non-secret identifier for a master secret: the first 8 base64
+/// characters of its auth public key, as shown in the paired devices list.
+pub fn fingerprint(master_secret: &[u8; KEY_BYTES]) -> String {
+    let mut encoded = STANDARD.encode(derive_auth_public_key(master_secret));
+    encoded.truncate(8);
+    encoded
+}
+
+pub struct ContentKeyPair {
+    pub secret_key: SecretKey,
+}
+
+/// Matches libsodium's `crypto_box_seed_keypair`: SHA-512(seed)[0..31] is
+/// used as the X25519 secret key.
+pub fn derive_content_key_pair(master_secret: &[u8; KEY_BYTES]) -> ContentKeyPair {
+    let seed = kdf_derive_from_key(CONTENT_SUBKEY_ID, CONTENT_CONTEXT, master_secret);
+    let hash = Zeroizing::new(Sha512::digest(seed.as_slice()));
+    let mut secret = Zeroizing::new([0u8; KEY_BYTES]);
+    secret.copy_from_slice(&hash[..KEY_BYTES]);
+    ContentKeyPair {
+        secret_key: SecretKey::from(*secret),
+    }
+}
+
+/// Opens a `crypto_box_seal` envelope holding a session DEK.
+pub fn unwrap_dek(wrapped_base64: &str, key_pair: &ContentKeyPair) -> Result<Key> {
+    let sealed = STANDARD.decode(wrapped_base64)?;
+    if sealed.len() < SEAL_OVERHEAD {
+        return Err(Error::UnwrapFailed);
+    }
+    let opened = Zeroizing::new(
+        key_pair
+            .secret_key
+            .unseal(&sealed)
+            .map_err(|_| Error::UnwrapFailed)?,
+    );
+    if opened.len() != KEY_BYTES {
+        return Err(Error::InvalidKeyLength {
+            expected: KEY_BYTES,
+            actual: opened.len(),
+        });
+    }
+    let mut dek = Zeroizing::new([0u8; KEY_BYTES]);
+    dek.copy_from_slice(&opened);
+    Ok(dek)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    pub(crate) struct Vectors {
+        pub master_secret: String,
+        pub auth_public_key: String,
+        pub fingerprint: String,
+        pub content_public_key: String,
+        pub content_secret_key: String,
+        pub dek: String,
+        pub wrapped_dek: String,
+        pub payload_json: String,
+        pub envelope_nonce: String,
+        pub encrypted_c: String,
+    }
+
+    pub(crate) fn vectors() -> Vectors {
+        serde_json::from_str(include_str!(
+            "../../../../../packages/shared/tests/fixtures/crypto-vectors.json"
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn derives_auth_public_key_and_fingerprint() {
+        let v = vectors();
+        let master = decode_key(&v.master_secret).unwrap();
+        assert_eq!(
+            STANDARD.encode(derive_auth_public_key(&master)),
+            v.auth_public_key
+        );
+        assert_eq!(fingerprint(&master), v.fingerprint);
+    }
+
+    #[test]
+    fn derives_content_key_pair() {
+        let v = vectors();
+        let master = decode_key(&v.master_secret).unwrap();
+        let key_pair = derive_content_key_pair(&master);
+        assert_eq!(
+            STANDARD.encode(key_pair.secret_key.public_key().as_bytes()),
+            v.content_public_key
+        );
+        assert_eq!(
+            STANDARD.encode(key_pair.secret_key.to_bytes()),
+            v.content_secret_key
+        );
+    }
+
+    #[test]
+    fn unwraps_sealed_dek() {
+        let v = vectors();
+        let master = decode_key(&v.master_secret).unwrap();
+        let key_pair = derive_content_key_pair(&master);
+        let dek = unwrap_dek(&v.wrapped_dek, &key_pair).unwrap();
+        assert_eq!(STANDARD.encode(*dek), v.dek);
+    }
+
+    #[test]
+    fn rejects_dek_sealed_for_another_key() {
+        let v = vectors();
+        let other = derive_content_key_pair(&[9u8; KEY_BYTES]);
+        assert!(matches!(
+            unwrap_dek(&v.wrapped_dek, &other),
+            Err(Error::UnwrapFailed)
+        ));
+    }
+}
@@ -0,0 +1,11 @@
+//! Native E2EE core. Mirrors `packages/shared/src/crypto` byte-for-byte so the
+//! webview can hand key material to Rust instead of keeping it in the JS heap.
+
+pub mod commands;
+mod envelope;
+mod keys;
+mod state;
+
+pub use envelope::{decrypt_payload, encrypt_payload, is_encrypted_payload};
+pub use keys::{decode_key, fingerprint, Key, KEY_BYTES};
+pub use state::E2eeState;
@@ -0,0 +1,298 @@
+//! Native counterpart of `E2EEManager` in `apps/webui/src/lib/e2ee.ts`.
+//! Master secrets, their content key pairs and the session DEKs stay in
+//! Rust memory; the webview only ever sees fingerprints and hands events
+//! and payloads over to be decrypted and encrypted here.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::Serialize;
+use serde_json::Value;
+
+use super::envelope::{decrypt_payload, encrypt_payload, is_encrypted_payload};
+use super::keys::{self, ContentKeyPair, Key, KEY_BYTES};
+use crate::error::{Error, Result};
+use crate::protocol::SessionEvent;
+
+/// One event of a decrypted batch. When decryption fails, `event` is the
+/// event as received and `error` says why, the same event the webview
+/// buffers until a matching key arrives.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptedEvent {
+    pub event: SessionEvent,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+struct SessionDek {
+    dek: Key,
+    revision: Option<u64>,
+}
+
+#[derive(Default)]
+struct Keyring {
+    /// Content key pairs keyed by master secret fingerprint.
+    content_key_pairs: HashMap<String, ContentKeyPair>,
+    /// Ed25519 auth public keys keyed by the same fingerprints.
+    auth_public_keys: HashMap<String, [u8; KEY_BYTES]>,
+    session_deks: HashMap<String, SessionDek>,
+    session_to_fingerprint: HashMap<String, String>,
+}
+
+impl Keyring {
+    fn session_dek(&self, session_id: &str, revision: Option<u64>) -> Option<&Key> {
+        let entry = self.session_deks.get(session_id)?;
+        match revision {
+            Some(revision) if entry.revision != Some(revision) => None,
+            _ => Some(&entry.dek),
+        }
+    }
+
+    fn try_unwrap(
+        &mut self,
+        session_id: &str,
+        wrapped_dek: &str,
+        fingerprint: &str,
+        revision: Option<u64>,
+    ) -> bool {
+        let Some(key_pair) = self.content_key_pairs.get(fingerprint) else {
+            return false;
+        };
+        let Ok(dek) = keys::unwrap_dek(wrapped_dek, key_pair) else {
+            return false;
+        };
+        self.session_deks
+            .insert(session_id.to_owned(), SessionDek { dek, revision });
+        self.session_to_fingerprint
+            .insert(session_id.to_owned(), fingerprint.to_owned());
+        true
+    }
+}
+
+#[derive(Default)]
+pub struct E2eeState {
+    keyring: Mutex<Keyring>,
+}
+
+impl E2eeState {
+    fn keyring(&self) -> std::sync::MutexGuard<'_, Keyring> {
+        self.keyring.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Derives and keeps the content key pair for a base64 master secret.
+    /// Returns its fingerprint.
+    pub fn add_secret(&self, base64_secret: &str) -> Result<String> {
+        let master = keys::decode_key(base64_secret)?;
+        let fingerprint = keys::fingerprint(&master);
+        let mut keyring = self.keyring();
+        if !keyring.content_key_pairs.contains_key(&fingerprint) {
+            keyring
+                .content_key_pairs
+                .insert(fingerprint.clone(), keys::derive_content_key_pair(&master));
+            keyring
+                .auth_public_keys
+                .insert(fingerprint.clone(), keys::derive_auth_public_key(&master));
+        }
+        Ok(fingerprint)
+    }
+
+    pub fn remove_secret(&self, fingerprint: &str) {
+        let mut keyring = self.keyring();
+        keyring.content_key_pairs.remove(fingerprint);
+        keyring.auth_public_keys.remove(fingerprint);
+        let orphaned: Vec<String> = keyring
+            .session_to_fingerprint
+            .iter()
+            .filter(|(_, fp)| fp.as_str() == fingerprint)
+            .map(|(session_id, _)| session_id.clone())
+            .collect();
+        for session_id in orphaned {
+            keyring.session_to_fingerprint.remove(&session_id);
+            keyring.session_deks.remove(&session_id);
+        }
+    }
+
+    /// Whether a CLI signing with this auth key holds one of the paired
+    /// master secrets.
+    pub fn is_paired_auth_key(&self, public_key: &[u8; KEY_BYTES]) -> bool {
+        self.keyring()
+            .auth_public_keys
+            .values()
+            .any(|paired| paired == public_key)
+    }
+
+    pub fn clear(&self) {
+        *self.keyring() = Keyring::default();
+    }
+
+    pub fn forget_session(&self, session_id: &str) {
+        let mut keyring = self.keyring();
+        keyring.session_deks.remove(session_id);
+        keyring.session_to_fingerprint.remove(session_id);
+    }
+
+    pub fn has_session_dek(&self, session_id: &str, revision: Option<u64>) -> bool {
+        self.keyring().session_dek(session_id, revision).is_some()
+    }
+
+    /// Tries the key that opened this session before, then every paired key.
+    pub fn unwrap_session_dek(
+        &self,
+        session_id: &str,
+        wrapped_dek: &str,
+        revision: Option<u64>,
+    ) -> bool {
+        let mut keyring = self.keyring();
+        if let Some(fingerprint) = keyring.session_to_fingerprint.get(session_id).cloned() {
+            if keyring.try_unwrap(session_id, wrapped_dek, &fingerprint, revision) {
+                return true;
+            }
+        }
+        let fingerprints: Vec<String> = keyring.content_key_pairs.keys().cloned().collect();
+        fingerprints
+            .iter()
+            .any(|fingerprint| keyring.try_unwrap(session_id, wrapped_dek, fingerprint, revision))
+    }
+
+    /// Replaces an encrypted payload with its plaintext when the matching
+    /// session DEK is available. Events without a DEK pass through unchanged.
+    pub fn decrypt_event(&self, mut event: SessionEvent) -> Result<SessionEvent> {
+        if !is_encrypted_payload(&event.payload) {
+            return Ok(event);
+        }
+        let keyring = self.keyring();
+        let Some(dek) = keyring.session_dek(&event.session_id, Some(event.revision)) else {
+            return Ok(event);
+        };
+        let combined = event.payload["c"].as_str().unwrap_or_default();
+        event.payload = decrypt_payload(combined, dek)?;
+        Ok(event)
+    }
+
+    /// Decrypts each event on its own; failures are reported per event.
+    pub fn decrypt_events(&self, events: Vec<SessionEvent>) -> Vec<DecryptedEvent> {
+        events
+            .into_iter()
+            .map(|event| match self.decrypt_event(event.clone()) {
+                Ok(event) => DecryptedEvent { event, error: None },
+                Err(err) => DecryptedEvent {
+                    event,
+                    error: Some(err.to_string()),
+                },
+            })
+            .collect()
+    }
+
+    pub fn encrypt_for_session(
+        &self,
+        session_id: &str,
+        payload: Value,
+        revision: Option<u64>,
+        encryption_required: Option<bool>,
+    ) -> Result<Value> {
+        if encryption_required == Some(false) {
+            return Ok(payload);
+        }
+        if encryption_required == Some(true) && revision.is_none() {
+            return Err(Error::MissingSessionKey);
+        }
+        let keyring = self.keyring();
+        match keyring.session_dek(session_id, revision) {
+            Some(dek) => encrypt_payload(&payload, dek),
+            None if encryption_required == Some(true)
+                || keyring.session_deks.contains_key(session_id) =>
+            {
+                Err(Error::MissingSessionKey)
+            }
+            None => Ok(payload),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::e2ee::keys::tests::vectors;
+    use serde_json::json;
+
+    fn event(session_id: &str, revision: u64, payload: Value) -> SessionEvent {
+        SessionEvent {
+            session_id: session_id.to_owned(),
+            machine_id: "machine-1".to_owned(),
+            incarnation_generation: None,
+            revision,
+            seq: 1,
+            kind: "user_message".to_owned(),
+            protocol_message_id: None,
+            created_at: "2026-01-01T00:00:00.000Z".to_owned(),
+            payload,
+        }
+    }
+
+    #[test]
+    fn decrypts_events_only_for_the_unwrapped_revision() {
+        let v = vectors();
+        let state = E2eeState::default();
+        assert_eq!(state.add_secret(&v.master_secret).unwrap(), v.fingerprint);
+        assert!(state.unwrap_session_dek("s1", &v.wrapped_dek, Some(2)));
+        assert!(state.has_session_dek("s1", Some(2)));
+        assert!(!state.has_session_dek("s1", Some(3)));
+
+        let encrypted = json!({ "t": "encrypted", "c": v.encrypted_c });
+        let decrypted = state
+            .decrypt_event(event("s1", 2, encrypted.clone()))
+            .unwrap();
+        assert_eq!(decrypted.payload["text"], "hello");
+        let stale = state.decrypt_event(event("s1", 3, encrypted)).unwrap();
+        assert!(is_encrypted_payload(&stale.payload));
+    }
+
+    #[test]
+    fn one_corrupt_event_does_not_fail_the_batch() {
+        let v = vectors();
+        let state = E2eeState::default();
+        state.add_secret(&v.master_secret).unwrap();
+        assert!(state.unwrap_session_dek("s1", &v.wrapped_dek, Some(2)));
+
+        let corrupt = json!({ "t": "encrypted", "c": "bm90IGNpcGhlcnRleHQ=" });
+        let good = json!({ "t": "encrypted", "c": v.encrypted_c });
+        let results =
+            state.decrypt_events(vec![event("s1", 2, corrupt.clone()), event("s1", 2, good)]);
+        assert_eq!(results.len(), 2);
+        assert!(results[0].error.is_some());
+        assert_eq!(results[0].event.payload, corrupt);
+        assert!(results[1].error.is_none());
+        assert_eq!(results[1].event.payload["text"], "hello");
+    }
+
+    #[test]
+    fn removing_a_secret_drops_its_session_deks() {
+        let v = vectors();
+        let state = E2eeState::default();
+        let fingerprint = state.add_secret(&v.master_secret).unwrap();
+        let auth_key = keys::decode_key(&v.auth_public_key).unwrap();
+        assert!(state.is_paired_auth_key(&auth_key));
+        assert!(state.unwrap_session_dek("s1", &v.wrapped_dek, None));
+        state.remove_secret(&fingerprint);
+        assert!(!state.is_paired_auth_key(&auth_key));
+        assert!(!state.has_session_dek("s1", None));
+        assert!(!state.unwrap_session_dek("s1", &v.wrapped_dek, None));
+    }
+
+    #[test]
+    fn refuses_plaintext_when_encryption_is_required() {
+        let state = E2eeState::default();
+        let payload = json!({ "prompt": [] });
+        assert_eq!(
+            state
+                .encrypt_for_session("s1", payload.clone(), Some(1), None)
+                .unwrap(),
+            payload
+        );
+        assert!(matches!(
+            state.encrypt_for_session("s1", payload, Some(1), Some(true)),
+            Err(Error::MissingSessionKey)
+        ));
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Serialize, Serializer};
+
+/// Errors returned from Tauri commands. Serialized as a plain message so the
+/// webview sees the same `Error.message` shape it gets from plugin commands.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Tauri(#[from] tauri::Error),
+    #[error(transparent)]
+    DeepLink(#[from] tauri_plugin_deep_link::Error),
+    #[cfg(not(target_os = "android"))]
+    #[error(transparent)]
+    Keyring(#[from] keyring::Error),
+    #[error(transparent)]
+    Http(#[from] tauri_plugin_http::reqwest::Error),
+    #[error(transparent)]
+    WebSocket(#[from] tokio_tungstenite::tungstenite::Error),
+    #[error("Invalid base64: {0}")]
+    Base64(#[from] base64::DecodeError),
+    #[error("Invalid JSON: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid key length: expected {expected} bytes, got {actual}")]
+    InvalidKeyLength { expected: usize, actual: usize },
+    #[error("Failed to unwrap DEK: decryption failed")]
+    UnwrapFailed,
+    #[error("Decryption failed: invalid ciphertext or key")]
+    DecryptFailed,
+    #[error("Encryption failed")]
+    EncryptFailed,
+    #[error(
+        "Cannot send without a matching session encryption key. Reload the session or pair the correct device key, then try again."
+    )]
+    MissingSessionKey,
+    #[error("Secret vault is locked")]
+    VaultLocked,
+    #[error("Incorrect vault passphrase")]
+    WrongPassphrase,
+    #[error("Secret vault error: {0}")]
+    Vault(String),
+    #[error("Invalid link: {0}")]
+    InvalidDeepLink(String),
+    #[error("Gateway error: {0}")]
+    Gateway(String),
+    #[error("Daemon error: {0}")]
+    Daemon(String),
+    #[error("Agent error: {0}")]
+    Agent(String),
+    #[error("Terminal error: {0}")]
+    Terminal(String),
+    #[error("Git error: {0}")]
+    Git(String),
+    #[error("Search error: {0}")]
+    Search(String),
+    #[error("Outline error: {0}")]
+    Outline(String),
+    #[error("Window error: {0}")]
+    Window(String),
+    #[error("Shortcut error: {0}")]
+    Shortcut(String),
+    #[error("Update error: {0}")]
+    Update(String),
+    #[error("Clipboard error: {0}")]
+    Clipboard(String),
+    #[error("Image error: {0}")]
+    Image(String),
+    #[error("Notification failed: {0}")]
+    Notification(String),
+    #[error("Database error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+}
+
+impl Serialize for Error {
+    fn serialize<S>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
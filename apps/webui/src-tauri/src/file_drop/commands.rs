@@ -0,0 +1,20 @@
+use tauri::ipc::Channel;
+use tauri::{State, Webview};
+
+use super::{FileDropEvent, FileDropState};
+
+/// Streams files dropped on this webview's window as prompt content
+/// blocks until it unlistens or navigates away. Returns the listener id.
+#[tauri::command]
+pub fn file_drop_listen(
+    webview: Webview,
+    file_drop: State<'_, FileDropState>,
+    on_event: Channel<FileDropEvent>,
+) -> u32 {
+    file_drop.listen(webview.label(), on_event)
+}
+
+#[tauri::command]
+pub fn file_drop_unlisten(file_drop: State<'_, FileDropState>, id: u32) {
+    file_drop.unlisten(id);
+}
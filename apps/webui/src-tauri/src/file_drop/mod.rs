@@ -0,0 +1,143 @@
+//! Files dragged from the OS onto a window. The webview never sees the
+//! paths of a native drop, so the window's drag-drop events are handled
+//! here: the files are read (see [`read`]) off the main thread and handed
+//! as prompt content blocks to the composer listening in that window.
+
+pub mod commands;
+mod read;
+
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+use serde::Serialize;
+use tauri::ipc::Channel;
+use tauri::{DragDropEvent, Manager, Runtime, Window};
+
+pub use self::read::{DropError, DroppedBlock};
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum FileDropEvent {
+    /// Files are being dragged over the window.
+    Hover { count: usize },
+    /// The drag left the window or was cancelled.
+    Leave,
+    Drop {
+        blocks: Vec<DroppedBlock>,
+        errors: Vec<DropError>,
+    },
+}
+
+struct Listener {
+    id: u32,
+    webview: String,
+    channel: Channel<FileDropEvent>,
+}
+
+#[derive(Default)]
+struct Inner {
+    listeners: Vec<Listener>,
+    next_listener_id: u32,
+}
+
+/// The composers taking drops. A window's drops go to its newest
+/// listener, so a composer that remounts takes over from the old one
+/// whatever order their unlisten arrives in.
+#[derive(Default)]
+pub struct FileDropState {
+    inner: Mutex<Inner>,
+}
+
+impl FileDropState {
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Returns the listener id.
+    pub fn listen(&self, webview: &str, channel: Channel<FileDropEvent>) -> u32 {
+        let mut inner = self.inner();
+        let id = inner.next_listener_id;
+        inner.next_listener_id += 1;
+        inner.listeners.push(Listener {
+            id,
+            webview: webview.to_owned(),
+            channel,
+        });
+        id
+    }
+
+    pub fn unlisten(&self, id: u32) {
+        self.inner().listeners.retain(|listener| listener.id != id);
+    }
+
+    /// Drops the channels of a webview that is navigating away.
+    pub fn forget_webview(&self, webview: &str) {
+        self.inner()
+            .listeners
+            .retain(|listener| listener.webview != webview);
+    }
+
+    fn channel(&self, webview: &str) -> Option<Channel<FileDropEvent>> {
+        self.inner()
+            .listeners
+            .iter()
+            .rev()
+            .find(|listener| listener.webview == webview)
+            .map(|listener| listener.channel.clone())
+    }
+}
+
+/// Forwards a window's drag-drop event to its composer. Drops on windows
+/// without one are ignored.
+pub fn on_drag_drop<R: Runtime>(window: &Window<R>, event: &DragDropEvent) {
+    let Some(state) = window.try_state::<FileDropState>() else {
+        return;
+    };
+    let Some(channel) = state.channel(window.label()) else {
+        return;
+    };
+    match event {
+        DragDropEvent::Enter { paths, .. } => {
+            let _ = channel.send(FileDropEvent::Hover { count: paths.len() });
+        }
+        DragDropEvent::Leave => {
+            let _ = channel.send(FileDropEvent::Leave);
+        }
+        DragDropEvent::Drop { paths, .. } => {
+            let paths: Vec<PathBuf> = paths.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                let dropped = read::read_dropped(&paths);
+                let _ = channel.send(FileDropEvent::Drop {
+                    blocks: dropped.blocks,
+                    errors: dropped.errors,
+                });
+            });
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn channel() -> Channel<FileDropEvent> {
+        Channel::new(|_| Ok(()))
+    }
+
+    #[test]
+    fn a_remounted_composer_keeps_its_drops_when_the_old_one_unlistens() {
+        let state = FileDropState::default();
+        let old = state.listen("main", channel());
+        let new = channel();
+        state.listen("main", new.clone());
+        state.listen("other", channel());
+
+        state.unlisten(old);
+        assert_eq!(state.channel("main").map(|c| c.id()), Some(new.id()));
+
+        state.forget_webview("main");
+        assert!(state.channel("main").is_none());
+        assert!(state.channel("other").is_some());
+    }
+}
@@ -0,0 +1,265 @@
+//! Turns dropped paths into prompt content blocks. Images keep their bytes
+//! for the webview to normalize like any other attachment; text becomes an
+//! embedded resource. Each file that cannot be used gets its own error so
+//! the rest of the drop still attaches.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use serde::Serialize;
+use url::Url;
+
+/// Files read from one drop; the rest are reported as skipped.
+pub const MAX_FILES: usize = 10;
+/// Images are downscaled in the webview, so the raw file may be large.
+pub const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+pub const MAX_TEXT_BYTES: u64 = 256 * 1024;
+/// Text across the whole drop, so a folder's worth of sources cannot swell
+/// one prompt.
+pub const MAX_TEXT_TOTAL_BYTES: u64 = 1024 * 1024;
+
+/// An ACP content block, in the shape the composer stores.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DroppedBlock {
+    #[serde(rename_all = "camelCase")]
+    Image {
+        data: String,
+        mime_type: &'static str,
+        uri: String,
+    },
+    Resource {
+        resource: TextResource,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextResource {
+    pub uri: String,
+    pub mime_type: &'static str,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DropError {
+    pub name: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize)]
+pub struct DroppedFiles {
+    pub blocks: Vec<DroppedBlock>,
+    pub errors: Vec<DropError>,
+}
+
+pub fn read_dropped(paths: &[impl AsRef<Path>]) -> DroppedFiles {
+    let mut dropped = DroppedFiles::default();
+    let mut text_budget = MAX_TEXT_TOTAL_BYTES;
+    for (index, path) in paths.iter().enumerate() {
+        let path = path.as_ref();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string());
+        let result = if index >= MAX_FILES {
+            Err(format!("Only {MAX_FILES} files can be dropped at once"))
+        } else {
+            read_file(path, &mut text_budget)
+        };
+        match result {
+            Ok(block) => dropped.blocks.push(block),
+            Err(message) => dropped.errors.push(DropError { name, message }),
+        }
+    }
+    dropped
+}
+
+fn read_file(path: &Path, text_budget: &mut u64) -> Result<DroppedBlock, String> {
+    let metadata = std::fs::metadata(path).map_err(|err| err.to_string())?;
+    if metadata.is_dir() {
+        return Err("Folders cannot be attached; drop the files instead".into());
+    }
+    let uri = Url::from_file_path(path)
+        .map_err(|_| "The path is not absolute".to_owned())?
+        .to_string();
+    let image_extension =
+        extension(path).is_some_and(|ext| IMAGE_EXTENSIONS.contains(&ext.as_str()));
+    let limit = if image_extension {
+        MAX_IMAGE_BYTES
+    } else {
+        MAX_TEXT_BYTES.min(*text_budget)
+    };
+    if metadata.len() > limit {
+        return Err(too_large(limit, image_extension, *text_budget));
+    }
+    // Read one byte past the limit in case the file grew since the stat.
+    let mut bytes = Vec::with_capacity(metadata.len() as usize);
+    File::open(path)
+        .and_then(|file| file.take(limit + 1).read_to_end(&mut bytes))
+        .map_err(|err| err.to_string())?;
+    if bytes.len() as u64 > limit {
+        return Err(too_large(limit, image_extension, *text_budget));
+    }
+
+    if let Some(mime_type) = sniff_image(&bytes) {
+        return Ok(DroppedBlock::Image {
+            data: STANDARD.encode(&bytes),
+            mime_type,
+            uri,
+        });
+    }
+    if image_extension {
+        return Err("Only PNG, JPEG, WebP and GIF images are supported".into());
+    }
+    if bytes.contains(&0) {
+        return Err("Binary files are not supported".into());
+    }
+    let text = String::from_utf8(bytes).map_err(|_| "The file is not UTF-8 text".to_owned())?;
+    *text_budget -= text.len() as u64;
+    Ok(DroppedBlock::Resource {
+        resource: TextResource {
+            uri,
+            mime_type: text_mime_type(path),
+            text,
+        },
+    })
+}
+
+fn too_large(limit: u64, image: bool, text_budget: u64) -> String {
+    if !image && text_budget < MAX_TEXT_BYTES {
+        return format!(
+            "Dropped text files may total {} KiB",
+            MAX_TEXT_TOTAL_BYTES / 1024
+        );
+    }
+    if image {
+        format!("Images must be {} MiB or smaller", limit / 1024 / 1024)
+    } else {
+        format!("Text files must be {} KiB or smaller", limit / 1024)
+    }
+}
+
+/// Extensions taken to mean an image, so unsupported formats get an error
+/// that says so rather than "binary file".
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "webp", "gif", "bmp", "tif", "tiff", "heic", "heif", "avif", "ico",
+];
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+}
+
+/// The prompt image type, from the file's magic bytes.
+fn sniff_image(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', ..] => Some("image/png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [b'G', b'I', b'F', b'8', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ => None,
+    }
+}
+
+fn text_mime_type(path: &Path) -> &'static str {
+    match extension(path).as_deref() {
+        Some("md" | "markdown") => "text/markdown",
+        Some("json") => "application/json",
+        Some("html" | "htm") => "text/html",
+        Some("css") => "text/css",
+        Some("csv") => "text/csv",
+        Some("xml" | "svg") => "application/xml",
+        Some("js" | "mjs" | "cjs") => "text/javascript",
+        Some("yaml" | "yml") => "application/yaml",
+        _ => "text/plain",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_images_and_text_and_reports_the_rest() {
+        let dir = std::env::temp_dir().join(format!("mobvibe-drop-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("folder")).unwrap();
+        let png = [&[0x89, b'P', b'N', b'G'][..], b"\r\n\x1a\n"].concat();
+        std::fs::write(dir.join("shot.png"), &png).unwrap();
+        std::fs::write(dir.join("notes.md"), "# Notes").unwrap();
+        std::fs::write(dir.join("photo.heic"), b"ftypheic").unwrap();
+        std::fs::write(dir.join("app.bin"), b"\x7fELF\0\0").unwrap();
+        std::fs::write(dir.join("big.txt"), vec![b'a'; MAX_TEXT_BYTES as usize + 1]).unwrap();
+
+        let names = [
+            "shot.png",
+            "notes.md",
+            "photo.heic",
+            "app.bin",
+            "big.txt",
+            "folder",
+        ];
+        let paths: Vec<_> = names.iter().map(|name| dir.join(name)).collect();
+        let dropped = read_dropped(&paths);
+
+        assert_eq!(
+            dropped.blocks,
+            vec![
+                DroppedBlock::Image {
+                    data: STANDARD.encode(&png),
+                    mime_type: "image/png",
+                    uri: Url::from_file_path(&paths[0]).unwrap().to_string(),
+                },
+                DroppedBlock::Resource {
+                    resource: TextResource {
+                        uri: Url::from_file_path(&paths[1]).unwrap().to_string(),
+                        mime_type: "text/markdown",
+                        text: "# Notes".into(),
+                    },
+                },
+            ]
+        );
+        let errors: Vec<_> = dropped
+            .errors
+            .iter()
+            .map(|error| (error.name.as_str(), error.message.as_str()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (
+                    "photo.heic",
+                    "Only PNG, JPEG, WebP and GIF images are supported"
+                ),
+                ("app.bin", "Binary files are not supported"),
+                ("big.txt", "Text files must be 256 KiB or smaller"),
+                (
+                    "folder",
+                    "Folders cannot be attached; drop the files instead"
+                ),
+            ]
+        );
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn serializes_acp_content_blocks() {
+        let block = DroppedBlock::Resource {
+            resource: TextResource {
+                uri: "file:///a.txt".into(),
+                mime_type: "text/plain",
+                text: "hi".into(),
+            },
+        };
+        assert_eq!(
+            serde_json::to_value(block).unwrap(),
+            serde_json::json!({
+                "type": "resource",
+                "resource": { "uri": "file:///a.txt", "mimeType": "text/plain", "text": "hi" }
+            })
+        );
+    }
+}
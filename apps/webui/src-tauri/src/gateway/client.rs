@@ -0,0 +1,128 @@
+//! Connection task: one WebSocket at a time, reconnecting with the same
+//! backoff `socket.ts` configured (1 s doubling up to 10 s, retried forever).
+
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::{mpsc, watch};
+use tokio_tungstenite::tungstenite::Message;
+
+use super::packet::{self, Packet};
+use super::{ConnectionState, GatewayConfig, GatewayState};
+use crate::error::{Error, Result};
+
+const INITIAL_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(10);
+/// Used until the Engine.IO handshake announces the real ping window.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(20);
+
+pub(super) async fn run<R: Runtime>(
+    app: AppHandle<R>,
+    mut config: watch::Receiver<Option<GatewayConfig>>,
+    mut outgoing: mpsc::UnboundedReceiver<String>,
+) {
+    let state = app.state::<GatewayState>();
+    let mut delay = INITIAL_DELAY;
+    let mut retrying = false;
+    loop {
+        let Some(current) = config.borrow_and_update().clone() else {
+            state.set_connection(ConnectionState::Idle, None);
+            if config.changed().await.is_err() {
+                return;
+            }
+            continue;
+        };
+
+        let connecting = if retrying {
+            ConnectionState::Reconnecting
+        } else {
+            ConnectionState::Connecting
+        };
+        state.set_connection(connecting, None);
+        let result = tokio::select! {
+            result = connect(&app, &current, &mut outgoing, &mut delay) => result,
+            changed = config.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+                delay = INITIAL_DELAY;
+                retrying = false;
+                continue;
+            }
+        };
+
+        let error = result.err().map(|err| err.to_string());
+        state.set_connection(ConnectionState::Reconnecting, error);
+        retrying = true;
+        tokio::select! {
+            _ = tokio::time::sleep(delay) => {}
+            changed = config.changed() => {
+                if changed.is_err() {
+                    return;
+                }
+                retrying = false;
+                delay = INITIAL_DELAY;
+                continue;
+            }
+        }
+        delay = (delay * 2).min(MAX_DELAY);
+    }
+}
+
+/// Runs one connection until it drops. Returns `Ok` when the gateway closed
+/// it cleanly.
+async fn connect<R: Runtime>(
+    app: &AppHandle<R>,
+    config: &GatewayConfig,
+    outgoing: &mut mpsc::UnboundedReceiver<String>,
+    delay: &mut Duration,
+) -> Result<()> {
+    let state = app.state::<GatewayState>();
+    let (socket, _) = tokio_tungstenite::connect_async(config.socket_url()?.as_str()).await?;
+    let (mut sink, mut stream) = socket.split();
+    let mut liveness = HANDSHAKE_TIMEOUT;
+    let mut connected = false;
+
+    loop {
+        tokio::select! {
+            message = tokio::time::timeout(liveness, stream.next()) => {
+                let message = message
+                    .map_err(|_| Error::Gateway("ping timeout".to_owned()))?;
+                let text = match message.transpose()? {
+                    None | Some(Message::Close(_)) => return Ok(()),
+                    Some(Message::Text(text)) => text,
+                    Some(_) => continue,
+                };
+                match packet::decode(&text) {
+                    Packet::Open(handshake) => {
+                        liveness =
+                            Duration::from_millis(handshake.ping_interval + handshake.ping_timeout);
+                        sink.send(Message::text(packet::connect(config.bearer_token())))
+                            .await?;
+                    }
+                    Packet::Ping => sink.send(Message::text(packet::PONG)).await?,
+                    Packet::Connected => {
+                        while outgoing.try_recv().is_ok() {}
+                        for frame in state.on_connected() {
+                            sink.send(Message::text(frame)).await?;
+                        }
+                        connected = true;
+                        *delay = INITIAL_DELAY;
+                        let app = app.clone();
+                        tauri::async_runtime::spawn(async move {
+                            let _ = app.state::<GatewayState>().refresh_sessions().await;
+                        });
+                    }
+                    Packet::ConnectError(message) => return Err(Error::Gateway(message)),
+                    Packet::Close | Packet::Disconnected => return Ok(()),
+                    Packet::Event { name, payload } => state.dispatch(&name, payload),
+                    Packet::Other => {}
+                }
+            }
+            Some(frame) = outgoing.recv(), if connected => {
+                sink.send(Message::text(frame)).await?;
+            }
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use serde_json::Value;
+use tauri::ipc::Channel;
+use tauri::{State, Webview};
+use zeroize::Zeroizing;
+
+use super::{
+    GatewayConfig, GatewayEvent, GatewaySnapshot, GatewayState, AUTH_TOKEN_KEY,
+    DEFAULT_GATEWAY_URL, GATEWAY_URL_KEY,
+};
+use crate::error::Result;
+use crate::settings::{self, SettingsStore};
+
+#[tauri::command]
+pub fn gateway_status(gateway: State<'_, GatewayState>) -> GatewaySnapshot {
+    gateway.snapshot()
+}
+
+/// Streams gateway events to the calling webview until it unlistens or
+/// navigates away. Returns the listener id.
+#[tauri::command]
+pub fn gateway_listen(
+    webview: Webview,
+    gateway: State<'_, GatewayState>,
+    on_event: Channel<GatewayEvent>,
+) -> u32 {
+    gateway.listen(webview.label(), on_event)
+}
+
+#[tauri::command]
+pub fn gateway_unlisten(gateway: State<'_, GatewayState>, id: u32) {
+    gateway.unlisten(id);
+}
+
+/// Persists the gateway URL and reconnects to it.
+#[tauri::command]
+pub fn gateway_set_url(
+    settings: State<'_, SettingsStore>,
+    gateway: State<'_, GatewayState>,
+    url: String,
+) -> Result<()> {
+    let config = GatewayConfig {
+        url,
+        token: gateway.config().and_then(|config| config.token),
+    };
+    config.socket_url()?;
+    settings.set(
+        settings::GATEWAY,
+        GATEWAY_URL_KEY,
+        &Value::String(config.url.clone()),
+    )?;
+    gateway.configure(Some(config));
+    Ok(())
+}
+
+/// Persists or clears the bearer token and reconnects with it.
+#[tauri::command]
+pub fn gateway_set_token(
+    settings: State<'_, SettingsStore>,
+    gateway: State<'_, GatewayState>,
+    token: Option<String>,
+) -> Result<()> {
+    let token = token.filter(|token| !token.is_empty()).map(Zeroizing::new);
+    match &token {
+        Some(token) => settings.set(
+            settings::AUTH,
+            AUTH_TOKEN_KEY,
+            &Value::String(token.to_string()),
+        )?,
+        None => settings.delete(settings::AUTH, AUTH_TOKEN_KEY)?,
+    }
+    let url = gateway
+        .config()
+        .map_or_else(|| DEFAULT_GATEWAY_URL.to_owned(), |config| config.url);
+    gateway.configure(Some(GatewayConfig { url, token }));
+    Ok(())
+}
+
+/// Closes the connection until the URL or token is set again.
+#[tauri::command]
+pub fn gateway_disconnect(gateway: State<'_, GatewayState>) {
+    gateway.configure(None);
+}
+
+#[tauri::command]
+pub fn gateway_subscribe_session(gateway: State<'_, GatewayState>, session_id: String) {
+    gateway.subscribe_session(&session_id);
+}
+
+#[tauri::command]
+pub fn gateway_unsubscribe_session(gateway: State<'_, GatewayState>, session_id: String) {
+    gateway.unsubscribe_session(&session_id);
+}
@@ -0,0 +1,187 @@
+//! Authenticated REST calls to the gateway for actions taken outside the
+//! webview, with the same instance-affinity handling as `api.ts`.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde_json::Value;
+use tauri_plugin_http::reqwest::header::CONTENT_TYPE;
+use tauri_plugin_http::reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
+
+use super::GatewayConfig;
+use crate::error::{Error, Result};
+
+const OWNER_ROUTING_PATH: &str = "/acp/routing";
+const OWNER_RESPONSE_HEADER: &str = "x-mobvibe-instance-id";
+const FORCE_INSTANCE_HEADER: &str = "fly-force-instance-id";
+const INSTANCE_AFFINITY_CHANGED_CODE: &str = "INSTANCE_AFFINITY_CHANGED";
+const OWNER_RESOLUTION_TIMEOUT: Duration = Duration::from_secs(3);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Gateway instance that owns this user, per gateway URL.
+struct Owner {
+    url: String,
+    instance_id: String,
+}
+
+#[derive(Default)]
+pub(super) struct GatewayHttp {
+    client: Client,
+    owner: Mutex<Option<Owner>>,
+}
+
+impl GatewayHttp {
+    pub(super) async fn request(
+        &self,
+        config: &GatewayConfig,
+        method: Method,
+        path: &str,
+        body: Option<&Value>,
+    ) -> Result<Value> {
+        let owner = match self.owner(&config.url) {
+            Some(owner) => Some(owner),
+            None if is_owner_routed(path) => self.resolve_owner(config).await,
+            None => None,
+        };
+        let response = self.send(config, &method, path, body, owner).await?;
+        let response = match self.check_response(config, response).await {
+            Err(Failure::AffinityChanged(owner)) if method == Method::GET => {
+                let response = self.send(config, &method, path, body, Some(owner)).await?;
+                self.check_response(config, response).await
+            }
+            other => other,
+        };
+        match response {
+            Ok(response)
+                if matches!(
+                    response.status(),
+                    StatusCode::NO_CONTENT | StatusCode::RESET_CONTENT
+                ) =>
+            {
+                Ok(Value::Null)
+            }
+            Ok(response) => Ok(serde_json::from_slice(&response.bytes().await?)?),
+            Err(Failure::AffinityChanged(_)) => {
+                Err(Error::Gateway(INSTANCE_AFFINITY_CHANGED_CODE.to_owned()))
+            }
+            Err(Failure::Error(err)) => Err(err),
+        }
+    }
+
+    async fn send(
+        &self,
+        config: &GatewayConfig,
+        method: &Method,
+        path: &str,
+        body: Option<&Value>,
+        owner: Option<String>,
+    ) -> Result<Response> {
+        let mut request = self
+            .authorized(config, method.clone(), path)
+            .timeout(REQUEST_TIMEOUT);
+        if let Some(owner) = owner {
+            request = request.header(FORCE_INSTANCE_HEADER, owner);
+        }
+        if let Some(body) = body {
+            request = request
+                .header(CONTENT_TYPE, "application/json")
+                .body(serde_json::to_vec(body)?);
+        }
+        Ok(request.send().await?)
+    }
+
+    fn authorized(&self, config: &GatewayConfig, method: Method, path: &str) -> RequestBuilder {
+        let url = format!("{}{path}", config.url.trim_end_matches('/'));
+        let request = self.client.request(method, url);
+        match config.bearer_token() {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+    }
+
+    /// Caches the advertised owner and splits failures into an affinity
+    /// change (retryable for idempotent requests) and everything else.
+    async fn check_response(
+        &self,
+        config: &GatewayConfig,
+        response: Response,
+    ) -> std::result::Result<Response, Failure> {
+        let advertised = advertised_owner(&response);
+        if let Some(owner) = &advertised {
+            self.set_owner(&config.url, owner);
+        }
+        if response.status().is_success() {
+            return Ok(response);
+        }
+        let status = response.status();
+        let body: Value = match response.bytes().await {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_default(),
+            Err(_) => Value::Null,
+        };
+        let error = &body["error"];
+        match (error["code"].as_str(), advertised) {
+            (Some(INSTANCE_AFFINITY_CHANGED_CODE), Some(owner)) => {
+                Err(Failure::AffinityChanged(owner))
+            }
+            _ => {
+                let message = error["message"]
+                    .as_str()
+                    .or(error.as_str())
+                    .map_or_else(|| status.to_string(), str::to_owned);
+                Err(Failure::Error(Error::Gateway(message)))
+            }
+        }
+    }
+
+    async fn resolve_owner(&self, config: &GatewayConfig) -> Option<String> {
+        let response = self
+            .authorized(config, Method::GET, OWNER_ROUTING_PATH)
+            .timeout(OWNER_RESOLUTION_TIMEOUT)
+            .send()
+            .await
+            .ok()?;
+        let owner = advertised_owner(&response)?;
+        self.set_owner(&config.url, &owner);
+        Some(owner)
+    }
+
+    fn owner(&self, url: &str) -> Option<String> {
+        let owner = self.owner.lock().unwrap_or_else(|e| e.into_inner());
+        owner
+            .as_ref()
+            .filter(|owner| owner.url == url)
+            .map(|owner| owner.instance_id.clone())
+    }
+
+    fn set_owner(&self, url: &str, instance_id: &str) {
+        *self.owner.lock().unwrap_or_else(|e| e.into_inner()) = Some(Owner {
+            url: url.to_owned(),
+            instance_id: instance_id.to_owned(),
+        });
+    }
+}
+
+enum Failure {
+    AffinityChanged(String),
+    Error(Error),
+}
+
+fn is_owner_routed(path: &str) -> bool {
+    path != OWNER_ROUTING_PATH
+        && (path == "/acp"
+            || path.starts_with("/acp/")
+            || path == "/fs"
+            || path.starts_with("/fs/")
+            || path == "/api/machines"
+            || path.starts_with("/api/machines?"))
+}
+
+fn advertised_owner(response: &Response) -> Option<String> {
+    let value = response
+        .headers()
+        .get(OWNER_RESPONSE_HEADER)?
+        .to_str()
+        .ok()?;
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_owned())
+}
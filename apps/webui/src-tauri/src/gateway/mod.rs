@@ -0,0 +1,513 @@
+//! Native client for the gateway's `/webui` Socket.IO namespace.
+//!
+//! The connection is owned by a Rust task rather than `socket.ts`, so it
+//! survives webview reloads, hidden windows and suspended JS contexts.
+//! Events are fanned out to every webview that called `gateway_listen`, and
+//! the latest `cli:status` per machine plus open permission requests are kept
+//! so a freshly loaded page can catch up. Native consumers such as the tray
+//! follow the same stream through [`GatewayState::events`].
+
+mod client;
+pub mod commands;
+mod http;
+mod packet;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Mutex, MutexGuard};
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_http::reqwest::Method;
+use tokio::sync::{broadcast, mpsc, watch};
+use zeroize::Zeroizing;
+
+use self::http::GatewayHttp;
+use crate::error::{Error, Result};
+use crate::protocol::{
+    CliStatusPayload, PermissionDecisionPayload, PermissionRequestPayload, SessionEvent,
+    SessionSummary, SessionsChangedPayload, SessionsResponse,
+};
+use crate::settings::{self, SettingsStore};
+
+/// Settings key shared with `gateway-config.ts`.
+const GATEWAY_URL_KEY: &str = "gatewayUrl";
+/// Settings key shared with `auth-token.ts`.
+const AUTH_TOKEN_KEY: &str = "bearerToken";
+const DEFAULT_GATEWAY_URL: &str = "http://localhost:3005";
+/// Native consumers only care about the latest state, so a small buffer is
+/// enough; a lagging receiver just re-reads the snapshot.
+const EVENT_BUFFER: usize = 64;
+
+#[derive(Clone)]
+pub struct GatewayConfig {
+    pub url: String,
+    pub token: Option<Zeroizing<String>>,
+}
+
+impl GatewayConfig {
+    /// Loads the URL and bearer token the webview persisted. Returns `None`
+    /// if neither was ever set, so a fresh install stays offline.
+    pub fn load(settings: &SettingsStore) -> Result<Option<Self>> {
+        let url = settings.string(settings::GATEWAY, GATEWAY_URL_KEY)?;
+        let token = settings.string(settings::AUTH, AUTH_TOKEN_KEY)?;
+        if url.is_none() && token.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            url: url.unwrap_or_else(|| DEFAULT_GATEWAY_URL.to_owned()),
+            token: token.map(Zeroizing::new),
+        }))
+    }
+
+    /// The token to present. The app's own local gateway wants its launch
+    /// token rather than the account's.
+    fn bearer_token(&self) -> Option<&str> {
+        #[cfg(not(any(target_os = "android", target_os = "ios")))]
+        if let Some(token) = crate::local_gateway::token_for(&self.url) {
+            return Some(token);
+        }
+        self.token.as_ref().map(|token| token.as_str())
+    }
+
+    /// WebSocket URL for the Engine.IO endpoint. The token is mirrored into
+    /// the query, as `socket.ts` does, for instance affinity at the edge.
+    fn socket_url(&self) -> Result<url::Url> {
+        let mut url = url::Url::parse(&self.url)
+            .map_err(|err| Error::Gateway(format!("invalid gateway URL: {err}")))?;
+        let scheme = match url.scheme() {
+            "http" => "ws",
+            "https" => "wss",
+            other => {
+                return Err(Error::Gateway(format!(
+                    "unsupported gateway URL scheme: {other}"
+                )))
+            }
+        };
+        let _ = url.set_scheme(scheme);
+        url.set_path("/socket.io/");
+        url.set_fragment(None);
+        {
+            let mut query = url.query_pairs_mut();
+            query.clear();
+            query
+                .append_pair("EIO", "4")
+                .append_pair("transport", "websocket");
+            if let Some(token) = self.bearer_token() {
+                query.append_pair("bearerToken", token);
+            }
+        }
+        Ok(url)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionState {
+    Idle,
+    Connecting,
+    Connected,
+    Reconnecting,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionStatus {
+    pub state: ConnectionState,
+    pub error: Option<String>,
+}
+
+/// Everything the gateway pushes to the webui, under the event names of
+/// `GatewayToWebuiEvents`, plus native connection status updates.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "event", content = "payload")]
+pub enum GatewayEvent {
+    #[serde(rename = "session:event")]
+    SessionEvent(SessionEvent),
+    #[serde(rename = "session:attached")]
+    SessionAttached(Value),
+    #[serde(rename = "session:detached")]
+    SessionDetached(Value),
+    #[serde(rename = "permission:request")]
+    PermissionRequest(PermissionRequestPayload),
+    #[serde(rename = "permission:result")]
+    PermissionResult(PermissionDecisionPayload),
+    #[serde(rename = "cli:status")]
+    CliStatus(CliStatusPayload),
+    #[serde(rename = "sessions:changed")]
+    SessionsChanged(SessionsChangedPayload),
+    #[serde(rename = "agent-teams:changed")]
+    AgentTeamsChanged(Value),
+    #[serde(rename = "connection", skip_deserializing)]
+    Connection(ConnectionStatus),
+}
+
+impl GatewayEvent {
+    /// Returns `None` for events outside the webui contract, or with a
+    /// payload that does not match it.
+    fn decode(name: &str, payload: Value) -> Option<Self> {
+        serde_json::from_value(json!({ "event": name, "payload": payload })).ok()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GatewaySnapshot {
+    pub url: Option<String>,
+    pub has_token: bool,
+    pub connection: ConnectionStatus,
+    pub machines: Vec<CliStatusPayload>,
+    pub pending_permissions: Vec<PermissionRequestPayload>,
+    pub sessions: Vec<SessionSummary>,
+    pub running_sessions: Vec<String>,
+    pub subscribed_sessions: Vec<String>,
+}
+
+struct Listener {
+    id: u32,
+    webview: String,
+    channel: Channel<GatewayEvent>,
+}
+
+struct Inner {
+    connection: ConnectionStatus,
+    subscriptions: BTreeSet<String>,
+    machines: BTreeMap<String, CliStatusPayload>,
+    permissions: Vec<PermissionRequestPayload>,
+    sessions: BTreeMap<String, SessionSummary>,
+    /// Sessions with a turn in flight, as seen on `session:event`.
+    running: BTreeSet<String>,
+    listeners: Vec<Listener>,
+    next_listener_id: u32,
+    events: broadcast::Sender<GatewayEvent>,
+}
+
+impl Inner {
+    fn emit(&mut self, event: &GatewayEvent) {
+        self.listeners
+            .retain(|listener| listener.channel.send(event.clone()).is_ok());
+        let _ = self.events.send(event.clone());
+    }
+
+    fn apply(&mut self, event: &GatewayEvent) {
+        match event {
+            GatewayEvent::SessionEvent(event) => match event.kind.as_str() {
+                "turn_end" | "session_error" => {
+                    self.running.remove(&event.session_id);
+                }
+                "user_message"
+                | "agent_message_chunk"
+                | "agent_thought_chunk"
+                | "tool_call"
+                | "tool_call_update"
+                | "permission_request" => {
+                    self.running.insert(event.session_id.clone());
+                }
+                _ => {}
+            },
+            GatewayEvent::SessionDetached(payload) => {
+                if let Some(session_id) = payload["sessionId"].as_str() {
+                    self.running.remove(session_id);
+                }
+            }
+            GatewayEvent::CliStatus(status) => {
+                self.machines
+                    .insert(status.machine_id.clone(), status.clone());
+            }
+            GatewayEvent::PermissionRequest(request) => {
+                self.permissions.retain(|pending| {
+                    (&pending.session_id, &pending.request_id)
+                        != (&request.session_id, &request.request_id)
+                });
+                self.permissions.push(request.clone());
+            }
+            GatewayEvent::PermissionResult(result) => {
+                self.permissions.retain(|pending| {
+                    (&pending.session_id, &pending.request_id)
+                        != (&result.session_id, &result.request_id)
+                });
+            }
+            GatewayEvent::SessionsChanged(changed) => {
+                for session in changed.added.iter().chain(&changed.updated) {
+                    self.sessions
+                        .insert(session.session_id.clone(), session.clone());
+                }
+                for session_id in &changed.removed {
+                    self.sessions.remove(session_id);
+                    self.running.remove(session_id);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+pub struct GatewayState {
+    inner: Mutex<Inner>,
+    config: watch::Sender<Option<GatewayConfig>>,
+    outgoing: mpsc::UnboundedSender<String>,
+    http: GatewayHttp,
+}
+
+impl GatewayState {
+    /// Manages the state and spawns the connection task.
+    pub fn start<R: Runtime>(app: &AppHandle<R>, config: Option<GatewayConfig>) {
+        let (config_tx, config_rx) = watch::channel(config);
+        let (outgoing_tx, outgoing_rx) = mpsc::unbounded_channel();
+        app.manage(Self {
+            inner: Mutex::new(Inner {
+                connection: ConnectionStatus {
+                    state: ConnectionState::Idle,
+                    error: None,
+                },
+                subscriptions: BTreeSet::new(),
+                machines: BTreeMap::new(),
+                permissions: Vec::new(),
+                sessions: BTreeMap::new(),
+                running: BTreeSet::new(),
+                listeners: Vec::new(),
+                next_listener_id: 0,
+                events: broadcast::channel(EVENT_BUFFER).0,
+            }),
+            config: config_tx,
+            outgoing: outgoing_tx,
+            http: GatewayHttp::default(),
+        });
+        tauri::async_runtime::spawn(client::run(app.clone(), config_rx, outgoing_rx));
+    }
+
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn config(&self) -> Option<GatewayConfig> {
+        self.config.borrow().clone()
+    }
+
+    /// Replaces the connection settings and reconnects. `None` disconnects.
+    pub fn configure(&self, config: Option<GatewayConfig>) {
+        self.config.send_replace(config);
+    }
+
+    /// Every event delivered to webviews, plus a synthetic
+    /// `sessions:changed` after the session list is fetched on connect.
+    pub fn events(&self) -> broadcast::Receiver<GatewayEvent> {
+        self.inner().events.subscribe()
+    }
+
+    pub fn snapshot(&self) -> GatewaySnapshot {
+        let config = self.config();
+        let inner = self.inner();
+        GatewaySnapshot {
+            url: config.as_ref().map(|config| config.url.clone()),
+            has_token: config.is_some_and(|config| config.token.is_some()),
+            connection: inner.connection.clone(),
+            machines: inner.machines.values().cloned().collect(),
+            pending_permissions: inner.permissions.clone(),
+            sessions: inner.sessions.values().cloned().collect(),
+            running_sessions: inner.running.iter().cloned().collect(),
+            subscribed_sessions: inner.subscriptions.iter().cloned().collect(),
+        }
+    }
+
+    /// Registers a webview channel and replays the current connection status,
+    /// machine statuses and open permission requests to it.
+    pub fn listen(&self, webview: &str, channel: Channel<GatewayEvent>) -> u32 {
+        let mut inner = self.inner();
+        let replay = std::iter::once(GatewayEvent::Connection(inner.connection.clone()))
+            .chain(
+                inner
+                    .machines
+                    .values()
+                    .cloned()
+                    .map(GatewayEvent::CliStatus),
+            )
+            .chain(
+                inner
+                    .permissions
+                    .iter()
+                    .cloned()
+                    .map(GatewayEvent::PermissionRequest),
+            );
+        for event in replay {
+            let _ = channel.send(event);
+        }
+        let id = inner.next_listener_id;
+        inner.next_listener_id += 1;
+        inner.listeners.push(Listener {
+            id,
+            webview: webview.to_owned(),
+            channel,
+        });
+        id
+    }
+
+    pub fn unlisten(&self, id: u32) {
+        self.inner().listeners.retain(|listener| listener.id != id);
+    }
+
+    /// Drops the channels of a webview that is navigating away; their JS
+    /// callbacks are gone.
+    pub fn forget_webview(&self, webview: &str) {
+        self.inner()
+            .listeners
+            .retain(|listener| listener.webview != webview);
+    }
+
+    pub fn subscribe_session(&self, session_id: &str) {
+        let mut inner = self.inner();
+        if inner.subscriptions.insert(session_id.to_owned()) {
+            self.send_if_connected(&inner, "subscribe:session", session_id);
+        }
+    }
+
+    pub fn unsubscribe_session(&self, session_id: &str) {
+        let mut inner = self.inner();
+        if inner.subscriptions.remove(session_id) {
+            self.send_if_connected(&inner, "unsubscribe:session", session_id);
+        }
+    }
+
+    /// Frames queued while disconnected are discarded on connect, because
+    /// [`Self::on_connected`] resubscribes from `subscriptions` anyway.
+    fn send_if_connected(&self, inner: &Inner, event: &str, session_id: &str) {
+        if inner.connection.state == ConnectionState::Connected {
+            let _ = self.outgoing.send(session_frame(event, session_id));
+        }
+    }
+
+    fn set_connection(&self, state: ConnectionState, error: Option<String>) {
+        let mut inner = self.inner();
+        inner.connection = ConnectionStatus { state, error };
+        let event = GatewayEvent::Connection(inner.connection.clone());
+        inner.emit(&event);
+    }
+
+    /// Marks the namespace connected and returns the frames that restore the
+    /// session subscriptions. Machine statuses are dropped because the
+    /// gateway re-sends `cli:status` for every connected CLI on connect.
+    fn on_connected(&self) -> Vec<String> {
+        let mut inner = self.inner();
+        inner.machines.clear();
+        inner.connection = ConnectionStatus {
+            state: ConnectionState::Connected,
+            error: None,
+        };
+        let event = GatewayEvent::Connection(inner.connection.clone());
+        inner.emit(&event);
+        inner
+            .subscriptions
+            .iter()
+            .map(|session_id| session_frame("subscribe:session", session_id))
+            .collect()
+    }
+
+    fn dispatch(&self, name: &str, payload: Value) {
+        let Some(event) = GatewayEvent::decode(name, payload) else {
+            return;
+        };
+        let mut inner = self.inner();
+        inner.apply(&event);
+        inner.emit(&event);
+    }
+
+    /// Replaces the session list with the gateway's. Only native consumers
+    /// are told; the webview fetches its own list.
+    async fn refresh_sessions(&self) -> Result<()> {
+        let response = self.request(Method::GET, "/acp/sessions", None).await?;
+        let SessionsResponse { sessions } = serde_json::from_value(response)?;
+        let mut inner = self.inner();
+        // Sessions of machines that went offline drop out of the listing
+        // without being removed, so prune them here instead of announcing a
+        // removal that would also evict them from the offline cache.
+        inner
+            .sessions
+            .retain(|id, _| sessions.iter().any(|session| &session.session_id == id));
+        let event = GatewayEvent::SessionsChanged(SessionsChangedPayload {
+            added: sessions,
+            updated: Vec::new(),
+            removed: Vec::new(),
+            extra: Default::default(),
+        });
+        inner.apply(&event);
+        let _ = inner.events.send(event);
+        Ok(())
+    }
+
+    /// Answers a permission request the same way the webview's permission
+    /// card does.
+    pub async fn respond_to_permission(&self, decision: &PermissionDecisionPayload) -> Result<()> {
+        let body = serde_json::to_value(decision)?;
+        self.request(Method::POST, "/acp/permission/decision", Some(&body))
+            .await?;
+        Ok(())
+    }
+
+    pub async fn cancel_session(&self, session_id: &str) -> Result<()> {
+        let body = json!({ "sessionId": session_id });
+        self.request(Method::POST, "/acp/session/cancel", Some(&body))
+            .await?;
+        Ok(())
+    }
+
+    async fn request(&self, method: Method, path: &str, body: Option<&Value>) -> Result<Value> {
+        let config = self
+            .config()
+            .ok_or_else(|| Error::Gateway("not configured".to_owned()))?;
+        self.http.request(&config, method, path, body).await
+    }
+}
+
+fn session_frame(event: &str, session_id: &str) -> String {
+    packet::event(event, &json!({ "sessionId": session_id }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_the_engine_io_socket_url() {
+        let config = GatewayConfig {
+            url: "https://gateway.example.com/base/".to_owned(),
+            token: Some(Zeroizing::new("a b".to_owned())),
+        };
+        assert_eq!(
+            config.socket_url().unwrap().as_str(),
+            "wss://gateway.example.com/socket.io/?EIO=4&transport=websocket&bearerToken=a+b"
+        );
+
+        let local = GatewayConfig {
+            url: DEFAULT_GATEWAY_URL.to_owned(),
+            token: None,
+        };
+        assert_eq!(
+            local.socket_url().unwrap().as_str(),
+            "ws://localhost:3005/socket.io/?EIO=4&transport=websocket"
+        );
+
+        let file = GatewayConfig {
+            url: "file:///tmp".to_owned(),
+            token: None,
+        };
+        assert!(file.socket_url().is_err());
+    }
+
+    #[test]
+    fn decodes_only_webui_events() {
+        let status = GatewayEvent::decode(
+            "cli:status",
+            json!({ "machineId": "m1", "connected": true, "userId": "u1" }),
+        );
+        let Some(GatewayEvent::CliStatus(status)) = status else {
+            panic!("expected cli:status");
+        };
+        assert_eq!(status.machine_id, "m1");
+        assert_eq!(status.extra["userId"], "u1");
+
+        assert!(GatewayEvent::decode("subscription:error", json!({})).is_none());
+        assert!(GatewayEvent::decode("connection", json!({ "state": "idle" })).is_none());
+        assert!(GatewayEvent::decode("cli:status", json!({ "connected": true })).is_none());
+    }
+}
@@ -0,0 +1,156 @@
+//! The slice of Engine.IO v4 / Socket.IO v5 framing the `/webui` namespace
+//! needs over a bare WebSocket: text packets only, no acks, no binary
+//! attachments.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+pub const NAMESPACE: &str = "/webui";
+/// Engine.IO pong, sent in reply to every server ping.
+pub const PONG: &str = "3";
+
+/// Engine.IO handshake data.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Handshake {
+    pub ping_interval: u64,
+    pub ping_timeout: u64,
+}
+
+#[derive(Debug)]
+pub enum Packet {
+    Open(Handshake),
+    Close,
+    Ping,
+    Connected,
+    ConnectError(String),
+    Disconnected,
+    Event {
+        name: String,
+        payload: Value,
+    },
+    /// Anything this client does not act on.
+    Other,
+}
+
+pub fn decode(text: &str) -> Packet {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some('0') => serde_json::from_str(chars.as_str()).map_or(Packet::Other, Packet::Open),
+        Some('1') => Packet::Close,
+        Some('2') => Packet::Ping,
+        Some('4') => decode_socket(chars.as_str()),
+        _ => Packet::Other,
+    }
+}
+
+fn decode_socket(text: &str) -> Packet {
+    let mut chars = text.chars();
+    let kind = chars.next();
+    let Some(data) = strip_namespace(chars.as_str()) else {
+        return Packet::Other;
+    };
+    match kind {
+        Some('0') => Packet::Connected,
+        Some('1') => Packet::Disconnected,
+        Some('2') => decode_event(data),
+        Some('4') => {
+            let message = serde_json::from_str::<Value>(data)
+                .ok()
+                .and_then(|value| value.get("message")?.as_str().map(str::to_owned))
+                .unwrap_or_else(|| "connection refused".to_owned());
+            Packet::ConnectError(message)
+        }
+        _ => Packet::Other,
+    }
+}
+
+/// Returns the packet data if it is addressed to [`NAMESPACE`].
+fn strip_namespace(text: &str) -> Option<&str> {
+    let rest = text.strip_prefix(NAMESPACE)?;
+    if rest.is_empty() {
+        return Some(rest);
+    }
+    let rest = rest.strip_prefix(',')?;
+    // Skip an ack id if the server asked for one; acks are not supported.
+    Some(rest.trim_start_matches(|c: char| c.is_ascii_digit()))
+}
+
+fn decode_event(data: &str) -> Packet {
+    let Ok(Value::Array(mut args)) = serde_json::from_str(data) else {
+        return Packet::Other;
+    };
+    if args.is_empty() {
+        return Packet::Other;
+    }
+    let Value::String(name) = args.remove(0) else {
+        return Packet::Other;
+    };
+    let payload = if args.is_empty() {
+        Value::Null
+    } else {
+        args.swap_remove(0)
+    };
+    Packet::Event { name, payload }
+}
+
+/// Namespace connect, carrying the same `auth` object as `socket.ts`.
+pub fn connect(token: Option<&str>) -> String {
+    format!("40{NAMESPACE},{}", json!({ "token": token }))
+}
+
+pub fn event(name: &str, payload: &Value) -> String {
+    format!("42{NAMESPACE},{}", json!([name, payload]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_engine_packets() {
+        let open = r#"0{"sid":"abc","upgrades":[],"pingInterval":25000,"pingTimeout":20000,"maxPayload":1000000}"#;
+        let Packet::Open(handshake) = decode(open) else {
+            panic!("expected open packet");
+        };
+        assert_eq!(handshake.ping_interval, 25000);
+        assert_eq!(handshake.ping_timeout, 20000);
+        assert!(matches!(decode("2"), Packet::Ping));
+        assert!(matches!(decode("1"), Packet::Close));
+    }
+
+    #[test]
+    fn decodes_namespace_packets() {
+        assert!(matches!(
+            decode(r#"40/webui,{"sid":"x"}"#),
+            Packet::Connected
+        ));
+        assert!(matches!(decode("41/webui,"), Packet::Disconnected));
+        let Packet::ConnectError(message) = decode(r#"44/webui,{"message":"AUTH_REQUIRED"}"#)
+        else {
+            panic!("expected connect error");
+        };
+        assert_eq!(message, "AUTH_REQUIRED");
+
+        let Packet::Event { name, payload } =
+            decode(r#"42/webui,7["cli:status",{"machineId":"m1","connected":true}]"#)
+        else {
+            panic!("expected event");
+        };
+        assert_eq!(name, "cli:status");
+        assert_eq!(payload["machineId"], "m1");
+
+        // Other namespaces are ignored.
+        assert!(matches!(decode(r#"42["cli:status",{}]"#), Packet::Other));
+    }
+
+    #[test]
+    fn encodes_client_packets() {
+        assert_eq!(connect(Some("t0k")), r#"40/webui,{"token":"t0k"}"#);
+        assert_eq!(connect(None), r#"40/webui,{"token":null}"#);
+        assert_eq!(
+            event("subscribe:session", &json!({ "sessionId": "s1" })),
+            r#"42/webui,["subscribe:session",{"sessionId":"s1"}]"#
+        );
+    }
+}
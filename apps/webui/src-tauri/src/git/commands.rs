@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use super::{BlameLine, Branch, FileDiffResponse, Grep, GrepOptions, Log, LogOptions, Status};
+use crate::error::Result;
+
+#[derive(Serialize)]
+pub struct BlameResponse {
+    lines: Vec<BlameLine>,
+}
+
+#[derive(Serialize)]
+pub struct BranchesResponse {
+    branches: Vec<Branch>,
+}
+
+/// Changed files under `cwd`, as `GitStatusResponse`.
+#[tauri::command]
+pub async fn git_status(cwd: PathBuf) -> Result<Status> {
+    super::status(&cwd).await
+}
+
+/// As `GitFileDiffResponse`.
+#[tauri::command]
+pub async fn git_file_diff(cwd: PathBuf, path: String) -> Result<FileDiffResponse> {
+    super::file_diff(&cwd, &path).await
+}
+
+/// As `GitLogResponse`.
+#[tauri::command]
+pub async fn git_log(
+    cwd: PathBuf,
+    max_count: Option<u32>,
+    skip: Option<u32>,
+    path: Option<String>,
+    author: Option<String>,
+    search: Option<String>,
+) -> Result<Log> {
+    let options = LogOptions {
+        max_count,
+        skip,
+        path,
+        author,
+        search,
+    };
+    super::log(&cwd, options).await
+}
+
+/// As `GitBlameResponse`.
+#[tauri::command]
+pub async fn git_blame(
+    cwd: PathBuf,
+    path: String,
+    start_line: Option<u32>,
+    end_line: Option<u32>,
+) -> Result<BlameResponse> {
+    let lines = super::blame(&cwd, &path, start_line, end_line).await?;
+    Ok(BlameResponse { lines })
+}
+
+/// As `GitBranchesResponse`.
+#[tauri::command]
+pub async fn git_branches(cwd: PathBuf) -> Result<BranchesResponse> {
+    let branches = super::branches(&cwd).await?;
+    Ok(BranchesResponse { branches })
+}
+
+/// As `GitGrepResponse`. `query` is a literal, case-insensitive string
+/// unless `regex` or `case_sensitive` say otherwise.
+#[tauri::command]
+pub async fn git_grep(
+    cwd: PathBuf,
+    query: String,
+    case_sensitive: Option<bool>,
+    regex: Option<bool>,
+    glob: Option<String>,
+) -> Result<Grep> {
+    let options = GrepOptions {
+        case_sensitive: case_sensitive.unwrap_or(false),
+        regex: regex.unwrap_or(false),
+        glob,
+    };
+    super::grep(&cwd, &query, options).await
+}
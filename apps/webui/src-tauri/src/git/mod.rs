@@ -0,0 +1,841 @@
+//! Git inspection of a working directory on this machine, for sessions
+//! that run where the app does. The views then skip the gateway round trip
+//! and keep working while the daemon is stopped.
+//!
+//! The repository is read with libgit2 rather than the `git` executable,
+//! on a blocking thread, and the results have the shapes of the gateway's
+//! git RPC responses (`GitStatusResponse`, `GitFileDiffResponse`,
+//! `GitLogEntry`, ...) that `git-utils.ts` in the CLI produces.
+
+pub mod commands;
+mod parse;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Component, Path, PathBuf};
+
+use git2::{
+    BlameOptions, BranchType, Diff, DiffFormat, DiffOptions, DiffStats, ReferenceType, Repository,
+    Sort, StatusOptions, StatusShow, Tree,
+};
+use regex::Regex;
+use serde::Serialize;
+
+use crate::error::{Error, Result};
+use crate::local_gateway::clock;
+use crate::search::content::ContentQuery;
+
+const DEFAULT_LOG_COUNT: u32 = 50;
+const MAX_GREP_RESULTS: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum FileStatus {
+    #[serde(rename = "M")]
+    Modified,
+    #[serde(rename = "A")]
+    Added,
+    #[serde(rename = "D")]
+    Deleted,
+    #[serde(rename = "?")]
+    Untracked,
+    #[serde(rename = "R")]
+    Renamed,
+    #[serde(rename = "C")]
+    Copied,
+    #[serde(rename = "U")]
+    Unmerged,
+    #[serde(rename = "!")]
+    Ignored,
+}
+
+impl FileStatus {
+    /// Which status a directory shows when its files differ.
+    fn priority(self) -> u8 {
+        match self {
+            Self::Added => 7,
+            Self::Deleted => 6,
+            Self::Modified => 5,
+            Self::Renamed => 4,
+            Self::Copied => 3,
+            Self::Unmerged => 2,
+            Self::Untracked => 1,
+            Self::Ignored => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FileEntry {
+    pub path: String,
+    pub status: FileStatus,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Status {
+    pub is_git_repo: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<String>,
+    pub files: Vec<FileEntry>,
+    pub dir_status: BTreeMap<String, FileStatus>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiff {
+    pub added_lines: Vec<u32>,
+    pub modified_lines: Vec<u32>,
+    pub deleted_lines: Vec<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub raw_diff: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiffResponse {
+    pub is_git_repo: bool,
+    pub path: String,
+    #[serde(flatten)]
+    pub diff: FileDiff,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    pub hash: String,
+    pub short_hash: String,
+    pub author: String,
+    pub author_email: String,
+    pub date: String,
+    pub subject: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub files_changed: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insertions: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deletions: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+    pub entries: Vec<LogEntry>,
+    pub has_more: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct LogOptions {
+    pub max_count: Option<u32>,
+    pub skip: Option<u32>,
+    pub path: Option<String>,
+    pub author: Option<String>,
+    pub search: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlameLine {
+    pub line_number: u32,
+    pub commit_hash: String,
+    pub short_hash: String,
+    pub author: String,
+    pub date: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct AheadBehind {
+    pub ahead: u32,
+    pub behind: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Branch {
+    pub name: String,
+    pub display_name: String,
+    pub current: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ahead_behind: Option<AheadBehind>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GrepResult {
+    pub path: String,
+    pub line_number: u32,
+    pub content: String,
+    pub match_start: u32,
+    pub match_end: u32,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Grep {
+    pub results: Vec<GrepResult>,
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct GrepOptions {
+    pub case_sensitive: bool,
+    pub regex: bool,
+    pub glob: Option<String>,
+}
+
+/// Runs `task` on a blocking thread, as libgit2 reads the disk.
+async fn blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tauri::async_runtime::spawn_blocking(task)
+        .await
+        .map_err(|err| Error::Git(err.to_string()))?
+}
+
+fn git_error(err: git2::Error) -> Error {
+    Error::Git(err.message().to_owned())
+}
+
+fn check_dir(cwd: &Path) -> Result<()> {
+    if cwd.is_dir() {
+        Ok(())
+    } else {
+        Err(Error::Git(format!("{} is not a directory", cwd.display())))
+    }
+}
+
+/// The repository whose working tree contains `cwd`.
+fn open(cwd: &Path) -> Option<Repository> {
+    Repository::discover(cwd)
+        .ok()
+        .filter(|repo| !repo.is_bare())
+}
+
+fn open_required(cwd: &Path) -> Result<Repository> {
+    open(cwd).ok_or_else(|| Error::Git(format!("{} is not in a git repository", cwd.display())))
+}
+
+/// Where `cwd` is in the working tree: empty at its top, otherwise the
+/// directories down to it, each followed by `/`.
+fn prefix(repo: &Repository, cwd: &Path) -> Result<String> {
+    let workdir = workdir(repo)?.canonicalize()?;
+    let cwd = cwd.canonicalize()?;
+    let relative = cwd.strip_prefix(&workdir).map_err(|_| {
+        Error::Git(format!(
+            "{} is outside {}",
+            cwd.display(),
+            workdir.display()
+        ))
+    })?;
+    Ok(relative
+        .components()
+        .map(|component| format!("{}/", component.as_os_str().to_string_lossy()))
+        .collect())
+}
+
+fn workdir(repo: &Repository) -> Result<PathBuf> {
+    repo.workdir()
+        .map(Path::to_path_buf)
+        .ok_or_else(|| Error::Git("The repository has no working tree".into()))
+}
+
+/// `path` relative to `cwd`, which it must not leave.
+fn relative_path(cwd: &Path, path: &str) -> Result<String> {
+    let path = Path::new(path);
+    let relative = if path.is_absolute() {
+        path.strip_prefix(cwd).unwrap_or(path)
+    } else {
+        path
+    };
+    let inside = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+    if !inside {
+        return Err(Error::Git(format!(
+            "{} is outside {}",
+            path.display(),
+            cwd.display()
+        )));
+    }
+    // Git takes `/` on every platform.
+    let parts: Vec<_> = relative
+        .components()
+        .filter(|component| matches!(component, Component::Normal(_)))
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    Ok(parts.join("/"))
+}
+
+fn lossy(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).into_owned()
+}
+
+/// The current branch, also before its first commit, or the short commit
+/// hash on a detached HEAD.
+fn current_branch(repo: &Repository) -> Option<String> {
+    let head = repo.find_reference("HEAD").ok()?;
+    if let Some(target) = head.symbolic_target() {
+        return Some(
+            target
+                .strip_prefix("refs/heads/")
+                .unwrap_or(target)
+                .to_owned(),
+        );
+    }
+    let commit = repo.find_object(head.target()?, None).ok()?;
+    commit.short_id().ok()?.as_str().map(str::to_owned)
+}
+
+pub async fn status(cwd: &Path) -> Result<Status> {
+    check_dir(cwd)?;
+    let cwd = cwd.to_path_buf();
+    blocking(move || {
+        let Some(repo) = open(&cwd) else {
+            return Ok(Status {
+                is_git_repo: false,
+                branch: None,
+                files: Vec::new(),
+                dir_status: BTreeMap::new(),
+            });
+        };
+        let files = changed_files(&repo)?;
+        Ok(Status {
+            is_git_repo: true,
+            branch: current_branch(&repo),
+            dir_status: parse::dir_status(&files),
+            files,
+        })
+    })
+    .await
+}
+
+/// What `git status` lists: paths from the top of the working tree, an
+/// untracked directory as one entry, and a rename by its new path.
+fn changed_files(repo: &Repository) -> Result<Vec<FileEntry>> {
+    let mut options = StatusOptions::new();
+    options
+        .show(StatusShow::IndexAndWorkdir)
+        .include_untracked(true)
+        .renames_head_to_index(true);
+    let statuses = repo.statuses(Some(&mut options)).map_err(git_error)?;
+    Ok(statuses
+        .iter()
+        .filter_map(|entry| {
+            let delta = entry.head_to_index().or_else(|| entry.index_to_workdir())?;
+            Some(FileEntry {
+                path: lossy(delta.new_file().path_bytes()?),
+                status: file_status(entry.status())?,
+            })
+        })
+        .collect())
+}
+
+/// The most significant of a file's index and working tree changes.
+fn file_status(flags: git2::Status) -> Option<FileStatus> {
+    use git2::Status as S;
+    let status = if flags.is_conflicted() {
+        FileStatus::Unmerged
+    } else if flags.is_wt_new() {
+        FileStatus::Untracked
+    } else if flags.is_ignored() {
+        FileStatus::Ignored
+    } else if flags.is_index_new() {
+        FileStatus::Added
+    } else if flags.intersects(S::INDEX_DELETED | S::WT_DELETED) {
+        FileStatus::Deleted
+    } else if flags.intersects(S::INDEX_RENAMED | S::WT_RENAMED) {
+        FileStatus::Renamed
+    } else if flags
+        .intersects(S::INDEX_MODIFIED | S::INDEX_TYPECHANGE | S::WT_MODIFIED | S::WT_TYPECHANGE)
+    {
+        FileStatus::Modified
+    } else {
+        return None;
+    };
+    Some(status)
+}
+
+/// The changes to `path` against `HEAD`, staged or not. A file git does
+/// not track yet counts as entirely added.
+pub async fn file_diff(cwd: &Path, path: &str) -> Result<FileDiffResponse> {
+    check_dir(cwd)?;
+    let relative = relative_path(cwd, path)?;
+    let (cwd, path) = (cwd.to_path_buf(), path.to_owned());
+    blocking(move || {
+        let Some(repo) = open(&cwd) else {
+            return Ok(FileDiffResponse {
+                is_git_repo: false,
+                path,
+                diff: FileDiff::default(),
+            });
+        };
+        let relative = format!("{}{relative}", prefix(&repo, &cwd)?);
+        Ok(FileDiffResponse {
+            is_git_repo: true,
+            path,
+            diff: working_diff(&repo, &relative)?,
+        })
+    })
+    .await
+}
+
+/// `git diff HEAD -- path`, for `path` from the top of the working tree.
+fn working_diff(repo: &Repository, path: &str) -> Result<FileDiff> {
+    // Before the first commit everything is new.
+    let head = repo.head().ok().and_then(|head| head.peel_to_tree().ok());
+    let mut options = DiffOptions::new();
+    options.pathspec(path).disable_pathspec_match(true);
+    let diff = repo
+        .diff_tree_to_workdir_with_index(head.as_ref(), Some(&mut options))
+        .map_err(git_error)?;
+    let raw = patch(&diff)?;
+    if !raw.trim().is_empty() {
+        return Ok(FileDiff {
+            raw_diff: Some(raw.clone()),
+            ..parse::diff(&raw)
+        });
+    }
+    let new = repo
+        .status_file(Path::new(path))
+        .is_ok_and(|status| status.is_wt_new() || status.is_index_new());
+    if !new {
+        return Ok(FileDiff::default());
+    }
+    let content = std::fs::read(workdir(repo)?.join(path))?;
+    Ok(parse::new_file_diff(
+        path,
+        &String::from_utf8_lossy(&content),
+    ))
+}
+
+/// The unified diff `git diff` prints.
+fn patch(diff: &Diff<'_>) -> Result<String> {
+    let mut raw = String::new();
+    diff.print(DiffFormat::Patch, |_, _, line| {
+        if matches!(line.origin(), '+' | '-' | ' ') {
+            raw.push(line.origin());
+        }
+        raw.push_str(&String::from_utf8_lossy(line.content()));
+        true
+    })
+    .map_err(git_error)?;
+    Ok(raw)
+}
+
+pub async fn log(cwd: &Path, options: LogOptions) -> Result<Log> {
+    check_dir(cwd)?;
+    let path = options
+        .path
+        .as_deref()
+        .filter(|path| !path.is_empty())
+        .map(|path| relative_path(cwd, path))
+        .transpose()?;
+    let pattern = |text: Option<String>| {
+        text.filter(|text| !text.is_empty())
+            .map(|text| Regex::new(&text))
+            .transpose()
+            .map_err(|err| Error::Git(err.to_string()))
+    };
+    let author = pattern(options.author)?;
+    let search = pattern(options.search)?;
+    let max_count = options.max_count.unwrap_or(DEFAULT_LOG_COUNT) as usize;
+    let mut skip = options.skip.unwrap_or(0) as usize;
+    let cwd = cwd.to_path_buf();
+    blocking(move || {
+        let repo = open_required(&cwd)?;
+        let path = match path {
+            Some(path) => Some(format!("{}{path}", prefix(&repo, &cwd)?)),
+            None => None,
+        };
+        let matches = |pattern: &Option<Regex>, text: &str| {
+            pattern
+                .as_ref()
+                .is_none_or(|pattern| pattern.is_match(text))
+        };
+        let mut walk = repo.revwalk().map_err(git_error)?;
+        walk.push_head().map_err(git_error)?;
+        walk.set_sorting(Sort::TIME).map_err(git_error)?;
+        let mut entries = Vec::new();
+        for oid in walk {
+            let commit = repo
+                .find_commit(oid.map_err(git_error)?)
+                .map_err(git_error)?;
+            let signature = commit.author();
+            let name = lossy(signature.name_bytes());
+            let email = lossy(signature.email_bytes());
+            if !matches(&author, &format!("{name} <{email}>"))
+                || !matches(&search, &lossy(commit.message_bytes()))
+            {
+                continue;
+            }
+            let tree = commit.tree().map_err(git_error)?;
+            if let Some(path) = &path {
+                if !touches(&repo, &commit, &tree, path)? {
+                    continue;
+                }
+            }
+            if skip > 0 {
+                skip -= 1;
+                continue;
+            }
+            let stats = numstat(&repo, &commit, &tree, path.as_deref())?;
+            entries.push(LogEntry {
+                hash: commit.id().to_string(),
+                short_hash: commit
+                    .as_object()
+                    .short_id()
+                    .map_err(git_error)?
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_owned(),
+                author: name,
+                author_email: email,
+                date: clock::format(signature.when().seconds() * 1000),
+                subject: commit.summary().unwrap_or_default().to_owned(),
+                body: commit
+                    .body()
+                    .map(str::trim)
+                    .filter(|body| !body.is_empty())
+                    .map(str::to_owned),
+                files_changed: stats.as_ref().map(|stats| stats.files_changed() as u32),
+                insertions: stats.as_ref().map(|stats| stats.insertions() as u32),
+                deletions: stats.as_ref().map(|stats| stats.deletions() as u32),
+            });
+            // One more than asked for tells whether there are more.
+            if entries.len() > max_count {
+                break;
+            }
+        }
+        let has_more = entries.len() > max_count;
+        entries.truncate(max_count);
+        Ok(Log { entries, has_more })
+    })
+    .await
+}
+
+/// Whether `commit` changed `path`. Like `git log -- path`, a merge did
+/// only if it differs there from every parent.
+fn touches(
+    repo: &Repository,
+    commit: &git2::Commit<'_>,
+    tree: &Tree<'_>,
+    path: &str,
+) -> Result<bool> {
+    if commit.parent_count() == 0 {
+        return changes(repo, None, tree, path);
+    }
+    for parent in commit.parents() {
+        let parent = parent.tree().map_err(git_error)?;
+        if !changes(repo, Some(&parent), tree, path)? {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn changes(repo: &Repository, old: Option<&Tree<'_>>, new: &Tree<'_>, path: &str) -> Result<bool> {
+    Ok(tree_diff(repo, old, new, Some(path))?.deltas().len() > 0)
+}
+
+/// What `git log --numstat` adds up for `commit`, limited to `path`: none
+/// for a merge or a commit that changes nothing.
+fn numstat(
+    repo: &Repository,
+    commit: &git2::Commit<'_>,
+    tree: &Tree<'_>,
+    path: Option<&str>,
+) -> Result<Option<DiffStats>> {
+    if commit.parent_count() > 1 {
+        return Ok(None);
+    }
+    let parent = match commit.parents().next() {
+        Some(parent) => Some(parent.tree().map_err(git_error)?),
+        None => None,
+    };
+    let mut diff = tree_diff(repo, parent.as_ref(), tree, path)?;
+    diff.find_similar(None).map_err(git_error)?;
+    let stats = diff.stats().map_err(git_error)?;
+    Ok(Some(stats).filter(|stats| stats.files_changed() > 0))
+}
+
+fn tree_diff<'r>(
+    repo: &'r Repository,
+    old: Option<&Tree<'_>>,
+    new: &Tree<'_>,
+    path: Option<&str>,
+) -> Result<Diff<'r>> {
+    let mut options = DiffOptions::new();
+    if let Some(path) = path {
+        options.pathspec(path);
+    }
+    repo.diff_tree_to_tree(old, Some(new), Some(&mut options))
+        .map_err(git_error)
+}
+
+/// Who last changed each line of `path`, optionally from `start_line` to
+/// `end_line`. Lines changed since the last commit belong to no commit,
+/// as in `git blame`.
+pub async fn blame(
+    cwd: &Path,
+    path: &str,
+    start_line: Option<u32>,
+    end_line: Option<u32>,
+) -> Result<Vec<BlameLine>> {
+    check_dir(cwd)?;
+    let relative = relative_path(cwd, path)?;
+    let cwd = cwd.to_path_buf();
+    blocking(move || {
+        let repo = open_required(&cwd)?;
+        let relative = format!("{}{relative}", prefix(&repo, &cwd)?);
+        let content = std::fs::read(workdir(&repo)?.join(&relative))?;
+        let committed = repo
+            .blame_file(Path::new(&relative), Some(&mut BlameOptions::new()))
+            .map_err(git_error)?;
+        let blame = committed.blame_buffer(&content).map_err(git_error)?;
+        let text = String::from_utf8_lossy(&content);
+        let lines: Vec<&str> = text.lines().collect();
+        let start = start_line.unwrap_or(1).max(1) as usize;
+        if start_line.is_some() && start > lines.len() {
+            return Err(Error::Git(format!(
+                "file {relative} has only {} lines",
+                lines.len()
+            )));
+        }
+        let end = end_line.map_or(lines.len(), |end| (end as usize).min(lines.len()));
+        // Blaming the buffer leaves hunks without signatures, so authors
+        // come from their commits.
+        let mut authors: HashMap<git2::Oid, (String, i64)> = HashMap::new();
+        let mut result = Vec::new();
+        for number in start..=end {
+            let Some(hunk) = blame.get_line(number) else {
+                continue;
+            };
+            let id = hunk.final_commit_id();
+            let (author, millis) = if id.is_zero() {
+                ("Not Committed Yet".to_owned(), clock::now_millis())
+            } else if let Some(author) = authors.get(&id) {
+                author.clone()
+            } else {
+                let commit = repo.find_commit(id).map_err(git_error)?;
+                let signature = commit.author();
+                let author = (
+                    lossy(signature.name_bytes()),
+                    signature.when().seconds() * 1000,
+                );
+                authors.insert(id, author.clone());
+                author
+            };
+            let commit_hash = id.to_string();
+            result.push(BlameLine {
+                line_number: number as u32,
+                short_hash: commit_hash[..7].to_owned(),
+                commit_hash,
+                author,
+                date: clock::format(millis),
+                content: lines[number - 1].to_owned(),
+            });
+        }
+        Ok(result)
+    })
+    .await
+}
+
+/// The files in the index under `prefix`, relative to it, each once
+/// whatever its merge stage.
+fn tracked_files(repo: &Repository, prefix: &str) -> Result<BTreeSet<String>> {
+    let index = repo.index().map_err(git_error)?;
+    Ok(index
+        .iter()
+        .filter_map(|entry| {
+            let path = String::from_utf8_lossy(&entry.path);
+            path.strip_prefix(prefix).map(str::to_owned)
+        })
+        .collect())
+}
+
+/// Local and remote-tracking branches, ordered like `git branch -a`.
+pub async fn branches(cwd: &Path) -> Result<Vec<Branch>> {
+    check_dir(cwd)?;
+    let cwd = cwd.to_path_buf();
+    blocking(move || {
+        let repo = open_required(&cwd)?;
+        let mut branches = Vec::new();
+        for item in repo.branches(None).map_err(git_error)? {
+            let (branch, kind) = item.map_err(git_error)?;
+            let reference = branch.get();
+            // `origin/HEAD` only names another remote branch.
+            if reference.kind() == Some(ReferenceType::Symbolic) {
+                continue;
+            }
+            let (Some(full_ref), Some(name)) = (reference.name(), reference.shorthand()) else {
+                continue;
+            };
+            let current = branch.is_head();
+            let upstream = branch.upstream().ok();
+            let ahead_behind = upstream.as_ref().and_then(|upstream| {
+                let (ahead, behind) = repo
+                    .graph_ahead_behind(reference.target()?, upstream.get().target()?)
+                    .ok()?;
+                (ahead > 0 || behind > 0).then_some(AheadBehind {
+                    ahead: ahead as u32,
+                    behind: behind as u32,
+                })
+            });
+            let upstream =
+                upstream.and_then(|upstream| upstream.get().shorthand().map(str::to_owned));
+            branches.push((
+                full_ref.to_owned(),
+                Branch {
+                    name: name.to_owned(),
+                    display_name: if current {
+                        format!("{name} (HEAD)")
+                    } else {
+                        name.to_owned()
+                    },
+                    current,
+                    remote: (kind == BranchType::Remote)
+                        .then(|| name.split('/').next().unwrap_or(name).to_owned()),
+                    upstream,
+                    ahead_behind,
+                },
+            ));
+        }
+        branches.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(branches.into_iter().map(|(_, branch)| branch).collect())
+    })
+    .await
+}
+
+/// Searches tracked files under `cwd` for `query`, a literal string unless
+/// `regex` is set. At most [`MAX_GREP_RESULTS`] matches are returned.
+pub async fn grep(cwd: &Path, query: &str, options: GrepOptions) -> Result<Grep> {
+    check_dir(cwd)?;
+    let query = ContentQuery::new(
+        query,
+        options.case_sensitive,
+        options.regex,
+        options.glob.as_deref(),
+    )?;
+    let cwd = cwd.to_path_buf();
+    blocking(move || {
+        let repo = open_required(&cwd)?;
+        let mut results = Vec::new();
+        for path in tracked_files(&repo, &prefix(&repo, &cwd)?)? {
+            if results.len() >= MAX_GREP_RESULTS {
+                break;
+            }
+            if query.includes(&path) {
+                query.search_file(&cwd, &path, &mut results);
+            }
+        }
+        let truncated = results.len() >= MAX_GREP_RESULTS;
+        results.truncate(MAX_GREP_RESULTS);
+        Ok(Grep { results, truncated })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use git2::{Signature, Time};
+
+    fn commit(repo: &Repository, message: &str, seconds: i64) {
+        let mut index = repo.index().unwrap();
+        index
+            .add_all(["*"], git2::IndexAddOption::DEFAULT, None)
+            .unwrap();
+        index.write().unwrap();
+        let tree = repo.find_tree(index.write_tree().unwrap()).unwrap();
+        let signature = Signature::new("Ada", "ada@example.com", &Time::new(seconds, 0)).unwrap();
+        let parent = repo.head().ok().and_then(|head| head.peel_to_commit().ok());
+        let parents: Vec<_> = parent.iter().collect();
+        repo.commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            message,
+            &tree,
+            &parents,
+        )
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn reads_a_repository_without_the_git_executable() {
+        let root = std::env::temp_dir().join(format!("mobvibe-git-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        let repo = Repository::init(&root).unwrap();
+        std::fs::write(root.join("src/a.rs"), "one\ntwo\nthree\n").unwrap();
+        commit(&repo, "Add a", 0);
+        std::fs::write(root.join("src/b.rs"), "fn b() {}\n").unwrap();
+        commit(&repo, "Add b\n\nWith a body.", 86_400);
+        std::fs::write(root.join("src/a.rs"), "one\n2\nthree\n").unwrap();
+        std::fs::write(root.join("notes.md"), "todo\n").unwrap();
+        std::fs::write(root.join(".gitignore"), "*.log\n").unwrap();
+        std::fs::write(root.join("debug.log"), "noise\n").unwrap();
+
+        let status = status(&root).await.unwrap();
+        assert!(status.is_git_repo);
+        assert!(status.branch.is_some());
+        let files: Vec<_> = status
+            .files
+            .iter()
+            .map(|file| (file.path.as_str(), file.status))
+            .collect();
+        assert!(files.contains(&("src/a.rs", FileStatus::Modified)));
+        assert!(files.contains(&("notes.md", FileStatus::Untracked)));
+        assert!(!files.iter().any(|(path, _)| *path == "debug.log"));
+        assert_eq!(status.dir_status["src"], FileStatus::Modified);
+
+        let diff = file_diff(&root.join("src"), "a.rs").await.unwrap().diff;
+        assert_eq!(diff.added_lines, [2]);
+        assert_eq!(diff.deleted_lines, [2]);
+        assert!(diff.raw_diff.unwrap().contains("-two\n+2\n"));
+        let untracked = file_diff(&root, "notes.md").await.unwrap().diff;
+        assert_eq!(untracked.added_lines, [1]);
+
+        let history = log(&root, LogOptions::default()).await.unwrap();
+        assert!(!history.has_more);
+        assert_eq!(history.entries.len(), 2);
+        assert_eq!(history.entries[0].subject, "Add b");
+        assert_eq!(history.entries[0].body.as_deref(), Some("With a body."));
+        assert_eq!(history.entries[0].date, "1970-01-02T00:00:00.000Z");
+        assert_eq!(history.entries[0].files_changed, Some(1));
+        assert_eq!(history.entries[1].insertions, Some(3));
+        let options = LogOptions {
+            path: Some("src/a.rs".into()),
+            ..LogOptions::default()
+        };
+        let history = log(&root, options).await.unwrap();
+        assert_eq!(history.entries.len(), 1);
+        assert_eq!(history.entries[0].subject, "Add a");
+        let options = LogOptions {
+            max_count: Some(1),
+            search: Some("^Add".into()),
+            ..LogOptions::default()
+        };
+        assert!(log(&root, options).await.unwrap().has_more);
+
+        let lines = blame(&root, "src/a.rs", Some(2), Some(3)).await.unwrap();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].author, "Not Committed Yet");
+        assert_eq!(lines[0].content, "2");
+        assert_eq!((lines[1].author.as_str(), lines[1].line_number), ("Ada", 3));
+
+        let branches = branches(&root).await.unwrap();
+        assert_eq!(branches.len(), 1);
+        assert!(branches[0].current);
+
+        let options = GrepOptions::default();
+        let found = grep(&root, "FN B", options).await.unwrap();
+        assert_eq!(found.results.len(), 1);
+        assert_eq!(found.results[0].path, "src/b.rs");
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
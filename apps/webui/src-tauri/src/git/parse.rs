@@ -0,0 +1,125 @@
+//! The line numbers and directory rollups `git-utils.ts` derives from git's
+//! output, producing the same values.
+
+use std::collections::BTreeMap;
+
+use super::{FileDiff, FileEntry, FileStatus};
+
+/// Gives each directory above a changed file the highest-priority status
+/// among its files.
+pub fn dir_status(files: &[FileEntry]) -> BTreeMap<String, FileStatus> {
+    let mut dirs: BTreeMap<String, FileStatus> = BTreeMap::new();
+    for file in files {
+        let mut end = 0;
+        while let Some(slash) = file.path[end..].find('/') {
+            end += slash;
+            let dir = &file.path[..end];
+            match dirs.get_mut(dir) {
+                Some(status) if status.priority() >= file.status.priority() => {}
+                Some(status) => *status = file.status,
+                None => {
+                    dirs.insert(dir.to_owned(), file.status);
+                }
+            }
+            end += 1;
+        }
+    }
+    dirs
+}
+
+/// Line numbers in the new file from a unified diff. Deletions are marked
+/// at the line they were removed before; `modifiedLines` stays empty, as
+/// it does in the CLI.
+pub fn diff(output: &str) -> FileDiff {
+    let mut diff = FileDiff::default();
+    let mut current = 0u32;
+    let mut in_hunk = false;
+    for line in output.split('\n') {
+        if let Some(start) = hunk_new_start(line) {
+            current = start;
+            in_hunk = true;
+            continue;
+        }
+        if !in_hunk {
+            continue;
+        }
+        if line.starts_with('+') && !line.starts_with("+++") {
+            diff.added_lines.push(current);
+            current += 1;
+        } else if line.starts_with('-') && !line.starts_with("---") {
+            let at = current.max(1);
+            if !diff.deleted_lines.contains(&at) {
+                diff.deleted_lines.push(at);
+            }
+        } else if !line.starts_with('\\') {
+            current += 1;
+        }
+    }
+    diff
+}
+
+/// The new-file start line of a `@@ -a,b +c,d @@` hunk header.
+fn hunk_new_start(line: &str) -> Option<u32> {
+    let rest = line.strip_prefix("@@ -")?;
+    let (_, rest) = rest.split_once(" +")?;
+    let (range, _) = rest.split_once(" @@")?;
+    range.split(',').next()?.parse().ok()
+}
+
+/// A diff that adds every non-empty line of a file git does not track yet.
+pub fn new_file_diff(path: &str, content: &str) -> FileDiff {
+    let lines: Vec<&str> = content
+        .split('\n')
+        .filter(|line| !line.is_empty())
+        .collect();
+    let mut raw = format!("--- /dev/null\n+++ b/{path}\n@@ -0,0 +1,{} @@", lines.len());
+    for line in &lines {
+        raw.push_str("\n+");
+        raw.push_str(line);
+    }
+    FileDiff {
+        added_lines: (1..=lines.len() as u32).collect(),
+        raw_diff: Some(raw),
+        ..FileDiff::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolls_status_up_to_directories() {
+        let files: Vec<FileEntry> = [
+            ("src/a.rs", FileStatus::Modified),
+            ("notes/todo.md", FileStatus::Untracked),
+            ("src/deep/b.rs", FileStatus::Added),
+        ]
+        .into_iter()
+        .map(|(path, status)| FileEntry {
+            path: path.to_owned(),
+            status,
+        })
+        .collect();
+        let dirs = dir_status(&files);
+        assert_eq!(dirs["src"], FileStatus::Added);
+        assert_eq!(dirs["src/deep"], FileStatus::Added);
+        assert_eq!(dirs["notes"], FileStatus::Untracked);
+        assert_eq!(dirs.len(), 3);
+    }
+
+    #[test]
+    fn parses_diff_hunks() {
+        let patch = "diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -1,3 +1,3 @@\n a\n-b\n+c\n d\n@@ -10 +10,2 @@\n x\n+y\n";
+        let parsed = diff(patch);
+        assert_eq!(parsed.added_lines, [2, 11]);
+        assert_eq!(parsed.deleted_lines, [2]);
+
+        let added = new_file_diff("n.txt", "one\n\ntwo\n");
+        assert_eq!(added.added_lines, [1, 2]);
+        assert_eq!(
+            added.raw_diff.as_deref(),
+            Some("--- /dev/null\n+++ b/n.txt\n@@ -0,0 +1,2 @@\n+one\n+two")
+        );
+    }
+}
@@ -1,7 +1,60 @@
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod app_menu;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod clipboard;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod daemon;
+mod deep_link;
+mod e2ee;
+mod error;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod file_drop;
+mod gateway;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod git;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod local_gateway;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod notifications;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod outline;
+mod preload;
+mod prompt_image;
+mod protocol;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod quick_prompt;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod search;
+mod session_cache;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod session_windows;
+mod settings;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod single_instance;
+mod sqlite;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod standalone;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod terminal;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod tray;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod updater;
+mod vault;
+
+use tauri::webview::PageLoadEvent;
+use tauri::Manager;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    let mut builder = tauri::Builder::default()
-        .plugin(tauri_plugin_store::Builder::new().build())
+    let mut builder = tauri::Builder::default();
+
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    {
+        builder = builder.plugin(single_instance::init());
+    }
+
+    builder = builder
         .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_http::init())
@@ -9,7 +62,18 @@ pub fn run() {
 
     #[cfg(not(any(target_os = "android", target_os = "ios")))]
     {
-        builder = builder.plugin(tauri_plugin_opener::init());
+        builder = builder
+            .plugin(tauri_plugin_opener::init())
+            .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+            .on_window_event(|window, event| match event {
+                tauri::WindowEvent::Destroyed => {
+                    if let Some(terminals) = window.try_state::<terminal::TerminalState>() {
+                        terminals.forget_window(window.label());
+                    }
+                }
+                tauri::WindowEvent::DragDrop(event) => file_drop::on_drag_drop(window, event),
+                _ => {}
+            });
     }
 
     #[cfg(any(target_os = "android", target_os = "ios"))]
@@ -18,11 +82,234 @@ pub fn run() {
     }
 
     builder
-        .setup(|_app| {
+        .manage(e2ee::E2eeState::default())
+        .invoke_handler(tauri::generate_handler![
+            e2ee::commands::e2ee_forget_session,
+            e2ee::commands::e2ee_unwrap_session_deks,
+            e2ee::commands::e2ee_decrypt_events,
+            e2ee::commands::e2ee_encrypt_payload,
+            vault::commands::vault_status,
+            vault::commands::vault_unlock,
+            vault::commands::vault_add_secret,
+            vault::commands::vault_list_secrets,
+            vault::commands::vault_remove_secret,
+            vault::commands::vault_clear,
+            deep_link::commands::deep_link_take_pending,
+            deep_link::commands::deep_link_accept_pair,
+            deep_link::commands::deep_link_reject_pair,
+            gateway::commands::gateway_status,
+            gateway::commands::gateway_listen,
+            gateway::commands::gateway_unlisten,
+            gateway::commands::gateway_set_url,
+            gateway::commands::gateway_set_token,
+            gateway::commands::gateway_disconnect,
+            gateway::commands::gateway_subscribe_session,
+            gateway::commands::gateway_unsubscribe_session,
+            session_cache::commands::session_cache_sessions,
+            session_cache::commands::session_cache_events,
+            session_cache::commands::session_cache_record_page,
+            session_cache::commands::session_search,
+            session_cache::commands::session_search_open,
+            settings::commands::settings_entries,
+            settings::commands::settings_get,
+            settings::commands::settings_set,
+            settings::commands::settings_delete,
+            settings::commands::settings_apply,
+            prompt_image::commands::prompt_image_normalize,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            local_gateway::commands::local_gateway_status,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            local_gateway::commands::local_gateway_start,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            local_gateway::commands::local_gateway_stop,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            daemon::commands::daemon_status,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            daemon::commands::daemon_start,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            daemon::commands::daemon_stop,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            daemon::commands::daemon_set_cli_path,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            daemon::commands::daemon_watch_logs,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            daemon::commands::daemon_unwatch_logs,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            daemon::commands::daemon_machine_id,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            standalone::commands::standalone_listen,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            standalone::commands::standalone_unlisten,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            standalone::commands::standalone_sessions,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            standalone::commands::standalone_start,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            standalone::commands::standalone_prompt,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            standalone::commands::standalone_cancel,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            standalone::commands::standalone_permission,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            standalone::commands::standalone_close,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            terminal::commands::terminal_open,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            terminal::commands::terminal_write,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            terminal::commands::terminal_resize,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            terminal::commands::terminal_close,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            terminal::commands::terminal_list,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            notifications::commands::notifications_permission_request,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            git::commands::git_status,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            git::commands::git_file_diff,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            git::commands::git_log,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            git::commands::git_blame,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            git::commands::git_branches,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            git::commands::git_grep,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            search::commands::search_paths,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            search::commands::search_content,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            search::commands::search_cancel,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            outline::commands::outline_extract,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            session_windows::commands::session_window_open,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            session_windows::commands::session_window_close,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            session_windows::commands::session_window_list,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            quick_prompt::commands::quick_prompt_status,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            quick_prompt::commands::quick_prompt_set_shortcut,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            quick_prompt::commands::quick_prompt_hide,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            app_menu::commands::app_menu_listen,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            app_menu::commands::app_menu_update,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            updater::commands::updater_status,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            updater::commands::updater_set_channel,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            updater::commands::updater_set_manifest_url,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            updater::commands::updater_check,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            updater::commands::updater_download,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            updater::commands::updater_install,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            file_drop::commands::file_drop_listen,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            file_drop::commands::file_drop_unlisten,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            clipboard::commands::clipboard_read,
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            clipboard::commands::clipboard_write_output,
+        ])
+        .on_page_load(|webview, payload| {
+            if payload.event() == PageLoadEvent::Started {
+                if let Some(gateway) = webview.try_state::<gateway::GatewayState>() {
+                    gateway.forget_webview(webview.label());
+                }
+                #[cfg(not(any(target_os = "android", target_os = "ios")))]
+                if let Some(daemon) = webview.try_state::<daemon::DaemonState>() {
+                    daemon.forget_webview(webview.label());
+                }
+                #[cfg(not(any(target_os = "android", target_os = "ios")))]
+                if let Some(standalone) = webview.try_state::<standalone::StandaloneState>() {
+                    standalone.forget_webview(webview.label());
+                }
+                #[cfg(not(any(target_os = "android", target_os = "ios")))]
+                if let Some(terminals) = webview.try_state::<terminal::TerminalState>() {
+                    terminals.forget_webview(webview.label());
+                }
+                #[cfg(not(any(target_os = "android", target_os = "ios")))]
+                if let Some(search) = webview.try_state::<search::SearchState>() {
+                    search.forget_webview(webview.label());
+                }
+                #[cfg(not(any(target_os = "android", target_os = "ios")))]
+                if let Some(menu) = webview.try_state::<app_menu::AppMenu>() {
+                    menu.forget_webview(webview.label());
+                }
+                #[cfg(not(any(target_os = "android", target_os = "ios")))]
+                if let Some(file_drop) = webview.try_state::<file_drop::FileDropState>() {
+                    file_drop.forget_webview(webview.label());
+                }
+            }
+        })
+        .setup(|app| {
             #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
             {
                 use tauri_plugin_deep_link::DeepLinkExt;
-                let _ = _app.deep_link().register_all();
+                let _ = app.deep_link().register_all();
+            }
+
+            let settings = settings::SettingsStore::open(&app.path().app_data_dir()?)?;
+            let secret_vault = vault::SecretVault::open(&app.path().app_data_dir()?);
+            // A vault that cannot be read leaves E2EE without paired secrets
+            // rather than keeping the app from starting.
+            if let Some(err) = secret_vault.status().error {
+                eprintln!("[vault] Failed to read the keyring: {err}");
+            }
+            if !secret_vault.is_locked() {
+                if let Err(err) = vault::migrate_legacy_store(&settings, &secret_vault) {
+                    eprintln!("[vault] Failed to migrate legacy secrets: {err}");
+                }
+                match secret_vault.load_into(&app.state::<e2ee::E2eeState>()) {
+                    Ok(failures) => {
+                        for (fingerprint, err) in failures {
+                            eprintln!("[vault] Failed to load paired secret {fingerprint}: {err}");
+                        }
+                    }
+                    Err(err) => eprintln!("[vault] Failed to load paired secrets: {err}"),
+                }
+            }
+            app.manage(secret_vault);
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            local_gateway::init(app.handle(), &settings)?;
+            preload::create_main_window(app, &settings)?;
+
+            deep_link::init(app.handle())?;
+
+            app.manage(session_cache::SessionCache::open(
+                &app.path().app_data_dir()?,
+            )?);
+
+            let gateway_config = gateway::GatewayConfig::load(&settings)?;
+            app.manage(settings);
+            gateway::GatewayState::start(app.handle(), gateway_config);
+            session_cache::init(app.handle());
+
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            {
+                app.manage(daemon::DaemonState::new(app.path().home_dir()?));
+                standalone::init(app.handle())?;
+                app.manage(terminal::TerminalState::default());
+                app.manage(search::SearchState::default());
+                app.manage(file_drop::FileDropState::default());
+                app.manage(clipboard::ClipboardState::default());
+                tray::init(app.handle())?;
+                notifications::init(app.handle());
+                app.manage(session_windows::SessionWindows::default());
+                session_windows::restore(app.handle())?;
+                quick_prompt::init(app.handle())?;
+                app_menu::init(app.handle())?;
+                updater::init(app.handle())?;
             }
 
             Ok(())
@@ -0,0 +1,99 @@
+//! `/cli` handshake check. The cloud gateway looks the signing key up in its
+//! device table; locally the only devices are the ones whose master secret is
+//! paired with this app, so a CLI is accepted when its token is signed by the
+//! auth key derived from one of those secrets.
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use ed25519_dalek::{Signature, VerifyingKey};
+use serde_json::Value;
+
+use super::clock;
+use crate::e2ee::{E2eeState, KEY_BYTES};
+
+/// Same freshness window as `verifySignedToken` in the cloud gateway.
+const MAX_TOKEN_AGE_MS: i64 = 5 * 60 * 1000;
+
+/// Returns the `connect_error` message for a rejected token.
+pub fn verify_cli_token(auth: &Value, e2ee: &E2eeState, now: i64) -> Result<(), &'static str> {
+    let public_key = verify_signature(auth, now).ok_or("INVALID_TOKEN")?;
+    if !e2ee.is_paired_auth_key(&public_key) {
+        return Err("DEVICE_NOT_REGISTERED");
+    }
+    Ok(())
+}
+
+/// Checks a `SignedAuthToken` and returns its public key. The signature
+/// covers `JSON.stringify(payload)`, which `serde_json` reproduces because
+/// the payload keeps its key order and holds only base64 and ISO strings.
+fn verify_signature(auth: &Value, now: i64) -> Option<[u8; KEY_BYTES]> {
+    let payload = auth.get("payload")?;
+    let timestamp = clock::parse(payload.get("timestamp")?.as_str()?)?;
+    if (now - timestamp).abs() > MAX_TOKEN_AGE_MS {
+        return None;
+    }
+    let public_key: [u8; KEY_BYTES] = STANDARD
+        .decode(payload.get("publicKey")?.as_str()?)
+        .ok()?
+        .try_into()
+        .ok()?;
+    let signature: [u8; 64] = STANDARD
+        .decode(auth.get("signature")?.as_str()?)
+        .ok()?
+        .try_into()
+        .ok()?;
+    VerifyingKey::from_bytes(&public_key)
+        .ok()?
+        .verify_strict(
+            serde_json::to_string(payload).ok()?.as_bytes(),
+            &Signature::from_bytes(&signature),
+        )
+        .ok()?;
+    Some(public_key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use serde_json::json;
+
+    fn token(key: &SigningKey, timestamp: &str) -> Value {
+        let payload = json!({
+            "publicKey": STANDARD.encode(key.verifying_key().to_bytes()),
+            "timestamp": timestamp,
+        });
+        let signature = key.sign(serde_json::to_string(&payload).unwrap().as_bytes());
+        json!({ "payload": payload, "signature": STANDARD.encode(signature.to_bytes()) })
+    }
+
+    #[test]
+    fn accepts_only_fresh_tokens_signed_by_the_embedded_key() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let now = clock::parse("2026-01-01T00:05:00.000Z").unwrap();
+        let fresh = token(&key, "2026-01-01T00:01:00.000Z");
+        assert_eq!(
+            verify_signature(&fresh, now),
+            Some(key.verifying_key().to_bytes())
+        );
+
+        let stale = token(&key, "2026-01-01T00:00:00.000Z");
+        assert_eq!(verify_signature(&stale, now + 1), None);
+
+        let mut forged = fresh.clone();
+        forged["payload"]["timestamp"] = json!("2026-01-01T00:02:00.000Z");
+        assert_eq!(verify_signature(&forged, now), None);
+        assert_eq!(verify_signature(&json!({}), now), None);
+    }
+
+    #[test]
+    fn rejects_keys_that_are_not_paired() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let now = clock::parse("2026-01-01T00:00:00.000Z").unwrap();
+        let fresh = token(&key, "2026-01-01T00:00:00.000Z");
+        assert_eq!(
+            verify_cli_token(&fresh, &E2eeState::default(), now),
+            Err("DEVICE_NOT_REGISTERED")
+        );
+    }
+}
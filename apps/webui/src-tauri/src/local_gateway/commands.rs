@@ -0,0 +1,47 @@
+use tauri::{AppHandle, State};
+
+use super::{url, LocalGateway, LocalGatewayConfig, LocalGatewayStatus};
+use crate::error::Result;
+use crate::gateway::commands::gateway_set_url;
+use crate::gateway::GatewayState;
+use crate::settings::SettingsStore;
+
+#[tauri::command]
+pub fn local_gateway_status(local_gateway: State<'_, LocalGateway>) -> LocalGatewayStatus {
+    local_gateway.status()
+}
+
+/// Starts the embedded gateway, keeps it enabled across restarts and points
+/// the app at it. `port` defaults to the last one used.
+#[tauri::command]
+pub fn local_gateway_start(
+    app: AppHandle,
+    settings: State<'_, SettingsStore>,
+    gateway: State<'_, GatewayState>,
+    local_gateway: State<'_, LocalGateway>,
+    port: Option<u16>,
+) -> Result<LocalGatewayStatus> {
+    let mut config = LocalGatewayConfig::load(&settings)?;
+    config.port = port.unwrap_or(config.port);
+    local_gateway.start(&app, config.port)?;
+    config.enabled = true;
+    config.save(&settings)?;
+    let status = local_gateway.status();
+    if let Some(port) = status.port {
+        gateway_set_url(settings, gateway, url(port))?;
+    }
+    Ok(status)
+}
+
+/// Stops the embedded gateway and keeps it off across restarts. The gateway
+/// URL is left as is; the app reconnects when the server is back.
+#[tauri::command]
+pub fn local_gateway_stop(
+    settings: State<'_, SettingsStore>,
+    local_gateway: State<'_, LocalGateway>,
+) -> Result<()> {
+    local_gateway.stop();
+    let mut config = LocalGatewayConfig::load(&settings)?;
+    config.enabled = false;
+    config.save(&settings)
+}
@@ -0,0 +1,981 @@
+//! Connected CLIs and webui sockets, and the routing between them. Mirrors
+//! `CliRegistry`, `cli-handlers.ts`, `webui-handlers.ts` and the RPC half of
+//! `SessionRouter` with a single implicit user: every webui socket sees every
+//! CLI. Payloads are kept as JSON and relayed untouched apart from the
+//! `machineId` stamps the cloud gateway adds; the cloud's sanitizers guard
+//! against other users' CLIs, which a local gateway does not have.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde_json::{json, Map, Value};
+use tokio::sync::{mpsc, oneshot};
+
+use super::{clock, packet, random_id};
+
+pub const CLI: &str = "/cli";
+pub const WEBUI: &str = "/webui";
+
+pub type ConnId = u64;
+
+pub enum Outgoing {
+    Frame(String),
+    Close,
+}
+
+pub type Outbox = mpsc::UnboundedSender<Outgoing>;
+
+/// An HTTP error response: a status and the `ErrorDetail` under `error`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiError {
+    pub status: u16,
+    pub detail: Value,
+}
+
+impl ApiError {
+    fn new(status: u16, code: &str, message: &str, retryable: bool, scope: &str) -> Self {
+        Self {
+            status,
+            detail: json!({
+                "code": code,
+                "message": message,
+                "retryable": retryable,
+                "scope": scope,
+            }),
+        }
+    }
+
+    pub fn invalid(message: &str) -> Self {
+        Self::new(400, "REQUEST_VALIDATION_FAILED", message, false, "request")
+    }
+
+    pub fn not_found(message: &str) -> Self {
+        Self::new(404, "AUTHORIZATION_FAILED", message, false, "request")
+    }
+
+    fn no_cli() -> Self {
+        Self::new(
+            503,
+            "AUTHORIZATION_FAILED",
+            "No CLI connected for this user",
+            false,
+            "request",
+        )
+    }
+
+    fn internal(message: &str) -> Self {
+        Self::new(500, "INTERNAL_ERROR", message, true, "service")
+    }
+
+    /// An `RpcError` from `rpc:response`: an `ErrorDetail` with an optional
+    /// HTTP status, as `toRpcAppError` reads it.
+    fn from_rpc(error: Value) -> Self {
+        let Value::Object(mut detail) = error else {
+            return Self::internal("Invalid RPC error from CLI");
+        };
+        let status = detail
+            .remove("status")
+            .and_then(|status| status.as_u64())
+            .filter(|status| (400..=599).contains(status))
+            .map_or(500, |status| status as u16);
+        Self {
+            status,
+            detail: Value::Object(detail),
+        }
+    }
+
+    pub fn body(&self) -> Value {
+        json!({ "error": self.detail })
+    }
+}
+
+/// Which CLI an RPC goes to.
+#[derive(Debug, Clone, Copy)]
+pub enum Target<'a> {
+    /// The CLI that owns the session.
+    Session(&'a str),
+    /// The machine's CLI, or the first connected one.
+    Machine(Option<&'a str>),
+}
+
+struct Cli {
+    machine_id: String,
+    hostname: String,
+    connected_at: i64,
+    backends: Vec<Value>,
+    backend_capabilities: Map<String, Value>,
+    sessions: Vec<Value>,
+    outbox: Outbox,
+}
+
+impl Cli {
+    fn status(&self, connected: bool) -> Value {
+        let mut status = json!({
+            "machineId": self.machine_id,
+            "connected": connected,
+            "hostname": self.hostname,
+        });
+        if connected {
+            status["sessionCount"] = json!(self.sessions.len());
+            if !self.backend_capabilities.is_empty() {
+                status["backendCapabilities"] = Value::Object(self.backend_capabilities.clone());
+            }
+        }
+        status
+    }
+
+    fn session_index(&self, session_id: &str) -> Option<usize> {
+        self.sessions
+            .iter()
+            .position(|session| session["sessionId"] == session_id)
+    }
+
+    fn backend(&self, backend_id: &str) -> Option<&Value> {
+        self.backends
+            .iter()
+            .find(|backend| backend["backendId"] == backend_id)
+    }
+
+    fn send(&self, name: &str, payload: &Value) {
+        let _ = self
+            .outbox
+            .send(Outgoing::Frame(packet::event(CLI, name, payload)));
+    }
+}
+
+struct Webui {
+    outbox: Outbox,
+    subscriptions: BTreeSet<String>,
+}
+
+struct PendingRpc {
+    cli: ConnId,
+    reply: oneshot::Sender<Result<Value, ApiError>>,
+}
+
+#[derive(Default)]
+struct Inner {
+    clis: BTreeMap<ConnId, Cli>,
+    webuis: BTreeMap<ConnId, Webui>,
+    pending: HashMap<String, PendingRpc>,
+}
+
+impl Inner {
+    fn broadcast(&self, name: &str, payload: &Value) {
+        let frame = packet::event(WEBUI, name, payload);
+        for webui in self.webuis.values() {
+            let _ = webui.outbox.send(Outgoing::Frame(frame.clone()));
+        }
+    }
+
+    fn to_subscribers(&self, session_id: &str, name: &str, payload: &Value) {
+        let frame = packet::event(WEBUI, name, payload);
+        for webui in self.webuis.values() {
+            if webui.subscriptions.contains(session_id) {
+                let _ = webui.outbox.send(Outgoing::Frame(frame.clone()));
+            }
+        }
+    }
+
+    fn cli_for_session(&self, session_id: &str) -> Option<ConnId> {
+        self.clis
+            .iter()
+            .find(|(_, cli)| cli.session_index(session_id).is_some())
+            .map(|(&id, _)| id)
+    }
+
+    fn cli_for_machine(&self, machine_id: &str) -> Option<ConnId> {
+        self.clis
+            .iter()
+            .find(|(_, cli)| cli.machine_id == machine_id)
+            .map(|(&id, _)| id)
+    }
+
+    fn resolve(&self, target: Target<'_>) -> Result<ConnId, ApiError> {
+        match target {
+            Target::Session(session_id) => self
+                .cli_for_session(session_id)
+                .ok_or_else(|| ApiError::not_found("Session not found")),
+            Target::Machine(Some(machine_id)) => self
+                .cli_for_machine(machine_id)
+                .ok_or_else(|| ApiError::not_found("Machine not found")),
+            Target::Machine(None) => self
+                .clis
+                .keys()
+                .next()
+                .copied()
+                .ok_or_else(ApiError::no_cli),
+        }
+    }
+
+    fn sessions_changed(&self, cli: &Cli, mut changes: Value) {
+        for key in ["added", "updated"] {
+            if let Some(sessions) = changes[key].as_array_mut() {
+                for session in sessions {
+                    session["machineId"] = json!(cli.machine_id);
+                }
+            }
+        }
+        self.broadcast("sessions:changed", &changes);
+    }
+
+    /// Forgets a CLI: fails its RPCs and tells webuis its attached sessions
+    /// are gone, as the cloud gateway does on disconnect.
+    fn remove_cli(&mut self, id: ConnId) -> Option<Cli> {
+        let cli = self.clis.remove(&id)?;
+        let failed: Vec<String> = self
+            .pending
+            .iter()
+            .filter(|(_, pending)| pending.cli == id)
+            .map(|(request_id, _)| request_id.clone())
+            .collect();
+        for request_id in failed {
+            if let Some(pending) = self.pending.remove(&request_id) {
+                let _ = pending
+                    .reply
+                    .send(Err(ApiError::internal("CLI disconnected")));
+            }
+        }
+        self.broadcast("cli:status", &cli.status(false));
+        let detached_at = clock::format(clock::now_millis());
+        for session in cli.sessions.iter().filter(|s| s["isAttached"] == true) {
+            self.broadcast(
+                "session:detached",
+                &json!({
+                    "sessionId": session["sessionId"],
+                    "machineId": cli.machine_id,
+                    "detachedAt": detached_at,
+                    "reason": "cli_disconnect",
+                }),
+            );
+        }
+        Some(cli)
+    }
+}
+
+#[derive(Default)]
+pub struct Hub {
+    inner: Mutex<Inner>,
+    next_conn: AtomicU64,
+}
+
+impl Hub {
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn next_conn(&self) -> ConnId {
+        self.next_conn.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// Adds a webui socket and replays `cli:status` for every connected CLI.
+    pub fn attach_webui(&self, id: ConnId, outbox: Outbox) {
+        let mut inner = self.inner();
+        for cli in inner.clis.values() {
+            let frame = packet::event(WEBUI, "cli:status", &cli.status(true));
+            let _ = outbox.send(Outgoing::Frame(frame));
+        }
+        inner.webuis.insert(
+            id,
+            Webui {
+                outbox,
+                subscriptions: BTreeSet::new(),
+            },
+        );
+    }
+
+    /// Drops a socket of either namespace.
+    pub fn detach(&self, id: ConnId) {
+        let mut inner = self.inner();
+        inner.webuis.remove(&id);
+        inner.remove_cli(id);
+    }
+
+    /// Closes every socket, for shutdown.
+    pub fn close_all(&self) {
+        let inner = self.inner();
+        let outboxes = inner
+            .clis
+            .values()
+            .map(|cli| &cli.outbox)
+            .chain(inner.webuis.values().map(|webui| &webui.outbox));
+        for outbox in outboxes {
+            let _ = outbox.send(Outgoing::Close);
+        }
+    }
+
+    pub fn webui_event(&self, id: ConnId, name: &str, payload: Value) {
+        let Some(session_id) = payload["sessionId"].as_str() else {
+            return;
+        };
+        let mut inner = self.inner();
+        let owned = inner.cli_for_session(session_id).is_some();
+        let Some(webui) = inner.webuis.get_mut(&id) else {
+            return;
+        };
+        match name {
+            "subscribe:session" if owned => {
+                webui.subscriptions.insert(session_id.to_owned());
+            }
+            "subscribe:session" => {
+                let frame = packet::event(
+                    WEBUI,
+                    "subscription:error",
+                    &json!({
+                        "sessionId": session_id,
+                        "error": "Not authorized to subscribe to this session",
+                    }),
+                );
+                let _ = webui.outbox.send(Outgoing::Frame(frame));
+            }
+            "unsubscribe:session" => {
+                webui.subscriptions.remove(session_id);
+            }
+            _ => {}
+        }
+    }
+
+    pub fn cli_event(&self, id: ConnId, outbox: &Outbox, name: &str, payload: Value) {
+        let mut inner = self.inner();
+        match name {
+            "cli:register" => return register(&mut inner, id, outbox, payload),
+            "rpc:response" => return resolve_rpc(&mut inner, id, payload),
+            _ => {}
+        }
+        if !inner.clis.contains_key(&id) {
+            return;
+        }
+        match name {
+            "sessions:list" => update_sessions(&mut inner, id, payload),
+            "sessions:changed" => update_sessions_incremental(&mut inner, id, payload),
+            "sessions:discovered" => {
+                let backend_id = payload["backendId"].as_str().unwrap_or_default();
+                add_discovered(
+                    &mut inner,
+                    id,
+                    backend_id,
+                    payload["sessions"].as_array().map_or(&[], Vec::as_slice),
+                    payload.get("capabilities").filter(|value| !value.is_null()),
+                );
+            }
+            "agent-teams:changed" => {
+                let machine_id = json!(inner.clis[&id].machine_id);
+                let mut payload = payload;
+                payload["machineId"] = machine_id.clone();
+                for key in ["added", "updated"] {
+                    if let Some(teams) = payload[key].as_array_mut() {
+                        for team in teams {
+                            team["machineId"] = machine_id.clone();
+                        }
+                    }
+                }
+                inner.broadcast(name, &payload);
+            }
+            "session:attached" | "session:detached" => {
+                let mut payload = payload;
+                payload["machineId"] = json!(inner.clis[&id].machine_id);
+                inner.broadcast(name, &payload);
+            }
+            "permission:request" | "permission:result" => {
+                if let Some(session_id) = payload["sessionId"].as_str() {
+                    inner.to_subscribers(session_id, name, &payload);
+                }
+            }
+            "session:event" => {
+                let Some(session_id) = payload["sessionId"].as_str().map(str::to_owned) else {
+                    return;
+                };
+                let cli = &inner.clis[&id];
+                let mut event = payload;
+                event["machineId"] = json!(cli.machine_id);
+                inner.to_subscribers(&session_id, name, &event);
+                // Acknowledge only once the event has been relayed.
+                let mut ack = json!({
+                    "sessionId": session_id,
+                    "revision": event["revision"],
+                    "upToSeq": event["seq"],
+                });
+                if let Some(generation) = event.get("incarnationGeneration") {
+                    ack["incarnationGeneration"] = generation.clone();
+                }
+                cli.send("events:ack", &ack);
+            }
+            _ => {}
+        }
+    }
+
+    /// Sends `event` to the target CLI and waits for its `rpc:response`.
+    pub async fn rpc(
+        &self,
+        target: Target<'_>,
+        event: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Result<Value, ApiError> {
+        let request_id = random_id();
+        let (reply, response) = oneshot::channel();
+        {
+            let mut inner = self.inner();
+            let cli = inner.resolve(target)?;
+            inner.clis[&cli].send(event, &json!({ "requestId": request_id, "params": params }));
+            inner
+                .pending
+                .insert(request_id.clone(), PendingRpc { cli, reply });
+        }
+        match tokio::time::timeout(timeout, response).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(ApiError::internal("CLI disconnected")),
+            Err(_) => {
+                self.inner().pending.remove(&request_id);
+                Err(ApiError::internal("RPC timeout"))
+            }
+        }
+    }
+
+    /// The machine id of the CLI a target resolves to.
+    pub fn machine_id(&self, target: Target<'_>) -> Result<String, ApiError> {
+        let inner = self.inner();
+        let cli = inner.resolve(target)?;
+        Ok(inner.clis[&cli].machine_id.clone())
+    }
+
+    pub fn machine_ids(&self) -> Vec<String> {
+        let inner = self.inner();
+        inner
+            .clis
+            .values()
+            .map(|cli| cli.machine_id.clone())
+            .collect()
+    }
+
+    /// Which CLI each session belongs to, for fanning a batch out.
+    pub fn group_by_machine(&self, session_ids: &[String]) -> BTreeMap<String, Vec<String>> {
+        let inner = self.inner();
+        let mut groups: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for session_id in session_ids {
+            // Unknown sessions go to the first CLI, as in `archiveAllSessions`.
+            let cli = inner
+                .cli_for_session(session_id)
+                .or_else(|| inner.clis.keys().next().copied());
+            if let Some(cli) = cli {
+                groups
+                    .entry(inner.clis[&cli].machine_id.clone())
+                    .or_default()
+                    .push(session_id.clone());
+            }
+        }
+        groups
+    }
+
+    /// Every session of every CLI, stamped with its machine.
+    pub fn sessions(&self) -> Vec<Value> {
+        let inner = self.inner();
+        inner
+            .clis
+            .values()
+            .flat_map(|cli| {
+                cli.sessions.iter().map(|session| {
+                    let mut session = session.clone();
+                    session["machineId"] = json!(cli.machine_id);
+                    session
+                })
+            })
+            .collect()
+    }
+
+    /// Backends of every CLI, first registration wins per backend id.
+    pub fn backends(&self) -> Vec<Value> {
+        let inner = self.inner();
+        let mut seen = BTreeSet::new();
+        inner
+            .clis
+            .values()
+            .flat_map(|cli| cli.backends.iter())
+            .filter(|backend| seen.insert(backend["backendId"].to_string()))
+            .cloned()
+            .collect()
+    }
+
+    /// Connected machines in the `/api/machines` shape. There is no machine
+    /// table, so only online machines are listed.
+    pub fn machines(&self) -> Vec<Value> {
+        let inner = self.inner();
+        inner
+            .clis
+            .values()
+            .map(|cli| {
+                let connected_at = clock::format(cli.connected_at);
+                json!({
+                    "id": cli.machine_id,
+                    "name": cli.hostname,
+                    "hostname": cli.hostname,
+                    "platform": null,
+                    "lastSeenAt": connected_at,
+                    "createdAt": connected_at,
+                    "isOnline": true,
+                })
+            })
+            .collect()
+    }
+
+    pub fn remove_sessions(&self, machine_id: &str, session_ids: &[String]) {
+        let mut inner = self.inner();
+        if let Some(cli) = inner.cli_for_machine(machine_id) {
+            update_sessions_incremental(
+                &mut inner,
+                cli,
+                json!({ "added": [], "updated": [], "removed": session_ids }),
+            );
+        }
+    }
+
+    /// Records sessions returned by `rpc:sessions:discover`.
+    pub fn add_discovered_sessions(&self, machine_id: &str, backend_id: &str, sessions: &[Value]) {
+        let mut inner = self.inner();
+        if let Some(cli) = inner.cli_for_machine(machine_id) {
+            add_discovered(&mut inner, cli, backend_id, sessions, None);
+        }
+    }
+}
+
+fn register(inner: &mut Inner, id: ConnId, outbox: &Outbox, info: Value) {
+    if inner.clis.contains_key(&id) {
+        return;
+    }
+    let Some(machine_id) = info["machineId"].as_str().filter(|id| !id.is_empty()) else {
+        let frame = packet::event(
+            CLI,
+            "cli:error",
+            &json!({
+                "code": "REGISTRATION_ERROR",
+                "message": "Failed to register machine. Please try again.",
+            }),
+        );
+        let _ = outbox.send(Outgoing::Frame(frame));
+        let _ = outbox.send(Outgoing::Close);
+        return;
+    };
+    // A machine has one live connection; the newer one wins.
+    if let Some(previous) = inner.cli_for_machine(machine_id) {
+        if let Some(previous) = inner.remove_cli(previous) {
+            let _ = previous.outbox.send(Outgoing::Close);
+        }
+    }
+    let cli = Cli {
+        machine_id: machine_id.to_owned(),
+        hostname: info["hostname"].as_str().unwrap_or_default().to_owned(),
+        connected_at: clock::now_millis(),
+        backends: info["backends"].as_array().cloned().unwrap_or_default(),
+        backend_capabilities: Map::new(),
+        sessions: Vec::new(),
+        outbox: outbox.clone(),
+    };
+    inner.broadcast("cli:status", &cli.status(true));
+    cli.send("cli:registered", &json!({ "machineId": machine_id }));
+    inner.clis.insert(id, cli);
+}
+
+fn resolve_rpc(inner: &mut Inner, id: ConnId, response: Value) {
+    let Some(request_id) = response["requestId"].as_str() else {
+        return;
+    };
+    // Only the CLI the request went to may answer it.
+    if inner
+        .pending
+        .get(request_id)
+        .is_none_or(|pending| pending.cli != id)
+    {
+        return;
+    }
+    let Some(pending) = inner.pending.remove(request_id) else {
+        return;
+    };
+    let result = match response.get("error").filter(|error| !error.is_null()) {
+        Some(error) => Err(ApiError::from_rpc(error.clone())),
+        None => Ok(response.get("result").cloned().unwrap_or(Value::Null)),
+    };
+    let _ = pending.reply.send(result);
+}
+
+/// Keeps the `_meta` the gateway already has when an update omits it.
+fn merge_meta(existing: &Value, incoming: &Value) -> Value {
+    match existing.get("_meta") {
+        Some(meta) if incoming.get("_meta").is_none() => {
+            let mut merged = incoming.clone();
+            merged["_meta"] = meta.clone();
+            merged
+        }
+        _ => incoming.clone(),
+    }
+}
+
+/// `sessions:list` replaces the whole list; webuis get the difference.
+fn update_sessions(inner: &mut Inner, id: ConnId, sessions: Value) {
+    let Value::Array(sessions) = sessions else {
+        return;
+    };
+    let Some(cli) = inner.clis.get_mut(&id) else {
+        return;
+    };
+    let mut added = Vec::new();
+    let mut updated = Vec::new();
+    let next: Vec<Value> = sessions
+        .iter()
+        .filter(|session| session["sessionId"].is_string())
+        .map(
+            |session| match cli.session_index(session["sessionId"].as_str().unwrap_or_default()) {
+                None => {
+                    added.push(session.clone());
+                    session.clone()
+                }
+                Some(index) => {
+                    let merged = merge_meta(&cli.sessions[index], session);
+                    if merged != cli.sessions[index] {
+                        updated.push(merged.clone());
+                    }
+                    merged
+                }
+            },
+        )
+        .collect();
+    let removed: Vec<Value> = cli
+        .sessions
+        .iter()
+        .filter(|session| !next.iter().any(|s| s["sessionId"] == session["sessionId"]))
+        .map(|session| session["sessionId"].clone())
+        .collect();
+    cli.sessions = next;
+    if added.is_empty() && updated.is_empty() && removed.is_empty() {
+        return;
+    }
+    let cli = &inner.clis[&id];
+    inner.sessions_changed(
+        cli,
+        json!({ "added": added, "updated": updated, "removed": removed }),
+    );
+}
+
+fn update_sessions_incremental(inner: &mut Inner, id: ConnId, payload: Value) {
+    let Some(cli) = inner.clis.get_mut(&id) else {
+        return;
+    };
+    // Capabilities only count for backends the CLI registered.
+    let capabilities: Option<Map<String, Value>> =
+        payload["backendCapabilities"].as_object().map(|snapshots| {
+            snapshots
+                .iter()
+                .filter(|(backend_id, _)| cli.backend(backend_id).is_some())
+                .map(|(backend_id, snapshot)| (backend_id.clone(), snapshot.clone()))
+                .collect()
+        });
+    let removed = payload["removed"].as_array().cloned().unwrap_or_default();
+    cli.sessions
+        .retain(|session| !removed.contains(&session["sessionId"]));
+    let mut updated = Vec::new();
+    for session in payload["updated"].as_array().into_iter().flatten() {
+        let index = cli.session_index(session["sessionId"].as_str().unwrap_or_default());
+        let merged = match index {
+            Some(index) => {
+                let merged = merge_meta(&cli.sessions[index], session);
+                cli.sessions[index] = merged.clone();
+                merged
+            }
+            None => session.clone(),
+        };
+        updated.push(merged);
+    }
+    let added = payload["added"].as_array().cloned().unwrap_or_default();
+    for session in &added {
+        if cli
+            .session_index(session["sessionId"].as_str().unwrap_or_default())
+            .is_none()
+        {
+            cli.sessions.push(session.clone());
+        }
+    }
+
+    let mut changes = json!({ "added": added, "updated": updated, "removed": removed });
+    if let Some(capabilities) = capabilities {
+        let changed = !capabilities.is_empty();
+        cli.backend_capabilities.extend(capabilities.clone());
+        changes["backendCapabilities"] = Value::Object(capabilities);
+        if changed {
+            let status = cli.status(true);
+            inner.broadcast("cli:status", &status);
+        }
+    }
+    let cli = &inner.clis[&id];
+    inner.sessions_changed(cli, changes);
+}
+
+/// Adds sessions an agent reported as persisted, without letting a stale
+/// listing overwrite an attached session or a pinned title.
+fn add_discovered(
+    inner: &mut Inner,
+    id: ConnId,
+    backend_id: &str,
+    sessions: &[Value],
+    capabilities: Option<&Value>,
+) {
+    let Some(cli) = inner.clis.get_mut(&id) else {
+        return;
+    };
+    let Some(backend) = cli.backend(backend_id) else {
+        return;
+    };
+    let backend_label = backend
+        .get("backendLabel")
+        .cloned()
+        .unwrap_or_else(|| json!(backend_id));
+    if let Some(capabilities) = capabilities {
+        cli.backend_capabilities
+            .insert(backend_id.to_owned(), capabilities.clone());
+    }
+
+    let now = clock::now_millis();
+    let mut added = Vec::new();
+    let mut updated = Vec::new();
+    for info in sessions {
+        let Some(session_id) = info["sessionId"].as_str() else {
+            continue;
+        };
+        let index = cli.session_index(session_id);
+        let existing = index.map(|index| &cli.sessions[index]);
+        let updated_at = [existing.map(|s| &s["updatedAt"]), Some(&info["updatedAt"])]
+            .into_iter()
+            .flatten()
+            .filter_map(|value| clock::parse(value.as_str()?))
+            .max()
+            .map_or_else(|| clock::format(now), clock::format);
+        let mut summary = json!({
+            "sessionId": session_id,
+            "title": info["title"].as_str().map_or_else(
+                || format!("Session {}", session_id.chars().take(8).collect::<String>()),
+                str::to_owned,
+            ),
+            "cwd": info["cwd"],
+            "updatedAt": updated_at,
+            "createdAt": existing
+                .and_then(|s| s.get("createdAt"))
+                .cloned()
+                .unwrap_or_else(|| json!(updated_at)),
+            "backendId": backend_id,
+            "backendLabel": backend_label,
+            "machineId": cli.machine_id,
+            "additionalDirectories": info
+                .get("additionalDirectories")
+                .filter(|dirs| !dirs.is_null())
+                .cloned()
+                .unwrap_or_else(|| json!([])),
+        });
+        for key in ["workspaceRootCwd", "_meta"] {
+            if let Some(value) = info.get(key) {
+                summary[key] = value.clone();
+            }
+        }
+
+        match (index, existing) {
+            (Some(index), Some(existing)) => {
+                if existing["isAttached"] == true {
+                    continue;
+                }
+                let mut merged = existing.clone();
+                if let (Some(merged), Some(summary)) = (merged.as_object_mut(), summary.as_object())
+                {
+                    for (key, value) in summary {
+                        if key == "title" && existing["isTitlePinned"] == true {
+                            continue;
+                        }
+                        merged.insert(key.clone(), value.clone());
+                    }
+                }
+                if merged != *existing {
+                    cli.sessions[index] = merged.clone();
+                    updated.push(merged);
+                }
+            }
+            _ => {
+                cli.sessions.push(summary.clone());
+                added.push(summary);
+            }
+        }
+    }
+
+    if added.is_empty() && updated.is_empty() && capabilities.is_none() {
+        return;
+    }
+    let mut changes = json!({ "added": added, "updated": updated, "removed": [] });
+    if let Some(capabilities) = capabilities {
+        changes["backendCapabilities"] = json!({ backend_id: capabilities });
+    }
+    let cli = &inner.clis[&id];
+    inner.sessions_changed(cli, changes);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn socket(hub: &Hub) -> (ConnId, Outbox, mpsc::UnboundedReceiver<Outgoing>) {
+        let (outbox, received) = mpsc::unbounded_channel();
+        (hub.next_conn(), outbox, received)
+    }
+
+    fn frames(received: &mut mpsc::UnboundedReceiver<Outgoing>) -> Vec<String> {
+        let mut frames = Vec::new();
+        while let Ok(outgoing) = received.try_recv() {
+            if let Outgoing::Frame(frame) = outgoing {
+                frames.push(frame);
+            }
+        }
+        frames
+    }
+
+    fn register_cli(hub: &Hub) -> (ConnId, Outbox, mpsc::UnboundedReceiver<Outgoing>) {
+        let (id, outbox, mut received) = socket(hub);
+        hub.cli_event(
+            id,
+            &outbox,
+            "cli:register",
+            json!({
+                "machineId": "m1",
+                "hostname": "box",
+                "backends": [{ "backendId": "codex", "backendLabel": "Codex" }],
+            }),
+        );
+        assert_eq!(
+            frames(&mut received),
+            [r#"42/cli,["cli:registered",{"machineId":"m1"}]"#]
+        );
+        (id, outbox, received)
+    }
+
+    #[test]
+    fn relays_session_events_to_subscribers_and_acks_them() {
+        let hub = Hub::default();
+        let (cli, cli_outbox, mut cli_frames) = register_cli(&hub);
+        hub.cli_event(
+            cli,
+            &cli_outbox,
+            "sessions:list",
+            json!([{ "sessionId": "s1", "title": "One", "isAttached": true }]),
+        );
+
+        let (webui, webui_outbox, mut webui_frames) = socket(&hub);
+        hub.attach_webui(webui, webui_outbox);
+        hub.webui_event(webui, "subscribe:session", json!({ "sessionId": "s1" }));
+        hub.webui_event(webui, "subscribe:session", json!({ "sessionId": "s2" }));
+        let replay = frames(&mut webui_frames);
+        assert!(
+            replay[0].starts_with(r#"42/webui,["cli:status",{"machineId":"m1","connected":true"#)
+        );
+        assert!(replay[1].contains("subscription:error"));
+
+        hub.cli_event(
+            cli,
+            &cli_outbox,
+            "session:event",
+            json!({ "sessionId": "s1", "revision": 2, "seq": 7, "kind": "turn_end" }),
+        );
+        let relayed = frames(&mut webui_frames);
+        assert_eq!(relayed.len(), 1);
+        assert!(relayed[0].contains(r#""machineId":"m1""#));
+        assert_eq!(
+            frames(&mut cli_frames),
+            [r#"42/cli,["events:ack",{"sessionId":"s1","revision":2,"upToSeq":7}]"#]
+        );
+
+        hub.detach(cli);
+        let gone = frames(&mut webui_frames);
+        assert!(gone[0].contains(r#""connected":false"#));
+        assert!(gone[1].contains(r#""reason":"cli_disconnect""#));
+        assert!(hub.sessions().is_empty());
+    }
+
+    #[test]
+    fn diffs_full_session_lists() {
+        let hub = Hub::default();
+        let (cli, outbox, _received) = register_cli(&hub);
+        let (webui, webui_outbox, mut webui_frames) = socket(&hub);
+        hub.attach_webui(webui, webui_outbox);
+        frames(&mut webui_frames);
+
+        hub.cli_event(
+            cli,
+            &outbox,
+            "sessions:list",
+            json!([{ "sessionId": "s1", "_meta": { "a": 1 } }, { "sessionId": "s2" }]),
+        );
+        hub.cli_event(
+            cli,
+            &outbox,
+            "sessions:list",
+            json!([{ "sessionId": "s1", "title": "renamed" }]),
+        );
+        let changes = frames(&mut webui_frames);
+        assert_eq!(changes.len(), 2);
+        assert!(changes[1].contains(r#""removed":["s2"]"#));
+        let sessions = hub.sessions();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0]["_meta"], json!({ "a": 1 }));
+        assert_eq!(sessions[0]["machineId"], "m1");
+    }
+
+    #[tokio::test]
+    async fn forwards_rpcs_to_the_owning_cli() {
+        let hub = Hub::default();
+        let (cli, outbox, mut received) = register_cli(&hub);
+        hub.cli_event(
+            cli,
+            &outbox,
+            "sessions:list",
+            json!([{ "sessionId": "s1" }]),
+        );
+
+        let call = hub.rpc(
+            Target::Session("s1"),
+            "rpc:session:cancel",
+            json!({ "sessionId": "s1" }),
+            Duration::from_secs(5),
+        );
+        let answer = async {
+            let request = loop {
+                if let Some(frame) = frames(&mut received).pop() {
+                    break frame;
+                }
+                tokio::task::yield_now().await;
+            };
+            let packet::Packet::Event { payload, .. } = packet::decode(&request) else {
+                panic!("expected an rpc event");
+            };
+            hub.cli_event(
+                cli,
+                &outbox,
+                "rpc:response",
+                json!({
+                    "requestId": payload["requestId"],
+                    "error": { "code": "SESSION_BUSY", "message": "busy", "status": 409 },
+                }),
+            );
+        };
+        let (result, ()) = tokio::join!(call, answer);
+        let error = result.unwrap_err();
+        assert_eq!(error.status, 409);
+        assert_eq!(
+            error.detail,
+            json!({ "code": "SESSION_BUSY", "message": "busy" })
+        );
+
+        let missing = hub
+            .rpc(
+                Target::Session("s9"),
+                "rpc:session:cancel",
+                Value::Null,
+                Duration::from_secs(5),
+            )
+            .await;
+        assert_eq!(missing.unwrap_err().status, 404);
+    }
+}
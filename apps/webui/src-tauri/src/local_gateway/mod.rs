@@ -0,0 +1,250 @@
+//! Optional gateway embedded in the desktop app, for running without any
+//! external service.
+//!
+//! It listens on loopback and speaks the same protocol as `apps/gateway`: a
+//! local `mobvibe-cli` registers on `/cli`, the webview (through `socket.ts`
+//! or the native client) joins `/webui`, and the REST routes in `api.ts` are
+//! forwarded to the owning CLI as RPCs. Durable `session:event`s are acked
+//! back with `events:ack` once delivered. There is a single implicit user:
+//! CLIs prove they belong to it by signing with a paired master secret, and
+//! the app proves it is itself with a bearer token made fresh on every
+//! launch, which only its own webviews and gateway client know.
+
+pub mod commands;
+
+mod auth;
+pub(crate) mod clock;
+mod hub;
+mod packet;
+mod routes;
+mod server;
+mod socket;
+
+use std::net::{Ipv4Addr, TcpListener};
+use std::sync::atomic::{AtomicU16, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, MutexGuard};
+
+use crypto_secretbox::aead::rand_core::RngCore;
+use crypto_secretbox::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::watch;
+
+use self::hub::Hub;
+use crate::error::{Error, Result};
+use crate::settings::{self, SettingsStore};
+
+/// Settings key in the gateway scope.
+const CONFIG_KEY: &str = "localGateway";
+/// The port the webview and `mobvibe-cli` default to.
+pub const DEFAULT_PORT: u16 = 3005;
+
+/// Required on REST and `/webui`. Webviews get it from the preload, the
+/// gateway client from [`token_for`].
+static TOKEN: LazyLock<String> = LazyLock::new(random_token);
+/// Port the server listens on, 0 while stopped.
+static PORT: AtomicU16 = AtomicU16::new(0);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalGatewayConfig {
+    pub enabled: bool,
+    pub port: u16,
+}
+
+impl Default for LocalGatewayConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            port: DEFAULT_PORT,
+        }
+    }
+}
+
+impl LocalGatewayConfig {
+    pub fn load(settings: &SettingsStore) -> Result<Self> {
+        Ok(settings
+            .get(settings::GATEWAY, CONFIG_KEY)?
+            .and_then(|value| serde_json::from_value(value).ok())
+            .unwrap_or_default())
+    }
+
+    pub fn save(&self, settings: &SettingsStore) -> Result<()> {
+        settings.set(settings::GATEWAY, CONFIG_KEY, &serde_json::to_value(self)?)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalGatewayStatus {
+    pub running: bool,
+    pub port: Option<u16>,
+    /// What the webview and `mobvibe-cli` should use as the gateway URL.
+    pub url: Option<String>,
+    /// Connected machines, in the `/api/machines` shape.
+    pub machines: Vec<Value>,
+}
+
+struct Running {
+    port: u16,
+    shutdown: watch::Sender<bool>,
+    hub: Arc<Hub>,
+}
+
+#[derive(Default)]
+pub struct LocalGateway {
+    running: Mutex<Option<Running>>,
+}
+
+impl LocalGateway {
+    fn running(&self) -> MutexGuard<'_, Option<Running>> {
+        self.running.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Binds `127.0.0.1:port` and serves on it, replacing a server already
+    /// running. Binding happens here so a taken port is reported.
+    pub fn start<R: Runtime>(&self, app: &AppHandle<R>, port: u16) -> Result<()> {
+        self.stop();
+        let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, port))
+            .and_then(|listener| listener.set_nonblocking(true).map(|()| listener))
+            .map_err(|err| Error::Gateway(format!("cannot listen on port {port}: {err}")))?;
+        let port = listener.local_addr()?.port();
+        let hub = Arc::new(Hub::default());
+        let (shutdown, shutdown_rx) = watch::channel(false);
+        tauri::async_runtime::spawn(server::run(app.clone(), hub.clone(), listener, shutdown_rx));
+        *self.running() = Some(Running {
+            port,
+            shutdown,
+            hub,
+        });
+        PORT.store(port, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Stops listening and disconnects every client.
+    pub fn stop(&self) {
+        if let Some(running) = self.running().take() {
+            PORT.store(0, Ordering::Relaxed);
+            let _ = running.shutdown.send(true);
+        }
+    }
+
+    pub fn status(&self) -> LocalGatewayStatus {
+        match &*self.running() {
+            Some(running) => LocalGatewayStatus {
+                running: true,
+                port: Some(running.port),
+                url: Some(url(running.port)),
+                machines: running.hub.machines(),
+            },
+            None => LocalGatewayStatus {
+                running: false,
+                port: None,
+                url: None,
+                machines: Vec::new(),
+            },
+        }
+    }
+}
+
+/// Loopback URL of a gateway on `port`.
+pub fn url(port: u16) -> String {
+    format!("http://localhost:{port}")
+}
+
+/// This launch's bearer token.
+pub fn token() -> &'static str {
+    &TOKEN
+}
+
+/// The bearer token to send to `gateway_url` when that is this app's
+/// running server, so it never goes anywhere else.
+pub fn token_for(gateway_url: &str) -> Option<&'static str> {
+    let port = PORT.load(Ordering::Relaxed);
+    let url = url::Url::parse(gateway_url).ok()?;
+    let loopback = matches!(url.host_str()?, "localhost" | "127.0.0.1" | "[::1]");
+    (port != 0 && loopback && url.port_or_known_default() == Some(port)).then(token)
+}
+
+/// What the preload hands app webviews: the token and where it applies.
+pub fn webview_auth() -> Value {
+    let port = PORT.load(Ordering::Relaxed);
+    json!({ "token": token(), "port": (port != 0).then_some(port) })
+}
+
+/// Whether `candidate` is this launch's token, compared in constant time.
+fn is_token(candidate: &str) -> bool {
+    let token = token().as_bytes();
+    let candidate = candidate.as_bytes();
+    candidate.len() == token.len()
+        && candidate
+            .iter()
+            .zip(token)
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+fn random_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Random UUID v4, the id format the cloud gateway uses.
+fn random_id() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|byte| format!("{byte:02x}")).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+/// Manages the state and starts the server if it was left enabled. A port
+/// that is taken now is not fatal; the app can still reach other gateways.
+/// Runs before the main window is created, so its preload knows the port.
+pub fn init<R: Runtime>(app: &AppHandle<R>, settings: &SettingsStore) -> Result<()> {
+    app.manage(LocalGateway::default());
+    let config = LocalGatewayConfig::load(settings)?;
+    if config.enabled {
+        let _ = app.state::<LocalGateway>().start(app, config.port);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn random_ids_are_uuid_v4() {
+        let id = random_id();
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "4");
+        assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+        assert_ne!(id, random_id());
+    }
+
+    #[test]
+    fn hands_the_token_only_to_the_running_server() {
+        assert!(is_token(token()));
+        assert!(!is_token(&token()[1..]));
+        assert!(!is_token(&random_token()));
+
+        PORT.store(4123, Ordering::Relaxed);
+        assert_eq!(token_for("http://localhost:4123"), Some(token()));
+        assert_eq!(token_for("http://127.0.0.1:4123/"), Some(token()));
+        assert_eq!(token_for("http://localhost:4124"), None);
+        assert_eq!(token_for("https://gateway.example.com:4123"), None);
+        PORT.store(0, Ordering::Relaxed);
+        assert_eq!(token_for("http://localhost:4123"), None);
+    }
+}
@@ -0,0 +1,186 @@
+//! Server side of the Engine.IO v4 / Socket.IO v5 framing, for the same slice
+//! of the protocol `gateway/packet.rs` speaks as a client: text packets over a
+//! bare WebSocket, no binary attachments. Ack ids are parsed and dropped;
+//! neither `mobvibe-cli` nor the webui emit with acks.
+
+use serde_json::{json, Value};
+
+/// Engine.IO ping, sent by the server every [`PING_INTERVAL_MS`].
+pub const PING: &str = "2";
+pub const PING_INTERVAL_MS: u64 = 25_000;
+pub const PING_TIMEOUT_MS: u64 = 20_000;
+const MAX_PAYLOAD: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, PartialEq)]
+pub enum Packet {
+    Pong,
+    Close,
+    Connect {
+        namespace: String,
+        auth: Value,
+    },
+    Disconnect {
+        namespace: String,
+    },
+    Event {
+        namespace: String,
+        name: String,
+        payload: Value,
+    },
+    /// Anything the server does not act on.
+    Other,
+}
+
+pub fn decode(text: &str) -> Packet {
+    let mut chars = text.chars();
+    match chars.next() {
+        Some('1') => Packet::Close,
+        Some('3') => Packet::Pong,
+        Some('4') => decode_socket(chars.as_str()),
+        _ => Packet::Other,
+    }
+}
+
+fn decode_socket(text: &str) -> Packet {
+    let mut chars = text.chars();
+    let kind = chars.next();
+    let (namespace, data) = split_namespace(chars.as_str());
+    match kind {
+        Some('0') => Packet::Connect {
+            namespace,
+            auth: serde_json::from_str(data).unwrap_or(Value::Null),
+        },
+        Some('1') => Packet::Disconnect { namespace },
+        Some('2') => {
+            let Ok(Value::Array(mut args)) = serde_json::from_str(data) else {
+                return Packet::Other;
+            };
+            if args.is_empty() {
+                return Packet::Other;
+            }
+            let Value::String(name) = args.remove(0) else {
+                return Packet::Other;
+            };
+            let payload = if args.is_empty() {
+                Value::Null
+            } else {
+                args.swap_remove(0)
+            };
+            Packet::Event {
+                namespace,
+                name,
+                payload,
+            }
+        }
+        _ => Packet::Other,
+    }
+}
+
+/// Splits off the namespace and any ack id, returning the JSON data.
+fn split_namespace(text: &str) -> (String, &str) {
+    let (namespace, rest) = match text.strip_prefix('/') {
+        Some(_) => match text.split_once(',') {
+            Some((namespace, rest)) => (namespace, rest),
+            None => (text, ""),
+        },
+        None => ("/", text),
+    };
+    (
+        namespace.to_owned(),
+        rest.trim_start_matches(|c: char| c.is_ascii_digit()),
+    )
+}
+
+/// Engine.IO handshake. Only the WebSocket transport is offered, so there is
+/// nothing to upgrade to.
+pub fn open(sid: &str) -> String {
+    format!(
+        "0{}",
+        json!({
+            "sid": sid,
+            "upgrades": [],
+            "pingInterval": PING_INTERVAL_MS,
+            "pingTimeout": PING_TIMEOUT_MS,
+            "maxPayload": MAX_PAYLOAD,
+        })
+    )
+}
+
+pub fn connected(namespace: &str, sid: &str) -> String {
+    format!("40{namespace},{}", json!({ "sid": sid }))
+}
+
+/// Refuses a namespace connect; the client sees `message` on `connect_error`.
+pub fn connect_error(namespace: &str, message: &str) -> String {
+    format!("44{namespace},{}", json!({ "message": message }))
+}
+
+pub fn disconnect(namespace: &str) -> String {
+    format!("41{namespace},")
+}
+
+pub fn event(namespace: &str, name: &str, payload: &Value) -> String {
+    format!("42{namespace},{}", json!([name, payload]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decodes_client_packets() {
+        assert_eq!(decode("3"), Packet::Pong);
+        assert_eq!(decode("1"), Packet::Close);
+        assert_eq!(
+            decode(r#"40/cli,{"signature":"s"}"#),
+            Packet::Connect {
+                namespace: "/cli".to_owned(),
+                auth: json!({ "signature": "s" }),
+            }
+        );
+        assert_eq!(
+            decode("40/webui,"),
+            Packet::Connect {
+                namespace: "/webui".to_owned(),
+                auth: Value::Null,
+            }
+        );
+        assert_eq!(
+            decode("41/cli,"),
+            Packet::Disconnect {
+                namespace: "/cli".to_owned(),
+            }
+        );
+        assert_eq!(
+            decode(r#"42/cli,5["cli:heartbeat"]"#),
+            Packet::Event {
+                namespace: "/cli".to_owned(),
+                name: "cli:heartbeat".to_owned(),
+                payload: Value::Null,
+            }
+        );
+        assert_eq!(
+            decode(r#"42["rpc:response",{"requestId":"r1"}]"#),
+            Packet::Event {
+                namespace: "/".to_owned(),
+                name: "rpc:response".to_owned(),
+                payload: json!({ "requestId": "r1" }),
+            }
+        );
+        assert_eq!(decode("451-/cli,[]"), Packet::Other);
+    }
+
+    #[test]
+    fn encodes_server_packets() {
+        assert!(open("abc").starts_with(r#"0{"sid":"abc","upgrades":[]"#));
+        assert_eq!(connected("/cli", "x"), r#"40/cli,{"sid":"x"}"#);
+        assert_eq!(
+            connect_error("/cli", "AUTH_REQUIRED"),
+            r#"44/cli,{"message":"AUTH_REQUIRED"}"#
+        );
+        assert_eq!(
+            event("/webui", "cli:status", &json!({ "connected": true })),
+            r#"42/webui,["cli:status",{"connected":true}]"#
+        );
+    }
+}
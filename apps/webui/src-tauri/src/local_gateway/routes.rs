@@ -0,0 +1,608 @@
+//! The REST surface `api.ts` calls, served from the hub. Almost every route
+//! is one RPC to one CLI with the request's parameters, so they are a table;
+//! the few that aggregate or post-process are spelled out in [`handle`].
+//! There is no account: `/api/auth/*`, devices and push notifications are
+//! cloud-only and answer 404.
+
+use std::time::Duration;
+
+use hyper::Method;
+use serde_json::{json, Map, Value};
+
+use super::hub::{ApiError, Hub, Target};
+use super::random_id;
+
+const RPC_TIMEOUT: Duration = Duration::from_secs(120);
+const AGENT_CAPABILITIES_TIMEOUT: Duration = Duration::from_secs(15);
+const AGENT_AUTHENTICATE_TIMEOUT: Duration = Duration::from_secs(125);
+const AGENT_LOGOUT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Query parameters the cloud routes parse with `Number.parseInt`.
+const NUMBER_PARAMS: &[&str] = &[
+    "revision",
+    "afterSeq",
+    "limit",
+    "maxCount",
+    "skip",
+    "startLine",
+    "endLine",
+];
+/// Query parameters the cloud routes compare with `"true"`.
+const BOOLEAN_PARAMS: &[&str] = &["caseSensitive", "regex"];
+
+#[derive(Clone, Copy)]
+enum By {
+    /// `sessionId` names a session a CLI owns.
+    Session,
+    /// `machineId` names a connected machine.
+    Machine,
+    /// The owner of `sessionId` if known, else `machineId`, else any CLI.
+    Owner,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Effect {
+    None,
+    /// The result is a `SessionSummary`; stamp its machine.
+    Summary,
+    /// The session is gone once the CLI confirms.
+    Removes,
+}
+
+struct Route {
+    method: Method,
+    path: &'static str,
+    event: &'static str,
+    by: By,
+    timeout: Duration,
+    effect: Effect,
+}
+
+const fn route(method: Method, path: &'static str, event: &'static str, by: By) -> Route {
+    Route {
+        method,
+        path,
+        event,
+        by,
+        timeout: RPC_TIMEOUT,
+        effect: Effect::None,
+    }
+}
+
+const fn summary(mut route: Route) -> Route {
+    route.effect = Effect::Summary;
+    route
+}
+
+const fn removes(mut route: Route) -> Route {
+    route.effect = Effect::Removes;
+    route
+}
+
+const fn timeout(mut route: Route, timeout: Duration) -> Route {
+    route.timeout = timeout;
+    route
+}
+
+const ROUTES: &[Route] = &[
+    summary(route(
+        Method::POST,
+        "/acp/session",
+        "rpc:session:create",
+        By::Owner,
+    )),
+    summary(route(
+        Method::PATCH,
+        "/acp/session",
+        "rpc:session:rename",
+        By::Session,
+    )),
+    summary(route(
+        Method::POST,
+        "/acp/session/close",
+        "rpc:session:close",
+        By::Session,
+    )),
+    removes(route(
+        Method::POST,
+        "/acp/session/delete",
+        "rpc:session:delete",
+        By::Session,
+    )),
+    removes(route(
+        Method::POST,
+        "/acp/session/archive",
+        "rpc:session:archive",
+        By::Session,
+    )),
+    route(
+        Method::POST,
+        "/acp/session/cancel",
+        "rpc:session:cancel",
+        By::Session,
+    ),
+    route(
+        Method::POST,
+        "/acp/session/mode",
+        "rpc:session:mode",
+        By::Session,
+    ),
+    route(
+        Method::POST,
+        "/acp/session/config-option",
+        "rpc:session:config",
+        By::Session,
+    ),
+    route(
+        Method::POST,
+        "/acp/session/model",
+        "rpc:session:model",
+        By::Session,
+    ),
+    summary(route(
+        Method::POST,
+        "/acp/session/load",
+        "rpc:session:load",
+        By::Owner,
+    )),
+    summary(route(
+        Method::POST,
+        "/acp/session/resume",
+        "rpc:session:resume",
+        By::Owner,
+    )),
+    summary(route(
+        Method::POST,
+        "/acp/session/reload",
+        "rpc:session:reload",
+        By::Owner,
+    )),
+    route(
+        Method::GET,
+        "/acp/session/events",
+        "rpc:session:events",
+        By::Session,
+    ),
+    route(
+        Method::POST,
+        "/acp/message",
+        "rpc:message:send",
+        By::Session,
+    ),
+    route(
+        Method::POST,
+        "/acp/permission/decision",
+        "rpc:permission:decision",
+        By::Session,
+    ),
+    timeout(
+        route(
+            Method::GET,
+            "/acp/backend/capabilities",
+            "rpc:agent:capabilities",
+            By::Machine,
+        ),
+        AGENT_CAPABILITIES_TIMEOUT,
+    ),
+    timeout(
+        route(
+            Method::POST,
+            "/acp/backend/authenticate",
+            "rpc:agent:authenticate",
+            By::Machine,
+        ),
+        AGENT_AUTHENTICATE_TIMEOUT,
+    ),
+    timeout(
+        route(
+            Method::POST,
+            "/acp/backend/logout",
+            "rpc:agent:logout",
+            By::Machine,
+        ),
+        AGENT_LOGOUT_TIMEOUT,
+    ),
+    route(Method::GET, "/fs/roots", "rpc:hostfs:roots", By::Machine),
+    route(
+        Method::GET,
+        "/fs/entries",
+        "rpc:hostfs:entries",
+        By::Machine,
+    ),
+    route(
+        Method::GET,
+        "/fs/git/branches",
+        "rpc:git:branchesForCwd",
+        By::Machine,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/roots",
+        "rpc:fs:roots",
+        By::Session,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/entries",
+        "rpc:fs:entries",
+        By::Session,
+    ),
+    route(Method::GET, "/fs/session/file", "rpc:fs:file", By::Session),
+    route(
+        Method::GET,
+        "/fs/session/resources",
+        "rpc:fs:resources",
+        By::Session,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/git/status",
+        "rpc:git:status",
+        By::Session,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/git/diff",
+        "rpc:git:fileDiff",
+        By::Session,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/git/log",
+        "rpc:git:log",
+        By::Session,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/git/show",
+        "rpc:git:show",
+        By::Session,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/git/blame",
+        "rpc:git:blame",
+        By::Session,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/git/branches",
+        "rpc:git:branches",
+        By::Session,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/git/stash",
+        "rpc:git:stashList",
+        By::Session,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/git/status-extended",
+        "rpc:git:statusExtended",
+        By::Session,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/git/search-log",
+        "rpc:git:searchLog",
+        By::Session,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/git/file-history",
+        "rpc:git:fileHistory",
+        By::Session,
+    ),
+    route(
+        Method::GET,
+        "/fs/session/git/grep",
+        "rpc:git:grep",
+        By::Session,
+    ),
+];
+
+/// Returns the status and JSON body; `None` means no body.
+pub(super) async fn handle(
+    hub: &Hub,
+    method: &Method,
+    path: &str,
+    query: Map<String, Value>,
+    body: Value,
+) -> (u16, Option<Value>) {
+    let params = if *method == Method::GET {
+        typed_query(query)
+    } else {
+        match body {
+            Value::Object(body) => Ok(body),
+            Value::Null => Ok(Map::new()),
+            _ => Err(ApiError::invalid("JSON object body required")),
+        }
+    };
+    let result = match params {
+        Ok(params) => dispatch(hub, method, path, params).await,
+        Err(error) => Err(error),
+    };
+    match result {
+        Ok(Some(body)) => (200, Some(body)),
+        Ok(None) => (204, None),
+        Err(error) => (error.status, Some(error.body())),
+    }
+}
+
+async fn dispatch(
+    hub: &Hub,
+    method: &Method,
+    path: &str,
+    mut params: Map<String, Value>,
+) -> Result<Option<Value>, ApiError> {
+    match (method, path) {
+        (&Method::GET, "/health") => return Ok(Some(json!({ "status": "ok" }))),
+        (&Method::GET, "/acp/routing") => return Ok(None),
+        (&Method::GET, "/acp/sessions") => return Ok(Some(json!({ "sessions": hub.sessions() }))),
+        (&Method::GET, "/acp/backends") => return Ok(Some(json!({ "backends": hub.backends() }))),
+        (&Method::GET, "/api/machines") => return Ok(Some(json!({ "machines": hub.machines() }))),
+        (&Method::POST, "/acp/message/id") => {
+            require(&params, "sessionId")?;
+            return Ok(Some(json!({ "messageId": random_id() })));
+        }
+        (&Method::POST, "/acp/message") if params.get("messageId").is_none_or(Value::is_null) => {
+            params.insert("messageId".to_owned(), json!(random_id()));
+        }
+        (&Method::POST, "/acp/session/archive-all") => {
+            return archive_all(hub, &params).await.map(Some)
+        }
+        (&Method::GET, "/acp/sessions/discover") => return discover(hub, params).await.map(Some),
+        (&Method::POST, "/acp/agent-teams") => {
+            let machine_id = require(&params, "machineId")?.to_owned();
+            // The route takes `leaderBackendId`; the RPC calls it `backendId`.
+            if let Some(backend_id) = params.remove("leaderBackendId") {
+                params.insert("backendId".to_owned(), backend_id);
+            }
+            return hub
+                .rpc(
+                    Target::Machine(Some(&machine_id)),
+                    "rpc:agent-team:create",
+                    Value::Object(params),
+                    RPC_TIMEOUT,
+                )
+                .await
+                .map(Some);
+        }
+        (&Method::GET, "/acp/agent-teams") => return list_agent_teams(hub, params).await.map(Some),
+        (&Method::GET, _) if path.starts_with("/acp/agent-teams/") => {
+            let team_id = &path["/acp/agent-teams/".len()..];
+            return get_agent_team(hub, team_id, params).await.map(Some);
+        }
+        _ => {}
+    }
+
+    let Some(route) = ROUTES
+        .iter()
+        .find(|route| route.method == *method && route.path == path)
+    else {
+        return Err(ApiError::not_found("Not found"));
+    };
+    let session_id = params
+        .get("sessionId")
+        .and_then(Value::as_str)
+        .map(str::to_owned);
+    let machine_id = params
+        .get("machineId")
+        .and_then(Value::as_str)
+        .map(str::to_owned);
+    let target = match route.by {
+        By::Session => Target::Session(
+            session_id
+                .as_deref()
+                .ok_or_else(|| ApiError::invalid("sessionId required"))?,
+        ),
+        By::Machine => Target::Machine(Some(
+            machine_id
+                .as_deref()
+                .ok_or_else(|| ApiError::invalid("machineId required"))?,
+        )),
+        By::Owner => match session_id.as_deref() {
+            Some(session_id) if hub.machine_id(Target::Session(session_id)).is_ok() => {
+                Target::Session(session_id)
+            }
+            _ => Target::Machine(machine_id.as_deref()),
+        },
+    };
+    let owner = hub.machine_id(target)?;
+    let mut result = hub
+        .rpc(target, route.event, Value::Object(params), route.timeout)
+        .await?;
+    match route.effect {
+        Effect::Summary if result.is_object() => result["machineId"] = json!(owner),
+        Effect::Removes => {
+            if let Some(session_id) = session_id {
+                hub.remove_sessions(&owner, &[session_id]);
+            }
+        }
+        _ => {}
+    }
+    Ok(Some(result))
+}
+
+fn require<'a>(params: &'a Map<String, Value>, key: &str) -> Result<&'a str, ApiError> {
+    params
+        .get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| ApiError::invalid(&format!("{key} required")))
+}
+
+fn typed_query(query: Map<String, Value>) -> Result<Map<String, Value>, ApiError> {
+    query
+        .into_iter()
+        .map(|(key, value)| {
+            let text = value.as_str().unwrap_or_default();
+            let value = if NUMBER_PARAMS.contains(&key.as_str()) {
+                json!(text
+                    .parse::<i64>()
+                    .map_err(|_| ApiError::invalid(&format!("{key} must be a number")))?)
+            } else if BOOLEAN_PARAMS.contains(&key.as_str()) {
+                json!(text == "true")
+            } else {
+                value
+            };
+            Ok((key, value))
+        })
+        .collect()
+}
+
+/// Archives sessions on whichever CLIs own them, counting what succeeded.
+async fn archive_all(hub: &Hub, params: &Map<String, Value>) -> Result<Value, ApiError> {
+    let session_ids: Vec<String> = params
+        .get("sessionIds")
+        .and_then(Value::as_array)
+        .ok_or_else(|| ApiError::invalid("sessionIds required"))?
+        .iter()
+        .filter_map(|id| id.as_str().map(str::to_owned))
+        .collect();
+    let mut archived = 0;
+    for (machine_id, session_ids) in hub.group_by_machine(&session_ids) {
+        let result = hub
+            .rpc(
+                Target::Machine(Some(&machine_id)),
+                "rpc:session:archive-all",
+                json!({ "sessionIds": session_ids }),
+                RPC_TIMEOUT,
+            )
+            .await;
+        if let Ok(result) = result {
+            archived += result["archivedCount"].as_u64().unwrap_or_default();
+            hub.remove_sessions(&machine_id, &session_ids);
+        }
+    }
+    Ok(json!({ "archivedCount": archived }))
+}
+
+async fn discover(hub: &Hub, params: Map<String, Value>) -> Result<Value, ApiError> {
+    let backend_id = require(&params, "backendId")?.to_owned();
+    let machine_id = params.get("machineId").and_then(Value::as_str);
+    let target = Target::Machine(machine_id);
+    let owner = hub.machine_id(target)?;
+    let rpc_params = json!({
+        "cwd": params.get("cwd"),
+        "cursor": params.get("cursor"),
+        "backendId": backend_id,
+    });
+    let result = hub
+        .rpc(target, "rpc:sessions:discover", rpc_params, RPC_TIMEOUT)
+        .await?;
+    if machine_id.is_some() {
+        let sessions = result["sessions"].as_array().map_or(&[][..], Vec::as_slice);
+        hub.add_discovered_sessions(&owner, &backend_id, sessions);
+    }
+    Ok(result)
+}
+
+/// Teams of one machine, or of every connected machine.
+async fn list_agent_teams(hub: &Hub, params: Map<String, Value>) -> Result<Value, ApiError> {
+    if let Some(machine_id) = params.get("machineId").and_then(Value::as_str) {
+        return hub
+            .rpc(
+                Target::Machine(Some(machine_id)),
+                "rpc:agent-teams:list",
+                Value::Object(params.clone()),
+                RPC_TIMEOUT,
+            )
+            .await;
+    }
+    let machine_ids = hub.machine_ids();
+    if machine_ids.is_empty() {
+        return Err(hub.machine_id(Target::Machine(None)).unwrap_err());
+    }
+    let mut teams = Vec::new();
+    for machine_id in machine_ids {
+        let mut params = params.clone();
+        params.insert("machineId".to_owned(), json!(machine_id));
+        let result = hub
+            .rpc(
+                Target::Machine(Some(&machine_id)),
+                "rpc:agent-teams:list",
+                Value::Object(params),
+                RPC_TIMEOUT,
+            )
+            .await?;
+        teams.extend(result["teams"].as_array().cloned().unwrap_or_default());
+    }
+    Ok(json!({ "teams": teams }))
+}
+
+async fn get_agent_team(
+    hub: &Hub,
+    team_id: &str,
+    params: Map<String, Value>,
+) -> Result<Value, ApiError> {
+    let target = Target::Machine(params.get("machineId").and_then(Value::as_str));
+    let machine_id = hub.machine_id(target)?;
+    let result = hub
+        .rpc(
+            target,
+            "rpc:agent-team:get",
+            json!({ "agentTeamId": team_id, "machineId": machine_id }),
+            RPC_TIMEOUT,
+        )
+        .await?;
+    if result["team"].is_null() {
+        return Err(ApiError::not_found("Not found"));
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn types_numeric_and_boolean_query_parameters() {
+        let query = Map::from_iter([
+            ("sessionId".to_owned(), json!("s1")),
+            ("maxCount".to_owned(), json!("20")),
+            ("regex".to_owned(), json!("true")),
+            ("path".to_owned(), json!("42")),
+        ]);
+        assert_eq!(
+            Value::Object(typed_query(query).unwrap()),
+            json!({ "sessionId": "s1", "maxCount": 20, "regex": true, "path": "42" })
+        );
+        let bad = Map::from_iter([("afterSeq".to_owned(), json!("x"))]);
+        assert_eq!(typed_query(bad).unwrap_err().status, 400);
+    }
+
+    #[tokio::test]
+    async fn answers_local_routes_without_a_cli() {
+        let hub = Hub::default();
+        let get = |path: &'static str| handle(&hub, &Method::GET, path, Map::new(), Value::Null);
+        assert_eq!(
+            get("/acp/sessions").await,
+            (200, Some(json!({ "sessions": [] })))
+        );
+        assert_eq!(get("/acp/routing").await, (204, None));
+        assert_eq!(get("/api/auth/session").await.0, 404);
+
+        let (status, body) = handle(
+            &hub,
+            &Method::POST,
+            "/acp/session",
+            Map::new(),
+            json!({ "backendId": "codex" }),
+        )
+        .await;
+        assert_eq!(status, 503);
+        assert_eq!(body.unwrap()["error"]["code"], "AUTHORIZATION_FAILED");
+
+        let (status, _) = handle(
+            &hub,
+            &Method::POST,
+            "/acp/session/cancel",
+            Map::new(),
+            json!({}),
+        )
+        .await;
+        assert_eq!(status, 400);
+    }
+}
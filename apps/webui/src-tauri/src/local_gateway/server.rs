@@ -0,0 +1,242 @@
+//! HTTP/1.1 front: the Socket.IO endpoint and the REST routes, on loopback
+//! only. Requests must name a loopback host, which defeats DNS rebinding, and
+//! browsers may only call in from the app's own origins. REST and `/webui`
+//! also need the launch token; a request with neither an Origin nor the
+//! token can only open a socket to join `/cli`, which verifies a signed
+//! device token of its own.
+
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{self, HeaderValue};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::{json, Map, Value};
+use tauri::{AppHandle, Runtime};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::WebSocketStream;
+
+use super::hub::{ApiError, Hub};
+use super::{is_token, routes, socket};
+
+/// Same body limit as the cloud gateway's `express.json`.
+const MAX_BODY_BYTES: usize = 4 * 1024 * 1024;
+
+struct Context<R: Runtime> {
+    app: AppHandle<R>,
+    hub: Arc<Hub>,
+    origins: Vec<String>,
+}
+
+pub(super) async fn run<R: Runtime>(
+    app: AppHandle<R>,
+    hub: Arc<Hub>,
+    listener: std::net::TcpListener,
+    mut shutdown: watch::Receiver<bool>,
+) {
+    let Ok(listener) = TcpListener::from_std(listener) else {
+        return;
+    };
+    let context = Arc::new(Context {
+        origins: crate::preload::app_origins(&app),
+        app,
+        hub,
+    });
+    loop {
+        let stream = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok((stream, _)) => stream,
+                Err(_) => continue,
+            },
+            _ = shutdown.changed() => break,
+        };
+        let context = context.clone();
+        tauri::async_runtime::spawn(async move {
+            let service = service_fn(move |request| {
+                let context = context.clone();
+                async move { Ok::<_, Infallible>(handle(&context, request).await) }
+            });
+            let _ = http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .with_upgrades()
+                .await;
+        });
+    }
+    context.hub.close_all();
+}
+
+async fn handle<R: Runtime>(
+    context: &Arc<Context<R>>,
+    request: Request<Incoming>,
+) -> Response<Full<Bytes>> {
+    if !is_loopback_host(&request) {
+        return json_response(StatusCode::MISDIRECTED_REQUEST, None);
+    }
+    let origin = request.headers().get(header::ORIGIN).cloned();
+    if let Some(origin) = &origin {
+        if !context
+            .origins
+            .iter()
+            .any(|allowed| origin.as_bytes() == allowed.as_bytes())
+        {
+            return json_response(StatusCode::FORBIDDEN, None);
+        }
+    }
+
+    let authorized = has_token(&request);
+    let mut response = if request.uri().path() == "/socket.io/" {
+        upgrade(context, request, authorized)
+    } else if request.method() == Method::OPTIONS && origin.is_some() {
+        // Preflights never carry credentials; the request that follows does.
+        json_response(StatusCode::NO_CONTENT, None)
+    } else if authorized {
+        rest(&context.hub, request).await
+    } else {
+        json_response(StatusCode::UNAUTHORIZED, None)
+    };
+    if let Some(origin) = origin {
+        let headers = response.headers_mut();
+        headers.insert(header::ACCESS_CONTROL_ALLOW_ORIGIN, origin);
+        headers.insert(header::VARY, HeaderValue::from_static("Origin"));
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_HEADERS,
+            HeaderValue::from_static("authorization, content-type, x-request-id"),
+        );
+        headers.insert(
+            header::ACCESS_CONTROL_ALLOW_METHODS,
+            HeaderValue::from_static("GET, POST, PATCH, OPTIONS"),
+        );
+    }
+    response
+}
+
+/// The token as `Authorization: Bearer`, or as the `bearerToken` query
+/// parameter the gateway client mirrors it into on the socket URL.
+fn has_token(request: &Request<Incoming>) -> bool {
+    let header = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    if header.is_some_and(is_token) {
+        return true;
+    }
+    parse_query(request.uri().query())
+        .get("bearerToken")
+        .and_then(Value::as_str)
+        .is_some_and(is_token)
+}
+
+fn is_loopback_host(request: &Request<Incoming>) -> bool {
+    let Some(host) = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+    else {
+        return false;
+    };
+    let host = match host.rsplit_once(':') {
+        Some((name, port)) if port.bytes().all(|b| b.is_ascii_digit()) => name,
+        _ => host,
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+/// Accepts `GET /socket.io/?EIO=4&transport=websocket`. Long polling is not
+/// offered; every client here connects with `transports: ["websocket"]`.
+fn upgrade<R: Runtime>(
+    context: &Arc<Context<R>>,
+    mut request: Request<Incoming>,
+    authorized: bool,
+) -> Response<Full<Bytes>> {
+    let query = parse_query(request.uri().query());
+    let key = request.headers().get(header::SEC_WEBSOCKET_KEY).cloned();
+    let (Some(key), Some("4"), Some("websocket")) = (
+        key,
+        query.get("EIO").and_then(Value::as_str),
+        query.get("transport").and_then(Value::as_str),
+    ) else {
+        return json_response(
+            StatusCode::BAD_REQUEST,
+            Some(json!({ "code": 0, "message": "Transport unknown" })),
+        );
+    };
+
+    let upgraded = hyper::upgrade::on(&mut request);
+    let app = context.app.clone();
+    let hub = context.hub.clone();
+    tauri::async_runtime::spawn(async move {
+        if let Ok(upgraded) = upgraded.await {
+            let socket =
+                WebSocketStream::from_raw_socket(TokioIo::new(upgraded), Role::Server, None).await;
+            socket::serve(app, hub, socket, authorized).await;
+        }
+    });
+
+    let mut response = Response::new(Full::default());
+    *response.status_mut() = StatusCode::SWITCHING_PROTOCOLS;
+    let headers = response.headers_mut();
+    headers.insert(header::CONNECTION, HeaderValue::from_static("Upgrade"));
+    headers.insert(header::UPGRADE, HeaderValue::from_static("websocket"));
+    if let Ok(accept) = HeaderValue::from_str(&derive_accept_key(key.as_bytes())) {
+        headers.insert(header::SEC_WEBSOCKET_ACCEPT, accept);
+    }
+    response
+}
+
+async fn rest(hub: &Hub, request: Request<Incoming>) -> Response<Full<Bytes>> {
+    let (parts, body) = request.into_parts();
+    let body = match Limited::new(body, MAX_BODY_BYTES).collect().await {
+        Ok(body) => body.to_bytes(),
+        Err(_) => return json_response(StatusCode::PAYLOAD_TOO_LARGE, None),
+    };
+    let body = if body.is_empty() {
+        Value::Null
+    } else {
+        // A JSON body must say so: the content type forces a CORS preflight,
+        // so pages on other origins cannot post simple requests here.
+        let is_json = parts
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.starts_with("application/json"));
+        match serde_json::from_slice(&body) {
+            Ok(body) if is_json => body,
+            _ => {
+                let error = ApiError::invalid("Invalid JSON body");
+                return json_response(StatusCode::BAD_REQUEST, Some(error.body()));
+            }
+        }
+    };
+    let query = parse_query(parts.uri.query());
+    let (status, body) = routes::handle(hub, &parts.method, parts.uri.path(), query, body).await;
+    json_response(
+        StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR),
+        body,
+    )
+}
+
+fn parse_query(query: Option<&str>) -> Map<String, Value> {
+    url::form_urlencoded::parse(query.unwrap_or_default().as_bytes())
+        .map(|(key, value)| (key.into_owned(), Value::String(value.into_owned())))
+        .collect()
+}
+
+fn json_response(status: StatusCode, body: Option<Value>) -> Response<Full<Bytes>> {
+    let mut response = Response::new(Full::new(Bytes::from(
+        body.map(|body| body.to_string()).unwrap_or_default(),
+    )));
+    *response.status_mut() = status;
+    response.headers_mut().insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static("application/json; charset=utf-8"),
+    );
+    response
+}
@@ -0,0 +1,145 @@
+//! One Engine.IO connection. Each socket joins exactly one namespace, which
+//! is all `mobvibe-cli`, `socket.ts` and the native client ever do. Joining
+//! `/webui` takes the launch token, on the upgrade request or as the
+//! handshake's `token`.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use futures_util::{SinkExt, StreamExt};
+use hyper::upgrade::Upgraded;
+use hyper_util::rt::TokioIo;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+use super::hub::{ConnId, Hub, Outgoing, CLI, WEBUI};
+use super::packet::{self, Packet, PING_INTERVAL_MS, PING_TIMEOUT_MS};
+use super::{auth, clock, is_token, random_id};
+use crate::e2ee::E2eeState;
+
+type Socket = WebSocketStream<TokioIo<Upgraded>>;
+
+pub(super) async fn serve<R: Runtime>(
+    app: AppHandle<R>,
+    hub: Arc<Hub>,
+    mut socket: Socket,
+    authorized: bool,
+) {
+    let sid = random_id();
+    if socket
+        .send(Message::text(packet::open(&sid)))
+        .await
+        .is_err()
+    {
+        return;
+    }
+    let id = hub.next_conn();
+    let (outbox, mut outgoing) = mpsc::unbounded_channel();
+    let mut namespace: Option<&'static str> = None;
+    let liveness = Duration::from_millis(PING_INTERVAL_MS + PING_TIMEOUT_MS);
+    let mut ping = tokio::time::interval(Duration::from_millis(PING_INTERVAL_MS));
+    ping.tick().await;
+    let mut last_seen = Instant::now();
+
+    loop {
+        tokio::select! {
+            message = socket.next() => {
+                last_seen = Instant::now();
+                let text = match message {
+                    Some(Ok(Message::Text(text))) => text,
+                    Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                    Some(Ok(_)) => continue,
+                };
+                match packet::decode(&text) {
+                    Packet::Connect { namespace: requested, auth } if namespace.is_none() => {
+                        let accepted = match requested.as_str() {
+                            CLI => auth::verify_cli_token(
+                                &auth,
+                                &app.state::<E2eeState>(),
+                                clock::now_millis(),
+                            )
+                            .map(|()| CLI),
+                            WEBUI if authorized || has_token(&auth) => Ok(WEBUI),
+                            WEBUI => Err("Unauthorized"),
+                            _ => Err("Invalid namespace"),
+                        };
+                        let frame = match accepted {
+                            Ok(joined) => {
+                                if joined == WEBUI {
+                                    hub.attach_webui(id, outbox.clone());
+                                }
+                                namespace = Some(joined);
+                                packet::connected(joined, &sid)
+                            }
+                            Err(message) => packet::connect_error(&requested, message),
+                        };
+                        if socket.send(Message::text(frame)).await.is_err() {
+                            break;
+                        }
+                    }
+                    Packet::Event { namespace: target, name, payload }
+                        if Some(target.as_str()) == namespace =>
+                    {
+                        dispatch(&hub, id, &outbox, &target, &name, payload);
+                    }
+                    Packet::Disconnect { namespace: target } if Some(target.as_str()) == namespace => {
+                        break;
+                    }
+                    Packet::Close => break,
+                    _ => {}
+                }
+            }
+            Some(outgoing) = outgoing.recv() => {
+                let frame = match outgoing {
+                    Outgoing::Frame(frame) => frame,
+                    Outgoing::Close => {
+                        if let Some(joined) = namespace {
+                            let _ = socket.send(Message::text(packet::disconnect(joined))).await;
+                        }
+                        break;
+                    }
+                };
+                if socket.send(Message::text(frame)).await.is_err() {
+                    break;
+                }
+            }
+            _ = ping.tick() => {
+                // Any packet counts as a pong; a client that sent nothing for
+                // a whole ping window is gone.
+                if last_seen.elapsed() > liveness {
+                    break;
+                }
+                if socket.send(Message::text(packet::PING)).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+
+    hub.detach(id);
+    let _ = socket.close(None).await;
+}
+
+fn dispatch(
+    hub: &Hub,
+    id: ConnId,
+    outbox: &mpsc::UnboundedSender<Outgoing>,
+    namespace: &str,
+    name: &str,
+    payload: serde_json::Value,
+) {
+    if namespace == CLI {
+        hub.cli_event(id, outbox, name, payload);
+    } else {
+        hub.webui_event(id, name, payload);
+    }
+}
+
+/// Whether the handshake's `auth` carries the launch token.
+fn has_token(auth: &serde_json::Value) -> bool {
+    auth.get("token")
+        .and_then(serde_json::Value::as_str)
+        .is_some_and(is_token)
+}
@@ -0,0 +1,16 @@
+use tauri::AppHandle;
+
+use super::PermissionText;
+use crate::error::Result;
+use crate::protocol::PermissionRequestPayload;
+
+/// Raises an actionable notification for a permission request the webview
+/// received, with `text` in its language.
+#[tauri::command]
+pub async fn notifications_permission_request(
+    app: AppHandle,
+    request: PermissionRequestPayload,
+    text: PermissionText,
+) -> Result<()> {
+    super::notify(&app, request, text).await
+}
@@ -0,0 +1,138 @@
+//! xdg notifications over D-Bus. The server reports the chosen action id,
+//! `default` for the body. One session bus connection serves every
+//! notification: a single task listens for `ActionInvoked` and
+//! `NotificationClosed` and answers the notification they name, so nothing
+//! waits while a notification is on screen.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use futures_util::StreamExt;
+use tauri::{AppHandle, Runtime};
+use tokio::sync::OnceCell;
+use zbus::zvariant::Value;
+use zbus::Connection;
+
+use super::{PermissionNotification, Response, OPEN_ACTION};
+use crate::error::{Error, Result};
+
+/// Stay on screen until acted on; the server closes it when dismissed.
+const NEVER_EXPIRE: i32 = 0;
+
+#[zbus::proxy(
+    interface = "org.freedesktop.Notifications",
+    default_service = "org.freedesktop.Notifications",
+    default_path = "/org/freedesktop/Notifications"
+)]
+trait Notifications {
+    #[allow(clippy::too_many_arguments)]
+    fn notify(
+        &self,
+        app_name: &str,
+        replaces_id: u32,
+        app_icon: &str,
+        summary: &str,
+        body: &str,
+        actions: &[&str],
+        hints: HashMap<&str, Value<'_>>,
+        expire_timeout: i32,
+    ) -> zbus::Result<u32>;
+
+    #[zbus(signal)]
+    fn action_invoked(&self, id: u32, action_key: &str) -> zbus::Result<()>;
+
+    #[zbus(signal)]
+    fn notification_closed(&self, id: u32, reason: u32) -> zbus::Result<()>;
+}
+
+type Responder = Box<dyn FnOnce(Response) + Send>;
+
+/// The connection, and the responders of notifications still on screen by
+/// server id.
+struct Server {
+    proxy: NotificationsProxy<'static>,
+    pending: Mutex<HashMap<u32, Responder>>,
+}
+
+static SERVER: OnceCell<Server> = OnceCell::const_new();
+
+async fn server() -> zbus::Result<&'static Server> {
+    SERVER
+        .get_or_try_init(|| async {
+            let connection = Connection::session().await?;
+            let proxy = NotificationsProxy::new(&connection).await?;
+            let actions = proxy.receive_action_invoked().await?;
+            let closed = proxy.receive_notification_closed().await?;
+            tauri::async_runtime::spawn(listen(actions, closed));
+            Ok(Server {
+                proxy,
+                pending: Mutex::default(),
+            })
+        })
+        .await
+}
+
+/// Answers each notification once: an action also closes it, and the
+/// close that follows finds no responder left.
+async fn listen(mut actions: ActionInvokedStream, mut closed: NotificationClosedStream) {
+    loop {
+        let (id, response) = tokio::select! {
+            Some(signal) = actions.next() => match signal.args() {
+                Ok(args) => (args.id, Response::from_action(Some(args.action_key))),
+                Err(_) => continue,
+            },
+            Some(signal) = closed.next() => match signal.args() {
+                Ok(args) => (args.id, Response::Dismissed),
+                Err(_) => continue,
+            },
+            else => return,
+        };
+        let responder = SERVER.get().and_then(|server| {
+            server
+                .pending
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&id)
+        });
+        if let Some(respond) = responder {
+            respond(response);
+        }
+    }
+}
+
+pub(super) async fn show<R: Runtime>(
+    app: &AppHandle<R>,
+    notification: PermissionNotification,
+    respond: impl FnOnce(Response) + Send + 'static,
+) -> Result<()> {
+    let server = server().await.map_err(notification_error)?;
+    let text = &notification.text;
+    let mut actions = vec![OPEN_ACTION, text.open.as_str()];
+    for action in &notification.actions {
+        actions.extend([action.id.as_str(), action.label.as_str()]);
+    }
+    let id = server
+        .proxy
+        .notify(
+            &app.package_info().name,
+            0,
+            "",
+            &text.title,
+            &text.body,
+            &actions,
+            HashMap::new(),
+            NEVER_EXPIRE,
+        )
+        .await
+        .map_err(notification_error)?;
+    server
+        .pending
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(id, Box::new(respond));
+    Ok(())
+}
+
+fn notification_error(err: zbus::Error) -> Error {
+    Error::Notification(err.to_string())
+}
@@ -0,0 +1,115 @@
+//! Notification Center via `mac-notification-sys`. Options go into the
+//! dropdown of the main button, which reports the chosen label back.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Once;
+
+use mac_notification_sys::{MainButton, Notification, NotificationResponse};
+use tauri::{AppHandle, Runtime};
+
+use super::{PermissionNotification, Response};
+use crate::error::{Error, Result};
+
+/// Dev builds are unbundled, so borrow Terminal's identity like the
+/// notification plugin does.
+const DEV_BUNDLE_ID: &str = "com.apple.Terminal";
+/// Notifications with options waiting for an answer at once. `send` blocks
+/// until the user acts, so each holds a thread of its own; past this the
+/// webview falls back to a plain notification.
+const MAX_WAITING: usize = 4;
+
+static SET_APPLICATION: Once = Once::new();
+static WAITING: AtomicUsize = AtomicUsize::new(0);
+
+/// One of the `MAX_WAITING` places, given back when dropped.
+struct WaitingSlot;
+
+impl WaitingSlot {
+    fn take() -> Option<Self> {
+        if WAITING.fetch_add(1, Ordering::SeqCst) < MAX_WAITING {
+            Some(Self)
+        } else {
+            WAITING.fetch_sub(1, Ordering::SeqCst);
+            None
+        }
+    }
+}
+
+impl Drop for WaitingSlot {
+    fn drop(&mut self) {
+        WAITING.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub(super) async fn show<R: Runtime>(
+    app: &AppHandle<R>,
+    notification: PermissionNotification,
+    respond: impl FnOnce(Response) + Send + 'static,
+) -> Result<()> {
+    let bundle_id = if tauri::is_dev() {
+        DEV_BUNDLE_ID.to_owned()
+    } else {
+        app.config().identifier.clone()
+    };
+    // Without options there is nothing to answer, so nothing to wait for.
+    let slot = if notification.actions.is_empty() {
+        None
+    } else {
+        Some(WaitingSlot::take().ok_or_else(|| {
+            Error::Notification("too many notifications are waiting for an answer".into())
+        })?)
+    };
+    let waits = slot.is_some();
+    let send = move || {
+        SET_APPLICATION.call_once(|| {
+            let _ = mac_notification_sys::set_application(&bundle_id);
+        });
+        // The bridge joins dropdown labels with commas.
+        let labels: Vec<String> = notification
+            .actions
+            .iter()
+            .map(|action| action.label.replace(',', " "))
+            .collect();
+        let labels: Vec<&str> = labels.iter().map(String::as_str).collect();
+
+        let mut toast = Notification::new();
+        toast
+            .title(&notification.text.title)
+            .message(&notification.text.body)
+            .close_button(&notification.text.dismiss)
+            .wait_for_click(waits);
+        match labels.as_slice() {
+            [] => {}
+            [label] => {
+                toast.main_button(MainButton::SingleAction(label));
+            }
+            labels => {
+                toast.main_button(MainButton::DropdownActions(
+                    &notification.text.respond,
+                    labels,
+                ));
+            }
+        }
+        let response = match toast.send() {
+            Ok(NotificationResponse::Click) => Response::Open,
+            Ok(NotificationResponse::ActionButton(label)) => {
+                let action = labels
+                    .iter()
+                    .position(|candidate| *candidate == label)
+                    .map(|index| notification.actions[index].id.as_str());
+                Response::from_action(action)
+            }
+            _ => Response::Dismissed,
+        };
+        drop(slot);
+        respond(response);
+    };
+    if waits {
+        std::thread::Builder::new()
+            .name("notification".into())
+            .spawn(send)?;
+    } else {
+        tauri::async_runtime::spawn_blocking(send);
+    }
+    Ok(())
+}
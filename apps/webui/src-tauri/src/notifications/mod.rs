@@ -0,0 +1,248 @@
+//! Native desktop notifications for permission requests. Each notification
+//! carries one button per offered `PermissionOption`; picking one answers the
+//! request from Rust, clicking the body opens the session. The webview asks
+//! for them with its decrypted request and text in its language, and falls
+//! back to the plugin notification when this fails or on mobile.
+
+pub mod commands;
+#[cfg(target_os = "linux")]
+mod linux;
+#[cfg(target_os = "macos")]
+mod macos;
+#[cfg(windows)]
+mod windows;
+
+#[cfg(target_os = "linux")]
+use self::linux as platform;
+#[cfg(target_os = "macos")]
+use self::macos as platform;
+#[cfg(windows)]
+use self::windows as platform;
+
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use serde::Deserialize;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::deep_link::{self, DeepLinkRoute};
+use crate::error::Result;
+use crate::gateway::GatewayState;
+use crate::protocol::PermissionRequestPayload;
+use crate::single_instance::focus_main_window;
+
+/// Action id of a click on the notification body (the xdg default action).
+const OPEN_ACTION: &str = "default";
+const OPTION_ACTION_PREFIX: &str = "option:";
+
+/// Notification text in the webview's language.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionText {
+    pub title: String,
+    pub body: String,
+    /// The action that opens the session, where the body has no click.
+    pub open: String,
+    /// The close button and the option dropdown on macOS.
+    pub dismiss: String,
+    pub respond: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Action {
+    id: String,
+    label: String,
+}
+
+#[derive(Debug, Clone)]
+struct PermissionNotification {
+    text: PermissionText,
+    actions: Vec<Action>,
+}
+
+impl PermissionNotification {
+    fn new(request: &PermissionRequestPayload, text: PermissionText) -> Self {
+        let actions = request
+            .options
+            .iter()
+            .map(|option| Action {
+                id: format!("{OPTION_ACTION_PREFIX}{}", option.option_id),
+                label: option.name.clone(),
+            })
+            .collect();
+        Self { text, actions }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Response {
+    Open,
+    Select(String),
+    Dismissed,
+}
+
+impl Response {
+    /// Maps a backend action id; anything unknown counts as dismissed.
+    fn from_action(action: Option<&str>) -> Self {
+        match action {
+            Some(OPEN_ACTION) => Self::Open,
+            Some(action) => action
+                .strip_prefix(OPTION_ACTION_PREFIX)
+                .map_or(Self::Dismissed, |id| Self::Select(id.to_owned())),
+            None => Self::Dismissed,
+        }
+    }
+}
+
+/// The requests a notification was raised for. Every webview reports the
+/// permission requests it receives, and replays them when it reloads.
+#[derive(Default)]
+pub struct Notifications {
+    shown: Mutex<BTreeSet<(String, String)>>,
+}
+
+impl Notifications {
+    /// Records `request` and returns whether it is new. Requests that are no
+    /// longer `pending` are forgotten first.
+    fn first_time(
+        &self,
+        request: &PermissionRequestPayload,
+        pending: &[PermissionRequestPayload],
+    ) -> bool {
+        let mut shown = self.shown.lock().unwrap_or_else(|e| e.into_inner());
+        shown.retain(|(session_id, request_id)| {
+            pending.iter().any(|pending| {
+                &pending.session_id == session_id && &pending.request_id == request_id
+            })
+        });
+        shown.insert((request.session_id.clone(), request.request_id.clone()))
+    }
+}
+
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    app.manage(Notifications::default());
+}
+
+/// Raises a notification for a permission request unless the main window
+/// is focused or one was already raised for it. Errors when the OS refuses,
+/// so the webview can fall back to a plain notification.
+async fn notify<R: Runtime>(
+    app: &AppHandle<R>,
+    request: PermissionRequestPayload,
+    text: PermissionText,
+) -> Result<()> {
+    let focused = app
+        .get_webview_window("main")
+        .and_then(|window| window.is_focused().ok())
+        .unwrap_or(false);
+    let pending = app.state::<GatewayState>().snapshot().pending_permissions;
+    if focused || !app.state::<Notifications>().first_time(&request, &pending) {
+        return Ok(());
+    }
+    let notification = PermissionNotification::new(&request, text);
+    let handle = app.clone();
+    platform::show(app, notification, move |response| {
+        respond(&handle, &request, response);
+    })
+    .await
+}
+
+fn respond<R: Runtime>(app: &AppHandle<R>, request: &PermissionRequestPayload, response: Response) {
+    match response {
+        Response::Open => {
+            focus_main_window(app);
+            deep_link::dispatch(
+                app,
+                DeepLinkRoute::Session {
+                    session_id: request.session_id.clone(),
+                    seq: None,
+                },
+            );
+        }
+        Response::Select(option_id) => {
+            let Some(decision) = request.select(&option_id) else {
+                return;
+            };
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let gateway = app.state::<GatewayState>();
+                // Already answered from the webview, the tray or another device.
+                let pending = gateway
+                    .snapshot()
+                    .pending_permissions
+                    .iter()
+                    .any(|pending| {
+                        pending.session_id == decision.session_id
+                            && pending.request_id == decision.request_id
+                    });
+                if pending {
+                    let _ = gateway.respond_to_permission(&decision).await;
+                }
+            });
+        }
+        Response::Dismissed => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn text() -> PermissionText {
+        serde_json::from_value(json!({
+            "title": "重构：需要权限确认",
+            "body": "工具：Edit main.rs",
+            "open": "打开",
+            "dismiss": "忽略",
+            "respond": "回应"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn builds_one_action_per_option() {
+        let request: PermissionRequestPayload = serde_json::from_value(json!({
+            "sessionId": "s1",
+            "requestId": "r1",
+            "options": [
+                { "optionId": "a", "name": "Allow", "kind": "allow_once" },
+                { "optionId": "r", "name": "Reject", "kind": "reject_once" }
+            ],
+            "toolCall": { "title": "Edit main.rs" }
+        }))
+        .unwrap();
+        let notification = PermissionNotification::new(&request, text());
+        assert_eq!(notification.text.title, "重构：需要权限确认");
+        let ids: Vec<_> = notification.actions.iter().map(|a| a.id.as_str()).collect();
+        assert_eq!(ids, ["option:a", "option:r"]);
+    }
+
+    #[test]
+    fn shows_each_pending_request_once() {
+        let request = |id: &str| -> PermissionRequestPayload {
+            serde_json::from_value(json!({
+                "sessionId": "s1", "requestId": id, "options": []
+            }))
+            .unwrap()
+        };
+        let notifications = Notifications::default();
+        let pending = [request("r1")];
+        assert!(notifications.first_time(&request("r1"), &pending));
+        assert!(!notifications.first_time(&request("r1"), &pending));
+        // Answered and then asked again under the same id.
+        assert!(notifications.first_time(&request("r2"), &[request("r2")]));
+        assert!(notifications.first_time(&request("r1"), &[request("r1")]));
+    }
+
+    #[test]
+    fn parses_backend_actions() {
+        assert_eq!(Response::from_action(Some("default")), Response::Open);
+        assert_eq!(
+            Response::from_action(Some("option:allow:once")),
+            Response::Select("allow:once".to_owned())
+        );
+        assert_eq!(Response::from_action(Some("__closed")), Response::Dismissed);
+        assert_eq!(Response::from_action(None), Response::Dismissed);
+    }
+}
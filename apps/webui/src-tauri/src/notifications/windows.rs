@@ -0,0 +1,59 @@
+//! WinRT toasts. Buttons carry the action id as their activation argument;
+//! a click on the body activates with no argument.
+
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, Runtime};
+use tauri_winrt_notification::{Scenario, Toast};
+
+use super::{PermissionNotification, Response, OPEN_ACTION};
+use crate::error::{Error, Result};
+
+pub(super) async fn show<R: Runtime>(
+    app: &AppHandle<R>,
+    notification: PermissionNotification,
+    respond: impl FnOnce(Response) + Send + 'static,
+) -> Result<()> {
+    let mut toast = Toast::new(&app_id(app))
+        .title(&notification.text.title)
+        .text1(&notification.text.body)
+        .scenario(Scenario::Reminder);
+    for action in &notification.actions {
+        toast = toast.add_button(&action.label, &action.id);
+    }
+
+    // Activation and dismissal are exclusive; whichever fires first answers.
+    let respond = Arc::new(Mutex::new(Some(respond)));
+    let dismissed = Arc::clone(&respond);
+    toast
+        .on_activated(move |action| {
+            if let Some(respond) = respond.lock().unwrap_or_else(|e| e.into_inner()).take() {
+                respond(Response::from_action(Some(
+                    action.as_deref().unwrap_or(OPEN_ACTION),
+                )));
+            }
+            Ok(())
+        })
+        .on_dismissed(move |_| {
+            if let Some(respond) = dismissed.lock().unwrap_or_else(|e| e.into_inner()).take() {
+                respond(Response::Dismissed);
+            }
+            Ok(())
+        })
+        .show()
+        .map_err(|err| Error::Notification(err.to_string()))
+}
+
+/// Unpackaged dev builds have no registered AppUserModelID, so fall back to
+/// PowerShell's like the notification plugin does.
+fn app_id<R: Runtime>(app: &AppHandle<R>) -> String {
+    let dev = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(|dir| dir.to_path_buf()))
+        .is_some_and(|dir| dir.ends_with("target/debug") || dir.ends_with("target/release"));
+    if dev {
+        Toast::POWERSHELL_APP_ID.to_owned()
+    } else {
+        app.config().identifier.clone()
+    }
+}
@@ -0,0 +1,11 @@
+use super::OutlineItem;
+use crate::error::{Error, Result};
+
+/// The outline of a previewed file, as `OutlineItem[]`. `language` is an
+/// `OutlineLanguage`; others are an error.
+#[tauri::command]
+pub async fn outline_extract(language: String, content: String) -> Result<Vec<OutlineItem>> {
+    tauri::async_runtime::spawn_blocking(move || super::extract(&language, &content))
+        .await
+        .map_err(|err| Error::Outline(err.to_string()))?
+}
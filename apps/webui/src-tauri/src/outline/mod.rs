@@ -0,0 +1,450 @@
+//! The symbol outline of the file preview, parsed with tree-sitter grammars
+//! built into the app instead of WASM grammars in the webview. The queries
+//! are the `.scm` files the webview's fallback runs too, so both produce
+//! the same outline.
+
+pub mod commands;
+
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock, Mutex};
+
+use serde::Serialize;
+use tree_sitter::{Language, Node, Parser, Query, QueryCursor, StreamingIterator};
+
+use crate::error::{Error, Result};
+
+const BASH_QUERY: &str = include_str!("../../../src/lib/outline-queries/bash.scm");
+const C_QUERY: &str = include_str!("../../../src/lib/outline-queries/c.scm");
+const CPP_QUERY: &str = include_str!("../../../src/lib/outline-queries/cpp.scm");
+const CSHARP_QUERY: &str = include_str!("../../../src/lib/outline-queries/csharp.scm");
+const GO_QUERY: &str = include_str!("../../../src/lib/outline-queries/go.scm");
+const JAVA_QUERY: &str = include_str!("../../../src/lib/outline-queries/java.scm");
+const JAVASCRIPT_QUERY: &str = include_str!("../../../src/lib/outline-queries/javascript.scm");
+const PHP_QUERY: &str = include_str!("../../../src/lib/outline-queries/php.scm");
+const PYTHON_QUERY: &str = include_str!("../../../src/lib/outline-queries/python.scm");
+const RUBY_QUERY: &str = include_str!("../../../src/lib/outline-queries/ruby.scm");
+const RUST_QUERY: &str = include_str!("../../../src/lib/outline-queries/rust.scm");
+/// Extends [`JAVASCRIPT_QUERY`].
+const TYPESCRIPT_QUERY: &str = include_str!("../../../src/lib/outline-queries/typescript.scm");
+
+/// The kinds a `@definition.<kind>` capture may name.
+const KINDS: [&str; 13] = [
+    "class",
+    "method",
+    "function",
+    "interface",
+    "type",
+    "enum",
+    "module",
+    "constant",
+    "property",
+    "field",
+    "constructor",
+    "variable",
+    "struct",
+];
+
+/// An `OutlineItem` of `CodePreview.tsx`. Indexes are in UTF-16 code
+/// units, as the preview slices JavaScript strings; lines start at 1.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutlineItem {
+    pub id: String,
+    pub label: String,
+    pub kind: &'static str,
+    pub start_index: usize,
+    pub end_index: usize,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub children: Vec<OutlineItem>,
+}
+
+/// Queries compile once per language, as they do in the webview.
+static QUERIES: LazyLock<Mutex<HashMap<&'static str, Arc<Query>>>> = LazyLock::new(Mutex::default);
+
+/// The grammar of an `OutlineLanguage` of the preview, and the queries
+/// its outline runs, joined.
+fn grammar(language: &str) -> Option<(&'static str, Language, &'static [&'static str])> {
+    let typescript = &[JAVASCRIPT_QUERY, TYPESCRIPT_QUERY];
+    let (key, grammar, queries): (_, _, &[&str]) = match language {
+        "bash" => ("bash", tree_sitter_bash::LANGUAGE, &[BASH_QUERY]),
+        "c" => ("c", tree_sitter_c::LANGUAGE, &[C_QUERY]),
+        "cpp" => ("cpp", tree_sitter_cpp::LANGUAGE, &[CPP_QUERY]),
+        "csharp" => ("csharp", tree_sitter_c_sharp::LANGUAGE, &[CSHARP_QUERY]),
+        "go" => ("go", tree_sitter_go::LANGUAGE, &[GO_QUERY]),
+        "java" => ("java", tree_sitter_java::LANGUAGE, &[JAVA_QUERY]),
+        "javascript" => (
+            "javascript",
+            tree_sitter_javascript::LANGUAGE,
+            &[JAVASCRIPT_QUERY],
+        ),
+        "php" => ("php", tree_sitter_php::LANGUAGE_PHP, &[PHP_QUERY]),
+        "python" => ("python", tree_sitter_python::LANGUAGE, &[PYTHON_QUERY]),
+        "ruby" => ("ruby", tree_sitter_ruby::LANGUAGE, &[RUBY_QUERY]),
+        "rust" => ("rust", tree_sitter_rust::LANGUAGE, &[RUST_QUERY]),
+        "typescript" => (
+            "typescript",
+            tree_sitter_typescript::LANGUAGE_TYPESCRIPT,
+            typescript,
+        ),
+        "tsx" => ("tsx", tree_sitter_typescript::LANGUAGE_TSX, typescript),
+        _ => return None,
+    };
+    Some((key, grammar.into(), queries))
+}
+
+fn query(key: &'static str, language: &Language, sources: &[&str]) -> Result<Arc<Query>> {
+    let mut queries = QUERIES.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(query) = queries.get(key) {
+        return Ok(query.clone());
+    }
+    let query = Query::new(language, &sources.join("\n"))
+        .map_err(|err| Error::Outline(format!("Invalid {key} query: {err}")))?;
+    let query = Arc::new(query);
+    queries.insert(key, query.clone());
+    Ok(query)
+}
+
+/// The outline of `content` as `language`, nested by containment and in
+/// source order.
+pub fn extract(language: &str, content: &str) -> Result<Vec<OutlineItem>> {
+    let (key, grammar, sources) = grammar(language)
+        .ok_or_else(|| Error::Outline(format!("Unsupported language: {language}")))?;
+    let query = query(key, &grammar, sources)?;
+    let mut parser = Parser::new();
+    parser
+        .set_language(&grammar)
+        .map_err(|err| Error::Outline(err.to_string()))?;
+    let tree = parser
+        .parse(content, None)
+        .ok_or_else(|| Error::Outline("Parsing was cancelled".into()))?;
+    let offsets = Utf16Offsets::new(content);
+    let names = query.capture_names();
+    let mut items = Vec::new();
+    let mut seen = HashSet::new();
+    let mut cursor = QueryCursor::new();
+    let mut matches = cursor.matches(&query, tree.root_node(), content.as_bytes());
+    while let Some(found) = matches.next() {
+        let capture = |wanted: &dyn Fn(&str) -> bool| {
+            found
+                .captures
+                .iter()
+                .find(|capture| wanted(names[capture.index as usize]))
+        };
+        let (Some(definition), Some(name)) = (
+            capture(&|name| name.starts_with("definition.")),
+            capture(&|name| name == "name"),
+        ) else {
+            continue;
+        };
+        let kind = names[definition.index as usize].trim_start_matches("definition.");
+        let Some(kind) = KINDS.into_iter().find(|known| *known == kind) else {
+            continue;
+        };
+        let label = normalize_label(&content[name.node.byte_range()]);
+        if label.is_empty() {
+            continue;
+        }
+        let item = outline_item(definition.node, kind, label, &offsets);
+        if seen.insert(item.id.clone()) {
+            items.push(item);
+        }
+    }
+    items.sort_by(|a, b| {
+        a.start_index
+            .cmp(&b.start_index)
+            .then(b.end_index.cmp(&a.end_index))
+    });
+    Ok(nest(&mut items.into_iter().peekable(), None))
+}
+
+fn outline_item(
+    node: Node<'_>,
+    kind: &'static str,
+    label: String,
+    offsets: &Utf16Offsets<'_>,
+) -> OutlineItem {
+    let start_index = offsets.at(node.start_byte(), node.start_position().row);
+    let end_index = offsets.at(node.end_byte(), node.end_position().row);
+    OutlineItem {
+        id: format!("{kind}-{start_index}-{end_index}"),
+        label,
+        kind,
+        start_index,
+        end_index,
+        start_line: node.start_position().row + 1,
+        end_line: node.end_position().row + 1,
+        children: Vec::new(),
+    }
+}
+
+fn normalize_label(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Puts each item under the closest earlier one it starts inside, as
+/// `buildOutlineTree` does; `items` are sorted by start, longest first.
+fn nest(
+    items: &mut std::iter::Peekable<std::vec::IntoIter<OutlineItem>>,
+    parent_end: Option<usize>,
+) -> Vec<OutlineItem> {
+    let mut level = Vec::new();
+    while let Some(mut item) =
+        items.next_if(|item| parent_end.is_none_or(|end| item.start_index < end))
+    {
+        item.children = nest(items, Some(item.end_index));
+        level.push(item);
+    }
+    level
+}
+
+/// Converts byte offsets to UTF-16 offsets, counting from the start of the
+/// offset's line.
+struct Utf16Offsets<'a> {
+    content: &'a str,
+    /// The byte and UTF-16 offsets at which each line starts.
+    lines: Vec<(usize, usize)>,
+}
+
+impl<'a> Utf16Offsets<'a> {
+    fn new(content: &'a str) -> Self {
+        let mut lines = vec![(0, 0)];
+        let mut utf16 = 0;
+        for (byte, char) in content.char_indices() {
+            utf16 += char.len_utf16();
+            if char == '\n' {
+                lines.push((byte + 1, utf16));
+            }
+        }
+        Self { content, lines }
+    }
+
+    fn at(&self, byte: usize, row: usize) -> usize {
+        let (line_byte, line_utf16) = self.lines.get(row).copied().unwrap_or((0, 0));
+        line_utf16
+            + self
+                .content
+                .get(line_byte..byte)
+                .map_or(0, |text| text.encode_utf16().count())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `kind label` per item, children indented under their parent.
+    fn outline(language: &str, content: &str) -> Vec<String> {
+        fn flatten(items: &[OutlineItem], depth: usize, lines: &mut Vec<String>) {
+            for item in items {
+                lines.push(format!(
+                    "{}{} {}",
+                    "  ".repeat(depth),
+                    item.kind,
+                    item.label
+                ));
+                flatten(&item.children, depth + 1, lines);
+            }
+        }
+        let mut lines = Vec::new();
+        flatten(&extract(language, content).unwrap(), 0, &mut lines);
+        lines
+    }
+
+    #[test]
+    fn outlines_bash() {
+        assert_eq!(
+            outline("bash", "build() {\n  make\n}\nfunction deploy { :; }\n"),
+            ["function build", "function deploy"]
+        );
+    }
+
+    #[test]
+    fn outlines_c() {
+        let source = "typedef struct { int x; } Point;\nstruct node { int v; };\nchar *name(void) { return 0; }\nint main(void) { return 0; }\n";
+        assert_eq!(
+            outline("c", source),
+            [
+                "struct Point",
+                "struct node",
+                "function name",
+                "function main"
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_cpp() {
+        let source =
+            "class Shape {\n  void draw();\n};\nenum Color { Red };\nint area() { return 0; }\n";
+        assert_eq!(
+            outline("cpp", source),
+            [
+                "class Shape",
+                "  function draw",
+                "enum Color",
+                "function area"
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_csharp() {
+        let source = "interface IShape {}\nclass Circle {\n  int radius;\n  Circle() {}\n  double Area { get; }\n  void Draw() {}\n}\n";
+        assert_eq!(
+            outline("csharp", source),
+            [
+                "interface IShape",
+                "class Circle",
+                "  field radius",
+                "  constructor Circle",
+                "  property Area",
+                "  method Draw",
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_go() {
+        let source = "package main\n\ntype Point struct{ X int }\ntype Shape interface{ Area() int }\nfunc (p Point) Area() int { return 0 }\nfunc main() {}\n";
+        assert_eq!(
+            outline("go", source),
+            [
+                "struct Point",
+                "interface Shape",
+                "method Area",
+                "function main"
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_java() {
+        let source = "class Circle {\n  int radius;\n  Circle() {}\n  int area() { return 0; }\n  enum Unit { CM }\n}\ninterface Shape {}\n";
+        assert_eq!(
+            outline("java", source),
+            [
+                "class Circle",
+                "  field radius",
+                "  constructor Circle",
+                "  method area",
+                "  enum Unit",
+                "interface Shape",
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_javascript() {
+        let source = "class Store {\n  constructor() {}\n  load() {}\n}\nconst save = () => {};\nfunction* ids() {}\nexports.run = function () {};\n";
+        assert_eq!(
+            outline("javascript", source),
+            [
+                "class Store",
+                "  method load",
+                "function save",
+                "function ids",
+                "function run",
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_php() {
+        let source = "<?php\ninterface Shape {}\ntrait Named {}\nclass Circle {\n  public function area() {}\n}\nfunction main() {}\n$run = function () {};\n";
+        assert_eq!(
+            outline("php", source),
+            [
+                "interface Shape",
+                "class Named",
+                "class Circle",
+                "  method area",
+                "function main",
+                "function $run",
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_python() {
+        let source = "LIMIT = 3\n\nclass Circle:\n    def area(self):\n        return 0\n";
+        assert_eq!(
+            outline("python", source),
+            ["variable LIMIT", "class Circle", "  function area"]
+        );
+    }
+
+    #[test]
+    fn outlines_ruby() {
+        let source = "module Shapes\n  class Circle\n    def self.unit\n    end\n  end\nend\n\ndescribe Circle do\nend\n";
+        assert_eq!(
+            outline("ruby", source),
+            [
+                "module Shapes",
+                "  class Circle",
+                "    method unit",
+                "method describe",
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_rust() {
+        let source = "mod shapes {\n    pub struct Circle;\n    impl Circle {\n        fn area(&self) {}\n    }\n}\ntrait Shape {\n    fn area(&self);\n}\nenum Unit { Cm }\n";
+        assert_eq!(
+            outline("rust", source),
+            [
+                "module shapes",
+                "  struct Circle",
+                "  class Circle",
+                "    function area",
+                "interface Shape",
+                "  function area",
+                "enum Unit",
+            ]
+        );
+    }
+
+    #[test]
+    fn outlines_typescript() {
+        let source = "interface Shape { area(): number }\ntype Id = string;\nenum Unit { Cm }\nmodule Shapes {\n  export function area() {}\n}\n";
+        assert_eq!(
+            outline("typescript", source),
+            [
+                "interface Shape",
+                "type Id",
+                "enum Unit",
+                "module Shapes",
+                "  function area",
+            ]
+        );
+        assert_eq!(
+            outline("tsx", "const App = () => <div />;\n"),
+            ["function App"]
+        );
+    }
+
+    #[test]
+    fn indexes_in_utf16_code_units() {
+        let items = extract("python", "# 📦 ünits\ndef area():\n    pass\n").unwrap();
+        let [item] = items.as_slice() else {
+            panic!("expected one item, got {items:?}");
+        };
+        // "# 📦 ünits\n" is 14 bytes but 11 UTF-16 code units
+        assert_eq!(
+            (
+                item.start_index,
+                item.end_index,
+                item.start_line,
+                item.end_line
+            ),
+            (11, 31, 2, 3)
+        );
+        assert_eq!(item.id, "function-11-31");
+    }
+
+    #[test]
+    fn rejects_unknown_languages() {
+        assert!(matches!(extract("cobol", ""), Err(Error::Outline(_))));
+    }
+}
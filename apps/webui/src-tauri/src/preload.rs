@@ -0,0 +1,168 @@
+//! Persisted settings, injected into each app webview before any page script
+//! runs. The main window is created here rather than from `tauri.conf.json` so
+//! its initialization script can carry the entries read at startup; the
+//! webview storage adapter reads them synchronously on the first tick. Windows
+//! opened later get the entries as they are when the window is created.
+//!
+//! Initialization scripts are fixed once the window exists. The webview
+//! snapshots its entries into `sessionStorage` on `pagehide`, and on reload
+//! the script fills in keys it does not carry from that snapshot; injected
+//! values always win. Credentials are never preloaded: the gateway token is
+//! read through the settings commands, so it never lands in page storage.
+//! The local gateway's launch token is injected on its own, outside the
+//! snapshot.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+use tauri::{App, AppHandle, Runtime, WebviewWindowBuilder};
+
+use crate::error::{Error, Result};
+use crate::settings::{self, SettingsStore};
+
+const MAIN_WINDOW: &str = "main";
+/// Global and `sessionStorage` key shared with `preloaded-settings.ts`.
+const PRELOAD_KEY: &str = "__MOBVIBE_PRELOAD__";
+/// Global shared with `local-gateway.ts`.
+const LOCAL_GATEWAY_KEY: &str = "__MOBVIBE_LOCAL_GATEWAY__";
+const PRELOADED_SCOPES: &[&str] = &[settings::APP_STATE, settings::GATEWAY];
+/// Where the bundled frontend is served from on each platform.
+const APP_ORIGINS: &[&str] = &[
+    "tauri://localhost",
+    "http://tauri.localhost",
+    "https://tauri.localhost",
+];
+
+/// Creates the main window from its config with the preload script attached.
+/// Must run before anything looks the window up.
+pub fn create_main_window(app: &App, settings: &SettingsStore) -> Result<()> {
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|window| window.label == MAIN_WINDOW)
+        .ok_or_else(|| Error::Tauri(tauri::Error::WindowNotFound))?;
+
+    WebviewWindowBuilder::from_config(app.handle(), config)?
+        .initialization_script(initialization_script(app.handle(), settings)?)
+        .build()?;
+    Ok(())
+}
+
+/// The script that hands the current entries to a new app window.
+pub fn initialization_script<R: Runtime>(
+    app: &AppHandle<R>,
+    settings: &SettingsStore,
+) -> Result<String> {
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    let local_gateway = crate::local_gateway::webview_auth();
+    #[cfg(any(target_os = "android", target_os = "ios"))]
+    let local_gateway = Value::Null;
+    script(&snapshot(settings)?, &local_gateway, &app_origins(app))
+}
+
+/// Origins the app's own pages load from, including the dev server in
+/// development builds.
+pub fn app_origins<R: Runtime>(app: &AppHandle<R>) -> Vec<String> {
+    let mut origins: Vec<String> = APP_ORIGINS
+        .iter()
+        .map(|&origin| origin.to_owned())
+        .collect();
+    if let Some(dev_url) = app
+        .config()
+        .build
+        .dev_url
+        .as_ref()
+        .filter(|_| tauri::is_dev())
+    {
+        origins.push(dev_url.origin().ascii_serialization());
+    }
+    origins
+}
+
+fn snapshot(settings: &SettingsStore) -> Result<BTreeMap<&'static str, BTreeMap<String, Value>>> {
+    PRELOADED_SCOPES
+        .iter()
+        .map(|&scope| Ok((scope, settings.entries(scope)?)))
+        .collect()
+}
+
+/// Only pages served by the app get the entries and the token; they must
+/// not reach a remote page the window navigates to.
+fn script(
+    snapshot: &BTreeMap<&'static str, BTreeMap<String, Value>>,
+    local_gateway: &Value,
+    origins: &[String],
+) -> Result<String> {
+    let key = serde_json::to_string(PRELOAD_KEY)?;
+    let local_gateway_key = serde_json::to_string(LOCAL_GATEWAY_KEY)?;
+    Ok(format!(
+        "(function () {{
+  if (!{origins}.includes(window.location.origin)) return;
+  var state = {snapshot};
+  try {{
+    var saved = JSON.parse(window.sessionStorage.getItem({key}) || "{{}}");
+    Object.keys(state).forEach(function (scope) {{
+      state[scope] = Object.assign({{}}, saved[scope], state[scope]);
+    }});
+  }} catch (_) {{}}
+  Object.defineProperty(window, {key}, {{ value: state, configurable: true }});
+  Object.defineProperty(window, {local_gateway_key}, {{ value: {local_gateway} }});
+}})();",
+        origins = serde_json::to_string(origins)?,
+        snapshot = serde_json::to_string(snapshot)?,
+        local_gateway = serde_json::to_string(local_gateway)?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn embeds_entries_as_json() {
+        let mut snapshot = BTreeMap::new();
+        snapshot.insert(
+            settings::APP_STATE,
+            BTreeMap::from([("ui".to_owned(), json!("{\"a\":\"</script>\"}"))]),
+        );
+        let local_gateway = json!({ "token": "t0k", "port": 3005 });
+        let script = script(&snapshot, &local_gateway, &["tauri://localhost".to_owned()]).unwrap();
+        assert!(script.contains(r#"["tauri://localhost"].includes(window.location.origin)"#));
+        assert!(script.contains(r#"var state = {"app-state":{"ui":"{\"a\":\"</script>\"}"}};"#));
+        assert!(script.contains(r#"getItem("__MOBVIBE_PRELOAD__")"#));
+        assert!(script.contains("Object.assign({}, saved[scope], state[scope])"));
+        assert!(script.contains(
+            r#"defineProperty(window, "__MOBVIBE_LOCAL_GATEWAY__", { value: {"token":"t0k","port":3005} })"#
+        ));
+    }
+
+    #[test]
+    fn never_preloads_credentials() {
+        assert!(!PRELOADED_SCOPES.contains(&settings::AUTH));
+        assert!(!PRELOADED_SCOPES.contains(&settings::LEGACY_SECRETS));
+
+        let dir = std::env::temp_dir().join(format!("mobvibe-preload-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(
+            dir.join("app-state.json"),
+            r#"{
+                "ui": "{}",
+                "mobvibe_e2ee_secrets": [{ "secret": "c2VjcmV0", "fingerprint": "f", "addedAt": 1 }],
+                "mobvibe_e2ee_master_secret": "c2VjcmV0"
+            }"#,
+        )
+        .unwrap();
+        let store = SettingsStore::open(&dir).unwrap();
+        let snapshot = snapshot(&store).unwrap();
+        assert_eq!(
+            snapshot[settings::APP_STATE].keys().collect::<Vec<_>>(),
+            ["ui"]
+        );
+        assert_eq!(store.entries(settings::LEGACY_SECRETS).unwrap().len(), 2);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,18 @@
+use super::{NormalizeOptions, NormalizedImage};
+use crate::error::{Error, Result};
+
+/// Normalizes a base64 image for a prompt. Async so the decoding and
+/// encoding run off the main thread.
+#[tauri::command]
+pub async fn prompt_image_normalize(
+    data: String,
+    options: Option<NormalizeOptions>,
+) -> Result<NormalizedImage> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let bytes =
+            base64::Engine::decode(&base64::engine::general_purpose::STANDARD, data.trim())?;
+        super::normalize(&bytes, &options.unwrap_or_default())
+    })
+    .await
+    .map_err(|err| Error::Image(err.to_string()))?
+}
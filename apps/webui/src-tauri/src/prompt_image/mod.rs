@@ -0,0 +1,280 @@
+//! Prompt images are normalized here before they join a prompt, on desktop
+//! and mobile alike: the image is decoded (turned upright by its EXIF
+//! orientation), downscaled to the longest edge allowed, and encoded again
+//! from pixels, which leaves EXIF, GPS and every other metadata chunk
+//! behind. Encodings are tried from best to smallest until one fits the
+//! byte limit, the same ladder `prompt-images.ts` walks with a canvas in
+//! the browser. The limits mirror `@mobvibe/shared`'s `PROMPT_IMAGE_*`.
+
+pub mod commands;
+
+use std::io::Cursor;
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageFormat, ImageReader, Limits, RgbImage};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Error, Result};
+
+/// `PROMPT_IMAGE_MAX_EDGE`.
+pub const DEFAULT_MAX_EDGE: u32 = 1600;
+/// `PROMPT_IMAGE_MAX_BYTES`.
+pub const DEFAULT_MAX_BYTES: usize = 512 * 1024;
+/// Edges below this would leave nothing for the agent to read.
+const MIN_EDGE: u32 = 64;
+/// Decoding refuses anything larger, so a crafted file cannot exhaust
+/// memory before it is scaled down.
+const MAX_SOURCE_EDGE: u32 = 16_384;
+const MAX_SOURCE_BYTES: usize = 40 * 1024 * 1024;
+const SCALES: [f64; 4] = [1.0, 0.85, 0.7, 0.55];
+const JPEG_QUALITIES: [u8; 6] = [92, 82, 72, 60, 50, 40];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Png,
+    Jpeg,
+    Webp,
+}
+
+impl OutputFormat {
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Webp => "image/webp",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NormalizeOptions {
+    /// Longest edge of the result, in pixels.
+    pub max_edge: Option<u32>,
+    /// Largest encoded size, in bytes.
+    pub max_bytes: Option<usize>,
+    /// Forces one format; by default the source's format is tried first.
+    pub format: Option<OutputFormat>,
+}
+
+/// An ACP image content block with the size it ended up at.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", rename = "image", rename_all = "camelCase")]
+pub struct NormalizedImage {
+    pub data: String,
+    pub mime_type: &'static str,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Normalizes an encoded PNG, JPEG, WebP or GIF. Animated GIFs keep only
+/// their first frame.
+pub fn normalize(bytes: &[u8], options: &NormalizeOptions) -> Result<NormalizedImage> {
+    if bytes.len() > MAX_SOURCE_BYTES {
+        return Err(Error::Image(format!(
+            "Images must be {} MiB or smaller",
+            MAX_SOURCE_BYTES / 1024 / 1024
+        )));
+    }
+    let max_edge = options
+        .max_edge
+        .unwrap_or(DEFAULT_MAX_EDGE)
+        .clamp(MIN_EDGE, MAX_SOURCE_EDGE);
+    let max_bytes = options.max_bytes.unwrap_or(DEFAULT_MAX_BYTES);
+
+    let mut reader = ImageReader::new(Cursor::new(bytes)).with_guessed_format()?;
+    let source = reader
+        .format()
+        .ok_or_else(|| Error::Image("Unrecognized image format".into()))?;
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_EDGE);
+    limits.max_image_height = Some(MAX_SOURCE_EDGE);
+    reader.limits(limits);
+    let mut decoder = reader.into_decoder().map_err(image_error)?;
+    let orientation = decoder.orientation().map_err(image_error)?;
+    let mut image = DynamicImage::from_decoder(decoder).map_err(image_error)?;
+    image.apply_orientation(orientation);
+
+    let formats = match options.format {
+        Some(format) => vec![format],
+        None => output_formats(source)?,
+    };
+    let cap = (max_edge as f64 / image.width().max(image.height()) as f64).min(1.0);
+    for scale in SCALES {
+        let resized = resize(&image, cap * scale);
+        for &format in &formats {
+            for encoded in encodings(&resized, format)? {
+                if encoded.len() <= max_bytes {
+                    return Ok(NormalizedImage {
+                        data: STANDARD.encode(encoded),
+                        mime_type: format.mime_type(),
+                        width: resized.width(),
+                        height: resized.height(),
+                    });
+                }
+            }
+        }
+    }
+    Err(Error::Image(format!(
+        "Image exceeds {} KiB after normalization",
+        max_bytes / 1024
+    )))
+}
+
+/// The formats to try for a source format, best first.
+fn output_formats(source: ImageFormat) -> Result<Vec<OutputFormat>> {
+    use OutputFormat::{Jpeg, Png, Webp};
+    match source {
+        ImageFormat::Jpeg => Ok(vec![Jpeg, Webp]),
+        ImageFormat::Png | ImageFormat::Gif => Ok(vec![Png, Webp, Jpeg]),
+        ImageFormat::WebP => Ok(vec![Webp, Jpeg]),
+        other => Err(Error::Image(format!(
+            "Unsupported image format: {}",
+            other.to_mime_type()
+        ))),
+    }
+}
+
+fn resize(image: &DynamicImage, scale: f64) -> DynamicImage {
+    if scale >= 1.0 {
+        return image.clone();
+    }
+    let width = ((image.width() as f64 * scale).round() as u32).max(1);
+    let height = ((image.height() as f64 * scale).round() as u32).max(1);
+    image.resize_exact(width, height, FilterType::Lanczos3)
+}
+
+/// Each encoding of `image` to try in `format`, from best to smallest.
+fn encodings(image: &DynamicImage, format: OutputFormat) -> Result<Vec<Vec<u8>>> {
+    let mut out = Vec::new();
+    match format {
+        OutputFormat::Png => {
+            let mut png = Vec::new();
+            image
+                .write_with_encoder(PngEncoder::new(&mut png))
+                .map_err(image_error)?;
+            out.push(png);
+        }
+        // The encoder is lossless only, so there is one size to try.
+        OutputFormat::Webp => {
+            let mut webp = Vec::new();
+            DynamicImage::ImageRgba8(image.to_rgba8())
+                .write_with_encoder(WebPEncoder::new_lossless(&mut webp))
+                .map_err(image_error)?;
+            out.push(webp);
+        }
+        OutputFormat::Jpeg => {
+            let rgb = flatten(image);
+            for quality in JPEG_QUALITIES {
+                let mut jpeg = Vec::new();
+                rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut jpeg, quality))
+                    .map_err(image_error)?;
+                out.push(jpeg);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// JPEG has no alpha; transparent pixels become white rather than black.
+fn flatten(image: &DynamicImage) -> DynamicImage {
+    if !image.color().has_alpha() {
+        return DynamicImage::ImageRgb8(image.to_rgb8());
+    }
+    let rgba = image.to_rgba8();
+    let rgb = RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |c: u8| ((c as u16 * a as u16 + 255 * (255 - a as u16)) / 255) as u8;
+        image::Rgb([blend(r), blend(g), blend(b)])
+    });
+    DynamicImage::ImageRgb8(rgb)
+}
+
+fn image_error(err: image::ImageError) -> Error {
+    Error::Image(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbaImage};
+
+    /// A JPEG with an EXIF segment (orientation 6, i.e. rotated 90°) right
+    /// after the SOI marker.
+    fn jpeg_with_exif(width: u32, height: u32) -> Vec<u8> {
+        let pixels = RgbImage::from_fn(width, height, |x, y| Rgb([x as u8, y as u8, 128]));
+        let mut jpeg = Vec::new();
+        DynamicImage::ImageRgb8(pixels)
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut jpeg, 90))
+            .unwrap();
+        let tiff: &[u8] = &[
+            b'M', b'M', 0, 42, 0, 0, 0, 8, // header
+            0, 1, // one entry
+            0x01, 0x12, 0, 3, 0, 0, 0, 1, 0, 6, 0, 0, // orientation = 6
+            0, 0, 0, 0, // no next IFD
+        ];
+        let mut app1 = b"Exif\0\0".to_vec();
+        app1.extend_from_slice(tiff);
+        let length = (app1.len() + 2) as u16;
+        let mut out = jpeg[..2].to_vec();
+        out.extend_from_slice(&[0xFF, 0xE1]);
+        out.extend_from_slice(&length.to_be_bytes());
+        out.extend_from_slice(&app1);
+        out.extend_from_slice(&jpeg[2..]);
+        out
+    }
+
+    #[test]
+    fn rotates_downscales_and_drops_metadata() {
+        let source = jpeg_with_exif(400, 200);
+        assert!(source.windows(4).any(|w| w == b"Exif"));
+
+        let options = NormalizeOptions {
+            max_edge: Some(100),
+            ..NormalizeOptions::default()
+        };
+        let image = normalize(&source, &options).unwrap();
+        assert_eq!(image.mime_type, "image/jpeg");
+        // Upright after rotation, then scaled to the longest edge.
+        assert_eq!((image.width, image.height), (50, 100));
+        let encoded = STANDARD.decode(&image.data).unwrap();
+        assert!(!encoded.windows(4).any(|w| w == b"Exif"));
+    }
+
+    #[test]
+    fn falls_back_to_smaller_encodings_to_fit() {
+        // Noise compresses badly, so PNG cannot fit and JPEG takes over.
+        let mut seed = 7u32;
+        let noise = RgbaImage::from_fn(300, 300, |_, _| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            let [a, b, c, _] = seed.to_be_bytes();
+            image::Rgba([a, b, c, 255])
+        });
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(noise)
+            .write_with_encoder(PngEncoder::new(&mut png))
+            .unwrap();
+
+        let options = NormalizeOptions {
+            max_bytes: Some(64 * 1024),
+            ..NormalizeOptions::default()
+        };
+        let image = normalize(&png, &options).unwrap();
+        assert_eq!(image.mime_type, "image/jpeg");
+        assert!(STANDARD.decode(&image.data).unwrap().len() <= 64 * 1024);
+
+        let forced = NormalizeOptions {
+            max_bytes: Some(1024),
+            format: Some(OutputFormat::Png),
+            ..NormalizeOptions::default()
+        };
+        assert!(normalize(&png, &forced).is_err());
+        assert!(normalize(b"not an image", &NormalizeOptions::default()).is_err());
+    }
+}
@@ -0,0 +1,203 @@
+//! Rust mirrors of the wire types in `packages/shared/src/types/socket-events.ts`.
+//! Only the fields the native side inspects are typed; everything else is
+//! passed through untouched.
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEvent {
+    pub session_id: String,
+    pub machine_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incarnation_generation: Option<u64>,
+    pub revision: u64,
+    pub seq: u64,
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub protocol_message_id: Option<String>,
+    pub created_at: String,
+    pub payload: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliStatusPayload {
+    pub machine_id: String,
+    pub connected: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_count: Option<u64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// ACP `PermissionOption`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionOption {
+    pub option_id: String,
+    pub name: String,
+    /// `allow_once`, `allow_always`, `reject_once` or `reject_always`.
+    pub kind: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionRequestPayload {
+    pub session_id: String,
+    pub request_id: String,
+    pub options: Vec<PermissionOption>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call: Option<Value>,
+}
+
+impl PermissionRequestPayload {
+    /// The tool call title the agent gave, if any.
+    pub fn title(&self) -> Option<&str> {
+        self.tool_call.as_ref()?.get("title")?.as_str()
+    }
+
+    /// Picks the narrowest option of the requested polarity: `allow_once`
+    /// over `allow_always`, `reject_once` over `reject_always`. Falls back to
+    /// `cancelled` when the agent offered no matching option.
+    pub fn decide(&self, allow: bool) -> PermissionDecisionPayload {
+        let prefix = if allow { "allow" } else { "reject" };
+        let option = self
+            .options
+            .iter()
+            .find(|option| option.kind == format!("{prefix}_once"))
+            .or_else(|| {
+                self.options
+                    .iter()
+                    .find(|option| option.kind.starts_with(prefix))
+            });
+        let outcome = match option {
+            Some(option) => json!({ "outcome": "selected", "optionId": option.option_id }),
+            None => json!({ "outcome": "cancelled" }),
+        };
+        self.decision(outcome)
+    }
+
+    /// Selects one of the offered options. Returns `None` for an id the agent
+    /// did not offer.
+    pub fn select(&self, option_id: &str) -> Option<PermissionDecisionPayload> {
+        self.options
+            .iter()
+            .any(|option| option.option_id == option_id)
+            .then(|| self.decision(json!({ "outcome": "selected", "optionId": option_id })))
+    }
+
+    fn decision(&self, outcome: Value) -> PermissionDecisionPayload {
+        PermissionDecisionPayload {
+            session_id: self.session_id.clone(),
+            request_id: self.request_id.clone(),
+            outcome,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionDecisionPayload {
+    pub session_id: String,
+    pub request_id: String,
+    pub outcome: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionSummary {
+    pub session_id: String,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub revision: Option<u64>,
+    /// The session DEK, wrapped for the paired devices of an E2EE session.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrapped_dek: Option<String>,
+    pub updated_at: String,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionsChangedPayload {
+    pub added: Vec<SessionSummary>,
+    pub updated: Vec<SessionSummary>,
+    pub removed: Vec<String>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionsResponse {
+    pub sessions: Vec<SessionSummary>,
+}
+
+/// One page of `GET /acp/session/events`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEventsResponse {
+    pub session_id: String,
+    pub revision: u64,
+    pub events: Vec<SessionEvent>,
+    #[serde(default)]
+    pub next_after_seq: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(kinds: &[&str]) -> PermissionRequestPayload {
+        serde_json::from_value(json!({
+            "sessionId": "s1",
+            "requestId": "r1",
+            "options": kinds
+                .iter()
+                .map(|kind| json!({ "optionId": format!("opt-{kind}"), "name": kind, "kind": kind }))
+                .collect::<Vec<_>>(),
+            "toolCall": { "toolCallId": "t1", "title": "Run cargo test" },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn picks_the_narrowest_permission_option() {
+        let request = request(&["allow_always", "allow_once", "reject_always"]);
+        assert_eq!(request.title(), Some("Run cargo test"));
+        assert_eq!(request.decide(true).outcome["optionId"], "opt-allow_once");
+        assert_eq!(
+            request.decide(false).outcome["optionId"],
+            "opt-reject_always"
+        );
+
+        let allow_only = self::request(&["allow_once"]);
+        assert_eq!(
+            allow_only.decide(false).outcome,
+            json!({ "outcome": "cancelled" })
+        );
+    }
+
+    #[test]
+    fn selects_only_offered_options() {
+        let request = request(&["allow_once", "reject_once"]);
+        let decision = request.select("opt-reject_once").unwrap();
+        assert_eq!(
+            (decision.session_id.as_str(), decision.request_id.as_str()),
+            ("s1", "r1")
+        );
+        assert_eq!(
+            decision.outcome,
+            json!({ "outcome": "selected", "optionId": "opt-reject_once" })
+        );
+        assert!(request.select("opt-allow_always").is_none());
+    }
+}
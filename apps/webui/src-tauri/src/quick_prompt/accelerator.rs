@@ -0,0 +1,187 @@
+//! Shortcut strings such as `CmdOrCtrl+Shift+Space`, in the format Electron
+//! and Tauri use for accelerators. The global-shortcut plugin parses them
+//! too; parsing here first applies the app's own rules and gives the
+//! plugin the shortcut with `CmdOrCtrl` resolved.
+
+use std::fmt;
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Modifiers {
+    pub control: bool,
+    pub alt: bool,
+    pub shift: bool,
+    /// Command on macOS, the Windows key elsewhere.
+    pub super_key: bool,
+}
+
+/// The keys a shortcut can end in. Letters and digits are kept as their
+/// uppercase ASCII character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    /// F1 to F12.
+    Function(u8),
+    Space,
+    Enter,
+    Tab,
+    Escape,
+    Up,
+    Down,
+    Left,
+    Right,
+    /// The key left of 1 on US layouts.
+    Backquote,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Accelerator {
+    pub modifiers: Modifiers,
+    pub key: Key,
+}
+
+impl Accelerator {
+    /// Parses `+`-separated modifiers followed by one key, ignoring case.
+    /// `CmdOrCtrl` is Command on macOS and Control elsewhere. A shortcut
+    /// needs a modifier unless its key is a function key, so typing in
+    /// other apps is never swallowed.
+    pub fn parse(shortcut: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::Shortcut(format!("{reason} in \"{shortcut}\""));
+        let mut modifiers = Modifiers::default();
+        let mut key = None;
+        for token in shortcut.split('+').map(str::trim) {
+            if key.is_some() {
+                return Err(invalid("The key must come last"));
+            }
+            match token.to_ascii_lowercase().as_str() {
+                "cmdorctrl" | "commandorcontrol" if cfg!(target_os = "macos") => {
+                    modifiers.super_key = true;
+                }
+                "cmdorctrl" | "commandorcontrol" | "ctrl" | "control" => modifiers.control = true,
+                "alt" | "option" => modifiers.alt = true,
+                "shift" => modifiers.shift = true,
+                "cmd" | "command" | "super" | "meta" => modifiers.super_key = true,
+                _ => key = Some(parse_key(token).ok_or_else(|| invalid("Unknown key"))?),
+            }
+        }
+        let key = key.ok_or_else(|| invalid("Missing key"))?;
+        if modifiers == Modifiers::default() && !matches!(key, Key::Function(_)) {
+            return Err(invalid("A modifier is required"));
+        }
+        Ok(Self { modifiers, key })
+    }
+}
+
+fn parse_key(token: &str) -> Option<Key> {
+    let lower = token.to_ascii_lowercase();
+    let key = match lower.as_str() {
+        "space" => Key::Space,
+        "enter" | "return" => Key::Enter,
+        "tab" => Key::Tab,
+        "esc" | "escape" => Key::Escape,
+        "up" => Key::Up,
+        "down" => Key::Down,
+        "left" => Key::Left,
+        "right" => Key::Right,
+        "`" | "backquote" => Key::Backquote,
+        _ => {
+            if let Some(number) = lower.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                return (1..=12).contains(&number).then_some(Key::Function(number));
+            }
+            let mut chars = token.chars();
+            let c = chars.next().filter(char::is_ascii_alphanumeric)?;
+            return chars
+                .next()
+                .is_none()
+                .then(|| Key::Char(c.to_ascii_uppercase()));
+        }
+    };
+    Some(key)
+}
+
+impl fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Modifiers {
+            control,
+            alt,
+            shift,
+            super_key,
+        } = self.modifiers;
+        for (held, name) in [
+            (control, "Ctrl"),
+            (alt, "Alt"),
+            (shift, "Shift"),
+            (super_key, "Super"),
+        ] {
+            if held {
+                write!(f, "{name}+")?;
+            }
+        }
+        match self.key {
+            Key::Char(c) => write!(f, "{c}"),
+            Key::Function(number) => write!(f, "F{number}"),
+            Key::Backquote => f.write_str("`"),
+            key => write!(f, "{key:?}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_shortcuts() {
+        let accelerator = Accelerator::parse("ctrl + Shift+space").unwrap();
+        assert_eq!(accelerator.key, Key::Space);
+        assert!(accelerator.modifiers.control && accelerator.modifiers.shift);
+        assert_eq!(accelerator.to_string(), "Ctrl+Shift+Space");
+
+        let accelerator = Accelerator::parse("CmdOrCtrl+Alt+k").unwrap();
+        assert_eq!(accelerator.key, Key::Char('K'));
+        assert_eq!(accelerator.modifiers.super_key, cfg!(target_os = "macos"),);
+        assert_eq!(Accelerator::parse("F9").unwrap().key, Key::Function(9));
+
+        for invalid in [
+            "",
+            "Shift",
+            "K",
+            "Ctrl+F13",
+            "Ctrl+K+Shift",
+            "Ctrl+é",
+            "Ctrl+Foo",
+        ] {
+            assert!(Accelerator::parse(invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn formats_shortcuts_the_plugin_parses() {
+        use tauri_plugin_global_shortcut::{Code, Modifiers as PluginModifiers, Shortcut};
+
+        let shortcut: Shortcut = Accelerator::parse("Ctrl+Shift+Space")
+            .unwrap()
+            .to_string()
+            .parse()
+            .unwrap();
+        assert_eq!(
+            shortcut,
+            Shortcut::new(
+                Some(PluginModifiers::CONTROL | PluginModifiers::SHIFT),
+                Code::Space
+            )
+        );
+        for shortcut in [
+            "CmdOrCtrl+Alt+K",
+            "Super+`",
+            "Alt+Up",
+            "Ctrl+Enter",
+            "Shift+7",
+            "F12",
+        ] {
+            let formatted = Accelerator::parse(shortcut).unwrap().to_string();
+            assert!(formatted.parse::<Shortcut>().is_ok(), "{formatted}");
+        }
+    }
+}
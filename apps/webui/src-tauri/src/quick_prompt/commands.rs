@@ -0,0 +1,26 @@
+use tauri::{AppHandle, State};
+
+use super::{QuickPromptState, QuickPromptStatus};
+use crate::error::Result;
+
+#[tauri::command]
+pub fn quick_prompt_status(quick_prompt: State<'_, QuickPromptState>) -> QuickPromptStatus {
+    quick_prompt.status()
+}
+
+/// Changes the global shortcut, e.g. `CmdOrCtrl+Shift+Space`; `null` turns
+/// it off. Async because registering on macOS waits on the main thread.
+#[tauri::command]
+pub async fn quick_prompt_set_shortcut(
+    app: AppHandle,
+    quick_prompt: State<'_, QuickPromptState>,
+    shortcut: Option<String>,
+) -> Result<QuickPromptStatus> {
+    quick_prompt.set_shortcut(&app, shortcut)
+}
+
+/// Puts the prompt window away, e.g. after sending or on Escape.
+#[tauri::command]
+pub fn quick_prompt_hide(app: AppHandle) -> Result<()> {
+    super::hide(&app)
+}
@@ -0,0 +1,220 @@
+//! Quick prompt: a global shortcut that brings up a small always-on-top
+//! window for sending a prompt to a session, whether the main window is
+//! showing or hidden in the tray.
+//!
+//! The shortcut is registered from Rust with the global-shortcut plugin and
+//! kept in the `quick-prompt` settings scope; no entry means
+//! [`DEFAULT_SHORTCUT`] and `null` means turned off. One that another
+//! application holds is reported in the status rather than failing startup.
+//! The window loads the app with `?quickPrompt=1`, which renders only the
+//! prompt form, and is hidden rather than closed so it opens instantly the
+//! next time.
+
+mod accelerator;
+pub mod commands;
+
+use std::sync::{Mutex, MutexGuard};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{
+    AppHandle, Manager, Runtime, WebviewUrl, WebviewWindow, WebviewWindowBuilder, WindowEvent,
+};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use self::accelerator::Accelerator;
+use crate::error::{Error, Result};
+use crate::preload;
+use crate::settings::{self, SettingsStore};
+
+pub const WINDOW_LABEL: &str = "quick-prompt";
+pub const DEFAULT_SHORTCUT: &str = "CmdOrCtrl+Shift+Space";
+const SHORTCUT_KEY: &str = "shortcut";
+const WIDTH: f64 = 640.0;
+const HEIGHT: f64 = 400.0;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuickPromptStatus {
+    /// None when the user turned the shortcut off.
+    pub shortcut: Option<String>,
+    pub registered: bool,
+    /// Why the shortcut could not be registered.
+    pub error: Option<String>,
+}
+
+#[derive(Default)]
+struct Inner {
+    shortcut: Option<String>,
+    hotkey: Option<Shortcut>,
+    error: Option<String>,
+}
+
+#[derive(Default)]
+pub struct QuickPromptState {
+    inner: Mutex<Inner>,
+}
+
+/// Registers the saved shortcut and manages [`QuickPromptState`].
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    let shortcut = match app
+        .state::<SettingsStore>()
+        .get(settings::QUICK_PROMPT, SHORTCUT_KEY)?
+    {
+        None => Some(DEFAULT_SHORTCUT.to_owned()),
+        Some(Value::String(shortcut)) if !shortcut.is_empty() => Some(shortcut),
+        Some(_) => None,
+    };
+    let state = QuickPromptState::default();
+    let registered = shortcut.as_deref().map(|shortcut| register(app, shortcut));
+    {
+        let mut inner = state.inner();
+        inner.shortcut = shortcut;
+        match registered {
+            Some(Ok(hotkey)) => inner.hotkey = Some(hotkey),
+            Some(Err(err)) => inner.error = Some(err.to_string()),
+            None => {}
+        }
+    }
+    app.manage(state);
+    Ok(())
+}
+
+fn register<R: Runtime>(app: &AppHandle<R>, shortcut: &str) -> Result<Shortcut> {
+    let hotkey: Shortcut = Accelerator::parse(shortcut)?
+        .to_string()
+        .parse()
+        .map_err(|err| Error::Shortcut(err.to_string()))?;
+    app.global_shortcut()
+        .on_shortcut(hotkey, |app, _, event| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            // Off the event loop, which building the window waits on.
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let _ = toggle(&app);
+            });
+        })
+        .map_err(|err| Error::Shortcut(err.to_string()))?;
+    Ok(hotkey)
+}
+
+/// Gives the shortcut back to the system.
+fn unregister<R: Runtime>(app: &AppHandle<R>, hotkey: Shortcut) {
+    let _ = app.global_shortcut().unregister(hotkey);
+}
+
+impl QuickPromptState {
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn status(&self) -> QuickPromptStatus {
+        let inner = self.inner();
+        QuickPromptStatus {
+            shortcut: inner.shortcut.clone(),
+            registered: inner.hotkey.is_some(),
+            error: inner.error.clone(),
+        }
+    }
+
+    /// Replaces the shortcut, or turns it off for `None`. A shortcut that
+    /// cannot be registered is not saved and the previous one stays.
+    pub fn set_shortcut<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        shortcut: Option<String>,
+    ) -> Result<QuickPromptStatus> {
+        let shortcut = shortcut.filter(|shortcut| !shortcut.trim().is_empty());
+        if let Some(shortcut) = &shortcut {
+            Accelerator::parse(shortcut)?;
+        }
+        // Registering may wait on the main thread, so the lock is not held
+        // across it. The old hotkey goes first in case the key is the same.
+        let (previous, old_hotkey) = {
+            let mut inner = self.inner();
+            (inner.shortcut.clone(), inner.hotkey.take())
+        };
+        if let Some(old_hotkey) = old_hotkey {
+            unregister(app, old_hotkey);
+        }
+        let hotkey = match shortcut.as_deref().map(|shortcut| register(app, shortcut)) {
+            Some(Err(err)) => {
+                let restored = previous.as_deref().and_then(|old| register(app, old).ok());
+                self.inner().hotkey = restored;
+                return Err(err);
+            }
+            hotkey => hotkey.and_then(Result::ok),
+        };
+        app.state::<SettingsStore>().set(
+            settings::QUICK_PROMPT,
+            SHORTCUT_KEY,
+            &serde_json::to_value(&shortcut)?,
+        )?;
+        {
+            let mut inner = self.inner();
+            inner.shortcut = shortcut;
+            inner.hotkey = hotkey;
+            inner.error = None;
+        }
+        Ok(self.status())
+    }
+}
+
+/// Shows the prompt window, creating it on first use, or hides it if it is
+/// already in front.
+pub fn toggle<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        if window.is_visible()? && window.is_focused()? {
+            window.hide()?;
+            return Ok(());
+        }
+        return show(&window);
+    }
+    let script = preload::initialization_script(app, &app.state::<SettingsStore>())?;
+    let window = WebviewWindowBuilder::new(
+        app,
+        WINDOW_LABEL,
+        WebviewUrl::App("index.html?quickPrompt=1".into()),
+    )
+    .title("Quick Prompt")
+    .inner_size(WIDTH, HEIGHT)
+    .resizable(false)
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .visible(false)
+    .initialization_script(script)
+    .build()?;
+    // Windows and Linux give every window the app menu bar.
+    #[cfg(not(target_os = "macos"))]
+    window.remove_menu()?;
+    let hidden = window.clone();
+    window.on_window_event(move |event| match event {
+        WindowEvent::CloseRequested { api, .. } => {
+            api.prevent_close();
+            let _ = hidden.hide();
+        }
+        // Put away like a launcher once something else is clicked.
+        WindowEvent::Focused(false) => {
+            let _ = hidden.hide();
+        }
+        _ => {}
+    });
+    show(&window)
+}
+
+fn show<R: Runtime>(window: &WebviewWindow<R>) -> Result<()> {
+    window.center()?;
+    window.show()?;
+    window.set_focus()?;
+    Ok(())
+}
+
+pub fn hide<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    if let Some(window) = app.get_webview_window(WINDOW_LABEL) {
+        window.hide()?;
+    }
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use tauri::ipc::Channel;
+use tauri::{State, Webview};
+
+use super::content::ContentQuery;
+use super::{PathMatch, SearchEvent, SearchState};
+use crate::error::Result;
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentSearchOptions {
+    #[serde(default)]
+    case_sensitive: bool,
+    #[serde(default)]
+    regex: bool,
+    glob: Option<String>,
+}
+
+/// Fuzzy-matches `query` against the files under `root`, a session's
+/// working directory. Returns at most `limit` matches, best first.
+#[tauri::command]
+pub async fn search_paths(
+    search: State<'_, SearchState>,
+    root: PathBuf,
+    query: String,
+    limit: Option<usize>,
+) -> Result<Vec<PathMatch>> {
+    search.search_paths(&root, &query, limit).await
+}
+
+/// Searches the contents of the files under `root` and streams matching
+/// lines to `on_event`. `query` is a literal, case-insensitive string
+/// unless the options say otherwise. Returns the search id.
+#[tauri::command]
+pub async fn search_content(
+    webview: Webview,
+    search: State<'_, SearchState>,
+    root: PathBuf,
+    query: String,
+    options: Option<ContentSearchOptions>,
+    on_event: Channel<SearchEvent>,
+) -> Result<u32> {
+    let options = options.unwrap_or_default();
+    let query = ContentQuery::new(
+        &query,
+        options.case_sensitive,
+        options.regex,
+        options.glob.as_deref(),
+    )?;
+    search
+        .search_content(webview.label(), &root, query, on_event)
+        .await
+}
+
+/// Stops a content search; it still ends with a `done` event.
+#[tauri::command]
+pub fn search_cancel(search: State<'_, SearchState>, id: u32) {
+    search.cancel(id);
+}
@@ -0,0 +1,135 @@
+//! Regex search of file contents, one result per matching line like
+//! `git grep`.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use glob::{MatchOptions, Pattern};
+use regex::{Regex, RegexBuilder};
+
+use crate::error::{Error, Result};
+use crate::git::GrepResult;
+
+/// Larger files are skipped; they are almost always generated.
+const MAX_FILE_SIZE: u64 = 4 * 1024 * 1024;
+/// A NUL byte in this much of a file marks it as binary.
+const BINARY_SNIFF: usize = 8 * 1024;
+
+pub struct ContentQuery {
+    regex: Regex,
+    glob: Option<Pattern>,
+    /// The glob has no `/`, so it applies to file names.
+    glob_name_only: bool,
+}
+
+impl ContentQuery {
+    /// `query` is literal unless `regex` is set. A glob such as `*.rs`
+    /// without a `/` matches file names in any directory.
+    pub fn new(query: &str, case_sensitive: bool, regex: bool, glob: Option<&str>) -> Result<Self> {
+        if query.is_empty() {
+            return Err(Error::Search("Empty query".into()));
+        }
+        let pattern = if regex {
+            query.to_owned()
+        } else {
+            regex::escape(query)
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(!case_sensitive)
+            .build()
+            .map_err(|err| Error::Search(err.to_string()))?;
+        let glob = glob
+            .filter(|glob| !glob.is_empty())
+            .map(Pattern::new)
+            .transpose()
+            .map_err(|err| Error::Search(format!("Invalid glob: {err}")))?;
+        Ok(Self {
+            glob_name_only: glob
+                .as_ref()
+                .is_some_and(|glob| !glob.as_str().contains('/')),
+            regex,
+            glob,
+        })
+    }
+
+    pub fn includes(&self, path: &str) -> bool {
+        let Some(glob) = &self.glob else {
+            return true;
+        };
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+        if self.glob_name_only {
+            let name = path.rsplit('/').next().unwrap_or(path);
+            glob.matches_with(name, options)
+        } else {
+            glob.matches_with(path, options)
+        }
+    }
+
+    /// Appends the matching lines of `root`/`path` to `results`. Files
+    /// that cannot be read, or are binary or too large, have none.
+    pub fn search_file(&self, root: &Path, path: &str, results: &mut Vec<GrepResult>) {
+        let Ok(mut file) = File::open(root.join(path)) else {
+            return;
+        };
+        if file
+            .metadata()
+            .map_or(true, |meta| !meta.is_file() || meta.len() > MAX_FILE_SIZE)
+        {
+            return;
+        }
+        let mut bytes = Vec::new();
+        if file.read_to_end(&mut bytes).is_err() {
+            return;
+        }
+        if bytes[..bytes.len().min(BINARY_SNIFF)].contains(&0) {
+            return;
+        }
+        self.search_text(path, &String::from_utf8_lossy(&bytes), results);
+    }
+
+    fn search_text(&self, path: &str, text: &str, results: &mut Vec<GrepResult>) {
+        for (index, line) in text.lines().enumerate() {
+            let Some(found) = self.regex.find(line) else {
+                continue;
+            };
+            // Offsets in UTF-16 code units, as JavaScript indexes strings.
+            let match_start = line[..found.start()].encode_utf16().count();
+            let match_end = match_start + found.as_str().encode_utf16().count();
+            results.push(GrepResult {
+                path: path.to_owned(),
+                line_number: index as u32 + 1,
+                content: line.to_owned(),
+                match_start: match_start as u32,
+                match_end: match_end as u32,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_lines_and_filters_paths() {
+        let query = ContentQuery::new("a.c", false, false, Some("*.rs")).unwrap();
+        assert!(query.includes("src/deep/lib.rs"));
+        assert!(!query.includes("src/lib.ts"));
+
+        let mut results = Vec::new();
+        query.search_text("x.rs", "abc\n→ A.C here\r\nnone", &mut results);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].line_number, 2);
+        assert_eq!(results[0].content, "→ A.C here");
+        assert_eq!((results[0].match_start, results[0].match_end), (2, 5));
+
+        let query = ContentQuery::new(r"fn \w+", true, true, Some("src/*.rs")).unwrap();
+        assert!(query.includes("src/main.rs"));
+        assert!(!query.includes("src/deep/main.rs"));
+        assert!(ContentQuery::new("(", false, true, None).is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! Fuzzy path matching in the manner of fzf's v1 algorithm: each
+//! whitespace-separated term must appear in order in the path, the
+//! tightest window around its first occurrence is scored, and matches at
+//! word starts, in runs and in the file name rank higher. Scoring is linear
+//! in the path length, so ranking a few hundred thousand paths stays in
+//! milliseconds.
+
+const SCORE_MATCH: i32 = 16;
+const BONUS_BOUNDARY: i32 = 8;
+const BONUS_SEPARATOR: i32 = 10;
+const BONUS_CAMEL: i32 = 7;
+const BONUS_CONSECUTIVE: i32 = 8;
+const BONUS_FILE_NAME: i32 = 4;
+const PENALTY_GAP_START: i32 = 3;
+const PENALTY_GAP_EXTENSION: i32 = 1;
+
+pub struct Matcher {
+    terms: Vec<Vec<char>>,
+    /// Smart case: a query with an upper-case letter matches case.
+    case_sensitive: bool,
+}
+
+fn fold(c: char) -> char {
+    if c.is_ascii() {
+        c.to_ascii_lowercase()
+    } else {
+        c.to_lowercase().next().unwrap_or(c)
+    }
+}
+
+impl Matcher {
+    /// `None` for a blank query, which matches everything equally.
+    pub fn new(query: &str) -> Option<Self> {
+        let case_sensitive = query.chars().any(char::is_uppercase);
+        let terms: Vec<Vec<char>> = query
+            .split_whitespace()
+            .map(|term| {
+                term.chars()
+                    .map(|c| if case_sensitive { c } else { fold(c) })
+                    .collect()
+            })
+            .collect();
+        (!terms.is_empty()).then_some(Self {
+            terms,
+            case_sensitive,
+        })
+    }
+
+    fn eq(&self, text: char, query: char) -> bool {
+        if self.case_sensitive {
+            text == query
+        } else {
+            fold(text) == query
+        }
+    }
+
+    /// The score of `path`, whose file name starts at char `name_start`,
+    /// or `None` if a term is missing. `chars` is scratch space reused
+    /// across calls.
+    pub fn score(&self, path: &str, name_start: usize, chars: &mut Vec<char>) -> Option<i32> {
+        chars.clear();
+        chars.extend(path.chars());
+        self.terms.iter().try_fold(0, |total, term| {
+            Some(total + self.match_term(chars, term, name_start, None)?)
+        })
+    }
+
+    /// The UTF-16 ranges of `path` that matched, merged where adjacent,
+    /// for highlighting in the webview.
+    pub fn highlight(&self, path: &str, name_start: usize) -> Vec<[usize; 2]> {
+        let chars: Vec<char> = path.chars().collect();
+        let mut matched = vec![false; chars.len()];
+        for term in &self.terms {
+            let mut positions = Vec::new();
+            self.match_term(&chars, term, name_start, Some(&mut positions));
+            for position in positions {
+                matched[position] = true;
+            }
+        }
+        let mut ranges: Vec<[usize; 2]> = Vec::new();
+        let mut offset = 0;
+        for (c, matched) in chars.iter().zip(matched) {
+            let end = offset + c.len_utf16();
+            if matched {
+                match ranges.last_mut() {
+                    Some(range) if range[1] == offset => range[1] = end,
+                    _ => ranges.push([offset, end]),
+                }
+            }
+            offset = end;
+        }
+        ranges
+    }
+
+    /// The better of the first window in the whole path and the first in
+    /// the file name, which the first window would otherwise hide.
+    fn match_term(
+        &self,
+        chars: &[char],
+        term: &[char],
+        name_start: usize,
+        positions: Option<&mut Vec<usize>>,
+    ) -> Option<i32> {
+        let anywhere = self.match_from(chars, term, 0, name_start, None)?;
+        let in_name = (name_start > 0)
+            .then(|| self.match_from(chars, term, name_start, name_start, None))
+            .flatten();
+        let from = match in_name {
+            Some(in_name) if in_name >= anywhere => name_start,
+            _ => 0,
+        };
+        match positions {
+            Some(positions) => self.match_from(chars, term, from, name_start, Some(positions)),
+            None => Some(anywhere.max(in_name.unwrap_or(i32::MIN))),
+        }
+    }
+
+    fn match_from(
+        &self,
+        chars: &[char],
+        term: &[char],
+        from: usize,
+        name_start: usize,
+        positions: Option<&mut Vec<usize>>,
+    ) -> Option<i32> {
+        // The first end at which the whole term has been seen...
+        let mut next = 0;
+        let mut end = None;
+        for (index, &c) in chars.iter().enumerate().skip(from) {
+            if self.eq(c, term[next]) {
+                next += 1;
+                if next == term.len() {
+                    end = Some(index);
+                    break;
+                }
+            }
+        }
+        let end = end?;
+        // ...and the latest start before it, for the tightest window.
+        let mut remaining = term.len();
+        let mut start = end;
+        for index in (from..=end).rev() {
+            if self.eq(chars[index], term[remaining - 1]) {
+                remaining -= 1;
+                if remaining == 0 {
+                    start = index;
+                    break;
+                }
+            }
+        }
+
+        let mut score = 0;
+        let mut next = 0;
+        let mut previous_match: Option<usize> = None;
+        let mut positions = positions;
+        for (index, &c) in chars.iter().enumerate().take(end + 1).skip(start) {
+            if next == term.len() || !self.eq(c, term[next]) {
+                continue;
+            }
+            next += 1;
+            score += SCORE_MATCH + bonus(chars, index);
+            if index >= name_start {
+                score += BONUS_FILE_NAME;
+            }
+            match previous_match {
+                Some(previous) if previous + 1 == index => score += BONUS_CONSECUTIVE,
+                Some(previous) => {
+                    let gap = (index - previous - 1) as i32;
+                    score -= PENALTY_GAP_START + (gap - 1) * PENALTY_GAP_EXTENSION;
+                }
+                None => {}
+            }
+            previous_match = Some(index);
+            if let Some(positions) = positions.as_deref_mut() {
+                positions.push(index);
+            }
+        }
+        Some(score)
+    }
+}
+
+/// The bonus for a match at `index`, by what precedes it.
+fn bonus(chars: &[char], index: usize) -> i32 {
+    let Some(&previous) = index.checked_sub(1).and_then(|i| chars.get(i)) else {
+        return BONUS_SEPARATOR;
+    };
+    let c = chars[index];
+    match previous {
+        '/' | '\\' => BONUS_SEPARATOR,
+        '_' | '-' | '.' | ' ' => BONUS_BOUNDARY,
+        _ if previous.is_lowercase() && c.is_uppercase() => BONUS_CAMEL,
+        _ if !previous.is_alphanumeric() && c.is_alphanumeric() => BONUS_BOUNDARY,
+        _ => 0,
+    }
+}
+
+/// The char index where the file name of `path` starts.
+pub fn name_start(path: &str) -> usize {
+    path.rfind('/')
+        .map_or(0, |slash| path[..=slash].chars().count())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(matcher: &Matcher, path: &str) -> Option<i32> {
+        matcher.score(path, name_start(path), &mut Vec::new())
+    }
+
+    #[test]
+    fn prefers_file_names_and_word_starts() {
+        let matcher = Matcher::new("cp").unwrap();
+        let file_name = score(&matcher, "src/components/CodePreview.tsx").unwrap();
+        let scattered = score(&matcher, "src/components/app/Chat.tsx").unwrap();
+        assert!(file_name > scattered);
+        assert_eq!(score(&matcher, "README.md"), None);
+
+        let matcher = Matcher::new("lib git").unwrap();
+        assert!(score(&matcher, "src/lib/local-git.ts").is_some());
+        assert_eq!(score(&matcher, "src/lib/api.ts"), None);
+
+        // Smart case.
+        assert!(score(&Matcher::new("API").unwrap(), "src/lib/api.ts").is_none());
+        assert!(Matcher::new("  ").is_none());
+    }
+
+    #[test]
+    fn highlights_in_utf16_units() {
+        let matcher = Matcher::new("ré").unwrap();
+        assert_eq!(matcher.highlight("docs/𝒳ré.md", 5), [[7, 9]]);
+        let matcher = Matcher::new("ab").unwrap();
+        assert_eq!(matcher.highlight("a/xab", 2), [[3, 5]]);
+    }
+}
@@ -0,0 +1,552 @@
+//! Fuzzy path and content search over workspaces on this machine, for the
+//! `@` picker and the search views of local sessions.
+//!
+//! Each root gets a path index, listed by a walk that honours `.gitignore`
+//! in repositories and skips build and dependency directories elsewhere.
+//! A watcher on the root then keeps the index current: changed paths are
+//! relisted on their own, and only a changed ignore file or dropped events
+//! relist the whole root. Where the root cannot be watched, an index older
+//! than [`REFRESH_AFTER`] is relisted in the background instead. Queries
+//! are always served from the index as it is. Content searches read the
+//! indexed files on a pool of threads, stream their matches over a channel
+//! and stop when cancelled or when their webview reloads.
+
+pub mod commands;
+pub(crate) mod content;
+mod fuzzy;
+mod walk;
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use tauri::ipc::Channel;
+
+use self::content::ContentQuery;
+use self::fuzzy::Matcher;
+use crate::error::{Error, Result};
+use crate::git::GrepResult;
+
+/// How stale the index of a root without a watcher may get.
+const REFRESH_AFTER: Duration = Duration::from_secs(10);
+/// Events this close together are applied to an index at once.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(200);
+/// Files whose change may hide or reveal any path under the root.
+const IGNORE_FILES: &[&str] = &[".gitignore", ".ignore", ".git/info/exclude"];
+/// Indexes kept at once; the least recently queried goes first.
+const MAX_INDEXES: usize = 8;
+const DEFAULT_PATH_LIMIT: usize = 50;
+const MAX_CONTENT_RESULTS: usize = 2000;
+const MAX_SEARCH_THREADS: usize = 8;
+/// Matches are sent once this many are pending...
+const BATCH_SIZE: usize = 100;
+/// ...or once the oldest has waited this long.
+const BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+struct IndexedPath {
+    path: String,
+    name_start: usize,
+}
+
+struct Index {
+    paths: Arc<Vec<IndexedPath>>,
+    /// Keeps `paths` current until the index is dropped; `None` if the
+    /// root could not be watched.
+    watcher: Option<RecommendedWatcher>,
+    built_at: Instant,
+    used_at: Instant,
+    refreshing: bool,
+}
+
+/// A `SessionFsResourceEntry` with where the query matched.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PathMatch {
+    pub name: String,
+    pub path: PathBuf,
+    pub relative_path: String,
+    pub score: i32,
+    /// UTF-16 ranges of `relative_path`.
+    pub highlight_ranges: Vec<[usize; 2]>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum SearchEvent {
+    Matches {
+        results: Vec<GrepResult>,
+    },
+    /// Always the last event. `truncated` if the search stopped at the
+    /// result limit.
+    Done {
+        truncated: bool,
+        cancelled: bool,
+    },
+}
+
+struct Search {
+    webview: String,
+    cancelled: Arc<AtomicBool>,
+}
+
+#[derive(Default)]
+struct Inner {
+    indexes: HashMap<PathBuf, Index>,
+    searches: BTreeMap<u32, Search>,
+    next_search_id: u32,
+}
+
+#[derive(Default, Clone)]
+pub struct SearchState {
+    inner: Arc<Mutex<Inner>>,
+}
+
+fn index_paths(files: Vec<String>) -> Arc<Vec<IndexedPath>> {
+    Arc::new(
+        files
+            .into_iter()
+            .map(|path| IndexedPath {
+                name_start: fuzzy::name_start(&path),
+                path,
+            })
+            .collect(),
+    )
+}
+
+impl SearchState {
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// The indexed paths of `root`, listing and watching it on first use.
+    async fn paths(&self, root: &Path) -> Result<Arc<Vec<IndexedPath>>> {
+        if !root.is_dir() {
+            return Err(Error::Search(format!(
+                "{} is not a directory",
+                root.display()
+            )));
+        }
+        {
+            let mut inner = self.inner();
+            if let Some(index) = inner.indexes.get_mut(root) {
+                index.used_at = Instant::now();
+                if index.watcher.is_none()
+                    && index.built_at.elapsed() > REFRESH_AFTER
+                    && !index.refreshing
+                {
+                    index.refreshing = true;
+                    let state = self.clone();
+                    let root = root.to_path_buf();
+                    tauri::async_runtime::spawn_blocking(move || {
+                        state.store(&root, index_paths(walk::list(&root)));
+                    });
+                }
+                return Ok(index.paths.clone());
+            }
+        }
+        let (sender, events) = mpsc::channel();
+        let listed = root.to_path_buf();
+        let (files, watcher) = tauri::async_runtime::spawn_blocking(move || {
+            // Watch first so that nothing changing during the walk is missed
+            let watcher = watch(&listed, sender);
+            (walk::list(&listed), watcher)
+        })
+        .await
+        .map_err(|err| Error::Search(err.to_string()))?;
+        let paths = index_paths(files);
+        let watched = watcher.is_some();
+        if self.insert(root, paths.clone(), watcher) && watched {
+            let state = self.clone();
+            let root = root.to_path_buf();
+            std::thread::spawn(move || state.follow(&root, events));
+        }
+        Ok(paths)
+    }
+
+    /// Adds the index of `root`, evicting the least recently used one if
+    /// there are too many. Returns false, dropping `watcher`, if another
+    /// query indexed `root` first.
+    fn insert(
+        &self,
+        root: &Path,
+        paths: Arc<Vec<IndexedPath>>,
+        watcher: Option<RecommendedWatcher>,
+    ) -> bool {
+        let mut inner = self.inner();
+        if inner.indexes.contains_key(root) {
+            return false;
+        }
+        if inner.indexes.len() >= MAX_INDEXES {
+            let oldest = inner
+                .indexes
+                .iter()
+                .min_by_key(|(_, index)| index.used_at)
+                .map(|(root, _)| root.clone());
+            if let Some(oldest) = oldest {
+                inner.indexes.remove(&oldest);
+            }
+        }
+        let now = Instant::now();
+        inner.indexes.insert(
+            root.to_path_buf(),
+            Index {
+                paths,
+                watcher,
+                built_at: now,
+                used_at: now,
+                refreshing: false,
+            },
+        );
+        true
+    }
+
+    /// Replaces the paths of the index of `root`, if it is still kept.
+    fn store(&self, root: &Path, paths: Arc<Vec<IndexedPath>>) {
+        if let Some(index) = self.inner().indexes.get_mut(root) {
+            index.paths = paths;
+            index.built_at = Instant::now();
+            index.refreshing = false;
+        }
+    }
+
+    /// Replaces what the index of `root` holds at or under each `changed`
+    /// path with `found`, the files there now.
+    fn update(&self, root: &Path, changed: &BTreeSet<String>, found: BTreeSet<String>) {
+        let mut inner = self.inner();
+        let Some(index) = inner.indexes.get_mut(root) else {
+            return;
+        };
+        let mut files: Vec<String> = index
+            .paths
+            .iter()
+            .filter(|entry| !is_under_any(&entry.path, changed) && !found.contains(&entry.path))
+            .map(|entry| entry.path.clone())
+            .collect();
+        files.extend(found);
+        index.paths = index_paths(files);
+    }
+
+    /// Applies the events of the watcher on `root` to its index until the
+    /// index, and with it the watcher, is dropped.
+    fn follow(&self, root: &Path, events: Receiver<notify::Result<Event>>) {
+        let canonical = root.canonicalize().ok();
+        while let Ok(event) = events.recv() {
+            let mut changed = BTreeSet::new();
+            let mut relist = false;
+            let mut next = Some(event);
+            while let Some(event) = next {
+                match event {
+                    Ok(event) if event.need_rescan() => relist = true,
+                    Ok(Event {
+                        kind:
+                            EventKind::Access(_)
+                            | EventKind::Modify(ModifyKind::Data(_) | ModifyKind::Metadata(_)),
+                        ..
+                    }) => {}
+                    Ok(event) => {
+                        for path in &event.paths {
+                            let relative = walk::relative(root, path)
+                                .or_else(|| walk::relative(canonical.as_deref()?, path));
+                            let Some(relative) = relative else {
+                                continue;
+                            };
+                            let name = relative.rsplit('/').next().unwrap_or_default();
+                            if relative.is_empty()
+                                || IGNORE_FILES.contains(&name)
+                                || IGNORE_FILES.contains(&relative.as_str())
+                            {
+                                relist = true;
+                            } else if relative != ".git" && !relative.starts_with(".git/") {
+                                changed.insert(relative);
+                            }
+                        }
+                    }
+                    // Events were lost
+                    Err(_) => relist = true,
+                }
+                next = events.recv_timeout(WATCH_DEBOUNCE).ok();
+            }
+            if relist {
+                self.store(root, index_paths(walk::list(root)));
+            } else if !changed.is_empty() {
+                let found = changed
+                    .iter()
+                    .filter(|relative| !is_inside_any(relative, &changed))
+                    .flat_map(|relative| walk::list_under(root, &root.join(relative)))
+                    .collect();
+                self.update(root, &changed, found);
+            }
+        }
+    }
+
+    /// The `limit` paths under `root` that best match `query`, best first;
+    /// the first `limit` paths for a blank query.
+    pub async fn search_paths(
+        &self,
+        root: &Path,
+        query: &str,
+        limit: Option<usize>,
+    ) -> Result<Vec<PathMatch>> {
+        let paths = self.paths(root).await?;
+        let limit = limit.unwrap_or(DEFAULT_PATH_LIMIT);
+        let matcher = Matcher::new(query);
+        let root = root.to_path_buf();
+        tauri::async_runtime::spawn_blocking(move || {
+            let Some(matcher) = matcher else {
+                return paths
+                    .iter()
+                    .take(limit)
+                    .map(|entry| path_match(&root, entry, 0, Vec::new()))
+                    .collect();
+            };
+            let mut chars = Vec::new();
+            let mut scored: Vec<(i32, usize)> = paths
+                .iter()
+                .enumerate()
+                .filter_map(|(index, entry)| {
+                    let score = matcher.score(&entry.path, entry.name_start, &mut chars)?;
+                    Some((score, index))
+                })
+                .collect();
+            // Best score first, then the shorter path.
+            let order = |a: &(i32, usize), b: &(i32, usize)| {
+                b.0.cmp(&a.0)
+                    .then_with(|| paths[a.1].path.len().cmp(&paths[b.1].path.len()))
+            };
+            if scored.len() > limit && limit > 0 {
+                scored.select_nth_unstable_by(limit - 1, order);
+            }
+            scored.truncate(limit);
+            scored.sort_unstable_by(order);
+            scored
+                .into_iter()
+                .map(|(score, index)| {
+                    let entry = &paths[index];
+                    let ranges = matcher.highlight(&entry.path, entry.name_start);
+                    path_match(&root, entry, score, ranges)
+                })
+                .collect()
+        })
+        .await
+        .map_err(|err| Error::Search(err.to_string()))
+    }
+
+    /// Starts searching the files under `root` for `query` and streams the
+    /// matches to `channel`. Returns the search id for [`Self::cancel`].
+    pub async fn search_content(
+        &self,
+        webview: &str,
+        root: &Path,
+        query: ContentQuery,
+        channel: Channel<SearchEvent>,
+    ) -> Result<u32> {
+        let paths = self.paths(root).await?;
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let id = {
+            let mut inner = self.inner();
+            let id = inner.next_search_id;
+            inner.next_search_id += 1;
+            inner.searches.insert(
+                id,
+                Search {
+                    webview: webview.to_owned(),
+                    cancelled: cancelled.clone(),
+                },
+            );
+            id
+        };
+        let state = self.clone();
+        let root = root.to_path_buf();
+        std::thread::spawn(move || {
+            let truncated = run_content_search(&root, &paths, &query, &cancelled, &channel);
+            state.inner().searches.remove(&id);
+            let _ = channel.send(SearchEvent::Done {
+                truncated,
+                cancelled: !truncated && cancelled.load(Ordering::Relaxed),
+            });
+        });
+        Ok(id)
+    }
+
+    pub fn cancel(&self, id: u32) {
+        if let Some(search) = self.inner().searches.get(&id) {
+            search.cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Cancels the searches of a webview that is navigating away.
+    pub fn forget_webview(&self, webview: &str) {
+        for search in self.inner().searches.values() {
+            if search.webview == webview {
+                search.cancelled.store(true, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Watches `root` for [`SearchState::follow`]; `None` if it cannot be
+/// watched, as when the system's limit on watches is reached.
+fn watch(root: &Path, events: Sender<notify::Result<Event>>) -> Option<RecommendedWatcher> {
+    let watched = notify::recommended_watcher(events).and_then(|mut watcher| {
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .map(|()| watcher)
+    });
+    match watched {
+        Ok(watcher) => Some(watcher),
+        Err(err) => {
+            eprintln!("Failed to watch {}: {err}", root.display());
+            None
+        }
+    }
+}
+
+/// Whether `path` is one of `changed` or inside one of them.
+fn is_under_any(path: &str, changed: &BTreeSet<String>) -> bool {
+    changed.contains(path) || is_inside_any(path, changed)
+}
+
+/// Whether `path` is inside one of the directories in `changed`.
+fn is_inside_any(path: &str, changed: &BTreeSet<String>) -> bool {
+    path.match_indices('/')
+        .any(|(end, _)| changed.contains(&path[..end]))
+}
+
+fn path_match(root: &Path, entry: &IndexedPath, score: i32, ranges: Vec<[usize; 2]>) -> PathMatch {
+    PathMatch {
+        name: entry
+            .path
+            .rsplit('/')
+            .next()
+            .unwrap_or(&entry.path)
+            .to_owned(),
+        path: root.join(&entry.path),
+        relative_path: entry.path.clone(),
+        score,
+        highlight_ranges: ranges,
+    }
+}
+
+/// Searches `paths` on a pool of threads until done, cancelled or at the
+/// result limit. Returns whether the limit was hit.
+fn run_content_search(
+    root: &Path,
+    paths: &[IndexedPath],
+    query: &ContentQuery,
+    cancelled: &AtomicBool,
+    channel: &Channel<SearchEvent>,
+) -> bool {
+    let next = AtomicUsize::new(0);
+    let found = AtomicUsize::new(0);
+    let truncated = AtomicBool::new(false);
+    let threads = std::thread::available_parallelism()
+        .map_or(1, |threads| threads.get())
+        .min(MAX_SEARCH_THREADS);
+    std::thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                let mut batch = Vec::new();
+                let mut batch_started = Instant::now();
+                while !cancelled.load(Ordering::Relaxed) {
+                    let Some(entry) = paths.get(next.fetch_add(1, Ordering::Relaxed)) else {
+                        break;
+                    };
+                    if !query.includes(&entry.path) {
+                        continue;
+                    }
+                    if batch.is_empty() {
+                        batch_started = Instant::now();
+                    }
+                    let before = batch.len();
+                    query.search_file(root, &entry.path, &mut batch);
+                    let total = found.fetch_add(batch.len() - before, Ordering::Relaxed)
+                        + batch.len()
+                        - before;
+                    if total >= MAX_CONTENT_RESULTS {
+                        // Other threads may have added theirs first.
+                        let over = total - MAX_CONTENT_RESULTS;
+                        batch.truncate(batch.len().saturating_sub(over));
+                        truncated.store(true, Ordering::Relaxed);
+                        cancelled.store(true, Ordering::Relaxed);
+                    }
+                    if batch.len() >= BATCH_SIZE
+                        || (!batch.is_empty() && batch_started.elapsed() >= BATCH_INTERVAL)
+                    {
+                        let results = std::mem::take(&mut batch);
+                        let _ = channel.send(SearchEvent::Matches { results });
+                    }
+                }
+                if !batch.is_empty() {
+                    let _ = channel.send(SearchEvent::Matches { results: batch });
+                }
+            });
+        }
+    });
+    truncated.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn indexed(search: &SearchState, root: &Path) -> Vec<String> {
+        let mut paths: Vec<_> = search
+            .search_paths(root, "", Some(usize::MAX))
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|found| found.relative_path)
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    /// Polls until the watcher has applied a change, or gives up.
+    async fn eventually(search: &SearchState, root: &Path, expected: &[&str]) {
+        for _ in 0..100 {
+            if indexed(search, root).await == expected {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(indexed(search, root).await, expected);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn keeps_the_index_current_from_a_watcher() {
+        let root = std::env::temp_dir().join(format!("mobvibe-index-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/a.rs"), "").unwrap();
+        let search = SearchState::default();
+        assert_eq!(indexed(&search, &root).await, ["src/a.rs"]);
+
+        std::fs::create_dir_all(root.join("src/new")).unwrap();
+        std::fs::write(root.join("src/new/b.rs"), "").unwrap();
+        std::fs::write(root.join("notes.md"), "").unwrap();
+        eventually(&search, &root, &["notes.md", "src/a.rs", "src/new/b.rs"]).await;
+
+        std::fs::rename(root.join("src/new"), root.join("lib")).unwrap();
+        std::fs::remove_file(root.join("notes.md")).unwrap();
+        eventually(&search, &root, &["lib/b.rs", "src/a.rs"]).await;
+
+        std::fs::write(root.join(".ignore"), "*.rs\n").unwrap();
+        eventually(&search, &root, &[".ignore"]).await;
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn matches_changed_paths_and_their_contents() {
+        let changed = BTreeSet::from(["src/new".to_owned()]);
+        assert!(is_under_any("src/new", &changed));
+        assert!(is_under_any("src/new/b.rs", &changed));
+        assert!(!is_under_any("src/newer.rs", &changed));
+        assert!(!is_inside_any("src/new", &changed));
+    }
+}
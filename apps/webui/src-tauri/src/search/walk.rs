@@ -0,0 +1,145 @@
+//! Listing the files of a workspace.
+
+use std::path::{Path, PathBuf};
+
+use ignore::WalkBuilder;
+
+/// Directories skipped outside git repositories, as the CLI's resource
+/// listing skips them.
+const IGNORED_DIRS: &[&str] = &[
+    "node_modules",
+    ".git",
+    "dist",
+    "build",
+    ".next",
+    ".nuxt",
+    ".output",
+    ".cache",
+    "__pycache__",
+    ".venv",
+    "venv",
+    "target",
+];
+
+/// Past this many files a walk stops; the root is likely a home directory
+/// rather than a project.
+const MAX_WALK_FILES: usize = 500_000;
+
+/// The files under `root`, relative to it with `/` separators. In a git
+/// repository every `.gitignore`, `info/exclude` and the global excludes
+/// apply, as they do for `git ls-files`; elsewhere [`IGNORED_DIRS`] are
+/// skipped.
+pub fn list(root: &Path) -> Vec<String> {
+    walk(root, None)
+}
+
+/// The files [`list`] would find at or under `path`, a file or directory
+/// somewhere under `root`.
+pub fn list_under(root: &Path, path: &Path) -> Vec<String> {
+    walk(root, Some(path.to_path_buf()))
+}
+
+/// Walks `root`, only into the directories on the way to `only` if given,
+/// so the ignore files of each of them apply to it.
+fn walk(root: &Path, only: Option<PathBuf>) -> Vec<String> {
+    let in_repo = root.ancestors().any(|dir| dir.join(".git").exists());
+    WalkBuilder::new(root)
+        .hidden(false)
+        .follow_links(false)
+        .filter_entry(move |entry| {
+            if entry.depth() == 0 {
+                return true;
+            }
+            let on_the_way = only.as_ref().is_none_or(|only| {
+                only.starts_with(entry.path()) || entry.path().starts_with(only)
+            });
+            let is_dir = entry.file_type().is_some_and(|kind| kind.is_dir());
+            let name = entry.file_name().to_string_lossy();
+            let ignored = is_dir
+                && if in_repo {
+                    name == ".git"
+                } else {
+                    IGNORED_DIRS.contains(&name.as_ref())
+                };
+            on_the_way && !ignored
+        })
+        .build()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_some_and(|kind| kind.is_file()))
+        .filter_map(|entry| relative(root, entry.path()))
+        .take(MAX_WALK_FILES)
+        .collect()
+}
+
+/// `path` relative to `root` with `/` separators.
+pub fn relative(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<_> = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect();
+    Some(parts.join("/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("mobvibe-walk-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&root);
+        root
+    }
+
+    fn write(root: &Path, path: &str) {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, "").unwrap();
+    }
+
+    fn sorted(mut files: Vec<String>) -> Vec<String> {
+        files.sort();
+        files
+    }
+
+    #[test]
+    fn walks_without_ignored_dirs() {
+        let root = temp_root("plain");
+        write(&root, "src/nested/a.rs");
+        write(&root, "node_modules/pkg/index.js");
+        std::fs::write(root.join("target"), "a file, not the build dir").unwrap();
+
+        assert_eq!(sorted(list(&root)), ["src/nested/a.rs", "target"]);
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+
+    #[test]
+    fn walks_a_repository_as_git_lists_it() {
+        let root = temp_root("repo");
+        write(&root, ".git/HEAD");
+        std::fs::write(root.join(".gitignore"), "*.log\nout/\n").unwrap();
+        write(&root, ".github/ci.yml");
+        write(&root, "build/script.rs");
+        write(&root, "debug.log");
+        write(&root, "out/bundle.js");
+        write(&root, "src/a.rs");
+
+        assert_eq!(
+            sorted(list(&root)),
+            [
+                ".github/ci.yml",
+                ".gitignore",
+                "build/script.rs",
+                "src/a.rs"
+            ]
+        );
+        assert_eq!(sorted(list(&root.join("src"))), ["a.rs"]);
+
+        write(&root, "src/new/b.rs");
+        write(&root, "out/chunk.js");
+        assert_eq!(list_under(&root, &root.join("src/new")), ["src/new/b.rs"]);
+        assert!(list_under(&root, &root.join("out/chunk.js")).is_empty());
+        assert!(list_under(&root, &root.join("gone.rs")).is_empty());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
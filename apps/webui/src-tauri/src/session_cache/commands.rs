@@ -0,0 +1,72 @@
+use tauri::{AppHandle, State};
+
+use super::{CachedEventsPage, CachedSession, SearchHit, SessionCache};
+use crate::deep_link::{self, DeepLinkRoute};
+use crate::e2ee::E2eeState;
+use crate::error::Result;
+use crate::protocol::SessionEventsResponse;
+
+/// Every cached session with its backfill cursor.
+#[tauri::command]
+pub async fn session_cache_sessions(cache: State<'_, SessionCache>) -> Result<Vec<CachedSession>> {
+    cache.sessions()
+}
+
+/// One page of cached events after `after_seq`, shaped like
+/// `GET /acp/session/events`.
+#[tauri::command]
+pub async fn session_cache_events(
+    cache: State<'_, SessionCache>,
+    session_id: String,
+    revision: Option<u64>,
+    after_seq: u64,
+    limit: Option<u32>,
+) -> Result<CachedEventsPage> {
+    cache.events(&session_id, revision, after_seq, limit)
+}
+
+/// Stores a page the webview backfilled from the gateway.
+#[tauri::command]
+pub async fn session_cache_record_page(
+    cache: State<'_, SessionCache>,
+    after_seq: u64,
+    page: SessionEventsResponse,
+) -> Result<()> {
+    cache.record_page(after_seq, &page)
+}
+
+/// Searches cached transcripts, first indexing whatever arrived since the
+/// last search and can be decrypted now. DEKs come from the cached
+/// summaries, so sessions the webview never opened are searchable too.
+#[tauri::command]
+pub async fn session_search(
+    cache: State<'_, SessionCache>,
+    e2ee: State<'_, E2eeState>,
+    query: String,
+    limit: Option<u32>,
+) -> Result<Vec<SearchHit>> {
+    for summary in cache.sessions()?.into_iter().filter_map(|s| s.summary) {
+        let Some(wrapped_dek) = &summary.wrapped_dek else {
+            continue;
+        };
+        if !e2ee.has_session_dek(&summary.session_id, summary.revision) {
+            e2ee.unwrap_session_dek(&summary.session_id, wrapped_dek, summary.revision);
+        }
+    }
+    cache.index_pending(|event| e2ee.decrypt_event(event))?;
+    cache.search(&query, limit)
+}
+
+/// Opens a search hit: routes the webview to the session, scrolled to `seq`.
+#[tauri::command]
+pub fn session_search_open(app: AppHandle, session_id: String, seq: u64) {
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    crate::single_instance::focus_main_window(&app);
+    deep_link::dispatch(
+        &app,
+        DeepLinkRoute::Session {
+            session_id,
+            seq: Some(seq),
+        },
+    );
+}
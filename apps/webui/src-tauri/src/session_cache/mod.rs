@@ -0,0 +1,457 @@
+//! Offline cache of session transcripts.
+//!
+//! `SessionEvent`s are stored keyed like the CLI WalStore, by
+//! `(sessionId, revision, seq)`, so the webview can render the last known
+//! transcript without a gateway and only backfill what is missing. Each
+//! session tracks `syncedSeq`: every event up to it is cached. Live events
+//! advance it while they are contiguous, backfilled pages across the range
+//! the gateway returned. Seeing a newer revision drops the older one.
+
+pub mod commands;
+mod schema;
+mod search;
+
+pub use search::SearchHit;
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::error::Result;
+use crate::gateway::{GatewayEvent, GatewayState};
+use crate::protocol::{
+    SessionEvent, SessionEventsResponse, SessionSummary, SessionsChangedPayload,
+};
+use crate::sqlite;
+
+const CACHE_FILE: &str = "session-cache.sqlite3";
+const DEFAULT_PAGE_SIZE: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 1000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedSession {
+    pub session_id: String,
+    /// Last summary seen in `sessions:changed`, if any.
+    pub summary: Option<SessionSummary>,
+    /// Revision of the cached events; `None` until one is seen.
+    pub revision: Option<u64>,
+    pub synced_seq: u64,
+    pub last_seq: Option<u64>,
+}
+
+/// Same shape as `SessionEventsResponse`, plus the backfill cursor.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CachedEventsPage {
+    pub session_id: String,
+    pub revision: u64,
+    pub events: Vec<SessionEvent>,
+    pub next_after_seq: Option<u64>,
+    pub has_more: bool,
+    /// Backfill from the gateway can resume after this seq.
+    pub synced_seq: u64,
+}
+
+pub struct SessionCache {
+    conn: Mutex<Connection>,
+}
+
+impl SessionCache {
+    pub fn open(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let conn = Connection::open(dir.join(CACHE_FILE))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        Self::with_connection(conn)
+    }
+
+    fn with_connection(mut conn: Connection) -> Result<Self> {
+        sqlite::migrate(&mut conn, schema::MIGRATIONS)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Stores live events. Stale revisions are ignored.
+    pub fn record_events(&self, events: &[SessionEvent]) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        for event in events {
+            if accept_revision(&tx, &event.session_id, event.revision)? {
+                insert_event(&tx, event)?;
+                advance_synced(&tx, &event.session_id, event.revision, 0)?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Stores a backfilled page requested with `after_seq`. The gateway
+    /// returned everything in `(after_seq, nextAfterSeq]`, so when that range
+    /// starts inside the synced prefix it extends it.
+    pub fn record_page(&self, after_seq: u64, page: &SessionEventsResponse) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        if accept_revision(&tx, &page.session_id, page.revision)? {
+            for event in &page.events {
+                if (&event.session_id, event.revision) == (&page.session_id, page.revision) {
+                    insert_event(&tx, event)?;
+                }
+            }
+            let covered = page
+                .events
+                .iter()
+                .map(|event| event.seq)
+                .chain(page.next_after_seq)
+                .max()
+                .unwrap_or(after_seq);
+            let synced = synced_seq(&tx, &page.session_id)?;
+            let floor = if after_seq <= synced { covered } else { 0 };
+            advance_synced(&tx, &page.session_id, page.revision, floor)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Reads cached events after `after_seq`. A `revision` other than the
+    /// cached one yields an empty page reporting the cached revision, the way
+    /// the gateway reports a reload.
+    pub fn events(
+        &self,
+        session_id: &str,
+        revision: Option<u64>,
+        after_seq: u64,
+        limit: Option<u32>,
+    ) -> Result<CachedEventsPage> {
+        let conn = self.conn();
+        let cached: Option<(Option<u64>, u64)> = conn
+            .query_row(
+                "SELECT revision, synced_seq FROM sessions WHERE session_id = ?1",
+                params![session_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        let (cached_revision, synced_seq) = match cached {
+            Some((Some(cached), synced)) => (cached, synced),
+            _ => (revision.unwrap_or(0), 0),
+        };
+        let mut page = CachedEventsPage {
+            session_id: session_id.to_owned(),
+            revision: cached_revision,
+            events: Vec::new(),
+            next_after_seq: None,
+            has_more: false,
+            synced_seq,
+        };
+        if revision.is_some_and(|revision| revision != cached_revision) {
+            return Ok(page);
+        }
+
+        let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+        let mut statement = conn.prepare_cached(
+            "SELECT event FROM session_events
+             WHERE session_id = ?1 AND revision = ?2 AND seq > ?3
+             ORDER BY seq LIMIT ?4",
+        )?;
+        let rows = statement.query_map(
+            params![session_id, cached_revision, after_seq, limit + 1],
+            |row| row.get::<_, String>(0),
+        )?;
+        for row in rows {
+            page.events.push(serde_json::from_str(&row?)?);
+        }
+        page.has_more = page.events.len() > limit as usize;
+        page.events.truncate(limit as usize);
+        page.next_after_seq = page.events.last().map(|event| event.seq);
+        Ok(page)
+    }
+
+    pub fn sessions(&self) -> Result<Vec<CachedSession>> {
+        let conn = self.conn();
+        let mut statement = conn.prepare(
+            "SELECT s.session_id, s.summary, s.revision, s.synced_seq,
+                    (SELECT MAX(seq) FROM session_events e
+                     WHERE e.session_id = s.session_id AND e.revision = s.revision)
+             FROM sessions s ORDER BY s.session_id",
+        )?;
+        let rows = statement.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+            ))
+        })?;
+        let mut sessions = Vec::new();
+        for row in rows {
+            let (session_id, summary, revision, synced_seq, last_seq) = row?;
+            sessions.push(CachedSession {
+                session_id,
+                summary: summary.and_then(|summary| serde_json::from_str(&summary).ok()),
+                revision,
+                synced_seq,
+                last_seq,
+            });
+        }
+        Ok(sessions)
+    }
+
+    /// Keeps summaries current and forgets removed sessions. A summary with
+    /// a newer revision compacts the cached events right away.
+    pub fn apply_sessions_changed(&self, changed: &SessionsChangedPayload) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        for summary in changed.added.iter().chain(&changed.updated) {
+            tx.execute(
+                "INSERT INTO sessions (session_id, summary) VALUES (?1, ?2)
+                 ON CONFLICT (session_id) DO UPDATE SET summary = excluded.summary",
+                params![summary.session_id, serde_json::to_string(summary)?],
+            )?;
+            if let Some(revision) = summary.revision {
+                accept_revision(&tx, &summary.session_id, revision)?;
+            }
+        }
+        for session_id in &changed.removed {
+            tx.execute(
+                "DELETE FROM session_events WHERE session_id = ?1",
+                params![session_id],
+            )?;
+            tx.execute(
+                "DELETE FROM sessions WHERE session_id = ?1",
+                params![session_id],
+            )?;
+            search::forget_session(&tx, session_id)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+/// Makes `revision` the session's current one if it is not older, dropping
+/// the events of the revision it replaces. Returns false for stale events.
+fn accept_revision(conn: &Connection, session_id: &str, revision: u64) -> Result<bool> {
+    let current: Option<Option<u64>> = conn
+        .query_row(
+            "SELECT revision FROM sessions WHERE session_id = ?1",
+            params![session_id],
+            |row| row.get(0),
+        )
+        .optional()?;
+    match current.flatten() {
+        Some(current) if current == revision => Ok(true),
+        Some(current) if current > revision => Ok(false),
+        _ => {
+            conn.execute(
+                "DELETE FROM session_events WHERE session_id = ?1 AND revision < ?2",
+                params![session_id, revision],
+            )?;
+            search::forget_revisions_before(conn, session_id, revision)?;
+            conn.execute(
+                "INSERT INTO sessions (session_id, revision, synced_seq) VALUES (?1, ?2, 0)
+                 ON CONFLICT (session_id) DO UPDATE SET revision = ?2, synced_seq = 0",
+                params![session_id, revision],
+            )?;
+            Ok(true)
+        }
+    }
+}
+
+fn insert_event(conn: &Connection, event: &SessionEvent) -> Result<()> {
+    conn.prepare_cached(
+        "INSERT OR IGNORE INTO session_events (session_id, revision, seq, kind, created_at, event)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+    )?
+    .execute(params![
+        event.session_id,
+        event.revision,
+        event.seq,
+        event.kind,
+        event.created_at,
+        serde_json::to_string(event)?,
+    ])?;
+    Ok(())
+}
+
+fn synced_seq(conn: &Connection, session_id: &str) -> Result<u64> {
+    Ok(conn
+        .query_row(
+            "SELECT synced_seq FROM sessions WHERE session_id = ?1",
+            params![session_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .unwrap_or(0))
+}
+
+/// Raises the synced prefix to at least `floor`, then across any cached
+/// events that directly follow it.
+fn advance_synced(conn: &Connection, session_id: &str, revision: u64, floor: u64) -> Result<()> {
+    let mut synced = synced_seq(conn, session_id)?.max(floor);
+    let mut statement = conn.prepare_cached(
+        "SELECT seq FROM session_events
+         WHERE session_id = ?1 AND revision = ?2 AND seq > ?3 ORDER BY seq",
+    )?;
+    let seqs = statement.query_map(params![session_id, revision, synced], |row| {
+        row.get::<_, u64>(0)
+    })?;
+    for seq in seqs {
+        let seq = seq?;
+        if seq != synced + 1 {
+            break;
+        }
+        synced = seq;
+    }
+    conn.execute(
+        "UPDATE sessions SET synced_seq = ?2 WHERE session_id = ?1",
+        params![session_id, synced],
+    )?;
+    Ok(())
+}
+
+/// Mirrors gateway traffic into the cache. Must run after both the cache and
+/// the gateway state are managed.
+pub fn init<R: Runtime>(app: &AppHandle<R>) {
+    let mut events = app.state::<GatewayState>().events();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let event = match events.recv().await {
+                Ok(event) => event,
+                Err(RecvError::Lagged(_)) => continue,
+                Err(RecvError::Closed) => return,
+            };
+            let cache = app.state::<SessionCache>();
+            // A failed write only costs a longer backfill later.
+            let _ = match &event {
+                GatewayEvent::SessionEvent(event) => {
+                    cache.record_events(std::slice::from_ref(event))
+                }
+                GatewayEvent::SessionsChanged(changed) => cache.apply_sessions_changed(changed),
+                _ => Ok(()),
+            };
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    pub(super) fn cache() -> SessionCache {
+        SessionCache::with_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn event(revision: u64, seq: u64) -> SessionEvent {
+        serde_json::from_value(json!({
+            "sessionId": "s1",
+            "machineId": "m1",
+            "revision": revision,
+            "seq": seq,
+            "kind": "agent_message_chunk",
+            "createdAt": "2026-01-01T00:00:00.000Z",
+            "payload": { "text": format!("chunk {seq}") }
+        }))
+        .unwrap()
+    }
+
+    fn page(revision: u64, seqs: &[u64]) -> SessionEventsResponse {
+        SessionEventsResponse {
+            session_id: "s1".to_owned(),
+            revision,
+            events: seqs.iter().map(|&seq| event(revision, seq)).collect(),
+            next_after_seq: seqs.last().copied(),
+        }
+    }
+
+    #[test]
+    fn live_events_only_advance_the_contiguous_prefix() {
+        let cache = cache();
+        cache
+            .record_events(&[event(1, 1), event(1, 2), event(1, 5)])
+            .unwrap();
+        assert_eq!(cache.events("s1", Some(1), 0, None).unwrap().synced_seq, 2);
+
+        // Backfilling the gap joins up with the live event after it.
+        cache.record_page(2, &page(1, &[3, 4])).unwrap();
+        let page = cache.events("s1", None, 0, None).unwrap();
+        assert_eq!(page.synced_seq, 5);
+        let seqs: Vec<_> = page.events.iter().map(|event| event.seq).collect();
+        assert_eq!(seqs, [1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn pages_past_the_synced_prefix_do_not_skip_the_gap() {
+        let cache = cache();
+        cache.record_page(0, &page(1, &[1, 2])).unwrap();
+        cache.record_page(10, &page(1, &[11, 12])).unwrap();
+        assert_eq!(cache.events("s1", None, 0, None).unwrap().synced_seq, 2);
+    }
+
+    #[test]
+    fn pages_by_after_seq() {
+        let cache = cache();
+        cache.record_page(0, &page(1, &[1, 2, 3])).unwrap();
+        let first = cache.events("s1", Some(1), 0, Some(2)).unwrap();
+        assert!(first.has_more);
+        assert_eq!(first.next_after_seq, Some(2));
+        let rest = cache.events("s1", Some(1), 2, Some(2)).unwrap();
+        assert!(!rest.has_more);
+        assert_eq!(rest.events.len(), 1);
+    }
+
+    #[test]
+    fn newer_revision_compacts_the_old_one() {
+        let cache = cache();
+        cache.record_page(0, &page(1, &[1, 2, 3])).unwrap();
+        cache.record_events(&[event(2, 1)]).unwrap();
+        // Stale events from the old revision are dropped.
+        cache.record_events(&[event(1, 4)]).unwrap();
+
+        let stale = cache.events("s1", Some(1), 0, None).unwrap();
+        assert_eq!((stale.revision, stale.events.len()), (2, 0));
+        let current = cache.events("s1", Some(2), 0, None).unwrap();
+        assert_eq!((current.events.len(), current.synced_seq), (1, 1));
+        let count: u64 = cache
+            .conn()
+            .query_row("SELECT COUNT(*) FROM session_events", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 1);
+    }
+
+    #[test]
+    fn tracks_summaries_and_removals() {
+        let cache = cache();
+        cache.record_events(&[event(1, 1)]).unwrap();
+        let changed: SessionsChangedPayload = serde_json::from_value(json!({
+            "added": [{ "sessionId": "s2", "title": "New", "updatedAt": "2026-01-02T00:00:00.000Z" }],
+            "updated": [{ "sessionId": "s1", "title": "Reloaded", "revision": 3, "updatedAt": "2026-01-02T00:00:00.000Z" }],
+            "removed": []
+        }))
+        .unwrap();
+        cache.apply_sessions_changed(&changed).unwrap();
+
+        let sessions = cache.sessions().unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert_eq!(sessions[0].revision, Some(3));
+        assert_eq!(sessions[0].last_seq, None);
+        assert_eq!(sessions[0].summary.as_ref().unwrap().title, "Reloaded");
+        assert_eq!(sessions[1].revision, None);
+
+        let removed: SessionsChangedPayload = serde_json::from_value(json!({
+            "added": [], "updated": [], "removed": ["s1"]
+        }))
+        .unwrap();
+        cache.apply_sessions_changed(&removed).unwrap();
+        assert_eq!(cache.sessions().unwrap().len(), 1);
+    }
+}
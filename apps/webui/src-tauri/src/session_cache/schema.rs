@@ -0,0 +1,42 @@
+//! Schema migrations, applied in order by `crate::sqlite::migrate`.
+
+pub(super) const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE sessions (
+        session_id TEXT PRIMARY KEY NOT NULL,
+        summary TEXT,
+        revision INTEGER,
+        synced_seq INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE TABLE session_events (
+        session_id TEXT NOT NULL,
+        revision INTEGER NOT NULL,
+        seq INTEGER NOT NULL,
+        kind TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        event TEXT NOT NULL,
+        PRIMARY KEY (session_id, revision, seq)
+    );
+",
+    "
+    ALTER TABLE session_events ADD COLUMN indexed INTEGER NOT NULL DEFAULT 0;
+    CREATE INDEX session_events_unindexed ON session_events (session_id, revision, seq)
+        WHERE indexed = 0;
+    CREATE VIRTUAL TABLE session_search USING fts5(
+        text,
+        session_id UNINDEXED,
+        revision UNINDEXED,
+        seq UNINDEXED,
+        kind UNINDEXED,
+        tokenize = 'trigram'
+    );
+    -- The last document per session, so streamed chunks extend it.
+    CREATE TABLE session_search_tail (
+        session_id TEXT PRIMARY KEY NOT NULL,
+        kind TEXT NOT NULL,
+        revision INTEGER NOT NULL,
+        last_seq INTEGER NOT NULL,
+        doc INTEGER NOT NULL
+    );
+",
+];
@@ -0,0 +1,482 @@
+//! Full-text search over cached transcripts.
+//!
+//! User messages, agent messages and tool calls are indexed lazily, right
+//! before a search, so encrypted events become searchable as soon as their
+//! session DEK is unwrapped. Streamed chunks of one message are merged into
+//! a single document that points at the message's first seq. The index uses
+//! the trigram tokenizer so text without word breaks (Chinese) matches too;
+//! terms shorter than a trigram fall back to `LIKE`.
+
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::Serialize;
+use serde_json::Value;
+
+use super::SessionCache;
+use crate::e2ee::is_encrypted_payload;
+use crate::error::Result;
+use crate::protocol::{SessionEvent, SessionSummary};
+
+const INDEXED_KINDS: &str = "'user_message', 'agent_message_chunk', 'tool_call'";
+/// Long messages are split so hits still land near the matching text.
+const MAX_DOCUMENT_BYTES: usize = 16 * 1024;
+const MIN_MATCH_CHARS: usize = 3;
+const DEFAULT_LIMIT: u32 = 50;
+const MAX_LIMIT: u32 = 200;
+const SNIPPET_BEFORE: usize = 40;
+const SNIPPET_AFTER: usize = 120;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub session_id: String,
+    pub session_title: Option<String>,
+    pub revision: u64,
+    /// First event of the matching message.
+    pub seq: u64,
+    pub kind: String,
+    pub snippet: String,
+    /// `[start, end)` UTF-16 offsets into `snippet`, like `fuzzySearch`.
+    pub highlight_ranges: Vec<(usize, usize)>,
+}
+
+impl SessionCache {
+    /// Indexes every cached event not indexed yet. Events that stay
+    /// encrypted after `decrypt` are retried on the next call.
+    pub fn index_pending(
+        &self,
+        decrypt: impl Fn(SessionEvent) -> Result<SessionEvent>,
+    ) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        tx.execute(
+            &format!(
+                "UPDATE session_events SET indexed = 1
+                 WHERE indexed = 0 AND kind NOT IN ({INDEXED_KINDS})"
+            ),
+            [],
+        )?;
+        let pending = {
+            let mut statement = tx.prepare(
+                "SELECT event FROM session_events WHERE indexed = 0
+                 ORDER BY session_id, revision, seq",
+            )?;
+            let rows = statement.query_map([], |row| row.get::<_, String>(0))?;
+            rows.collect::<rusqlite::Result<Vec<_>>>()?
+        };
+        for event in pending {
+            let event: SessionEvent = serde_json::from_str(&event)?;
+            let key = (event.session_id.clone(), event.revision, event.seq);
+            // Payloads that fail to decrypt never will; skip them for good.
+            if let Ok(event) = decrypt(event) {
+                if is_encrypted_payload(&event.payload) {
+                    continue;
+                }
+                if let Some(text) = extract_text(&event.kind, &event.payload) {
+                    index_text(&tx, &event, &text)?;
+                }
+            }
+            tx.execute(
+                "UPDATE session_events SET indexed = 1
+                 WHERE session_id = ?1 AND revision = ?2 AND seq = ?3",
+                params![key.0, key.1, key.2],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Ranked hits for whitespace-separated terms, all of which must match.
+    pub fn search(&self, query: &str, limit: Option<u32>) -> Result<Vec<SearchHit>> {
+        let terms: Vec<&str> = query.split_whitespace().collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+        let (long, short): (Vec<&str>, Vec<&str>) = terms
+            .iter()
+            .partition(|term| term.chars().count() >= MIN_MATCH_CHARS);
+
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if !long.is_empty() {
+            conditions.push("session_search MATCH ?".to_owned());
+            values.push(
+                long.iter()
+                    .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            );
+        }
+        for term in &short {
+            conditions.push("session_search.text LIKE ? ESCAPE '\\'".to_owned());
+            values.push(format!("%{}%", escape_like(term)));
+        }
+        let order = if long.is_empty() {
+            "session_search.rowid DESC"
+        } else {
+            "bm25(session_search)"
+        };
+        let limit = limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+        let conn = self.conn();
+        let mut statement = conn.prepare(&format!(
+            "SELECT session_search.session_id, session_search.revision, session_search.seq,
+                    session_search.kind, session_search.text, sessions.summary
+             FROM session_search
+             LEFT JOIN sessions ON sessions.session_id = session_search.session_id
+             WHERE {}
+             ORDER BY {order} LIMIT {limit}",
+            conditions.join(" AND ")
+        ))?;
+        let rows = statement.query_map(params_from_iter(values), |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, u64>(1)?,
+                row.get::<_, u64>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, Option<String>>(5)?,
+            ))
+        })?;
+        let mut hits = Vec::new();
+        for row in rows {
+            let (session_id, revision, seq, kind, text, summary) = row?;
+            let (snippet, highlight_ranges) = snippet(&text, &terms);
+            hits.push(SearchHit {
+                session_id,
+                session_title: summary
+                    .and_then(|summary| serde_json::from_str::<SessionSummary>(&summary).ok())
+                    .map(|summary| summary.title)
+                    .filter(|title| !title.is_empty()),
+                revision,
+                seq,
+                kind,
+                snippet,
+                highlight_ranges,
+            });
+        }
+        Ok(hits)
+    }
+}
+
+pub(super) fn forget_session(conn: &Connection, session_id: &str) -> Result<()> {
+    conn.execute(
+        "DELETE FROM session_search WHERE session_id = ?1",
+        params![session_id],
+    )?;
+    conn.execute(
+        "DELETE FROM session_search_tail WHERE session_id = ?1",
+        params![session_id],
+    )?;
+    Ok(())
+}
+
+/// Drops the index entries of revisions older than `revision`.
+pub(super) fn forget_revisions_before(
+    conn: &Connection,
+    session_id: &str,
+    revision: u64,
+) -> Result<()> {
+    conn.execute(
+        "DELETE FROM session_search WHERE session_id = ?1 AND revision < ?2",
+        params![session_id, revision],
+    )?;
+    conn.execute(
+        "DELETE FROM session_search_tail WHERE session_id = ?1 AND revision < ?2",
+        params![session_id, revision],
+    )?;
+    Ok(())
+}
+
+/// Appends to the open document when the event continues the same message,
+/// otherwise starts a new one.
+fn index_text(conn: &Connection, event: &SessionEvent, text: &str) -> Result<()> {
+    let tail: Option<(String, u64, u64, i64)> = conn
+        .query_row(
+            "SELECT kind, revision, last_seq, doc FROM session_search_tail WHERE session_id = ?1",
+            params![event.session_id],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+        )
+        .optional()?;
+    let continues = event.kind != "tool_call"
+        && tail.as_ref().is_some_and(|(kind, revision, last_seq, _)| {
+            *kind == event.kind && *revision == event.revision && *last_seq + 1 == event.seq
+        });
+    if let (true, Some((_, _, _, doc))) = (continues, &tail) {
+        let current: String = conn.query_row(
+            "SELECT text FROM session_search WHERE rowid = ?1",
+            params![doc],
+            |row| row.get(0),
+        )?;
+        if current.len() + text.len() <= MAX_DOCUMENT_BYTES {
+            let separator = if event.kind == "user_message" {
+                "\n"
+            } else {
+                ""
+            };
+            conn.execute(
+                "UPDATE session_search SET text = ?2 WHERE rowid = ?1",
+                params![doc, format!("{current}{separator}{text}")],
+            )?;
+            conn.execute(
+                "UPDATE session_search_tail SET last_seq = ?2 WHERE session_id = ?1",
+                params![event.session_id, event.seq],
+            )?;
+            return Ok(());
+        }
+    }
+
+    conn.execute(
+        "INSERT INTO session_search (text, session_id, revision, seq, kind)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            text,
+            event.session_id,
+            event.revision,
+            event.seq,
+            event.kind
+        ],
+    )?;
+    let doc = conn.last_insert_rowid();
+    conn.execute(
+        "INSERT INTO session_search_tail (session_id, kind, revision, last_seq, doc)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT (session_id) DO UPDATE SET
+             kind = excluded.kind, revision = excluded.revision,
+             last_seq = excluded.last_seq, doc = excluded.doc",
+        params![event.session_id, event.kind, event.revision, event.seq, doc],
+    )?;
+    Ok(())
+}
+
+/// Searchable text of an ACP `SessionNotification` payload.
+fn extract_text(kind: &str, payload: &Value) -> Option<String> {
+    let update = &payload["update"];
+    let mut parts = Vec::new();
+    match kind {
+        "user_message" | "agent_message_chunk" => {
+            content_block_text(&update["content"], &mut parts)
+        }
+        "tool_call" => {
+            push_str(&update["title"], &mut parts);
+            for location in update["locations"].as_array().into_iter().flatten() {
+                push_str(&location["path"], &mut parts);
+            }
+            string_leaves(&update["rawInput"], &mut parts);
+            for content in update["content"].as_array().into_iter().flatten() {
+                match content["type"].as_str() {
+                    Some("content") => content_block_text(&content["content"], &mut parts),
+                    Some("diff") => push_str(&content["path"], &mut parts),
+                    _ => {}
+                }
+            }
+        }
+        _ => {}
+    }
+    let text = parts.join("\n");
+    (!text.trim().is_empty()).then_some(text)
+}
+
+fn content_block_text(block: &Value, parts: &mut Vec<String>) {
+    match block["type"].as_str() {
+        Some("text") => push_str(&block["text"], parts),
+        Some("resource_link") => {
+            push_str(&block["name"], parts);
+            push_str(&block["uri"], parts);
+        }
+        Some("resource") => {
+            push_str(&block["resource"]["uri"], parts);
+            push_str(&block["resource"]["text"], parts);
+        }
+        _ => {}
+    }
+}
+
+fn push_str(value: &Value, parts: &mut Vec<String>) {
+    if let Some(text) = value.as_str().filter(|text| !text.is_empty()) {
+        parts.push(text.to_owned());
+    }
+}
+
+fn string_leaves(value: &Value, parts: &mut Vec<String>) {
+    match value {
+        Value::String(_) => push_str(value, parts),
+        Value::Array(items) => items.iter().for_each(|item| string_leaves(item, parts)),
+        Value::Object(map) => map.values().for_each(|item| string_leaves(item, parts)),
+        _ => {}
+    }
+}
+
+fn escape_like(term: &str) -> String {
+    term.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// A window of `text` around the first matching term, with every term
+/// occurrence inside it highlighted. Matching is case-insensitive.
+fn snippet(text: &str, terms: &[&str]) -> (String, Vec<(usize, usize)>) {
+    let chars: Vec<char> = text.chars().collect();
+    let terms: Vec<Vec<char>> = terms
+        .iter()
+        .map(|term| term.chars().flat_map(char::to_lowercase).collect())
+        .collect();
+    let lowered: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    let matches_at = |index: usize| {
+        terms
+            .iter()
+            .filter(|term| lowered[index..].starts_with(term))
+            .map(Vec::len)
+            .max()
+    };
+
+    let first = (0..chars.len()).find(|&index| matches_at(index).is_some());
+    let start = first.map_or(0, |first| first.saturating_sub(SNIPPET_BEFORE));
+    let end = first.map_or(SNIPPET_BEFORE + SNIPPET_AFTER, |first| {
+        first + SNIPPET_AFTER
+    });
+    let end = end.min(chars.len());
+
+    let mut snippet = String::new();
+    let mut offset = 0;
+    if start > 0 {
+        snippet.push('…');
+        offset += 1;
+    }
+    let mut ranges = Vec::new();
+    let mut index = start;
+    while index < end {
+        if let Some(len) = matches_at(index) {
+            let len = len.min(end - index);
+            let width: usize = chars[index..index + len]
+                .iter()
+                .map(|c| c.len_utf16())
+                .sum();
+            ranges.push((offset, offset + width));
+            snippet.extend(&chars[index..index + len]);
+            offset += width;
+            index += len;
+        } else {
+            snippet.push(chars[index]);
+            offset += chars[index].len_utf16();
+            index += 1;
+        }
+    }
+    if end < chars.len() {
+        snippet.push('…');
+    }
+    (snippet.replace('\n', " "), ranges)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::cache;
+    use super::*;
+    use serde_json::json;
+
+    fn event(seq: u64, kind: &str, update: Value) -> SessionEvent {
+        serde_json::from_value(json!({
+            "sessionId": "s1",
+            "machineId": "m1",
+            "revision": 1,
+            "seq": seq,
+            "kind": kind,
+            "createdAt": "2026-01-01T00:00:00.000Z",
+            "payload": { "sessionId": "s1", "update": update }
+        }))
+        .unwrap()
+    }
+
+    fn chunk(seq: u64, text: &str) -> SessionEvent {
+        event(
+            seq,
+            "agent_message_chunk",
+            json!({ "sessionUpdate": "agent_message_chunk", "content": { "type": "text", "text": text } }),
+        )
+    }
+
+    #[test]
+    fn merges_streamed_chunks_into_one_hit() {
+        let cache = cache();
+        cache
+            .record_events(&[
+                event(
+                    1,
+                    "user_message",
+                    json!({ "sessionUpdate": "user_message_chunk", "content": { "type": "text", "text": "Please fix it" } }),
+                ),
+                chunk(2, "I fixed the migr"),
+                chunk(3, "ation bug in "),
+                chunk(4, "schema.rs"),
+                event(
+                    5,
+                    "tool_call",
+                    json!({ "sessionUpdate": "tool_call", "title": "Edit schema.rs", "rawInput": { "path": "src/schema.rs" } }),
+                ),
+            ])
+            .unwrap();
+        cache.index_pending(Ok).unwrap();
+
+        let hits = cache.search("migration BUG", None).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(
+            (hits[0].seq, hits[0].kind.as_str()),
+            (2, "agent_message_chunk")
+        );
+        assert_eq!(hits[0].snippet, "I fixed the migration bug in schema.rs");
+        assert_eq!(hits[0].highlight_ranges, [(12, 21), (22, 25)]);
+
+        let hits = cache.search("schema.rs", None).unwrap();
+        let seqs: Vec<_> = hits.iter().map(|hit| hit.seq).collect();
+        assert_eq!(seqs.len(), 2);
+        assert!(seqs.contains(&2) && seqs.contains(&5));
+    }
+
+    #[test]
+    fn short_terms_and_cjk_match() {
+        let cache = cache();
+        cache
+            .record_events(&[chunk(1, "修复了数据库迁移的问题")])
+            .unwrap();
+        cache.index_pending(Ok).unwrap();
+        assert_eq!(cache.search("迁移", None).unwrap().len(), 1);
+        assert_eq!(cache.search("数据库迁移", None).unwrap().len(), 1);
+        assert!(cache.search("部署", None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn encrypted_events_wait_for_their_key() {
+        let cache = cache();
+        let mut encrypted = chunk(1, "secret plan");
+        let plaintext = encrypted.payload.clone();
+        encrypted.payload = json!({ "t": "encrypted", "c": "AAAA" });
+        cache.record_events(&[encrypted]).unwrap();
+
+        cache.index_pending(Ok).unwrap();
+        assert!(cache.search("secret", None).unwrap().is_empty());
+
+        cache
+            .index_pending(|mut event| {
+                event.payload = plaintext.clone();
+                Ok(event)
+            })
+            .unwrap();
+        assert_eq!(cache.search("secret", None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn revision_change_drops_old_hits() {
+        let cache = cache();
+        cache.record_events(&[chunk(1, "old transcript")]).unwrap();
+        cache.index_pending(Ok).unwrap();
+        let mut reloaded = chunk(1, "new transcript");
+        reloaded.revision = 2;
+        cache.record_events(&[reloaded]).unwrap();
+        cache.index_pending(Ok).unwrap();
+
+        assert!(cache.search("old", None).unwrap().is_empty());
+        assert_eq!(cache.search("transcript", None).unwrap()[0].revision, 2);
+    }
+}
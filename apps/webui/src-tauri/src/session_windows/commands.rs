@@ -0,0 +1,23 @@
+use tauri::{AppHandle, State};
+
+use super::{SessionWindowInfo, SessionWindows};
+use crate::error::Result;
+
+/// Shows the session in its own window, focusing the window if the session
+/// already has one. Async because creating a window from a synchronous
+/// command deadlocks on Windows.
+#[tauri::command]
+pub async fn session_window_open(app: AppHandle, session_id: String) -> Result<SessionWindowInfo> {
+    super::open(&app, &session_id)
+}
+
+#[tauri::command]
+pub fn session_window_close(app: AppHandle, session_id: String) -> Result<()> {
+    super::close(&app, &session_id)
+}
+
+/// The session windows currently open.
+#[tauri::command]
+pub fn session_window_list(windows: State<'_, SessionWindows>) -> Vec<SessionWindowInfo> {
+    windows.list()
+}
@@ -0,0 +1,379 @@
+//! Extra windows that each show one session, so agents can sit side by side.
+//!
+//! A session window loads the same app as `main` with the session in its
+//! query string and gets the same preload script, so it starts from the
+//! current auth, gateway and webview state. E2EE keys, the vault and the
+//! gateway connection live in this process and serve every window alike.
+//!
+//! The open windows are kept in the `windows` settings scope, keyed by
+//! session, together with their last geometry, and reopened at the next
+//! launch. Closing a window forgets it; quitting the app does not.
+
+pub mod commands;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder, WindowEvent,
+};
+
+use crate::error::{Error, Result};
+use crate::gateway::GatewayState;
+use crate::preload;
+use crate::settings::{self, SettingsStore};
+
+/// Labels of session windows start with this; capabilities match on it.
+pub const LABEL_PREFIX: &str = "session-";
+/// Query parameter the webview reads the session from.
+const SESSION_PARAM: &str = "sessionId";
+/// Marks the page as a session window rather than `main`.
+const WINDOW_PARAM: &str = "sessionWindow";
+
+const DEFAULT_WIDTH: f64 = 900.0;
+const DEFAULT_HEIGHT: f64 = 800.0;
+const MIN_WIDTH: f64 = 375.0;
+const MIN_HEIGHT: f64 = 600.0;
+/// Moves and resizes arrive in bursts; geometry is written once they settle.
+const SAVE_DELAY: Duration = Duration::from_millis(500);
+/// A restored window must show this much of its top edge on some monitor,
+/// or it opens at the default position instead.
+const MIN_VISIBLE: i64 = 40;
+
+/// Outer position and inner size in physical pixels. While maximized they
+/// hold the bounds the window returns to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Geometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionWindowInfo {
+    pub label: String,
+    pub session_id: String,
+}
+
+struct OpenWindow {
+    session_id: String,
+    geometry: Option<Geometry>,
+}
+
+#[derive(Default)]
+struct Inner {
+    windows: BTreeMap<String, OpenWindow>,
+    /// Labels whose geometry changed since the last save.
+    dirty: BTreeSet<String>,
+    save_scheduled: bool,
+}
+
+#[derive(Default, Clone)]
+pub struct SessionWindows {
+    inner: Arc<Mutex<Inner>>,
+}
+
+/// The window label for a session. Characters labels cannot hold are
+/// escaped as `:` and two hex digits, so distinct sessions never share one.
+pub fn label(session_id: &str) -> String {
+    let mut label = String::from(LABEL_PREFIX);
+    for byte in session_id.bytes() {
+        if byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_' {
+            label.push(byte as char);
+        } else {
+            label.push_str(&format!(":{byte:02x}"));
+        }
+    }
+    label
+}
+
+/// Reopens the session windows that were open when the app last quit.
+pub fn restore<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    let saved = app.state::<SettingsStore>().entries(settings::WINDOWS)?;
+    for (session_id, geometry) in saved {
+        let geometry = serde_json::from_value(geometry).ok();
+        if build(app, &session_id, geometry).is_err() {
+            let _ = app
+                .state::<SettingsStore>()
+                .delete(settings::WINDOWS, &session_id);
+        }
+    }
+    Ok(())
+}
+
+/// Focuses the session's window, opening it at its last geometry if it is
+/// not open yet.
+pub fn open<R: Runtime>(app: &AppHandle<R>, session_id: &str) -> Result<SessionWindowInfo> {
+    if session_id.is_empty() {
+        return Err(Error::Window("Missing session id".into()));
+    }
+    let label = label(session_id);
+    if let Some(window) = app.get_webview_window(&label) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+        return Ok(SessionWindowInfo {
+            label,
+            session_id: session_id.to_owned(),
+        });
+    }
+    let settings = app.state::<SettingsStore>();
+    let geometry = settings
+        .get(settings::WINDOWS, session_id)?
+        .and_then(|geometry| serde_json::from_value(geometry).ok());
+    build(app, session_id, geometry)?;
+    settings.set(
+        settings::WINDOWS,
+        session_id,
+        &serde_json::to_value(geometry)?,
+    )?;
+    Ok(SessionWindowInfo {
+        label,
+        session_id: session_id.to_owned(),
+    })
+}
+
+pub fn close<R: Runtime>(app: &AppHandle<R>, session_id: &str) -> Result<()> {
+    if let Some(window) = app.get_webview_window(&label(session_id)) {
+        window.close()?;
+    }
+    Ok(())
+}
+
+fn build<R: Runtime>(
+    app: &AppHandle<R>,
+    session_id: &str,
+    geometry: Option<Geometry>,
+) -> Result<WebviewWindow<R>> {
+    let label = label(session_id);
+    let query = url::form_urlencoded::Serializer::new(String::new())
+        .append_pair(SESSION_PARAM, session_id)
+        .append_pair(WINDOW_PARAM, "1")
+        .finish();
+
+    let title = app
+        .state::<GatewayState>()
+        .snapshot()
+        .sessions
+        .into_iter()
+        .find(|session| session.session_id == session_id && !session.title.is_empty())
+        .map_or_else(|| "Mobvibe".to_owned(), |session| session.title);
+
+    let script = preload::initialization_script(app, &app.state::<SettingsStore>())?;
+    let window = WebviewWindowBuilder::new(
+        app,
+        &label,
+        WebviewUrl::App(format!("index.html?{query}").into()),
+    )
+    .title(title)
+    .inner_size(DEFAULT_WIDTH, DEFAULT_HEIGHT)
+    .min_inner_size(MIN_WIDTH, MIN_HEIGHT)
+    // Shown once the saved geometry is applied, so it does not jump.
+    .visible(false)
+    .initialization_script(script)
+    .build()?;
+
+    if let Some(geometry) = geometry {
+        let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+        let monitors: Vec<_> = window
+            .available_monitors()
+            .unwrap_or_default()
+            .iter()
+            .map(|monitor| (*monitor.position(), *monitor.size()))
+            .collect();
+        if is_visible(&geometry, &monitors) {
+            let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+        } else {
+            let _ = window.center();
+        }
+        if geometry.maximized {
+            let _ = window.maximize();
+        }
+    }
+    window.show()?;
+
+    let state = app.state::<SessionWindows>().inner().clone();
+    state.inner().windows.insert(
+        label.clone(),
+        OpenWindow {
+            session_id: session_id.to_owned(),
+            geometry,
+        },
+    );
+    let tracked = window.clone();
+    let handle = app.clone();
+    window.on_window_event(move |event| match event {
+        WindowEvent::Moved(_) | WindowEvent::Resized(_) => {
+            state.track(&handle, &tracked);
+        }
+        // Only a window the user closes is forgotten; those still open when
+        // the app quits are destroyed without this event.
+        WindowEvent::CloseRequested { .. } => {
+            if let Some(window) = state.forget(tracked.label()) {
+                let _ = handle
+                    .state::<SettingsStore>()
+                    .delete(settings::WINDOWS, &window.session_id);
+            }
+        }
+        WindowEvent::Destroyed => {
+            state.forget(tracked.label());
+        }
+        _ => {}
+    });
+    Ok(window)
+}
+
+/// Whether the top edge of `geometry` is on one of `monitors`, so the
+/// window can be grabbed and moved.
+fn is_visible(
+    geometry: &Geometry,
+    monitors: &[(PhysicalPosition<i32>, PhysicalSize<u32>)],
+) -> bool {
+    let (left, top) = (i64::from(geometry.x), i64::from(geometry.y));
+    let right = left + i64::from(geometry.width);
+    monitors.iter().any(|(position, size)| {
+        let (m_left, m_top) = (i64::from(position.x), i64::from(position.y));
+        let (m_right, m_bottom) = (
+            m_left + i64::from(size.width),
+            m_top + i64::from(size.height),
+        );
+        right.min(m_right) - left.max(m_left) >= MIN_VISIBLE
+            && top >= m_top
+            && top + MIN_VISIBLE <= m_bottom
+    })
+}
+
+impl SessionWindows {
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn list(&self) -> Vec<SessionWindowInfo> {
+        self.inner()
+            .windows
+            .iter()
+            .map(|(label, window)| SessionWindowInfo {
+                label: label.clone(),
+                session_id: window.session_id.clone(),
+            })
+            .collect()
+    }
+
+    /// Records the window's geometry and schedules a save.
+    fn track<R: Runtime>(&self, app: &AppHandle<R>, window: &WebviewWindow<R>) {
+        if window.is_minimized().unwrap_or(false) {
+            return;
+        }
+        let maximized = window.is_maximized().unwrap_or(false);
+        let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size()) else {
+            return;
+        };
+        let mut inner = self.inner();
+        let Some(open) = inner.windows.get_mut(window.label()) else {
+            return;
+        };
+        let geometry = match (maximized, open.geometry) {
+            (true, Some(restored)) => Geometry {
+                maximized: true,
+                ..restored
+            },
+            _ => Geometry {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+                maximized,
+            },
+        };
+        if open.geometry == Some(geometry) {
+            return;
+        }
+        open.geometry = Some(geometry);
+        inner.dirty.insert(window.label().to_owned());
+        if inner.save_scheduled {
+            return;
+        }
+        inner.save_scheduled = true;
+        let state = self.clone();
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(SAVE_DELAY).await;
+            state.save(&app);
+        });
+    }
+
+    fn save<R: Runtime>(&self, app: &AppHandle<R>) {
+        let changes: Vec<(String, Value)> = {
+            let mut inner = self.inner();
+            inner.save_scheduled = false;
+            let dirty = std::mem::take(&mut inner.dirty);
+            dirty
+                .iter()
+                .filter_map(|label| inner.windows.get(label))
+                .filter_map(|window| {
+                    let geometry = serde_json::to_value(window.geometry).ok()?;
+                    Some((window.session_id.clone(), geometry))
+                })
+                .collect()
+        };
+        let settings = app.state::<SettingsStore>();
+        for (session_id, geometry) in changes {
+            let _ = settings.set(settings::WINDOWS, &session_id, &geometry);
+        }
+    }
+
+    fn forget(&self, label: &str) -> Option<OpenWindow> {
+        let mut inner = self.inner();
+        inner.dirty.remove(label);
+        inner.windows.remove(label)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_are_valid_and_distinct() {
+        assert_eq!(label("9f1c-42_a"), "session-9f1c-42_a");
+        assert_eq!(label("a.b"), "session-a:2eb");
+        assert_ne!(label("a.b"), label("a:2eb"));
+        assert!(label("ü/x y")
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':')));
+    }
+
+    #[test]
+    fn restores_only_reachable_positions() {
+        let monitors = [
+            (PhysicalPosition::new(0, 0), PhysicalSize::new(1920, 1080)),
+            (
+                PhysicalPosition::new(1920, 0),
+                PhysicalSize::new(1280, 1024),
+            ),
+        ];
+        let at = |x, y| Geometry {
+            x,
+            y,
+            width: 900,
+            height: 800,
+            maximized: false,
+        };
+        assert!(is_visible(&at(100, 100), &monitors));
+        assert!(is_visible(&at(2500, 900), &monitors));
+        // Mostly off the left edge, or above every monitor.
+        assert!(!is_visible(&at(-880, 100), &monitors));
+        assert!(!is_visible(&at(100, -20), &monitors));
+        // Where a third monitor used to be.
+        assert!(!is_visible(&at(3300, 100), &monitors));
+    }
+}
@@ -0,0 +1,54 @@
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+use tauri::State;
+
+use super::{SettingChange, SettingsStore};
+use crate::error::Result;
+
+/// Every entry of a scope, for stores the webview keeps in memory.
+#[tauri::command]
+pub fn settings_entries(
+    settings: State<'_, SettingsStore>,
+    scope: String,
+) -> Result<BTreeMap<String, Value>> {
+    settings.entries(&scope)
+}
+
+#[tauri::command]
+pub fn settings_get(
+    settings: State<'_, SettingsStore>,
+    scope: String,
+    key: String,
+) -> Result<Option<Value>> {
+    settings.get(&scope, &key)
+}
+
+#[tauri::command]
+pub fn settings_set(
+    settings: State<'_, SettingsStore>,
+    scope: String,
+    key: String,
+    value: Value,
+) -> Result<()> {
+    settings.set(&scope, &key, &value)
+}
+
+#[tauri::command]
+pub fn settings_delete(
+    settings: State<'_, SettingsStore>,
+    scope: String,
+    key: String,
+) -> Result<()> {
+    settings.delete(&scope, &key)
+}
+
+/// Writes several keys in one transaction.
+#[tauri::command]
+pub fn settings_apply(
+    settings: State<'_, SettingsStore>,
+    scope: String,
+    changes: Vec<SettingChange>,
+) -> Result<()> {
+    settings.apply(&scope, &changes)
+}
@@ -0,0 +1,64 @@
+//! One-time import of the JSON files `tauri-plugin-store` used to keep in
+//! the app data directory. Each file becomes a scope; entries already in the
+//! database win. A file is only deleted once its import has committed, so a
+//! crash in between just imports it again. Master secrets in
+//! `app-state.json` go to their own scope, which is never preloaded.
+
+use std::fs;
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::{Map, Value};
+
+use super::{APP_STATE, AUTH, GATEWAY, LEGACY_SECRETS, LEGACY_SECRET_KEYS};
+use crate::error::Result;
+
+const LEGACY_FILES: &[(&str, &str)] = &[
+    ("app-state.json", APP_STATE),
+    ("auth.json", AUTH),
+    ("gateway.json", GATEWAY),
+];
+
+pub(super) fn import(conn: &mut Connection, dir: &Path) -> Result<()> {
+    for (file, scope) in LEGACY_FILES {
+        let path = dir.join(file);
+        let Ok(raw) = fs::read(&path) else {
+            continue;
+        };
+        // A file the old store left half-written is kept for inspection.
+        let Ok(entries) = serde_json::from_slice::<Map<String, Value>>(&raw) else {
+            continue;
+        };
+
+        let tx = conn.transaction()?;
+        let imported = tx
+            .query_row(
+                "SELECT 1 FROM legacy_imports WHERE file = ?1",
+                params![file],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !imported {
+            for (key, value) in &entries {
+                let scope = if *scope == APP_STATE && LEGACY_SECRET_KEYS.contains(&key.as_str()) {
+                    LEGACY_SECRETS
+                } else {
+                    scope
+                };
+                tx.execute(
+                    "INSERT OR IGNORE INTO settings (scope, key, value) VALUES (?1, ?2, ?3)",
+                    params![scope, key, serde_json::to_string(value)?],
+                )?;
+            }
+            tx.execute(
+                "INSERT INTO legacy_imports (file) VALUES (?1)",
+                params![file],
+            )?;
+        }
+        tx.commit()?;
+        // Removes the plaintext bearer token; retried on the next launch.
+        let _ = fs::remove_file(&path);
+    }
+    Ok(())
+}
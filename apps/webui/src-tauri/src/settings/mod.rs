@@ -0,0 +1,251 @@
+//! Settings and persisted webview state in SQLite.
+//!
+//! Entries are JSON values keyed by `(scope, key)`. Scopes replace the JSON
+//! files `tauri-plugin-store` used to write: `app-state` backs the Zustand
+//! `SyncStateStorage`, `auth` the bearer token and `gateway` the gateway URL;
+//! `daemon` holds the `mobvibe` CLI path chosen in the app, `windows` the
+//! open session windows with their geometry, `quick-prompt` the global
+//! shortcut, `menu` the language of the application menu and `updater` the
+//! release channel and manifest URL. `legacy-secrets` holds the master
+//! secrets found in an imported `app-state.json` until the vault takes them.
+//! Every write is its own transaction with `synchronous = FULL`, so a crash
+//! loses at most the write in flight, never the rest of the state, and
+//! `secure_delete` overwrites what a delete removes.
+
+pub mod commands;
+mod legacy;
+mod schema;
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::sqlite;
+
+/// Scopes shared with the webview (`settings-store.ts`).
+pub const APP_STATE: &str = "app-state";
+pub const AUTH: &str = "auth";
+pub const GATEWAY: &str = "gateway";
+pub const DAEMON: &str = "daemon";
+pub const WINDOWS: &str = "windows";
+pub const QUICK_PROMPT: &str = "quick-prompt";
+pub const MENU: &str = "menu";
+pub const UPDATER: &str = "updater";
+/// Never preloaded into a webview, unlike the `app-state` keys it was
+/// imported from.
+pub const LEGACY_SECRETS: &str = "legacy-secrets";
+
+/// The `app-state` keys `E2EEManager` used to keep plaintext master secrets
+/// under; the legacy import moves them to [`LEGACY_SECRETS`].
+pub const LEGACY_SECRET_KEYS: [&str; 2] = ["mobvibe_e2ee_secrets", "mobvibe_e2ee_master_secret"];
+
+const SETTINGS_FILE: &str = "settings.sqlite3";
+
+/// One write of a batch; a missing value deletes the key.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingChange {
+    pub key: String,
+    #[serde(default)]
+    pub value: Option<Value>,
+}
+
+pub struct SettingsStore {
+    conn: Mutex<Connection>,
+}
+
+impl SettingsStore {
+    /// Opens the database in `dir`, importing any legacy store files there.
+    pub fn open(dir: &Path) -> Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let conn = Connection::open(dir.join(SETTINGS_FILE))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "FULL")?;
+        conn.pragma_update(None, "secure_delete", true)?;
+        let store = Self::with_connection(conn)?;
+        legacy::import(&mut store.conn(), dir)?;
+        Ok(store)
+    }
+
+    fn with_connection(mut conn: Connection) -> Result<Self> {
+        sqlite::migrate(&mut conn, schema::MIGRATIONS)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn get(&self, scope: &str, key: &str) -> Result<Option<Value>> {
+        let value: Option<String> = self
+            .conn()
+            .query_row(
+                "SELECT value FROM settings WHERE scope = ?1 AND key = ?2",
+                params![scope, key],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()?)
+    }
+
+    /// A string entry, treating an empty string as unset.
+    pub fn string(&self, scope: &str, key: &str) -> Result<Option<String>> {
+        Ok(match self.get(scope, key)? {
+            Some(Value::String(value)) if !value.is_empty() => Some(value),
+            _ => None,
+        })
+    }
+
+    pub fn entries(&self, scope: &str) -> Result<BTreeMap<String, Value>> {
+        let conn = self.conn();
+        let mut statement =
+            conn.prepare_cached("SELECT key, value FROM settings WHERE scope = ?1")?;
+        let rows = statement.query_map(params![scope], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })?;
+        let mut entries = BTreeMap::new();
+        for row in rows {
+            let (key, value) = row?;
+            entries.insert(key, serde_json::from_str(&value)?);
+        }
+        Ok(entries)
+    }
+
+    pub fn set(&self, scope: &str, key: &str, value: &Value) -> Result<()> {
+        write(&self.conn(), scope, key, Some(value))
+    }
+
+    pub fn delete(&self, scope: &str, key: &str) -> Result<()> {
+        write(&self.conn(), scope, key, None)
+    }
+
+    /// Applies every change or none of them.
+    pub fn apply(&self, scope: &str, changes: &[SettingChange]) -> Result<()> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        for change in changes {
+            write(&tx, scope, &change.key, change.value.as_ref())?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn write(conn: &Connection, scope: &str, key: &str, value: Option<&Value>) -> Result<()> {
+    match value {
+        Some(value) => conn
+            .prepare_cached(
+                "INSERT INTO settings (scope, key, value) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (scope, key) DO UPDATE SET value = excluded.value",
+            )?
+            .execute(params![scope, key, serde_json::to_string(value)?])?,
+        None => conn
+            .prepare_cached("DELETE FROM settings WHERE scope = ?1 AND key = ?2")?
+            .execute(params![scope, key])?,
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn store() -> SettingsStore {
+        SettingsStore::with_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("mobvibe-settings-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn writes_single_keys_per_scope() {
+        let store = store();
+        store
+            .set(APP_STATE, "ui", &json!("{\"theme\":\"dark\"}"))
+            .unwrap();
+        store.set(AUTH, "bearerToken", &json!("t1")).unwrap();
+        store.set(AUTH, "bearerToken", &json!("t2")).unwrap();
+
+        assert_eq!(
+            store.string(AUTH, "bearerToken").unwrap().as_deref(),
+            Some("t2")
+        );
+        assert_eq!(store.entries(APP_STATE).unwrap().len(), 1);
+        store.delete(AUTH, "bearerToken").unwrap();
+        assert_eq!(store.get(AUTH, "bearerToken").unwrap(), None);
+    }
+
+    #[test]
+    fn applies_batches_atomically() {
+        let store = store();
+        store.set(APP_STATE, "a", &json!(1)).unwrap();
+        let changes: Vec<SettingChange> = serde_json::from_value(json!([
+            { "key": "a" },
+            { "key": "b", "value": [1, 2] }
+        ]))
+        .unwrap();
+        store.apply(APP_STATE, &changes).unwrap();
+        let entries = store.entries(APP_STATE).unwrap();
+        assert_eq!(entries.keys().collect::<Vec<_>>(), ["b"]);
+        assert_eq!(entries["b"], json!([1, 2]));
+    }
+
+    #[test]
+    fn sets_aside_secrets_an_earlier_import_left_in_app_state() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        sqlite::migrate(&mut conn, &schema::MIGRATIONS[..1]).unwrap();
+        for key in ["ui", LEGACY_SECRET_KEYS[0], LEGACY_SECRET_KEYS[1]] {
+            write(&conn, APP_STATE, key, Some(&json!("v"))).unwrap();
+        }
+        let store = SettingsStore::with_connection(conn).unwrap();
+        assert_eq!(
+            store.entries(APP_STATE).unwrap().keys().collect::<Vec<_>>(),
+            ["ui"]
+        );
+        assert_eq!(store.entries(LEGACY_SECRETS).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn imports_legacy_store_files_once() {
+        let dir = temp_dir("legacy");
+        fs::write(dir.join("auth.json"), r#"{ "bearerToken": "legacy" }"#).unwrap();
+        fs::write(dir.join("gateway.json"), "{ \"gatewayUrl\": ").unwrap();
+
+        let store = SettingsStore::open(&dir).unwrap();
+        assert_eq!(
+            store.string(AUTH, "bearerToken").unwrap().as_deref(),
+            Some("legacy")
+        );
+        assert!(!dir.join("auth.json").exists());
+        // Corrupt files are left alone.
+        assert!(dir.join("gateway.json").exists());
+        store.set(AUTH, "bearerToken", &json!("fresh")).unwrap();
+        drop(store);
+
+        // A file that reappears is not imported over newer state.
+        fs::write(dir.join("auth.json"), r#"{ "bearerToken": "legacy" }"#).unwrap();
+        let store = SettingsStore::open(&dir).unwrap();
+        assert_eq!(
+            store.string(AUTH, "bearerToken").unwrap().as_deref(),
+            Some("fresh")
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
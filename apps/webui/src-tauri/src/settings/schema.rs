@@ -0,0 +1,21 @@
+//! Schema migrations, applied in order by `crate::sqlite::migrate`.
+
+pub(super) const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE settings (
+        scope TEXT NOT NULL,
+        key TEXT NOT NULL,
+        value TEXT NOT NULL,
+        PRIMARY KEY (scope, key)
+    ) WITHOUT ROWID;
+    CREATE TABLE legacy_imports (
+        file TEXT PRIMARY KEY NOT NULL
+    );
+",
+    "
+    -- Master secrets an earlier import left in the preloaded `app-state`.
+    UPDATE OR REPLACE settings SET scope = 'legacy-secrets'
+    WHERE scope = 'app-state'
+        AND key IN ('mobvibe_e2ee_secrets', 'mobvibe_e2ee_master_secret');
+",
+];
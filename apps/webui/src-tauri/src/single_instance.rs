@@ -0,0 +1,68 @@
+//! Desktop single-instance handling. A second launch (typically the OS
+//! opening a `mobvibe://` link) hands its links to the running process and
+//! exits; the running window is brought to the front.
+
+use tauri::plugin::TauriPlugin;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::deep_link;
+
+/// Must be the first plugin registered so later plugins never run in the
+/// short-lived second process.
+pub fn init<R: Runtime>() -> TauriPlugin<R> {
+    tauri_plugin_single_instance::init(|app, argv, _cwd| {
+        for link in deep_links(argv) {
+            deep_link::open_url(app, &link);
+        }
+        focus_main_window(app);
+    })
+}
+
+/// The `mobvibe://` links among a second launch's command line. The app
+/// takes no other arguments, so the rest (and the program path) is dropped.
+fn deep_links(argv: Vec<String>) -> Vec<String> {
+    argv.into_iter()
+        .skip(1)
+        .filter(|arg| deep_link::is_deep_link(arg))
+        .collect()
+}
+
+pub fn focus_main_window<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_only_the_deep_links_of_a_second_launch() {
+        let argv = [
+            "/Applications/Mobvibe.app/Contents/MacOS/mobvibe",
+            "--flag",
+            "mobvibe://session/s1?seq=3",
+            "https://example.com",
+            "mobvibe:not-a-link",
+            "mobvibe://pair?secret=c2VjcmV0",
+        ]
+        .map(str::to_owned)
+        .to_vec();
+
+        assert_eq!(
+            deep_links(argv),
+            vec![
+                "mobvibe://session/s1?seq=3",
+                "mobvibe://pair?secret=c2VjcmV0"
+            ]
+        );
+    }
+
+    #[test]
+    fn never_takes_the_program_path_as_a_link() {
+        assert!(deep_links(vec!["mobvibe://session/s1".to_owned()]).is_empty());
+    }
+}
@@ -0,0 +1,57 @@
+//! Helpers shared by the SQLite-backed stores (settings, session cache).
+
+use rusqlite::Connection;
+
+use crate::error::Result;
+
+/// Applies `migrations` in order, in one transaction, skipping the ones
+/// already recorded in `user_version`.
+pub fn migrate(conn: &mut Connection, migrations: &[&str]) -> Result<()> {
+    let version: usize = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    let tx = conn.transaction()?;
+    for (index, migration) in migrations.iter().enumerate().skip(version) {
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index + 1)?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn user_version(conn: &Connection) -> usize {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn applies_only_new_migrations() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn, &["CREATE TABLE a (x INTEGER);"]).unwrap();
+        assert_eq!(user_version(&conn), 1);
+
+        // Re-running the first migration would fail: the table exists.
+        migrate(
+            &mut conn,
+            &[
+                "CREATE TABLE a (x INTEGER);",
+                "ALTER TABLE a ADD COLUMN y INTEGER;",
+            ],
+        )
+        .unwrap();
+        assert_eq!(user_version(&conn), 2);
+        conn.execute("INSERT INTO a (x, y) VALUES (1, 2)", [])
+            .unwrap();
+    }
+
+    #[test]
+    fn rolls_back_a_failed_run() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        let err = migrate(&mut conn, &["CREATE TABLE a (x INTEGER);", "NOT SQL;"]);
+        assert!(err.is_err());
+        assert_eq!(user_version(&conn), 0);
+        assert!(conn.execute("INSERT INTO a (x) VALUES (1)", []).is_err());
+    }
+}
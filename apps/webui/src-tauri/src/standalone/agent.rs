@@ -0,0 +1,105 @@
+//! Spawning an agent command. The command is looked up on the daemon's
+//! search path, since most agents install their ACP shim with npm or bun,
+//! and the last lines it writes to stderr are kept for error reports.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::{Child, ChildStderr, ChildStdin, ChildStdout, Command};
+
+use crate::daemon::search_path;
+use crate::error::{Error, Result};
+
+const STDERR_LINES: usize = 20;
+
+#[cfg(windows)]
+const EXTENSIONS: &[&str] = &["", ".cmd", ".exe"];
+#[cfg(not(windows))]
+const EXTENSIONS: &[&str] = &[""];
+
+/// The last lines an agent wrote to stderr.
+#[derive(Clone, Default)]
+pub struct StderrTail(Arc<Mutex<VecDeque<String>>>);
+
+impl StderrTail {
+    fn lines(&self) -> MutexGuard<'_, VecDeque<String>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn text(&self) -> String {
+        Vec::from(self.lines().clone()).join("\n")
+    }
+
+    async fn collect(self, stderr: ChildStderr) {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let mut tail = self.lines();
+            if tail.len() == STDERR_LINES {
+                tail.pop_front();
+            }
+            tail.push_back(line);
+        }
+    }
+}
+
+pub struct Agent {
+    pub child: Child,
+    pub stdin: ChildStdin,
+    pub stdout: ChildStdout,
+    pub stderr: StderrTail,
+}
+
+/// A bare command name is resolved on the search path; anything with a
+/// directory in it is used as is.
+fn resolve(command: &str, dirs: &[PathBuf]) -> Option<PathBuf> {
+    let path = Path::new(command);
+    if path.components().count() > 1 {
+        return Some(path.to_path_buf()).filter(|path| path.is_file());
+    }
+    dirs.iter()
+        .flat_map(|dir| {
+            EXTENSIONS
+                .iter()
+                .map(move |extension| dir.join(format!("{command}{extension}")))
+        })
+        .find(|path| path.is_file())
+}
+
+pub fn spawn(command: &str, args: &[String], cwd: &Path, user_home: &Path) -> Result<Agent> {
+    let dirs = search_path(user_home);
+    let program =
+        resolve(command, &dirs).ok_or_else(|| Error::Agent(format!("{command} not found")))?;
+    let mut path = vec![program.parent().map(Path::to_path_buf).unwrap_or_default()];
+    path.extend(dirs);
+    let mut command = Command::new(program);
+    command
+        .args(args)
+        .current_dir(cwd)
+        .env("PATH", std::env::join_paths(path).unwrap_or_default())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let mut child = command.spawn()?;
+    let (Some(stdin), Some(stdout), Some(stderr)) =
+        (child.stdin.take(), child.stdout.take(), child.stderr.take())
+    else {
+        return Err(Error::Agent("agent stdio is not piped".to_owned()));
+    };
+    let tail = StderrTail::default();
+    tauri::async_runtime::spawn(tail.clone().collect(stderr));
+    Ok(Agent {
+        child,
+        stdin,
+        stdout,
+        stderr: tail,
+    })
+}
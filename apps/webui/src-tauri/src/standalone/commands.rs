@@ -0,0 +1,76 @@
+use std::path::PathBuf;
+
+use serde_json::Value;
+use tauri::ipc::Channel;
+use tauri::{State, Webview};
+
+use super::{PromptResult, StandaloneSession, StandaloneState};
+use crate::error::Result;
+use crate::protocol::SessionEvent;
+
+/// Streams standalone session events to the calling webview until it
+/// unlistens or navigates away. Returns the listener id.
+#[tauri::command]
+pub fn standalone_listen(
+    webview: Webview,
+    standalone: State<'_, StandaloneState>,
+    on_event: Channel<SessionEvent>,
+) -> u32 {
+    standalone.listen(webview.label(), on_event)
+}
+
+#[tauri::command]
+pub fn standalone_unlisten(standalone: State<'_, StandaloneState>, id: u32) {
+    standalone.unlisten(id);
+}
+
+#[tauri::command]
+pub fn standalone_sessions(standalone: State<'_, StandaloneState>) -> Vec<StandaloneSession> {
+    standalone.sessions()
+}
+
+/// Spawns an ACP agent, e.g. `claude-code-acp` or `gemini
+/// --experimental-acp`, and opens a session in `cwd`.
+#[tauri::command]
+pub async fn standalone_start(
+    standalone: State<'_, StandaloneState>,
+    command: String,
+    args: Option<Vec<String>>,
+    cwd: PathBuf,
+) -> Result<StandaloneSession> {
+    standalone
+        .start(&command, &args.unwrap_or_default(), &cwd)
+        .await
+}
+
+/// Sends a prompt of ACP content blocks and resolves when the turn ends.
+#[tauri::command]
+pub async fn standalone_prompt(
+    standalone: State<'_, StandaloneState>,
+    session_id: String,
+    prompt: Vec<Value>,
+    message_id: String,
+) -> Result<PromptResult> {
+    standalone.prompt(&session_id, prompt, &message_id).await
+}
+
+#[tauri::command]
+pub fn standalone_cancel(standalone: State<'_, StandaloneState>, session_id: String) -> Result<()> {
+    standalone.cancel(&session_id)
+}
+
+/// Answers a permission request; no `optionId` cancels it.
+#[tauri::command]
+pub fn standalone_permission(
+    standalone: State<'_, StandaloneState>,
+    session_id: String,
+    request_id: String,
+    option_id: Option<String>,
+) -> Result<()> {
+    standalone.respond_permission(&session_id, &request_id, option_id.as_deref())
+}
+
+#[tauri::command]
+pub fn standalone_close(standalone: State<'_, StandaloneState>, session_id: String) -> Result<()> {
+    standalone.close(&session_id)
+}
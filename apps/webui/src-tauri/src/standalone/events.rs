@@ -0,0 +1,71 @@
+//! How ACP traffic maps onto `SessionEvent` kinds, as `session-manager.ts`
+//! maps it, so cached and live transcripts look the same either way.
+
+use serde_json::{json, Value};
+
+/// `resolveSessionUpdateEventKind`: the event kind of a `session/update`.
+pub fn update_kind(update: &Value) -> &'static str {
+    match update["sessionUpdate"].as_str().unwrap_or_default() {
+        "user_message_chunk" => "user_message",
+        "agent_message_chunk" => "agent_message_chunk",
+        "agent_thought_chunk" => "agent_thought_chunk",
+        "tool_call" => "tool_call",
+        "tool_call_update" => "tool_call_update",
+        "session_info_update"
+        | "current_mode_update"
+        | "available_commands_update"
+        | "plan"
+        | "config_option_update" => "session_info_update",
+        "usage_update" => "usage_update",
+        "plan_update" => "plan_update",
+        "plan_removed" => "plan_removed",
+        _ => "unknown_update",
+    }
+}
+
+/// `encodeProtocolRequestId`: the `requestId` of a permission request, which
+/// keeps the JSON type of the agent's id. A null id falls back to
+/// `fallback`.
+pub fn protocol_request_id(id: &Value, fallback: &str) -> String {
+    match id {
+        Value::Null => format!("null:{fallback}"),
+        Value::String(id) => format!("string:{id}"),
+        Value::Number(id) => format!("number:{id}"),
+        other => format!("object:{other}"),
+    }
+}
+
+/// The `ErrorDetail` the CLI reports when an agent goes away.
+pub fn connection_closed(stderr: &str) -> Value {
+    let mut detail = "The agent closed its output".to_owned();
+    if !stderr.is_empty() {
+        detail.push_str("\nBackend stderr:\n");
+        detail.push_str(stderr);
+    }
+    json!({
+        "code": "ACP_CONNECTION_CLOSED",
+        "message": "ACP connection closed",
+        "retryable": true,
+        "scope": "service",
+        "detail": detail,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_like_the_cli() {
+        let kind = |update: &str| update_kind(&json!({ "sessionUpdate": update }));
+        assert_eq!(kind("user_message_chunk"), "user_message");
+        assert_eq!(kind("available_commands_update"), "session_info_update");
+        assert_eq!(kind("plan"), "session_info_update");
+        assert_eq!(kind("something_new"), "unknown_update");
+        assert_eq!(update_kind(&Value::Null), "unknown_update");
+
+        assert_eq!(protocol_request_id(&json!(7), "call-1"), "number:7");
+        assert_eq!(protocol_request_id(&json!("7"), "call-1"), "string:7");
+        assert_eq!(protocol_request_id(&Value::Null, "call-1"), "null:call-1");
+    }
+}
@@ -0,0 +1,236 @@
+//! Standalone mode: the app drives ACP agents on this machine itself, with
+//! no `mobvibe-cli` or gateway in between.
+//!
+//! Each session spawns its agent command and speaks ACP to it over stdio.
+//! What the agent sends becomes `SessionEvent`s of the kinds and payloads
+//! `session-manager.ts` writes to its WAL, under the machine id
+//! [`MACHINE_ID`], so the webview renders them as it renders the CLI's.
+//! Events are fanned out to every webview that called `standalone_listen`
+//! and recorded in the session cache, which is where a reloaded page reads
+//! the transcript back from.
+
+mod agent;
+pub mod commands;
+mod events;
+mod rpc;
+mod session;
+#[cfg(test)]
+mod stub;
+
+pub use session::PromptResult;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::Serialize;
+use serde_json::Value;
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::mpsc;
+
+use self::session::Session;
+use crate::error::{Error, Result};
+use crate::protocol::SessionEvent;
+use crate::session_cache::SessionCache;
+
+pub const MACHINE_ID: &str = "standalone";
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StandaloneSession {
+    pub session_id: String,
+    pub machine_id: &'static str,
+    pub command: String,
+    pub cwd: PathBuf,
+    pub agent_info: Option<Value>,
+    /// A turn is in flight.
+    pub busy: bool,
+}
+
+struct Entry {
+    session: Arc<Session>,
+    command: String,
+    cwd: PathBuf,
+}
+
+impl Entry {
+    fn describe(&self) -> StandaloneSession {
+        StandaloneSession {
+            session_id: self.session.session_id().to_owned(),
+            machine_id: MACHINE_ID,
+            command: self.command.clone(),
+            cwd: self.cwd.clone(),
+            agent_info: self.session.agent_info.clone(),
+            busy: self.session.is_busy(),
+        }
+    }
+}
+
+struct Listener {
+    id: u32,
+    webview: String,
+    channel: Channel<SessionEvent>,
+}
+
+#[derive(Default)]
+struct Inner {
+    sessions: BTreeMap<String, Entry>,
+    listeners: Vec<Listener>,
+    next_listener_id: u32,
+}
+
+pub struct StandaloneState {
+    /// The user's home, for the agent search path.
+    user_home: PathBuf,
+    events: mpsc::UnboundedSender<SessionEvent>,
+    inner: Mutex<Inner>,
+}
+
+impl StandaloneState {
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn session(&self, session_id: &str) -> Result<Arc<Session>> {
+        self.inner()
+            .sessions
+            .get(session_id)
+            .map(|entry| entry.session.clone())
+            .ok_or_else(|| Error::Agent(format!("Session not found: {session_id}")))
+    }
+
+    pub fn sessions(&self) -> Vec<StandaloneSession> {
+        self.inner()
+            .sessions
+            .values()
+            .map(Entry::describe)
+            .collect()
+    }
+
+    /// Spawns `command` in `cwd` and opens a session on it.
+    pub async fn start(
+        &self,
+        command: &str,
+        args: &[String],
+        cwd: &Path,
+    ) -> Result<StandaloneSession> {
+        if !cwd.is_dir() {
+            return Err(Error::Agent(format!(
+                "{} is not a directory",
+                cwd.display()
+            )));
+        }
+        let agent = agent::spawn(command, args, cwd, &self.user_home)?;
+        let (connection, incoming) = rpc::Connection::new(agent.stdout, agent.stdin);
+        let session = Session::connect(
+            connection,
+            incoming,
+            cwd,
+            self.events.clone(),
+            agent.stderr,
+            Some(agent.child),
+        )
+        .await?;
+        let entry = Entry {
+            session: Arc::new(session),
+            command: command.to_owned(),
+            cwd: cwd.to_path_buf(),
+        };
+        let described = entry.describe();
+        if let Some(replaced) = self
+            .inner()
+            .sessions
+            .insert(described.session_id.clone(), entry)
+        {
+            replaced.session.close();
+        }
+        Ok(described)
+    }
+
+    pub async fn prompt(
+        &self,
+        session_id: &str,
+        prompt: Vec<Value>,
+        message_id: &str,
+    ) -> Result<PromptResult> {
+        self.session(session_id)?.prompt(prompt, message_id).await
+    }
+
+    pub fn cancel(&self, session_id: &str) -> Result<()> {
+        self.session(session_id)?.cancel();
+        Ok(())
+    }
+
+    pub fn respond_permission(
+        &self,
+        session_id: &str,
+        request_id: &str,
+        option_id: Option<&str>,
+    ) -> Result<()> {
+        self.session(session_id)?
+            .respond_permission(request_id, option_id)
+    }
+
+    /// Stops the agent. The cached transcript is kept.
+    pub fn close(&self, session_id: &str) -> Result<()> {
+        let entry = self
+            .inner()
+            .sessions
+            .remove(session_id)
+            .ok_or_else(|| Error::Agent(format!("Session not found: {session_id}")))?;
+        entry.session.close();
+        Ok(())
+    }
+
+    /// Streams the events of every standalone session to a webview.
+    /// Returns the listener id.
+    pub fn listen(&self, webview: &str, channel: Channel<SessionEvent>) -> u32 {
+        let mut inner = self.inner();
+        let id = inner.next_listener_id;
+        inner.next_listener_id += 1;
+        inner.listeners.push(Listener {
+            id,
+            webview: webview.to_owned(),
+            channel,
+        });
+        id
+    }
+
+    pub fn unlisten(&self, id: u32) {
+        self.inner().listeners.retain(|listener| listener.id != id);
+    }
+
+    /// Drops the channels of a webview that is navigating away.
+    pub fn forget_webview(&self, webview: &str) {
+        self.inner()
+            .listeners
+            .retain(|listener| listener.webview != webview);
+    }
+
+    fn emit(&self, event: &SessionEvent) {
+        self.inner()
+            .listeners
+            .retain(|listener| listener.channel.send(event.clone()).is_ok());
+    }
+}
+
+/// Manages the state and spawns the task that caches and fans out events.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    let (events, mut rx) = mpsc::unbounded_channel();
+    app.manage(StandaloneState {
+        user_home: app.path().home_dir()?,
+        events,
+        inner: Mutex::default(),
+    });
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(event) = rx.recv().await {
+            let _ = app
+                .state::<SessionCache>()
+                .record_events(std::slice::from_ref(&event));
+            app.state::<StandaloneState>().emit(&event);
+        }
+    });
+    Ok(())
+}
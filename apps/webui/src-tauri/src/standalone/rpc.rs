@@ -0,0 +1,236 @@
+//! JSON-RPC 2.0 as ACP frames it on stdio: one message per line. Both sides
+//! send requests; responses to ours are matched by id, and everything the
+//! agent sends on its own is handed to the session as [`Incoming`].
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::error::{Error, Result};
+
+const METHOD_NOT_FOUND: i64 = -32601;
+
+pub enum Incoming {
+    Notification {
+        method: String,
+        params: Value,
+    },
+    Request {
+        id: Value,
+        method: String,
+        params: Value,
+    },
+    /// Queued by [`Connection::flush`]; answer it when it comes up.
+    Flush(oneshot::Sender<()>),
+}
+
+/// An error response to one of the agent's requests.
+pub struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    pub fn method_not_found(method: &str) -> Self {
+        Self {
+            code: METHOD_NOT_FOUND,
+            message: format!("Method not found: {method}"),
+        }
+    }
+}
+
+type Pending = Mutex<HashMap<u64, oneshot::Sender<Result<Value>>>>;
+
+#[derive(Clone)]
+pub struct Connection {
+    outgoing: mpsc::UnboundedSender<String>,
+    /// Weak, so the receiver still closes when the agent does.
+    incoming: mpsc::WeakUnboundedSender<Incoming>,
+    pending: Arc<Pending>,
+    next_id: Arc<AtomicU64>,
+}
+
+impl Connection {
+    /// Spawns the reader and writer tasks. The receiver closes once the
+    /// agent closes its output, after every pending request has failed.
+    pub fn new<R, W>(reader: R, writer: W) -> (Self, mpsc::UnboundedReceiver<Incoming>)
+    where
+        R: AsyncRead + Unpin + Send + 'static,
+        W: AsyncWrite + Unpin + Send + 'static,
+    {
+        let (outgoing, outgoing_rx) = mpsc::unbounded_channel();
+        let (incoming, incoming_rx) = mpsc::unbounded_channel();
+        let pending = Arc::new(Pending::default());
+        tauri::async_runtime::spawn(write(writer, outgoing_rx));
+        let connection = Self {
+            outgoing,
+            incoming: incoming.downgrade(),
+            pending,
+            next_id: Arc::default(),
+        };
+        tauri::async_runtime::spawn(read(reader, connection.pending.clone(), incoming));
+        (connection, incoming_rx)
+    }
+
+    pub async fn request(&self, method: &str, params: Value) -> Result<Value> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        lock(&self.pending).insert(id, tx);
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+        rx.await.unwrap_or_else(|_| Err(closed()))
+    }
+
+    /// Resolves once the receiver has handled every message the agent sent
+    /// before now. Responses skip that queue, so a request can resolve while
+    /// the notifications sent ahead of its response are still waiting.
+    pub async fn flush(&self) {
+        let Some(incoming) = self.incoming.upgrade() else {
+            return;
+        };
+        let (tx, rx) = oneshot::channel();
+        if incoming.send(Incoming::Flush(tx)).is_ok() {
+            drop(incoming);
+            let _ = rx.await;
+        }
+    }
+
+    pub fn notify(&self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    pub fn respond(&self, id: Value, result: std::result::Result<Value, RpcError>) {
+        self.send(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": error.code, "message": error.message },
+            }),
+        });
+    }
+
+    /// Messages sent after the agent exited are dropped; the reader fails
+    /// pending requests and the session reports the exit.
+    fn send(&self, message: Value) {
+        let _ = self.outgoing.send(message.to_string());
+    }
+}
+
+fn lock(pending: &Pending) -> MutexGuard<'_, HashMap<u64, oneshot::Sender<Result<Value>>>> {
+    pending.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn closed() -> Error {
+    Error::Agent("ACP connection closed".to_owned())
+}
+
+async fn write<W: AsyncWrite + Unpin>(
+    mut writer: W,
+    mut outgoing: mpsc::UnboundedReceiver<String>,
+) {
+    while let Some(mut line) = outgoing.recv().await {
+        line.push('\n');
+        if writer.write_all(line.as_bytes()).await.is_err() || writer.flush().await.is_err() {
+            break;
+        }
+    }
+}
+
+async fn read<R: AsyncRead + Unpin>(
+    reader: R,
+    pending: Arc<Pending>,
+    incoming: mpsc::UnboundedSender<Incoming>,
+) {
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        // Agents that log to stdout are tolerated; only JSON-RPC counts.
+        let Ok(mut message) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let params = message["params"].take();
+        match (message["method"].as_str(), message.get("id")) {
+            (Some(method), Some(id)) => {
+                let _ = incoming.send(Incoming::Request {
+                    id: id.clone(),
+                    method: method.to_owned(),
+                    params,
+                });
+            }
+            (Some(method), None) => {
+                let _ = incoming.send(Incoming::Notification {
+                    method: method.to_owned(),
+                    params,
+                });
+            }
+            (None, Some(id)) => {
+                let Some(tx) = id.as_u64().and_then(|id| lock(&pending).remove(&id)) else {
+                    continue;
+                };
+                let result = match message.get("error") {
+                    Some(error) => Err(Error::Agent(
+                        error["message"]
+                            .as_str()
+                            .unwrap_or("request failed")
+                            .to_owned(),
+                    )),
+                    None => Ok(message["result"].take()),
+                };
+                let _ = tx.send(result);
+            }
+            (None, None) => {}
+        }
+    }
+    for (_, tx) in lock(&pending).drain() {
+        let _ = tx.send(Err(closed()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn matches_responses_and_forwards_agent_messages() {
+        let (client, agent) = tokio::io::duplex(4096);
+        let (client_read, client_write) = tokio::io::split(client);
+        let (agent_read, mut agent_write) = tokio::io::split(agent);
+        let (connection, mut incoming) = Connection::new(client_read, client_write);
+
+        let request = tokio::spawn({
+            let connection = connection.clone();
+            async move { connection.request("ping", json!({})).await }
+        });
+        let mut agent_lines = BufReader::new(agent_read).lines();
+        let sent: Value =
+            serde_json::from_str(&agent_lines.next_line().await.unwrap().unwrap()).unwrap();
+        assert_eq!(sent["method"], "ping");
+
+        let replies = [
+            "not json".to_owned(),
+            json!({ "jsonrpc": "2.0", "method": "note", "params": { "n": 1 } }).to_string(),
+            json!({ "jsonrpc": "2.0", "id": sent["id"], "result": { "ok": true } }).to_string(),
+        ];
+        for reply in replies {
+            agent_write
+                .write_all(format!("{reply}\n").as_bytes())
+                .await
+                .unwrap();
+        }
+        assert_eq!(request.await.unwrap().unwrap(), json!({ "ok": true }));
+        let Some(Incoming::Notification { method, params }) = incoming.recv().await else {
+            panic!("expected a notification");
+        };
+        assert_eq!((method.as_str(), params), ("note", json!({ "n": 1 })));
+
+        let request = tokio::spawn(async move { connection.request("ping", json!({})).await });
+        agent_lines.next_line().await.unwrap();
+        drop(agent_write);
+        drop(agent_lines);
+        assert!(request.await.unwrap().is_err());
+        assert!(incoming.recv().await.is_none());
+    }
+}
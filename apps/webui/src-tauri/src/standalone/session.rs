@@ -0,0 +1,389 @@
+//! One ACP session on its own agent process: the handshake, prompts,
+//! permission requests and the events they produce.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::process::Child;
+use tokio::sync::mpsc;
+
+use super::agent::StderrTail;
+use super::events;
+use super::rpc::{Connection, Incoming, RpcError};
+use super::MACHINE_ID;
+use crate::error::{Error, Result};
+use crate::local_gateway::clock;
+use crate::protocol::SessionEvent;
+
+const PROTOCOL_VERSION: u64 = 1;
+/// Sessions never restart in place, so their events stay in one revision.
+const REVISION: u64 = 1;
+
+/// What `session/prompt` answered; also the `turn_end` payload.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptResult {
+    pub stop_reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<Value>,
+}
+
+/// Everything the task serving the agent's messages needs.
+struct Shared {
+    session_id: String,
+    connection: Connection,
+    events: mpsc::UnboundedSender<SessionEvent>,
+    last_seq: Mutex<u64>,
+    /// Open permission requests by `requestId`, with the JSON-RPC id to
+    /// answer them on.
+    permissions: Mutex<HashMap<String, Value>>,
+    /// Set once the app closes the session, so the exit is not an error.
+    closed: AtomicBool,
+    stderr: StderrTail,
+}
+
+impl Shared {
+    fn permissions(&self) -> MutexGuard<'_, HashMap<String, Value>> {
+        self.permissions.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Assigns the next seq and sends the event. The seq lock is held while
+    /// sending, so events arrive in seq order.
+    fn push(&self, kind: &str, payload: Value) -> u64 {
+        let mut last_seq = self.last_seq.lock().unwrap_or_else(|e| e.into_inner());
+        *last_seq += 1;
+        let _ = self.events.send(SessionEvent {
+            session_id: self.session_id.clone(),
+            machine_id: MACHINE_ID.to_owned(),
+            incarnation_generation: None,
+            revision: REVISION,
+            seq: *last_seq,
+            kind: kind.to_owned(),
+            protocol_message_id: payload["update"]["messageId"].as_str().map(str::to_owned),
+            created_at: clock::format(clock::now_millis()),
+            payload,
+        });
+        *last_seq
+    }
+
+    fn request_permission(&self, id: Value, params: Value) {
+        let fallback = match params["toolCall"]["toolCallId"].as_str() {
+            Some(tool_call_id) => tool_call_id.to_owned(),
+            None => format!(
+                "seq-{}",
+                *self.last_seq.lock().unwrap_or_else(|e| e.into_inner())
+            ),
+        };
+        let request_id = events::protocol_request_id(&id, &fallback);
+        self.permissions().insert(request_id.clone(), id);
+        self.push(
+            "permission_request",
+            json!({
+                "sessionId": self.session_id,
+                "requestId": request_id,
+                "options": params["options"],
+                "toolCall": params["toolCall"],
+            }),
+        );
+    }
+
+    /// Answers a permission request; `None` cancels it.
+    fn decide(&self, request_id: &str, option_id: Option<&str>) -> Result<()> {
+        let id = self
+            .permissions()
+            .remove(request_id)
+            .ok_or_else(|| Error::Agent(format!("No pending permission request {request_id}")))?;
+        let outcome = match option_id {
+            Some(option_id) => json!({ "outcome": "selected", "optionId": option_id }),
+            None => json!({ "outcome": "cancelled" }),
+        };
+        self.connection
+            .respond(id, Ok(json!({ "outcome": outcome.clone() })));
+        self.push(
+            "permission_result",
+            json!({
+                "sessionId": self.session_id,
+                "requestId": request_id,
+                "outcome": outcome,
+            }),
+        );
+        Ok(())
+    }
+}
+
+/// Handles the agent's notifications and requests until it exits.
+async fn serve(shared: Arc<Shared>, mut incoming: mpsc::UnboundedReceiver<Incoming>) {
+    while let Some(message) = incoming.recv().await {
+        match message {
+            Incoming::Notification { method, params }
+                if method == "session/update" && params["sessionId"] == shared.session_id =>
+            {
+                shared.push(events::update_kind(&params["update"]), params);
+            }
+            Incoming::Notification { .. } => {}
+            Incoming::Flush(done) => {
+                let _ = done.send(());
+            }
+            Incoming::Request { id, method, params } if method == "session/request_permission" => {
+                shared.request_permission(id, params);
+            }
+            // No fs or terminal capabilities are advertised, so nothing else
+            // should be asked of the client.
+            Incoming::Request { id, method, .. } => {
+                shared
+                    .connection
+                    .respond(id, Err(RpcError::method_not_found(&method)));
+            }
+        }
+    }
+    shared.permissions().clear();
+    if !shared.closed.load(Ordering::Acquire) {
+        shared.push(
+            "session_error",
+            json!({ "error": events::connection_closed(&shared.stderr.text()) }),
+        );
+    }
+}
+
+pub struct Session {
+    shared: Arc<Shared>,
+    /// `agentInfo` from the agent's `initialize` response, if it sent one.
+    pub agent_info: Option<Value>,
+    busy: AtomicBool,
+    child: Mutex<Option<Child>>,
+}
+
+impl Session {
+    /// Runs `initialize` and `session/new` on a fresh connection, then
+    /// serves the agent's messages in the background.
+    pub async fn connect(
+        connection: Connection,
+        incoming: mpsc::UnboundedReceiver<Incoming>,
+        cwd: &Path,
+        events: mpsc::UnboundedSender<SessionEvent>,
+        stderr: StderrTail,
+        child: Option<Child>,
+    ) -> Result<Self> {
+        let initialized = connection
+            .request(
+                "initialize",
+                json!({
+                    "protocolVersion": PROTOCOL_VERSION,
+                    "clientInfo": { "name": "mobvibe", "version": env!("CARGO_PKG_VERSION") },
+                    "clientCapabilities": {
+                        "fs": { "readTextFile": false, "writeTextFile": false },
+                        "terminal": false,
+                    },
+                }),
+            )
+            .await?;
+        if initialized["protocolVersion"].as_u64() != Some(PROTOCOL_VERSION) {
+            return Err(Error::Agent(format!(
+                "ACP protocol version mismatch: the agent speaks {}",
+                initialized["protocolVersion"]
+            )));
+        }
+        let created = connection
+            .request("session/new", json!({ "cwd": cwd, "mcpServers": [] }))
+            .await?;
+        let session_id = created["sessionId"]
+            .as_str()
+            .ok_or_else(|| Error::Agent("session/new returned no sessionId".to_owned()))?
+            .to_owned();
+
+        let shared = Arc::new(Shared {
+            session_id,
+            connection,
+            events,
+            last_seq: Mutex::new(0),
+            permissions: Mutex::default(),
+            closed: AtomicBool::new(false),
+            stderr,
+        });
+        tauri::async_runtime::spawn(serve(shared.clone(), incoming));
+        Ok(Self {
+            shared,
+            agent_info: initialized.get("agentInfo").cloned(),
+            busy: AtomicBool::new(false),
+            child: Mutex::new(child),
+        })
+    }
+
+    pub fn session_id(&self) -> &str {
+        &self.shared.session_id
+    }
+
+    pub fn is_busy(&self) -> bool {
+        self.busy.load(Ordering::Acquire)
+    }
+
+    /// Sends a prompt and waits for the turn to end. The prompt is recorded
+    /// as `user_message` events tagged with `message_id`, like the CLI
+    /// records the agent's echo of it.
+    pub async fn prompt(&self, prompt: Vec<Value>, message_id: &str) -> Result<PromptResult> {
+        if self.busy.swap(true, Ordering::AcqRel) {
+            return Err(Error::Agent(
+                "Another message is already active for this session".to_owned(),
+            ));
+        }
+        let shared = &self.shared;
+        for block in &prompt {
+            shared.push(
+                "user_message",
+                json!({
+                    "sessionId": shared.session_id,
+                    "update": { "sessionUpdate": "user_message_chunk", "content": block },
+                    "messageId": message_id,
+                }),
+            );
+        }
+        let response = shared
+            .connection
+            .request(
+                "session/prompt",
+                json!({ "sessionId": shared.session_id, "prompt": prompt }),
+            )
+            .await;
+        self.busy.store(false, Ordering::Release);
+        let mut response = response?;
+        shared.connection.flush().await;
+        let result = PromptResult {
+            stop_reason: response["stopReason"]
+                .as_str()
+                .unwrap_or("end_turn")
+                .to_owned(),
+            usage: Some(response["usage"].take()).filter(Value::is_object),
+        };
+        shared.push("turn_end", serde_json::to_value(&result)?);
+        Ok(result)
+    }
+
+    /// Asks the agent to stop the current turn. Open permission requests
+    /// are cancelled, as ACP requires of the client.
+    pub fn cancel(&self) {
+        let shared = &self.shared;
+        shared
+            .connection
+            .notify("session/cancel", json!({ "sessionId": shared.session_id }));
+        let open: Vec<String> = shared.permissions().keys().cloned().collect();
+        for request_id in open {
+            let _ = shared.decide(&request_id, None);
+        }
+    }
+
+    pub fn respond_permission(&self, request_id: &str, option_id: Option<&str>) -> Result<()> {
+        self.shared.decide(request_id, option_id)
+    }
+
+    /// Kills the agent without reporting its exit as an error.
+    pub fn close(&self) {
+        self.shared.closed.store(true, Ordering::Release);
+        if let Some(mut child) = self.child.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            let _ = child.start_kill();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::stub;
+    use super::*;
+
+    async fn connect() -> (Arc<Session>, mpsc::UnboundedReceiver<SessionEvent>) {
+        let (client, agent) = tokio::io::duplex(4096);
+        let (client_read, client_write) = tokio::io::split(client);
+        let (agent_read, agent_write) = tokio::io::split(agent);
+        tokio::spawn(stub::serve(agent_read, agent_write));
+        let (connection, incoming) = Connection::new(client_read, client_write);
+        let (tx, rx) = mpsc::unbounded_channel();
+        let session = Session::connect(
+            connection,
+            incoming,
+            Path::new("/tmp"),
+            tx,
+            StderrTail::default(),
+            None,
+        )
+        .await
+        .unwrap();
+        (Arc::new(session), rx)
+    }
+
+    fn start_prompt(session: &Arc<Session>) -> tokio::task::JoinHandle<Result<PromptResult>> {
+        let session = session.clone();
+        tokio::spawn(async move {
+            session
+                .prompt(vec![json!({ "type": "text", "text": "hi" })], "m1")
+                .await
+        })
+    }
+
+    async fn next_permission(events: &mut mpsc::UnboundedReceiver<SessionEvent>) -> String {
+        loop {
+            let event = events.recv().await.unwrap();
+            if event.kind == "permission_request" {
+                return event.payload["requestId"].as_str().unwrap().to_owned();
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn runs_a_turn_through_a_permission_request() {
+        let (session, mut events) = connect().await;
+        assert_eq!(session.session_id(), stub::SESSION_ID);
+
+        let turn = start_prompt(&session);
+        let request_id = next_permission(&mut events).await;
+        assert_eq!(request_id, "number:0");
+        session
+            .respond_permission(&request_id, Some(stub::ALLOW))
+            .unwrap();
+        assert_eq!(turn.await.unwrap().unwrap().stop_reason, "end_turn");
+        assert!(!session.is_busy());
+
+        let mut rest = Vec::new();
+        while let Ok(event) = events.try_recv() {
+            rest.push(event);
+        }
+        let kinds: Vec<&str> = rest.iter().map(|event| event.kind.as_str()).collect();
+        assert_eq!(kinds, ["permission_result", "tool_call_update", "turn_end"]);
+        assert_eq!(rest[0].seq, 5);
+        assert_eq!(rest[1].payload["update"]["status"], "completed");
+        assert!(session.respond_permission(&request_id, None).is_err());
+    }
+
+    #[tokio::test]
+    async fn cancel_ends_the_turn_and_agent_exit_is_reported() {
+        let (session, mut events) = connect().await;
+        let turn = start_prompt(&session);
+        let kinds = [
+            "user_message",
+            "agent_message_chunk",
+            "tool_call",
+            "permission_request",
+        ];
+        for kind in kinds {
+            let event = events.recv().await.unwrap();
+            assert_eq!(event.kind, kind);
+            assert_eq!(event.machine_id, MACHINE_ID);
+        }
+        session.cancel();
+        assert_eq!(turn.await.unwrap().unwrap().stop_reason, "cancelled");
+        let result = events.recv().await.unwrap();
+        assert_eq!(result.kind, "permission_result");
+        assert_eq!(result.payload["outcome"]["outcome"], "cancelled");
+
+        session.shared.connection.notify(stub::EXIT, Value::Null);
+        loop {
+            let event = events.recv().await.unwrap();
+            if event.kind == "session_error" {
+                assert_eq!(event.payload["error"]["code"], "ACP_CONNECTION_CLOSED");
+                break;
+            }
+        }
+    }
+}
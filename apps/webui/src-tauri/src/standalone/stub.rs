@@ -0,0 +1,129 @@
+//! A scripted ACP agent, so the client can be exercised without a real one.
+//! Each prompt is echoed back, then a tool call asks for permission and
+//! completes if [`ALLOW`] was chosen or fails otherwise. `session/cancel`
+//! ends the turn with `cancelled`; the [`EXIT`] notification stops the agent.
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+
+pub const SESSION_ID: &str = "stub-session";
+pub const ALLOW: &str = "allow";
+pub const EXIT: &str = "stub/exit";
+const PERMISSION_REQUEST: u64 = 0;
+const TOOL_CALL_ID: &str = "call-1";
+
+struct Agent<W> {
+    writer: W,
+    /// The id of the prompt in progress.
+    prompt: Option<Value>,
+}
+
+impl<W: AsyncWrite + Unpin> Agent<W> {
+    async fn send(&mut self, message: Value) {
+        let line = format!("{message}\n");
+        let _ = self.writer.write_all(line.as_bytes()).await;
+    }
+
+    async fn respond(&mut self, id: &Value, result: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+            .await;
+    }
+
+    async fn update(&mut self, update: Value) {
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "method": "session/update",
+            "params": { "sessionId": SESSION_ID, "update": update },
+        }))
+        .await;
+    }
+
+    async fn prompt(&mut self, id: Value, params: &Value) {
+        let text = params["prompt"][0]["text"].as_str().unwrap_or_default();
+        self.update(json!({
+            "sessionUpdate": "agent_message_chunk",
+            "content": { "type": "text", "text": format!("echo: {text}") },
+        }))
+        .await;
+        self.update(json!({
+            "sessionUpdate": "tool_call",
+            "toolCallId": TOOL_CALL_ID,
+            "title": "Write notes.txt",
+            "kind": "edit",
+            "status": "pending",
+        }))
+        .await;
+        self.send(json!({
+            "jsonrpc": "2.0",
+            "id": PERMISSION_REQUEST,
+            "method": "session/request_permission",
+            "params": {
+                "sessionId": SESSION_ID,
+                "toolCall": { "toolCallId": TOOL_CALL_ID },
+                "options": [
+                    { "optionId": ALLOW, "name": "Allow", "kind": "allow_once" },
+                    { "optionId": "reject", "name": "Reject", "kind": "reject_once" },
+                ],
+            },
+        }))
+        .await;
+        self.prompt = Some(id);
+    }
+
+    async fn permission_decided(&mut self, result: &Value) {
+        let Some(prompt) = self.prompt.take() else {
+            return;
+        };
+        let allowed = result["outcome"]["optionId"] == ALLOW;
+        self.update(json!({
+            "sessionUpdate": "tool_call_update",
+            "toolCallId": TOOL_CALL_ID,
+            "status": if allowed { "completed" } else { "failed" },
+        }))
+        .await;
+        self.respond(&prompt, json!({ "stopReason": "end_turn" }))
+            .await;
+    }
+}
+
+pub async fn serve(reader: impl AsyncRead + Unpin, writer: impl AsyncWrite + Unpin) {
+    let mut agent = Agent {
+        writer,
+        prompt: None,
+    };
+    let mut lines = BufReader::new(reader).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        let Ok(message) = serde_json::from_str::<Value>(&line) else {
+            continue;
+        };
+        let id = message["id"].clone();
+        match message["method"].as_str() {
+            Some("initialize") => {
+                let result = json!({
+                    "protocolVersion": 1,
+                    "agentCapabilities": {},
+                    "agentInfo": { "name": "stub", "version": "0.0.0" },
+                    "authMethods": [],
+                });
+                agent.respond(&id, result).await;
+            }
+            Some("session/new") => {
+                agent.respond(&id, json!({ "sessionId": SESSION_ID })).await;
+            }
+            Some("session/prompt") => agent.prompt(id, &message["params"]).await,
+            Some("session/cancel") => {
+                if let Some(prompt) = agent.prompt.take() {
+                    agent
+                        .respond(&prompt, json!({ "stopReason": "cancelled" }))
+                        .await;
+                }
+            }
+            Some(EXIT) => return,
+            Some(_) => {}
+            None if id == PERMISSION_REQUEST => {
+                agent.permission_decided(&message["result"]).await;
+            }
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use std::path::PathBuf;
+
+use tauri::ipc::Channel;
+use tauri::{State, Webview};
+
+use super::{Size, TerminalEvent, TerminalInfo, TerminalState};
+use crate::error::Result;
+
+/// Opens a shell in `cwd`, usually a session's working directory or
+/// worktree, and streams its output until it exits. `shell` defaults to
+/// the user's login shell.
+#[tauri::command]
+pub fn terminal_open(
+    webview: Webview,
+    terminals: State<'_, TerminalState>,
+    cwd: PathBuf,
+    shell: Option<PathBuf>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+    on_event: Channel<TerminalEvent>,
+) -> Result<TerminalInfo> {
+    let size = cols.zip(rows).map(|(cols, rows)| Size { cols, rows });
+    terminals.open(&webview, &cwd, shell, size, on_event)
+}
+
+/// Sends keyboard input, as the terminal emulator encodes it.
+#[tauri::command]
+pub fn terminal_write(terminals: State<'_, TerminalState>, id: u32, data: String) -> Result<()> {
+    terminals.write(id, &data)
+}
+
+#[tauri::command]
+pub fn terminal_resize(
+    terminals: State<'_, TerminalState>,
+    id: u32,
+    cols: u16,
+    rows: u16,
+) -> Result<()> {
+    terminals.resize(id, Size { cols, rows })
+}
+
+#[tauri::command]
+pub fn terminal_close(terminals: State<'_, TerminalState>, id: u32) -> Result<()> {
+    terminals.close(id)
+}
+
+#[tauri::command]
+pub fn terminal_list(webview: Webview, terminals: State<'_, TerminalState>) -> Vec<TerminalInfo> {
+    terminals.list(webview.label())
+}
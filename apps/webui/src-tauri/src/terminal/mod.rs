@@ -0,0 +1,352 @@
+//! Shells on pseudo-terminals, opened in a session's working directory when
+//! the app runs on the same machine as the session.
+//!
+//! Any number of terminals can run at once. Each belongs to the webview
+//! that opened it and streams its output there over a channel. Terminals
+//! are hung up when that webview reloads or its window is destroyed, and a
+//! shell that outlives the hangup by [`KILL_GRACE`] is killed. Output is
+//! drained by one blocking thread per terminal, which also reaps the shell.
+//! The pseudo-terminals themselves (`openpty` on Unix, ConPTY on Windows)
+//! come from `portable-pty`.
+
+pub mod commands;
+
+use std::collections::BTreeMap;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use portable_pty::{native_pty_system, ChildKiller, CommandBuilder, MasterPty, PtySize};
+use serde::Serialize;
+use tauri::ipc::Channel;
+use tauri::{Manager, Runtime, Webview};
+
+use crate::error::{Error, Result};
+
+const KILL_GRACE: Duration = Duration::from_secs(3);
+const READ_BUFFER: usize = 16 * 1024;
+const DEFAULT_SIZE: Size = Size { cols: 80, rows: 24 };
+
+#[derive(Debug, Clone, Copy)]
+pub struct Size {
+    pub cols: u16,
+    pub rows: u16,
+}
+
+impl From<Size> for PtySize {
+    fn from(size: Size) -> Self {
+        PtySize {
+            rows: size.rows,
+            cols: size.cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum TerminalEvent {
+    Output {
+        data: String,
+    },
+    /// `code` is `None` if a signal ended the shell.
+    Exit {
+        code: Option<i32>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TerminalInfo {
+    pub id: u32,
+    pub shell: PathBuf,
+    pub cwd: PathBuf,
+    pub pid: u32,
+}
+
+struct Terminal {
+    info: TerminalInfo,
+    webview: String,
+    window: String,
+    master: Box<dyn MasterPty + Send>,
+    writer: Box<dyn Write + Send>,
+    killer: Box<dyn ChildKiller + Send + Sync>,
+    exited: Arc<AtomicBool>,
+}
+
+impl Terminal {
+    /// Hangs up the shell and kills it if it is still running after the
+    /// grace period.
+    fn close(self) {
+        let Terminal {
+            info,
+            master,
+            writer,
+            mut killer,
+            exited,
+            ..
+        } = self;
+        // Closing the master ends a ConPTY session. On Unix the reader
+        // thread still holds it open, so the shell is sent SIGHUP instead.
+        drop((master, writer));
+        #[cfg(unix)]
+        let _ = killer.kill();
+        std::thread::spawn(move || {
+            std::thread::sleep(KILL_GRACE);
+            if !exited.load(Ordering::Acquire) {
+                force_kill(info.pid, killer.as_mut());
+            }
+        });
+    }
+}
+
+/// A shell may ignore SIGHUP. It leads its own session, so killing its
+/// process group also ends the jobs it started.
+#[cfg(unix)]
+fn force_kill(pid: u32, _killer: &mut (dyn ChildKiller + Send + Sync)) {
+    if let Ok(pid) = i32::try_from(pid) {
+        // SAFETY: plain syscall; a group that is gone is not an error worth
+        // reporting.
+        unsafe { libc::kill(-pid, libc::SIGKILL) };
+    }
+}
+
+/// Terminates the shell; its console is already closed.
+#[cfg(windows)]
+fn force_kill(_pid: u32, killer: &mut (dyn ChildKiller + Send + Sync)) {
+    let _ = killer.kill();
+}
+
+#[derive(Default)]
+struct Inner {
+    terminals: BTreeMap<u32, Terminal>,
+    next_id: u32,
+}
+
+#[derive(Default)]
+pub struct TerminalState {
+    inner: Mutex<Inner>,
+}
+
+/// `$SHELL` as a login shell, so it sees the user's profile even when the
+/// app was started from the Finder or a desktop entry.
+#[cfg(unix)]
+fn default_shell() -> (PathBuf, Vec<&'static str>) {
+    let shell = std::env::var_os("SHELL")
+        .filter(|shell| !shell.is_empty())
+        .map_or_else(|| PathBuf::from("/bin/sh"), PathBuf::from);
+    (shell, vec!["-l"])
+}
+
+#[cfg(windows)]
+fn default_shell() -> (PathBuf, Vec<&'static str>) {
+    (PathBuf::from("powershell.exe"), vec!["-NoLogo"])
+}
+
+/// Decodes the complete UTF-8 at the start of `buf` and leaves a character
+/// split across reads in it for the next one.
+fn take_utf8(buf: &mut Vec<u8>) -> String {
+    let complete = match std::str::from_utf8(buf) {
+        Err(err) if err.error_len().is_none() => err.valid_up_to(),
+        _ => buf.len(),
+    };
+    let rest = buf.split_off(complete);
+    let text = String::from_utf8_lossy(buf).into_owned();
+    *buf = rest;
+    text
+}
+
+impl TerminalState {
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Starts `shell`, or the user's shell, in `cwd` for `webview`.
+    pub fn open<R: Runtime>(
+        &self,
+        webview: &Webview<R>,
+        cwd: &Path,
+        shell: Option<PathBuf>,
+        size: Option<Size>,
+        channel: Channel<TerminalEvent>,
+    ) -> Result<TerminalInfo> {
+        if !cwd.is_dir() {
+            return Err(Error::Terminal(format!(
+                "{} is not a directory",
+                cwd.display()
+            )));
+        }
+        let (shell, args) = match shell {
+            Some(shell) => (shell, Vec::new()),
+            None => default_shell(),
+        };
+        let mut command = CommandBuilder::new(&shell);
+        command.args(args);
+        command.cwd(cwd);
+        command.env("TERM", "xterm-256color");
+        command.env("COLORTERM", "truecolor");
+        let pair = native_pty_system()
+            .openpty(size.unwrap_or(DEFAULT_SIZE).into())
+            .map_err(|err| spawn_error(&shell, err))?;
+        let mut child = pair
+            .slave
+            .spawn_command(command)
+            .map_err(|err| spawn_error(&shell, err))?;
+        // Only the shell may hold the slave side, so its exit ends the reads.
+        drop(pair.slave);
+        let master = pair.master;
+        let mut reader = master
+            .try_clone_reader()
+            .map_err(|err| spawn_error(&shell, err))?;
+        let writer = master
+            .take_writer()
+            .map_err(|err| spawn_error(&shell, err))?;
+        let killer = child.clone_killer();
+
+        let mut inner = self.inner();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        let info = TerminalInfo {
+            id,
+            shell,
+            cwd: cwd.to_path_buf(),
+            pid: child.process_id().unwrap_or_default(),
+        };
+        let exited = Arc::new(AtomicBool::new(false));
+        inner.terminals.insert(
+            id,
+            Terminal {
+                info: info.clone(),
+                webview: webview.label().to_owned(),
+                window: webview.window().label().to_owned(),
+                master,
+                writer,
+                killer,
+                exited: exited.clone(),
+            },
+        );
+
+        let app = webview.app_handle().clone();
+        std::thread::spawn(move || {
+            let mut buf = vec![0; READ_BUFFER];
+            let mut pending = Vec::new();
+            // The output is drained even once the webview is gone, so the
+            // shell never blocks on a full terminal.
+            while let Ok(read @ 1..) = reader.read(&mut buf) {
+                pending.extend_from_slice(&buf[..read]);
+                let data = take_utf8(&mut pending);
+                if !data.is_empty() {
+                    let _ = channel.send(TerminalEvent::Output { data });
+                }
+            }
+            let code = child
+                .wait()
+                .ok()
+                .filter(|status| status.signal().is_none())
+                .map(|status| status.exit_code() as i32);
+            exited.store(true, Ordering::Release);
+            app.state::<TerminalState>().exited(id);
+            let _ = channel.send(TerminalEvent::Exit { code });
+        });
+        Ok(info)
+    }
+
+    fn exited(&self, id: u32) {
+        self.inner().terminals.remove(&id);
+    }
+
+    pub fn write(&self, id: u32, data: &str) -> Result<()> {
+        let mut inner = self.inner();
+        let terminal = inner.terminals.get_mut(&id).ok_or_else(|| not_found(id))?;
+        terminal.writer.write_all(data.as_bytes())?;
+        terminal.writer.flush()?;
+        Ok(())
+    }
+
+    pub fn resize(&self, id: u32, size: Size) -> Result<()> {
+        let inner = self.inner();
+        let terminal = inner.terminals.get(&id).ok_or_else(|| not_found(id))?;
+        terminal
+            .master
+            .resize(size.into())
+            .map_err(|err| Error::Terminal(err.to_string()))
+    }
+
+    pub fn close(&self, id: u32) -> Result<()> {
+        let terminal = self
+            .inner()
+            .terminals
+            .remove(&id)
+            .ok_or_else(|| not_found(id))?;
+        terminal.close();
+        Ok(())
+    }
+
+    /// The terminals a webview has open.
+    pub fn list(&self, webview: &str) -> Vec<TerminalInfo> {
+        self.inner()
+            .terminals
+            .values()
+            .filter(|terminal| terminal.webview == webview)
+            .map(|terminal| terminal.info.clone())
+            .collect()
+    }
+
+    fn close_where(&self, matches: impl Fn(&Terminal) -> bool) {
+        let closed: Vec<Terminal> = {
+            let mut inner = self.inner();
+            let ids: Vec<u32> = inner
+                .terminals
+                .iter()
+                .filter(|(_, terminal)| matches(terminal))
+                .map(|(id, _)| *id)
+                .collect();
+            ids.iter()
+                .filter_map(|id| inner.terminals.remove(id))
+                .collect()
+        };
+        for terminal in closed {
+            terminal.close();
+        }
+    }
+
+    /// Closes the terminals of a webview that is navigating away; nothing
+    /// is left to show their output.
+    pub fn forget_webview(&self, webview: &str) {
+        self.close_where(|terminal| terminal.webview == webview);
+    }
+
+    /// Closes the terminals of a destroyed window.
+    pub fn forget_window(&self, window: &str) {
+        self.close_where(|terminal| terminal.window == window);
+    }
+}
+
+fn spawn_error(shell: &Path, err: impl std::fmt::Display) -> Error {
+    Error::Terminal(format!("{}: {err}", shell.display()))
+}
+
+fn not_found(id: u32) -> Error {
+    Error::Terminal(format!("Terminal {id} is not running"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_split_characters_for_the_next_read() {
+        let mut buf = "a→".as_bytes()[..2].to_vec();
+        assert_eq!(take_utf8(&mut buf), "a");
+        assert_eq!(buf, &"→".as_bytes()[..1]);
+        buf.extend_from_slice(&"→".as_bytes()[1..]);
+        assert_eq!(take_utf8(&mut buf), "→");
+        assert!(buf.is_empty());
+
+        let mut invalid = vec![b'x', 0xff, b'y'];
+        assert_eq!(take_utf8(&mut invalid), "x\u{fffd}y");
+    }
+}
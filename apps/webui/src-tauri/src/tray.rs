@@ -0,0 +1,507 @@
+//! Desktop tray icon. Shows gateway and machine status, counts pending
+//! permission requests and offers quick actions. Closing the main window
+//! hides it to the tray so `permission:request` events keep arriving.
+
+use tauri::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Runtime, WindowEvent};
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::watch;
+
+use crate::app_menu::{self, Locale};
+use crate::deep_link::{self, DeepLinkRoute};
+use crate::error::Result;
+use crate::gateway::{ConnectionState, GatewaySnapshot, GatewayState};
+use crate::settings::SettingsStore;
+use crate::single_instance::focus_main_window;
+
+const TRAY_ID: &str = "main";
+const MAX_RECENT_SESSIONS: usize = 10;
+
+const SHOW_ID: &str = "tray:show";
+const QUIT_ID: &str = "tray:quit";
+const APPROVE_ID: &str = "tray:approve-oldest";
+const DENY_ID: &str = "tray:deny-oldest";
+const CANCEL_RUNNING_ID: &str = "tray:cancel-running";
+const SESSION_ID_PREFIX: &str = "tray:session:";
+
+/// The parts of the gateway snapshot the tray renders, in the locale it
+/// renders them in. The menu is only rebuilt when this changes, not on
+/// every streamed chunk.
+#[derive(Debug, PartialEq, Eq)]
+struct TrayModel {
+    locale: Locale,
+    connection: ConnectionState,
+    machines: Vec<String>,
+    connected_machines: usize,
+    sessions: Vec<(String, String)>,
+    pending_permissions: usize,
+    oldest_permission: Option<String>,
+    running_sessions: usize,
+}
+
+impl TrayModel {
+    fn new(snapshot: &GatewaySnapshot, locale: Locale) -> Self {
+        let text = Text(locale);
+        let machines = snapshot
+            .machines
+            .iter()
+            .map(|machine| {
+                let name = machine.hostname.as_deref().unwrap_or(&machine.machine_id);
+                text.machine(name, machine.connected, machine.session_count)
+            })
+            .collect();
+
+        let mut sessions: Vec<_> = snapshot.sessions.iter().collect();
+        sessions.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+        let sessions = sessions
+            .into_iter()
+            .take(MAX_RECENT_SESSIONS)
+            .map(|session| {
+                let title = if session.title.is_empty() {
+                    session.session_id.clone()
+                } else {
+                    session.title.clone()
+                };
+                (session.session_id.clone(), title)
+            })
+            .collect();
+
+        let oldest_permission = snapshot.pending_permissions.first().map(|request| {
+            request
+                .title()
+                .unwrap_or_else(|| text.permission_request())
+                .to_owned()
+        });
+
+        Self {
+            locale,
+            connection: snapshot.connection.state,
+            machines,
+            connected_machines: snapshot
+                .machines
+                .iter()
+                .filter(|machine| machine.connected)
+                .count(),
+            sessions,
+            pending_permissions: snapshot.pending_permissions.len(),
+            oldest_permission,
+            running_sessions: snapshot.running_sessions.len(),
+        }
+    }
+
+    fn text(&self) -> Text {
+        Text(self.locale)
+    }
+
+    fn tooltip(&self) -> String {
+        self.text().tooltip(
+            self.connection,
+            self.connected_machines,
+            self.pending_permissions,
+        )
+    }
+
+    fn menu<R: Runtime>(&self, app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
+        let text = self.text();
+        let menu = Menu::new(app)?;
+        menu.append(&disabled(app, text.connection(self.connection))?)?;
+        if self.machines.is_empty() {
+            menu.append(&disabled(app, text.no_machines())?)?;
+        }
+        for machine in &self.machines {
+            menu.append(&disabled(app, machine)?)?;
+        }
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+        let sessions = Submenu::new(app, text.open_session(), !self.sessions.is_empty())?;
+        for (session_id, title) in &self.sessions {
+            let id = format!("{SESSION_ID_PREFIX}{session_id}");
+            sessions.append(&MenuItem::with_id(app, id, title, true, None::<&str>)?)?;
+        }
+        menu.append(&sessions)?;
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+        menu.append(&disabled(app, &text.pending(self.pending_permissions))?)?;
+        let oldest = self.oldest_permission.as_deref();
+        menu.append(&MenuItem::with_id(
+            app,
+            APPROVE_ID,
+            text.approve(oldest),
+            oldest.is_some(),
+            None::<&str>,
+        )?)?;
+        menu.append(&MenuItem::with_id(
+            app,
+            DENY_ID,
+            text.deny(oldest),
+            oldest.is_some(),
+            None::<&str>,
+        )?)?;
+        menu.append(&MenuItem::with_id(
+            app,
+            CANCEL_RUNNING_ID,
+            text.cancel_running(self.running_sessions),
+            self.running_sessions > 0,
+            None::<&str>,
+        )?)?;
+        menu.append(&PredefinedMenuItem::separator(app)?)?;
+
+        menu.append(&MenuItem::with_id(
+            app,
+            SHOW_ID,
+            text.show(),
+            true,
+            None::<&str>,
+        )?)?;
+        menu.append(&MenuItem::with_id(
+            app,
+            QUIT_ID,
+            text.quit(),
+            true,
+            None::<&str>,
+        )?)?;
+        Ok(menu)
+    }
+}
+
+/// Tray text in the languages the webui ships, following the language the
+/// webviews report to the app menu.
+struct Text(Locale);
+
+impl Text {
+    fn connection(&self, state: ConnectionState) -> &'static str {
+        match (self.0, state) {
+            (Locale::En, ConnectionState::Idle) => "Gateway not configured",
+            (Locale::En, ConnectionState::Connecting) => "Connecting to gateway…",
+            (Locale::En, ConnectionState::Connected) => "Connected to gateway",
+            (Locale::En, ConnectionState::Reconnecting) => "Reconnecting to gateway…",
+            (Locale::Zh, ConnectionState::Idle) => "未配置网关",
+            (Locale::Zh, ConnectionState::Connecting) => "正在连接网关…",
+            (Locale::Zh, ConnectionState::Connected) => "已连接网关",
+            (Locale::Zh, ConnectionState::Reconnecting) => "正在重新连接网关…",
+        }
+    }
+
+    fn machine(&self, name: &str, connected: bool, sessions: Option<usize>) -> String {
+        match (self.0, connected, sessions) {
+            (Locale::En, false, _) => format!("○ {name} (offline)"),
+            (Locale::En, true, Some(1)) => format!("● {name} (1 session)"),
+            (Locale::En, true, Some(count)) => format!("● {name} ({count} sessions)"),
+            (Locale::Zh, false, _) => format!("○ {name}（离线）"),
+            (Locale::Zh, true, Some(count)) => format!("● {name}（{count} 个会话）"),
+            (_, true, None) => format!("● {name}"),
+        }
+    }
+
+    fn tooltip(&self, connection: ConnectionState, machines: usize, pending: usize) -> String {
+        let connection = self.connection(connection);
+        match self.0 {
+            Locale::En => {
+                let mut tooltip = format!(
+                    "Mobvibe — {connection}, {machines} machine{}",
+                    plural(machines)
+                );
+                if pending > 0 {
+                    tooltip.push_str(&format!(
+                        ", {pending} pending permission{}",
+                        plural(pending)
+                    ));
+                }
+                tooltip
+            }
+            Locale::Zh => {
+                let mut tooltip = format!("Mobvibe — {connection}，{machines} 台机器");
+                if pending > 0 {
+                    tooltip.push_str(&format!("，{pending} 个待处理的权限请求"));
+                }
+                tooltip
+            }
+        }
+    }
+
+    fn no_machines(&self) -> &'static str {
+        match self.0 {
+            Locale::En => "No machines connected",
+            Locale::Zh => "没有已连接的机器",
+        }
+    }
+
+    fn open_session(&self) -> &'static str {
+        match self.0 {
+            Locale::En => "Open Session",
+            Locale::Zh => "打开会话",
+        }
+    }
+
+    fn permission_request(&self) -> &'static str {
+        match self.0 {
+            Locale::En => "permission request",
+            Locale::Zh => "权限请求",
+        }
+    }
+
+    fn pending(&self, count: usize) -> String {
+        match (self.0, count) {
+            (Locale::En, 0) => "No pending permissions".to_owned(),
+            (Locale::En, count) => format!("{count} pending permission{}", plural(count)),
+            (Locale::Zh, 0) => "没有待处理的权限请求".to_owned(),
+            (Locale::Zh, count) => format!("{count} 个待处理的权限请求"),
+        }
+    }
+
+    fn approve(&self, oldest: Option<&str>) -> String {
+        match (self.0, oldest) {
+            (Locale::En, Some(title)) => format!("Approve “{title}”"),
+            (Locale::En, None) => "Approve Oldest Request".to_owned(),
+            (Locale::Zh, Some(title)) => format!("批准“{title}”"),
+            (Locale::Zh, None) => "批准最早的请求".to_owned(),
+        }
+    }
+
+    fn deny(&self, oldest: Option<&str>) -> String {
+        match (self.0, oldest) {
+            (Locale::En, Some(title)) => format!("Deny “{title}”"),
+            (Locale::En, None) => "Deny Oldest Request".to_owned(),
+            (Locale::Zh, Some(title)) => format!("拒绝“{title}”"),
+            (Locale::Zh, None) => "拒绝最早的请求".to_owned(),
+        }
+    }
+
+    fn cancel_running(&self, count: usize) -> String {
+        match (self.0, count) {
+            (Locale::En, 0) => "Cancel Running Turns".to_owned(),
+            (Locale::En, count) => format!("Cancel Running Turns ({count})"),
+            (Locale::Zh, 0) => "取消运行中的回合".to_owned(),
+            (Locale::Zh, count) => format!("取消运行中的回合（{count}）"),
+        }
+    }
+
+    fn show(&self) -> &'static str {
+        match self.0 {
+            Locale::En => "Show Mobvibe",
+            Locale::Zh => "显示 Mobvibe",
+        }
+    }
+
+    fn quit(&self) -> &'static str {
+        match self.0 {
+            Locale::En => "Quit Mobvibe",
+            Locale::Zh => "退出 Mobvibe",
+        }
+    }
+}
+
+fn disabled<R: Runtime>(app: &AppHandle<R>, text: &str) -> tauri::Result<MenuItem<R>> {
+    MenuItem::new(app, text, false, None::<&str>)
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+/// The language of the tray text, set by the app menu when a webview
+/// reports a new one.
+pub struct TrayLocale(watch::Sender<Locale>);
+
+/// Relabels the tray, if there is one.
+pub fn set_locale<R: Runtime>(app: &AppHandle<R>, locale: Locale) {
+    if let Some(tray) = app.try_state::<TrayLocale>() {
+        tray.0.send_replace(locale);
+    }
+}
+
+/// Builds the tray, keeps it in sync with the gateway and the app's
+/// language and turns closing the main window into hiding it. Must run
+/// after the gateway and settings states are managed.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    let gateway = app.state::<GatewayState>();
+    let (locale_tx, mut locale) =
+        watch::channel(app_menu::saved_locale(&app.state::<SettingsStore>())?);
+    app.manage(TrayLocale(locale_tx));
+    let model = TrayModel::new(&gateway.snapshot(), *locale.borrow_and_update());
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID)
+        .menu(&model.menu(app)?)
+        .tooltip(model.tooltip())
+        .show_menu_on_left_click(false)
+        .on_menu_event(on_menu_event)
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                focus_main_window(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+
+    let mut events = gateway.events();
+    let handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let mut model = model;
+        loop {
+            tokio::select! {
+                event = events.recv() => match event {
+                    Ok(_) | Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return,
+                },
+                changed = locale.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                }
+            }
+            let next = TrayModel::new(
+                &handle.state::<GatewayState>().snapshot(),
+                *locale.borrow_and_update(),
+            );
+            if next == model {
+                continue;
+            }
+            if let Some(tray) = handle.tray_by_id(TRAY_ID) {
+                if let Ok(menu) = next.menu(&handle) {
+                    let _ = tray.set_menu(Some(menu));
+                }
+                let _ = tray.set_tooltip(Some(next.tooltip()));
+            }
+            model = next;
+        }
+    });
+
+    // Only hide instead of closing once the tray exists to bring it back.
+    if let Some(window) = app.get_webview_window("main") {
+        let hidden = window.clone();
+        window.on_window_event(move |event| {
+            if let WindowEvent::CloseRequested { api, .. } = event {
+                api.prevent_close();
+                let _ = hidden.hide();
+            }
+        });
+    }
+    Ok(())
+}
+
+fn on_menu_event<R: Runtime>(app: &AppHandle<R>, event: MenuEvent) {
+    let id = event.id().as_ref();
+    match id {
+        SHOW_ID => focus_main_window(app),
+        QUIT_ID => app.exit(0),
+        APPROVE_ID | DENY_ID => {
+            let allow = id == APPROVE_ID;
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let gateway = app.state::<GatewayState>();
+                let Some(request) = gateway.snapshot().pending_permissions.into_iter().next()
+                else {
+                    return;
+                };
+                let _ = gateway.respond_to_permission(&request.decide(allow)).await;
+            });
+        }
+        CANCEL_RUNNING_ID => {
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let gateway = app.state::<GatewayState>();
+                for session_id in gateway.snapshot().running_sessions {
+                    let _ = gateway.cancel_session(&session_id).await;
+                }
+            });
+        }
+        _ => {
+            if let Some(session_id) = id.strip_prefix(SESSION_ID_PREFIX) {
+                focus_main_window(app);
+                deep_link::dispatch(
+                    app,
+                    DeepLinkRoute::Session {
+                        session_id: session_id.to_owned(),
+                        seq: None,
+                    },
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateway::ConnectionStatus;
+    use serde_json::json;
+
+    fn snapshot() -> GatewaySnapshot {
+        GatewaySnapshot {
+            url: None,
+            has_token: true,
+            connection: ConnectionStatus {
+                state: ConnectionState::Connected,
+                error: None,
+            },
+            machines: vec![
+                serde_json::from_value(json!({
+                    "machineId": "m1", "connected": true, "hostname": "studio", "sessionCount": 2
+                }))
+                .unwrap(),
+                serde_json::from_value(json!({ "machineId": "m2", "connected": false })).unwrap(),
+            ],
+            pending_permissions: vec![serde_json::from_value(json!({
+                "sessionId": "s1", "requestId": "r1", "options": [],
+                "toolCall": { "title": "Edit main.rs" }
+            }))
+            .unwrap()],
+            sessions: vec![
+                serde_json::from_value(json!({
+                    "sessionId": "s1", "title": "Old", "updatedAt": "2026-01-01T00:00:00.000Z"
+                }))
+                .unwrap(),
+                serde_json::from_value(json!({
+                    "sessionId": "s2", "title": "", "updatedAt": "2026-02-01T00:00:00.000Z"
+                }))
+                .unwrap(),
+            ],
+            running_sessions: vec!["s1".to_owned()],
+            subscribed_sessions: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn summarizes_the_gateway_snapshot() {
+        let model = TrayModel::new(&snapshot(), Locale::En);
+        assert_eq!(model.machines, ["● studio (2 sessions)", "○ m2 (offline)"]);
+        assert_eq!(
+            model.sessions,
+            [
+                ("s2".to_owned(), "s2".to_owned()),
+                ("s1".to_owned(), "Old".to_owned())
+            ]
+        );
+        assert_eq!(model.oldest_permission.as_deref(), Some("Edit main.rs"));
+        assert_eq!(
+            model.tooltip(),
+            "Mobvibe — Connected to gateway, 1 machine, 1 pending permission"
+        );
+    }
+
+    #[test]
+    fn follows_the_app_language() {
+        let model = TrayModel::new(&snapshot(), Locale::Zh);
+        assert_eq!(model.machines, ["● studio（2 个会话）", "○ m2（离线）"]);
+        assert_eq!(
+            model.tooltip(),
+            "Mobvibe — 已连接网关，1 台机器，1 个待处理的权限请求"
+        );
+        assert_eq!(
+            model.text().approve(Some("Edit main.rs")),
+            "批准“Edit main.rs”"
+        );
+        assert_ne!(model, TrayModel::new(&snapshot(), Locale::En));
+    }
+}
@@ -0,0 +1,60 @@
+use tauri::ipc::Channel;
+use tauri::{AppHandle, Manager, State};
+
+use super::{DownloadProgress, ReleaseChannel, UpdateInfo, UpdaterState, UpdaterStatus};
+use crate::error::Result;
+
+#[tauri::command]
+pub fn updater_status(updater: State<'_, UpdaterState>) -> UpdaterStatus {
+    updater.status()
+}
+
+#[tauri::command]
+pub fn updater_set_channel(
+    app: AppHandle,
+    updater: State<'_, UpdaterState>,
+    channel: ReleaseChannel,
+) -> Result<UpdaterStatus> {
+    updater.set_channel(&app, channel)
+}
+
+/// Overrides the manifest URL template, e.g. a local server while testing;
+/// `null` restores the one the build ships with.
+#[tauri::command]
+pub fn updater_set_manifest_url(
+    app: AppHandle,
+    updater: State<'_, UpdaterState>,
+    url: Option<String>,
+) -> Result<UpdaterStatus> {
+    updater.set_manifest_url(&app, url)
+}
+
+/// Checks the channel now; resolves to the update, or `null` when the app
+/// is up to date.
+#[tauri::command]
+pub async fn updater_check(updater: State<'_, UpdaterState>) -> Result<Option<UpdateInfo>> {
+    updater.check().await
+}
+
+/// Downloads and verifies the update the last check found, reporting
+/// progress on `on_progress`.
+#[tauri::command]
+pub async fn updater_download(
+    app: AppHandle,
+    updater: State<'_, UpdaterState>,
+    on_progress: Channel<DownloadProgress>,
+) -> Result<UpdateInfo> {
+    let dir = app.path().app_cache_dir()?.join("updates");
+    updater
+        .download(dir, |downloaded, total| {
+            let _ = on_progress.send(DownloadProgress { downloaded, total });
+        })
+        .await
+}
+
+/// Installs the downloaded update and restarts. The webview asks the user
+/// first; nothing restarts on its own.
+#[tauri::command]
+pub async fn updater_install(app: AppHandle, updater: State<'_, UpdaterState>) -> Result<()> {
+    updater.install(&app)
+}
@@ -0,0 +1,185 @@
+//! Downloads an artifact into a staging file, hashing as it goes, and only
+//! keeps it when the signature over the digest checks out.
+
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use ed25519_dalek::{Signature, VerifyingKey};
+use sha2::{Digest, Sha256};
+use tauri_plugin_http::reqwest::Client;
+
+use super::manifest::Artifact;
+use crate::error::{Error, Result};
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Parses the base64 public key releases are signed with.
+pub fn public_key(encoded: &str) -> Result<VerifyingKey> {
+    let bytes = STANDARD.decode(encoded.trim())?;
+    let bytes: [u8; 32] = bytes
+        .as_slice()
+        .try_into()
+        .map_err(|_| Error::InvalidKeyLength {
+            expected: 32,
+            actual: bytes.len(),
+        })?;
+    VerifyingKey::from_bytes(&bytes).map_err(|_| Error::Update("Invalid update public key".into()))
+}
+
+/// Fetches `artifact` to `dest`, calling `on_progress` with the bytes so far
+/// and the total when the server sends one.
+pub async fn download(
+    client: &Client,
+    artifact: &Artifact,
+    public_key: &VerifyingKey,
+    dest: &Path,
+    mut on_progress: impl FnMut(u64, Option<u64>),
+) -> Result<()> {
+    let mut response = client.get(&artifact.url).send().await?.error_for_status()?;
+    let total = response.content_length();
+    let partial = dest.with_extension("partial");
+    let mut file = File::create(&partial)?;
+    let mut hasher = Sha256::new();
+    let mut downloaded = 0;
+    let mut reported = Instant::now();
+    let written: Result<()> = async {
+        while let Some(chunk) = response.chunk().await? {
+            hasher.update(&chunk);
+            file.write_all(&chunk)?;
+            downloaded += chunk.len() as u64;
+            if reported.elapsed() >= PROGRESS_INTERVAL {
+                reported = Instant::now();
+                on_progress(downloaded, total);
+            }
+        }
+        file.sync_all()?;
+        verify(public_key, &hasher.finalize(), &artifact.signature)
+    }
+    .await;
+    drop(file);
+    if let Err(err) = written {
+        let _ = std::fs::remove_file(&partial);
+        return Err(err);
+    }
+    on_progress(downloaded, total);
+    std::fs::rename(&partial, dest)?;
+    Ok(())
+}
+
+fn verify(public_key: &VerifyingKey, digest: &[u8], signature: &str) -> Result<()> {
+    let signature: [u8; 64] = STANDARD
+        .decode(signature.trim())?
+        .try_into()
+        .map_err(|_| Error::Update("Malformed update signature".into()))?;
+    public_key
+        .verify_strict(digest, &Signature::from_bytes(&signature))
+        .map_err(|_| Error::Update("The update's signature does not match".into()))
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// A static file server on a loopback port; returns its base URL.
+    pub async fn serve(files: Vec<(&'static str, Vec<u8>)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut stream, _)) = listener.accept().await else {
+                    return;
+                };
+                let mut request = vec![0; 4096];
+                let read = stream.read(&mut request).await.unwrap_or(0);
+                let request = String::from_utf8_lossy(&request[..read]);
+                let path = request.split(' ').nth(1).unwrap_or_default();
+                let response = match files.iter().find(|(name, _)| *name == path) {
+                    Some((_, body)) => {
+                        let mut response = format!(
+                            "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                            body.len()
+                        )
+                        .into_bytes();
+                        response.extend_from_slice(body);
+                        response
+                    }
+                    None => {
+                        b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+                            .to_vec()
+                    }
+                };
+                let _ = stream.write_all(&response).await;
+            }
+        });
+        base
+    }
+
+    pub fn sign(key: &SigningKey, body: &[u8]) -> String {
+        STANDARD.encode(key.sign(&Sha256::digest(body)).to_bytes())
+    }
+
+    #[tokio::test]
+    async fn keeps_only_artifacts_with_a_valid_signature() {
+        let key = SigningKey::from_bytes(&[3; 32]);
+        let body = b"new build".to_vec();
+        let base = serve(vec![("/app.bin", body.clone())]).await;
+        let dir = std::env::temp_dir().join(format!("mobvibe-update-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let dest = dir.join("app.bin");
+        let artifact = Artifact {
+            url: format!("{base}/app.bin"),
+            signature: sign(&key, &body),
+        };
+
+        let mut progress = Vec::new();
+        download(
+            &Client::new(),
+            &artifact,
+            &key.verifying_key(),
+            &dest,
+            |done, total| progress.push((done, total)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), body);
+        assert_eq!(progress.last(), Some(&(9, Some(9))));
+
+        std::fs::remove_file(&dest).unwrap();
+        let forged = Artifact {
+            signature: sign(&key, b"other build"),
+            ..artifact
+        };
+        let err = download(
+            &Client::new(),
+            &forged,
+            &key.verifying_key(),
+            &dest,
+            |_, _| {},
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("signature"));
+        assert!(!dest.exists() && !dest.with_extension("partial").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn verifies_signatures_from_the_signing_script() {
+        // From `scripts/sign-update.mjs sign` with the key whose seed is
+        // all sevens.
+        let encoded = "6kpsY+KcUgq+9VB7Ey7F+ZVHdq6+vnuSQh7qaRRG0iw=";
+        let signature = "FFirbTZCIPEHh5KSSi8aeHbM9wIRFj1IIt0m3G8t4m5DEC78y502Y6u/0n1thsthlGcZYjhybA7MvFVEVb9SBw==";
+        let key = public_key(encoded).unwrap();
+        assert_eq!(key, SigningKey::from_bytes(&[7; 32]).verifying_key());
+        let digest = Sha256::digest(b"signed by sign-update.mjs\n");
+        verify(&key, &digest, signature).unwrap();
+        assert!(verify(&key, &Sha256::digest(b"tampered\n"), signature).is_err());
+    }
+}
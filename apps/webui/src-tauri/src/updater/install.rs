@@ -0,0 +1,126 @@
+//! Puts a verified artifact in place of the running build. Only bundles the
+//! app can replace itself are updated: an AppImage, the macOS `.app` and
+//! the Windows NSIS install. Builds from `.deb` or `.rpm` packages belong
+//! to the package manager and are only told about new versions.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstallKind {
+    /// The `.AppImage` file `$APPIMAGE` points at.
+    AppImage(PathBuf),
+    /// The `.app` bundle the executable runs from; artifacts are the
+    /// `.app.tar.gz` the bundler writes.
+    MacApp(PathBuf),
+    /// An NSIS setup `.exe`, run silently over the existing install.
+    Nsis,
+}
+
+impl InstallKind {
+    /// How the running build was installed, when the app can update it.
+    pub fn detect() -> Option<Self> {
+        if cfg!(target_os = "linux") {
+            return std::env::var_os("APPIMAGE").map(|path| Self::AppImage(path.into()));
+        }
+        if cfg!(target_os = "windows") {
+            return Some(Self::Nsis);
+        }
+        if cfg!(target_os = "macos") {
+            let exe = std::env::current_exe().ok()?;
+            return exe
+                .ancestors()
+                .find(|dir| dir.extension().is_some_and(|ext| ext == "app"))
+                .map(|bundle| Self::MacApp(bundle.to_owned()));
+        }
+        None
+    }
+
+    /// The bundle suffix of the manifest platform key.
+    pub fn bundle(&self) -> &'static str {
+        match self {
+            Self::AppImage(_) => "appimage",
+            Self::MacApp(_) => "app",
+            Self::Nsis => "nsis",
+        }
+    }
+
+    /// Whether the installer relaunches the app itself, so the app only
+    /// has to exit.
+    pub fn relaunches(&self) -> bool {
+        matches!(self, Self::Nsis)
+    }
+
+    /// Installs `artifact`. For NSIS the installer is started and finishes
+    /// after the app exits.
+    pub fn install(&self, artifact: &Path) -> Result<()> {
+        match self {
+            Self::AppImage(target) => replace_appimage(artifact, target),
+            Self::MacApp(bundle) => replace_bundle(artifact, bundle),
+            Self::Nsis => run_nsis(artifact),
+        }
+    }
+}
+
+/// Copies next to the old file first so the final rename stays on one
+/// filesystem and the swap is atomic.
+fn replace_appimage(artifact: &Path, target: &Path) -> Result<()> {
+    let staged = target.with_extension("AppImage.update");
+    std::fs::copy(artifact, &staged)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&staged, std::fs::Permissions::from_mode(0o755))?;
+    }
+    std::fs::rename(&staged, target)?;
+    Ok(())
+}
+
+/// Unpacks with the system `tar` and swaps the bundles, putting the old
+/// one back if the new one cannot be moved in.
+fn replace_bundle(artifact: &Path, bundle: &Path) -> Result<()> {
+    let unpacked = artifact.with_extension("unpacked");
+    let _ = std::fs::remove_dir_all(&unpacked);
+    std::fs::create_dir_all(&unpacked)?;
+    let status = Command::new("tar")
+        .arg("-xzf")
+        .arg(artifact)
+        .arg("-C")
+        .arg(&unpacked)
+        .status()?;
+    if !status.success() {
+        return Err(Error::Update(format!(
+            "Could not unpack the update ({status})"
+        )));
+    }
+    let new_bundle = std::fs::read_dir(&unpacked)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| path.extension().is_some_and(|ext| ext == "app"))
+        .ok_or_else(|| Error::Update("The update holds no .app bundle".into()))?;
+    let backup = bundle.with_extension("app.old");
+    let _ = std::fs::remove_dir_all(&backup);
+    std::fs::rename(bundle, &backup)?;
+    if let Err(err) = std::fs::rename(&new_bundle, bundle) {
+        let _ = std::fs::rename(&backup, bundle);
+        return Err(err.into());
+    }
+    let _ = std::fs::remove_dir_all(&backup);
+    let _ = std::fs::remove_dir_all(&unpacked);
+    Ok(())
+}
+
+/// `/S` installs silently and `/R` starts the app again afterwards.
+fn run_nsis(artifact: &Path) -> Result<()> {
+    let mut command = Command::new(artifact);
+    command.args(["/S", "/R"]);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    command.spawn()?;
+    Ok(())
+}
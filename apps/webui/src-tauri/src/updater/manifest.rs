@@ -0,0 +1,204 @@
+//! Release manifests, laid out like the static JSON of
+//! `tauri-plugin-updater`:
+//!
+//! ```json
+//! {
+//!   "version": "0.2.0",
+//!   "notes": "…",
+//!   "pub_date": "2026-10-01T00:00:00Z",
+//!   "platforms": {
+//!     "linux-x86_64-appimage": { "url": "https://…/Mobvibe.AppImage", "signature": "…" }
+//!   }
+//! }
+//! ```
+//!
+//! Unlike that plugin's, a `signature` is the base64 Ed25519 signature of
+//! the artifact's SHA-256 digest, as `scripts/sign-update.mjs sign` prints
+//! it, not a minisign signature. Platform keys are `{os}-{arch}` with an
+//! optional `-{bundle}` suffix; the suffixed entry wins. Each channel has
+//! its own manifest.
+
+use std::collections::BTreeMap;
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::error::{Error, Result};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    #[default]
+    Stable,
+    Beta,
+}
+
+impl ReleaseChannel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Stable => "stable",
+            Self::Beta => "beta",
+        }
+    }
+
+    /// The manifests to read, in order. Beta also follows stable, so a
+    /// stable release newer than the last beta is not missed.
+    pub fn manifests(self) -> &'static [ReleaseChannel] {
+        match self {
+            Self::Stable => &[Self::Stable],
+            Self::Beta => &[Self::Beta, Self::Stable],
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    pub version: String,
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub pub_date: Option<String>,
+    pub platforms: BTreeMap<String, Artifact>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Artifact {
+    pub url: String,
+    /// Base64 Ed25519 signature of the artifact's SHA-256 digest.
+    pub signature: String,
+}
+
+/// An update offered by a manifest for this platform.
+#[derive(Debug, Clone)]
+pub struct Release {
+    pub version: Version,
+    pub notes: Option<String>,
+    pub pub_date: Option<String>,
+    pub channel: ReleaseChannel,
+    pub artifact: Artifact,
+}
+
+impl Manifest {
+    /// The release for the first of `platforms` the manifest lists.
+    pub fn release(self, channel: ReleaseChannel, platforms: &[String]) -> Result<Option<Release>> {
+        let version = Version::parse(self.version.trim_start_matches('v'))
+            .map_err(|err| Error::Update(format!("Invalid version \"{}\": {err}", self.version)))?;
+        let Some(artifact) = platforms
+            .iter()
+            .find_map(|platform| self.platforms.get(platform))
+        else {
+            return Ok(None);
+        };
+        check_url(&artifact.url)?;
+        Ok(Some(Release {
+            version,
+            notes: self.notes,
+            pub_date: self.pub_date,
+            channel,
+            artifact: artifact.clone(),
+        }))
+    }
+}
+
+/// `{os}-{arch}-{bundle}`, then `{os}-{arch}`.
+pub fn platform_keys(bundle: Option<&str>) -> Vec<String> {
+    let os = match std::env::consts::OS {
+        "macos" => "darwin",
+        os => os,
+    };
+    let base = format!("{os}-{}", std::env::consts::ARCH);
+    bundle
+        .map(|bundle| format!("{base}-{bundle}"))
+        .into_iter()
+        .chain([base])
+        .collect()
+}
+
+/// The manifest URL for `channel`: `{channel}` in the template is replaced.
+pub fn manifest_url(template: &str, channel: ReleaseChannel) -> Result<String> {
+    let url = template.replace("{channel}", channel.as_str());
+    check_url(&url)?;
+    Ok(url)
+}
+
+/// Updates come over HTTPS, or plain HTTP from this machine so a build can
+/// be tested against a local file server. Signatures are checked either way.
+pub fn check_url(raw: &str) -> Result<()> {
+    let url =
+        Url::parse(raw).map_err(|err| Error::Update(format!("Invalid URL \"{raw}\": {err}")))?;
+    let loopback = matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]"));
+    match url.scheme() {
+        "https" => Ok(()),
+        "http" if loopback => Ok(()),
+        _ => Err(Error::Update(format!(
+            "Updates must use HTTPS, or HTTP from localhost: {raw}"
+        ))),
+    }
+}
+
+/// The newest of `releases` that is newer than `current`.
+pub fn newest(current: &Version, releases: Vec<Release>) -> Option<Release> {
+    releases
+        .into_iter()
+        .filter(|release| release.version > *current)
+        .max_by(|a, b| a.version.cmp(&b.version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn manifest(version: &str) -> Manifest {
+        serde_json::from_value(json!({
+            "version": version,
+            "notes": "Fixes",
+            "platforms": {
+                "linux-x86_64": { "url": "https://example.com/plain", "signature": "a" },
+                "linux-x86_64-appimage": { "url": "https://example.com/appimage", "signature": "b" },
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn picks_the_newest_release_for_this_bundle() {
+        let platforms = [
+            "linux-x86_64-appimage".to_owned(),
+            "linux-x86_64".to_owned(),
+        ];
+        let beta = manifest("v0.3.0-beta.1")
+            .release(ReleaseChannel::Beta, &platforms)
+            .unwrap()
+            .unwrap();
+        assert_eq!(beta.artifact.url, "https://example.com/appimage");
+        let stable = manifest("0.3.0")
+            .release(ReleaseChannel::Stable, &platforms[1..])
+            .unwrap()
+            .unwrap();
+        assert_eq!(stable.artifact.url, "https://example.com/plain");
+
+        let current = Version::parse("0.2.0").unwrap();
+        let newest = newest(&current, vec![beta.clone(), stable]).unwrap();
+        assert_eq!(newest.version.to_string(), "0.3.0");
+        assert!(super::newest(&Version::parse("0.3.0").unwrap(), vec![beta]).is_none());
+
+        assert!(manifest("0.3.0")
+            .release(ReleaseChannel::Stable, &["windows-x86_64".to_owned()])
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn only_allows_https_or_local_http() {
+        assert_eq!(
+            manifest_url("https://example.com/{channel}.json", ReleaseChannel::Beta).unwrap(),
+            "https://example.com/beta.json"
+        );
+        assert!(check_url("http://127.0.0.1:8000/stable.json").is_ok());
+        assert!(check_url("http://localhost/stable.json").is_ok());
+        assert!(check_url("http://example.com/stable.json").is_err());
+        assert!(check_url("file:///tmp/stable.json").is_err());
+    }
+}
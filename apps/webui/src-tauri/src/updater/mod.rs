@@ -0,0 +1,451 @@
+//! In-app updates. Each release channel has a manifest (see [`manifest`])
+//! at a URL template such as `https://…/updates/{channel}.json`; artifacts
+//! are checked against an Ed25519 key built into the app before anything
+//! is installed, and the webview asks before the app restarts.
+//!
+//! This is not `tauri-plugin-updater`: a signature is a plain Ed25519
+//! signature over the artifact's SHA-256 digest rather than minisign, so
+//! `tauri signer` cannot produce them. `scripts/sign-update.mjs` creates the
+//! key pair and signs artifacts.
+//!
+//! Builds take `MOBVIBE_UPDATER_PUBKEY` (the base64 public key) and
+//! `MOBVIBE_UPDATER_URL` (the default template) from the environment.
+//! `build.rs` fails desktop release builds without a valid key; debug
+//! builds without one leave updates off and say so at startup.
+//!
+//! The template can be changed in settings (scope `updater`), which with
+//! HTTP allowed on loopback makes `python3 -m http.server` in a directory
+//! of manifests enough to test a build.
+
+pub mod commands;
+mod download;
+mod install;
+mod manifest;
+
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+use ed25519_dalek::VerifyingKey;
+use semver::Version;
+use serde::Serialize;
+use serde_json::Value;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_http::reqwest::Client;
+
+use self::install::InstallKind;
+pub use self::manifest::ReleaseChannel;
+use self::manifest::{Manifest, Release};
+use crate::error::{Error, Result};
+use crate::settings::{self, SettingsStore};
+
+const PUBLIC_KEY: Option<&str> = option_env!("MOBVIBE_UPDATER_PUBKEY");
+const DEFAULT_MANIFEST_URL: Option<&str> = option_env!("MOBVIBE_UPDATER_URL");
+const CHANNEL_KEY: &str = "channel";
+const MANIFEST_URL_KEY: &str = "manifestUrl";
+const FIRST_CHECK_DELAY: Duration = Duration::from_secs(30);
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub version: String,
+    pub notes: Option<String>,
+    pub pub_date: Option<String>,
+    pub channel: ReleaseChannel,
+    /// False when the package manager owns the install; the update can
+    /// only be announced.
+    pub installable: bool,
+    /// Downloaded, verified and waiting for a restart.
+    pub ready: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdaterStatus {
+    pub current_version: String,
+    pub channel: ReleaseChannel,
+    /// The template in use; None leaves updates unconfigured.
+    pub manifest_url: Option<String>,
+    pub default_manifest_url: Option<String>,
+    /// Whether this build can check for updates at all.
+    pub enabled: bool,
+    pub available: Option<UpdateInfo>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+#[derive(Default)]
+struct Inner {
+    channel: ReleaseChannel,
+    /// Overrides [`DEFAULT_MANIFEST_URL`].
+    manifest_url: Option<String>,
+    available: Option<Release>,
+    /// The verified artifact of `available`.
+    staged: Option<PathBuf>,
+    downloading: bool,
+}
+
+pub struct UpdaterState {
+    client: Client,
+    public_key: Option<VerifyingKey>,
+    current: Version,
+    install: Option<InstallKind>,
+    inner: Mutex<Inner>,
+}
+
+/// Loads the channel and manifest URL, manages [`UpdaterState`] and checks
+/// in the background, shortly after launch and then every few hours.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> Result<()> {
+    let settings = app.state::<SettingsStore>();
+    let channel = match settings.get(settings::UPDATER, CHANNEL_KEY)? {
+        Some(value) => serde_json::from_value(value).unwrap_or_default(),
+        None => ReleaseChannel::default(),
+    };
+    let manifest_url = match settings.get(settings::UPDATER, MANIFEST_URL_KEY)? {
+        Some(Value::String(url)) => Some(url),
+        _ => None,
+    };
+    let public_key = PUBLIC_KEY.map(download::public_key).transpose()?;
+    if public_key.is_none() {
+        eprintln!("Updates are off: built without MOBVIBE_UPDATER_PUBKEY");
+    }
+    let state = UpdaterState {
+        client: Client::builder().timeout(REQUEST_TIMEOUT).build()?,
+        public_key,
+        current: app.package_info().version.clone(),
+        install: InstallKind::detect(),
+        inner: Mutex::new(Inner {
+            channel,
+            manifest_url,
+            ..Inner::default()
+        }),
+    };
+    let enabled = state.status().enabled;
+    app.manage(state);
+
+    if enabled {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(FIRST_CHECK_DELAY).await;
+            loop {
+                let _ = app.state::<UpdaterState>().check().await;
+                tokio::time::sleep(CHECK_INTERVAL).await;
+            }
+        });
+    }
+    Ok(())
+}
+
+impl UpdaterState {
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn status(&self) -> UpdaterStatus {
+        let inner = self.inner();
+        let manifest_url = inner
+            .manifest_url
+            .clone()
+            .or_else(|| DEFAULT_MANIFEST_URL.map(str::to_owned));
+        UpdaterStatus {
+            current_version: self.current.to_string(),
+            channel: inner.channel,
+            enabled: self.public_key.is_some() && manifest_url.is_some(),
+            manifest_url,
+            default_manifest_url: DEFAULT_MANIFEST_URL.map(str::to_owned),
+            available: inner
+                .available
+                .as_ref()
+                .map(|release| self.info(&inner, release)),
+        }
+    }
+
+    fn info(&self, inner: &Inner, release: &Release) -> UpdateInfo {
+        UpdateInfo {
+            version: release.version.to_string(),
+            notes: release.notes.clone(),
+            pub_date: release.pub_date.clone(),
+            channel: release.channel,
+            installable: self.install.is_some(),
+            ready: inner.staged.is_some(),
+        }
+    }
+
+    /// Switches channels and saves the choice. What the old channel found
+    /// is dropped until the next check.
+    pub fn set_channel<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        channel: ReleaseChannel,
+    ) -> Result<UpdaterStatus> {
+        app.state::<SettingsStore>().set(
+            settings::UPDATER,
+            CHANNEL_KEY,
+            &serde_json::to_value(channel)?,
+        )?;
+        self.reset(|inner| inner.channel = channel);
+        Ok(self.status())
+    }
+
+    /// Overrides the manifest URL template, or goes back to the built-in
+    /// one for `None`.
+    pub fn set_manifest_url<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        url: Option<String>,
+    ) -> Result<UpdaterStatus> {
+        let url = url.filter(|url| !url.trim().is_empty());
+        let settings = app.state::<SettingsStore>();
+        match &url {
+            Some(url) => {
+                manifest::manifest_url(url, ReleaseChannel::Stable)?;
+                settings.set(
+                    settings::UPDATER,
+                    MANIFEST_URL_KEY,
+                    &Value::String(url.clone()),
+                )?;
+            }
+            None => settings.delete(settings::UPDATER, MANIFEST_URL_KEY)?,
+        }
+        self.reset(|inner| inner.manifest_url = url);
+        Ok(self.status())
+    }
+
+    fn reset(&self, change: impl FnOnce(&mut Inner)) {
+        let mut inner = self.inner();
+        change(&mut inner);
+        inner.available = None;
+        if let Some(staged) = inner.staged.take() {
+            let _ = std::fs::remove_file(staged);
+        }
+    }
+
+    /// Reads the channel's manifests and records the newest release for
+    /// this platform, if it is newer than the running build.
+    pub async fn check(&self) -> Result<Option<UpdateInfo>> {
+        let status = self.status();
+        let Some(template) = status.manifest_url.filter(|_| status.enabled) else {
+            return Err(Error::Update(
+                "Updates are not configured for this build".into(),
+            ));
+        };
+        let platforms = manifest::platform_keys(self.install.as_ref().map(InstallKind::bundle));
+        let release = fetch_release(
+            &self.client,
+            &template,
+            status.channel,
+            &self.current,
+            &platforms,
+        )
+        .await?;
+        let mut inner = self.inner();
+        let unchanged = matches!(
+            (&inner.available, &release),
+            (Some(old), Some(new)) if old.version == new.version && old.artifact == new.artifact
+        );
+        if !unchanged {
+            if let Some(staged) = inner.staged.take() {
+                let _ = std::fs::remove_file(staged);
+            }
+            inner.available = release;
+        }
+        Ok(inner
+            .available
+            .as_ref()
+            .map(|release| self.info(&inner, release)))
+    }
+
+    /// Downloads and verifies the update the last check found into `dir`.
+    pub async fn download(
+        &self,
+        dir: PathBuf,
+        on_progress: impl FnMut(u64, Option<u64>),
+    ) -> Result<UpdateInfo> {
+        let public_key = self
+            .public_key
+            .ok_or_else(|| Error::Update("Updates are not configured for this build".into()))?;
+        if self.install.is_none() {
+            return Err(Error::Update(
+                "This install is managed by a package manager; update it there".into(),
+            ));
+        }
+        let release = {
+            let mut inner = self.inner();
+            if inner.downloading {
+                return Err(Error::Update("The update is already downloading".into()));
+            }
+            let release = inner
+                .available
+                .clone()
+                .ok_or_else(|| Error::Update("No update to download".into()))?;
+            if inner.staged.is_some() {
+                return Ok(self.info(&inner, &release));
+            }
+            inner.downloading = true;
+            release
+        };
+        let dest = dir.join(artifact_name(&release));
+        let result = async {
+            std::fs::create_dir_all(&dir)?;
+            download::download(
+                &self.client,
+                &release.artifact,
+                &public_key,
+                &dest,
+                on_progress,
+            )
+            .await
+        }
+        .await;
+        let mut inner = self.inner();
+        inner.downloading = false;
+        result?;
+        // A check may have replaced the release while this one downloaded.
+        if inner
+            .available
+            .as_ref()
+            .is_none_or(|available| available.artifact != release.artifact)
+        {
+            let _ = std::fs::remove_file(&dest);
+            return Err(Error::Update("A newer update was found; try again".into()));
+        }
+        inner.staged = Some(dest);
+        Ok(self.info(&inner, &release))
+    }
+
+    /// Installs the downloaded update, then restarts the app, or exits and
+    /// leaves it to the installer to start it again.
+    pub fn install<R: Runtime>(&self, app: &AppHandle<R>) -> Result<()> {
+        let staged = self
+            .inner()
+            .staged
+            .clone()
+            .ok_or_else(|| Error::Update("No update has been downloaded".into()))?;
+        let Some(install) = &self.install else {
+            return Err(Error::Update("This install cannot update itself".into()));
+        };
+        install.install(&staged)?;
+        if install.relaunches() {
+            app.exit(0);
+            return Ok(());
+        }
+        app.restart()
+    }
+}
+
+/// Reads every manifest the channel follows and keeps the newest release.
+/// A manifest that is missing or has nothing for this platform counts as
+/// no update; one that cannot be read is an error.
+async fn fetch_release(
+    client: &Client,
+    template: &str,
+    channel: ReleaseChannel,
+    current: &Version,
+    platforms: &[String],
+) -> Result<Option<Release>> {
+    let mut releases = Vec::new();
+    for &manifest_channel in channel.manifests() {
+        let url = manifest::manifest_url(template, manifest_channel)?;
+        let response = client.get(&url).send().await?;
+        if response.status() == tauri_plugin_http::reqwest::StatusCode::NOT_FOUND {
+            continue;
+        }
+        let manifest: Manifest =
+            serde_json::from_slice(&response.error_for_status()?.bytes().await?)?;
+        releases.extend(manifest.release(manifest_channel, platforms)?);
+    }
+    Ok(manifest::newest(current, releases))
+}
+
+/// The artifact's file name, so installers keep the extension they need.
+fn artifact_name(release: &Release) -> String {
+    let name = release
+        .artifact
+        .url
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.split(['?', '#']).next())
+        .filter(|name| !name.is_empty() && !name.contains(".."))
+        .unwrap_or("update");
+    format!("{}-{name}", release.version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::download::tests::{serve, sign};
+    use super::*;
+    use ed25519_dalek::SigningKey;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn checks_and_downloads_from_a_local_server() {
+        let key = SigningKey::from_bytes(&[5; 32]);
+        let body = b"beta build".to_vec();
+        let platform = manifest::platform_keys(None).remove(0);
+        let manifest = |version: &str, path: &str| {
+            serde_json::to_vec(&json!({
+                "version": version,
+                "platforms": { &platform: { "url": path, "signature": sign(&key, &body) } }
+            }))
+            .unwrap()
+        };
+        // The URLs need the port, which is only known once serving.
+        let base = serve(vec![]).await;
+        let base = serve(vec![
+            ("/stable.json", manifest("0.2.0", &format!("{base}/unused"))),
+            (
+                "/beta.json",
+                manifest("0.3.0-beta.1", &format!("{base}/app.bin")),
+            ),
+            ("/app.bin", body.clone()),
+        ])
+        .await;
+        let template = format!("{base}/{{channel}}.json");
+        let current = Version::parse("0.1.0").unwrap();
+        let client = Client::new();
+        let platforms = [platform.clone()];
+
+        let stable = fetch_release(
+            &client,
+            &template,
+            ReleaseChannel::Stable,
+            &current,
+            &platforms,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(stable.version.to_string(), "0.2.0");
+        let beta = fetch_release(
+            &client,
+            &template,
+            ReleaseChannel::Beta,
+            &current,
+            &platforms,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert_eq!(beta.channel, ReleaseChannel::Beta);
+        assert_eq!(artifact_name(&beta), "0.3.0-beta.1-app.bin");
+
+        let missing = format!("{base}/nightly-{{channel}}.json");
+        assert!(fetch_release(
+            &client,
+            &missing,
+            ReleaseChannel::Stable,
+            &current,
+            &platforms
+        )
+        .await
+        .unwrap()
+        .is_none());
+    }
+}
@@ -0,0 +1,65 @@
+use tauri::State;
+
+use super::{migrate_legacy_store, SecretInfo, SecretVault, VaultStatus};
+use crate::e2ee::E2eeState;
+use crate::error::Result;
+use crate::settings::SettingsStore;
+
+#[tauri::command]
+pub fn vault_status(vault: State<'_, SecretVault>) -> VaultStatus {
+    vault.status()
+}
+
+/// Unlocks the file vault (Argon2 is slow, so this runs off the main thread),
+/// finishes any pending legacy migration and loads the secrets for E2EE.
+#[tauri::command]
+pub async fn vault_unlock(
+    settings: State<'_, SettingsStore>,
+    vault: State<'_, SecretVault>,
+    e2ee: State<'_, E2eeState>,
+    passphrase: String,
+) -> Result<()> {
+    let passphrase = zeroize::Zeroizing::new(passphrase);
+    vault.unlock(&passphrase)?;
+    migrate_legacy_store(&settings, &vault)?;
+    for (fingerprint, err) in vault.load_into(&e2ee)? {
+        eprintln!("[vault] Failed to load paired secret {fingerprint}: {err}");
+    }
+    Ok(())
+}
+
+/// Persists a paired master secret and makes it available for decryption.
+#[tauri::command]
+pub fn vault_add_secret(
+    vault: State<'_, SecretVault>,
+    e2ee: State<'_, E2eeState>,
+    secret: String,
+) -> Result<SecretInfo> {
+    let secret = zeroize::Zeroizing::new(secret);
+    let info = vault.add(&secret)?;
+    e2ee.add_secret(&secret)?;
+    Ok(info)
+}
+
+#[tauri::command]
+pub fn vault_list_secrets(vault: State<'_, SecretVault>) -> Result<Vec<SecretInfo>> {
+    vault.list()
+}
+
+#[tauri::command]
+pub fn vault_remove_secret(
+    vault: State<'_, SecretVault>,
+    e2ee: State<'_, E2eeState>,
+    fingerprint: String,
+) -> Result<()> {
+    vault.remove(&fingerprint)?;
+    e2ee.remove_secret(&fingerprint);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn vault_clear(vault: State<'_, SecretVault>, e2ee: State<'_, E2eeState>) -> Result<()> {
+    vault.clear()?;
+    e2ee.clear();
+    Ok(())
+}
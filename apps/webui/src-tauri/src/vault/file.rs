@@ -0,0 +1,148 @@
+//! Passphrase-encrypted vault file, used where no OS keyring is reachable.
+//! The key is derived with Argon2id and the secrets are sealed with the same
+//! secretbox envelope as session payloads.
+
+use std::fs;
+use std::path::PathBuf;
+
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use crypto_secretbox::aead::rand_core::RngCore;
+use crypto_secretbox::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use zeroize::Zeroizing;
+
+use super::StoredSecret;
+use crate::e2ee::{self, Key, KEY_BYTES};
+use crate::error::{Error, Result};
+
+const FILE_VERSION: u32 = 1;
+const SALT_BYTES: usize = 16;
+
+#[derive(Serialize, Deserialize)]
+struct VaultFile {
+    version: u32,
+    salt: String,
+    payload: Value,
+}
+
+pub(super) struct EncryptedFile {
+    path: PathBuf,
+    salt: [u8; SALT_BYTES],
+    key: Option<Key>,
+}
+
+impl EncryptedFile {
+    pub(super) fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            salt: [0; SALT_BYTES],
+            key: None,
+        }
+    }
+
+    /// Derives the file key from `passphrase` and returns the stored secrets.
+    /// Creates an empty vault when the file does not exist yet.
+    pub(super) fn unlock(&mut self, passphrase: &str) -> Result<Vec<StoredSecret>> {
+        if !self.path.exists() {
+            OsRng.fill_bytes(&mut self.salt);
+            self.key = Some(derive_key(passphrase, &self.salt)?);
+            self.write(&[])?;
+            return Ok(Vec::new());
+        }
+
+        let file: VaultFile = serde_json::from_slice(&fs::read(&self.path)?)?;
+        if file.version != FILE_VERSION {
+            return Err(Error::Vault(format!(
+                "unsupported vault file version {}",
+                file.version
+            )));
+        }
+        let salt = STANDARD.decode(&file.salt)?;
+        self.salt = salt
+            .try_into()
+            .map_err(|_| Error::Vault("corrupt vault salt".to_owned()))?;
+        let key = derive_key(passphrase, &self.salt)?;
+        let combined = file
+            .payload
+            .get("c")
+            .and_then(Value::as_str)
+            .ok_or_else(|| Error::Vault("corrupt vault payload".to_owned()))?;
+        let secrets = match e2ee::decrypt_payload(combined, &key) {
+            Ok(value) => serde_json::from_value(value)?,
+            Err(Error::DecryptFailed) => return Err(Error::WrongPassphrase),
+            Err(err) => return Err(err),
+        };
+        self.key = Some(key);
+        Ok(secrets)
+    }
+
+    /// Atomically replaces the vault file.
+    pub(super) fn write(&self, secrets: &[StoredSecret]) -> Result<()> {
+        let key = self.key.as_ref().ok_or(Error::VaultLocked)?;
+        let plaintext = serde_json::to_value(secrets)?;
+        let file = VaultFile {
+            version: FILE_VERSION,
+            salt: STANDARD.encode(self.salt),
+            payload: e2ee::encrypt_payload(&plaintext, key)?,
+        };
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, serde_json::to_vec(&file)?)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+fn derive_key(passphrase: &str, salt: &[u8]) -> Result<Key> {
+    let mut key = Zeroizing::new([0u8; KEY_BYTES]);
+    Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut())
+        .map_err(|err| Error::Vault(format!("key derivation failed: {err}")))?;
+    Ok(key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("mobvibe-vault-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir.join("secrets.vault")
+    }
+
+    fn stored(secret: &str) -> StoredSecret {
+        StoredSecret {
+            secret: Zeroizing::new(secret.to_owned()),
+            fingerprint: "x+cj9N1n".to_owned(),
+            added_at: 1,
+        }
+    }
+
+    #[test]
+    fn round_trips_secrets_with_the_right_passphrase() {
+        let path = temp_path("round-trip");
+        let mut file = EncryptedFile::new(path.clone());
+        assert!(file.unlock("correct horse").unwrap().is_empty());
+        file.write(&[stored("c2VjcmV0")]).unwrap();
+
+        let raw = fs::read_to_string(&path).unwrap();
+        assert!(!raw.contains("c2VjcmV0"));
+
+        let mut reopened = EncryptedFile::new(path.clone());
+        let secrets = reopened.unlock("correct horse").unwrap();
+        assert_eq!(secrets.len(), 1);
+        assert_eq!(secrets[0].secret.as_str(), "c2VjcmV0");
+
+        let mut wrong = EncryptedFile::new(path);
+        assert!(matches!(
+            wrong.unlock("battery staple"),
+            Err(Error::WrongPassphrase)
+        ));
+    }
+}
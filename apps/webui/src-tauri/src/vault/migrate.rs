@@ -0,0 +1,51 @@
+use serde_json::Value;
+
+use super::SecretVault;
+use crate::error::{Error, Result};
+use crate::settings::{SettingChange, SettingsStore, LEGACY_SECRETS, LEGACY_SECRET_KEYS};
+
+/// One-time move of the master secrets `E2EEManager` used to keep in plain
+/// `app-state` settings, which the legacy import set aside in
+/// `legacy-secrets`, into the vault. The entries are only deleted once
+/// every valid secret has been persisted, so a locked vault simply retries on
+/// the next unlock. Returns the number of secrets found.
+pub fn migrate_legacy_store(settings: &SettingsStore, vault: &SecretVault) -> Result<usize> {
+    if vault.is_locked() {
+        return Err(Error::VaultLocked);
+    }
+    let [secrets_key, legacy_key] = LEGACY_SECRET_KEYS;
+    let stored = settings.get(LEGACY_SECRETS, secrets_key)?;
+    let legacy = settings.get(LEGACY_SECRETS, legacy_key)?;
+    if stored.is_none() && legacy.is_none() {
+        return Ok(0);
+    }
+
+    let mut secrets: Vec<String> = Vec::new();
+    if let Some(Value::Array(items)) = stored {
+        secrets.extend(
+            items
+                .iter()
+                .filter_map(|item| item.get("secret").and_then(Value::as_str))
+                .map(str::to_owned),
+        );
+    }
+    if let Some(Value::String(secret)) = legacy {
+        secrets.push(secret);
+    }
+
+    for secret in &secrets {
+        match vault.add(secret) {
+            Ok(_) | Err(Error::Base64(_) | Error::InvalidKeyLength { .. }) => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    settings.apply(
+        LEGACY_SECRETS,
+        &LEGACY_SECRET_KEYS.map(|key| SettingChange {
+            key: key.to_owned(),
+            value: None,
+        }),
+    )?;
+    Ok(secrets.len())
+}
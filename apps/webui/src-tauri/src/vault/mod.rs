@@ -0,0 +1,381 @@
+//! Rust-owned storage for paired E2EE master secrets.
+//!
+//! Secrets live in the OS keyring where one is reachable, and otherwise in a
+//! passphrase-encrypted file in the app data dir (headless Linux, Android).
+//! The webview hands a secret over once when pairing and afterwards only sees
+//! fingerprints.
+
+pub mod commands;
+mod file;
+mod migrate;
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use self::file::EncryptedFile;
+use crate::e2ee::{self, E2eeState};
+use crate::error::{Error, Result};
+
+pub use migrate::migrate_legacy_store;
+
+const VAULT_FILE: &str = "secrets.vault";
+/// Lets headless installs unlock the file vault without a UI prompt.
+const PASSPHRASE_ENV: &str = "MOBVIBE_VAULT_PASSPHRASE";
+
+#[cfg(not(target_os = "android"))]
+const KEYRING_SERVICE: &str = "com.ericoolen.mobvibe";
+#[cfg(not(target_os = "android"))]
+const KEYRING_USER: &str = "e2ee-secrets";
+
+/// Same shape as `StoredSecret` in `apps/webui/src/lib/e2ee.ts`.
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StoredSecret {
+    secret: Zeroizing<String>,
+    fingerprint: String,
+    added_at: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretInfo {
+    pub fingerprint: String,
+    pub added_at: u64,
+}
+
+impl From<&StoredSecret> for SecretInfo {
+    fn from(stored: &StoredSecret) -> Self {
+        Self {
+            fingerprint: stored.fingerprint.clone(),
+            added_at: stored.added_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VaultBackendKind {
+    Keyring,
+    File,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VaultStatus {
+    pub backend: VaultBackendKind,
+    pub locked: bool,
+    /// Why the keyring could not be read, while it keeps the vault locked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+enum Backend {
+    #[cfg(not(target_os = "android"))]
+    Keyring(keyring::Entry),
+    File(EncryptedFile),
+}
+
+struct Inner {
+    backend: Backend,
+    /// `None` while the file vault is waiting for its passphrase, or while
+    /// the keyring cannot be read.
+    secrets: Option<Vec<StoredSecret>>,
+    /// The keyring read error. A keyring that is there but unreadable stays
+    /// locked rather than falling back to an empty file vault.
+    error: Option<String>,
+}
+
+impl Inner {
+    fn secrets(&self) -> Result<&Vec<StoredSecret>> {
+        match (&self.secrets, &self.error) {
+            (Some(secrets), _) => Ok(secrets),
+            (None, Some(error)) => Err(Error::Vault(error.clone())),
+            (None, None) => Err(Error::VaultLocked),
+        }
+    }
+
+    /// Persists `secrets` and only then makes them current, so a failed
+    /// write leaves the vault as it is stored.
+    fn replace(&mut self, secrets: Vec<StoredSecret>) -> Result<()> {
+        match &self.backend {
+            #[cfg(not(target_os = "android"))]
+            Backend::Keyring(entry) => {
+                let json = Zeroizing::new(serde_json::to_string(&secrets)?);
+                entry.set_password(&json)?;
+            }
+            Backend::File(file) => file.write(&secrets)?,
+        }
+        self.secrets = Some(secrets);
+        Ok(())
+    }
+}
+
+pub struct SecretVault {
+    inner: Mutex<Inner>,
+}
+
+impl SecretVault {
+    /// Picks the keyring when there is one, and the encrypted file
+    /// otherwise. The file vault is unlocked right away if
+    /// `MOBVIBE_VAULT_PASSPHRASE` is set.
+    pub fn open(data_dir: &Path) -> Self {
+        #[cfg(not(target_os = "android"))]
+        if let Some((entry, secrets)) = open_keyring() {
+            let (secrets, error) = match secrets {
+                Ok(secrets) => (Some(secrets), None),
+                Err(err) => (None, Some(err.to_string())),
+            };
+            return Self::with(Backend::Keyring(entry), secrets, error);
+        }
+
+        let vault = Self::with(
+            Backend::File(EncryptedFile::new(data_dir.join(VAULT_FILE))),
+            None,
+            None,
+        );
+        if let Ok(passphrase) = std::env::var(PASSPHRASE_ENV) {
+            let passphrase = Zeroizing::new(passphrase);
+            let _ = vault.unlock(&passphrase);
+        }
+        vault
+    }
+
+    fn with(backend: Backend, secrets: Option<Vec<StoredSecret>>, error: Option<String>) -> Self {
+        Self {
+            inner: Mutex::new(Inner {
+                backend,
+                secrets,
+                error,
+            }),
+        }
+    }
+
+    fn inner(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn status(&self) -> VaultStatus {
+        let inner = self.inner();
+        let backend = match inner.backend {
+            #[cfg(not(target_os = "android"))]
+            Backend::Keyring(_) => VaultBackendKind::Keyring,
+            Backend::File(_) => VaultBackendKind::File,
+        };
+        VaultStatus {
+            backend,
+            locked: inner.secrets.is_none(),
+            error: inner.error.clone(),
+        }
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.inner().secrets.is_none()
+    }
+
+    /// Unlocks the file vault, creating it on first use. For the keyring
+    /// backend, retries a read that failed.
+    pub fn unlock(&self, passphrase: &str) -> Result<()> {
+        let mut inner = self.inner();
+        if inner.secrets.is_some() {
+            return Ok(());
+        }
+        match &mut inner.backend {
+            #[cfg(not(target_os = "android"))]
+            Backend::Keyring(entry) => match read_keyring(entry) {
+                Ok(secrets) => {
+                    inner.secrets = Some(secrets);
+                    inner.error = None;
+                }
+                Err(err) => {
+                    inner.error = Some(err.to_string());
+                    return Err(err);
+                }
+            },
+            Backend::File(file) => {
+                let secrets = file.unlock(passphrase)?;
+                inner.secrets = Some(secrets);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn list(&self) -> Result<Vec<SecretInfo>> {
+        let inner = self.inner();
+        Ok(inner.secrets()?.iter().map(SecretInfo::from).collect())
+    }
+
+    /// Stores a base64 master secret. Adding a secret that is already paired
+    /// returns the existing entry.
+    pub fn add(&self, base64_secret: &str) -> Result<SecretInfo> {
+        let master = e2ee::decode_key(base64_secret)?;
+        let fingerprint = e2ee::fingerprint(&master);
+
+        let mut inner = self.inner();
+        let secrets = inner.secrets()?;
+        if let Some(existing) = secrets.iter().find(|s| s.fingerprint == fingerprint) {
+            return Ok(existing.into());
+        }
+        let stored = StoredSecret {
+            secret: Zeroizing::new(base64_secret.to_owned()),
+            fingerprint,
+            added_at: now_millis(),
+        };
+        let info = SecretInfo::from(&stored);
+        let mut secrets = secrets.clone();
+        secrets.push(stored);
+        inner.replace(secrets)?;
+        Ok(info)
+    }
+
+    pub fn remove(&self, fingerprint: &str) -> Result<()> {
+        let mut inner = self.inner();
+        let mut secrets = inner.secrets()?.clone();
+        secrets.retain(|s| s.fingerprint != fingerprint);
+        inner.replace(secrets)
+    }
+
+    pub fn clear(&self) -> Result<()> {
+        let mut inner = self.inner();
+        inner.secrets()?;
+        inner.replace(Vec::new())
+    }
+
+    /// Derives content keys for every stored secret into the E2EE state.
+    /// A secret that cannot be loaded does not keep the others out; the
+    /// failures are returned with their fingerprints.
+    pub fn load_into(&self, state: &E2eeState) -> Result<Vec<(String, Error)>> {
+        let inner = self.inner();
+        Ok(inner
+            .secrets()?
+            .iter()
+            .filter_map(|stored| {
+                let err = state.add_secret(&stored.secret).err()?;
+                Some((stored.fingerprint.clone(), err))
+            })
+            .collect())
+    }
+}
+
+/// The keyring entry and what reading it gave, or `None` when no keyring
+/// backend is available, where the file vault takes over.
+#[cfg(not(target_os = "android"))]
+fn open_keyring() -> Option<(keyring::Entry, Result<Vec<StoredSecret>>)> {
+    let entry = keyring::Entry::new(KEYRING_SERVICE, KEYRING_USER).ok()?;
+    match read_keyring(&entry) {
+        Err(Error::Keyring(keyring::Error::PlatformFailure(_))) => None,
+        secrets => Some((entry, secrets)),
+    }
+}
+
+#[cfg(not(target_os = "android"))]
+fn read_keyring(entry: &keyring::Entry) -> Result<Vec<StoredSecret>> {
+    match entry.get_password() {
+        Ok(json) => {
+            let json = Zeroizing::new(json);
+            Ok(serde_json::from_str(&json)?)
+        }
+        Err(keyring::Error::NoEntry) => Ok(Vec::new()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine as _;
+
+    use super::*;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("mobvibe-vault-{}-{name}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn file_vault(dir: &Path) -> SecretVault {
+        let vault = SecretVault::with(
+            Backend::File(EncryptedFile::new(dir.join(VAULT_FILE))),
+            None,
+            None,
+        );
+        vault.unlock("correct horse").unwrap();
+        vault
+    }
+
+    #[test]
+    fn a_failed_write_leaves_the_vault_as_stored() {
+        let dir = temp_dir("failed-write");
+        let vault = file_vault(&dir);
+        let first = vault.add(&STANDARD.encode([1u8; 32])).unwrap();
+
+        // A directory where the temporary file goes makes every write fail.
+        std::fs::create_dir_all(dir.join(VAULT_FILE).with_extension("tmp")).unwrap();
+        assert!(vault.add(&STANDARD.encode([2u8; 32])).is_err());
+        assert!(vault.remove(&first.fingerprint).is_err());
+        assert!(vault.clear().is_err());
+
+        let listed: Vec<_> = vault
+            .list()
+            .unwrap()
+            .into_iter()
+            .map(|s| s.fingerprint)
+            .collect();
+        assert_eq!(listed, vec![first.fingerprint]);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn loads_every_secret_it_can_and_reports_the_rest() {
+        let valid = STANDARD.encode([1u8; 32]);
+        let stored = |secret: &str, fingerprint: &str| StoredSecret {
+            secret: Zeroizing::new(secret.to_owned()),
+            fingerprint: fingerprint.to_owned(),
+            added_at: 1,
+        };
+        let vault = SecretVault::with(
+            Backend::File(EncryptedFile::new(temp_dir("load").join(VAULT_FILE))),
+            Some(vec![
+                stored("not base64!", "broken"),
+                stored(&valid, "valid"),
+            ]),
+            None,
+        );
+
+        let failures = vault.load_into(&E2eeState::default()).unwrap();
+        let failed: Vec<_> = failures
+            .iter()
+            .map(|(fingerprint, _)| fingerprint.as_str())
+            .collect();
+        assert_eq!(failed, vec!["broken"]);
+    }
+
+    #[test]
+    fn an_unreadable_keyring_reports_its_error_instead_of_being_empty() {
+        let vault = SecretVault::with(
+            Backend::File(EncryptedFile::new(temp_dir("error").join(VAULT_FILE))),
+            None,
+            Some("Invalid JSON: expected value".to_owned()),
+        );
+        assert!(vault.is_locked());
+        assert_eq!(
+            vault.status().error.as_deref(),
+            Some("Invalid JSON: expected value")
+        );
+        assert!(matches!(vault.list(), Err(Error::Vault(_))));
+        assert!(matches!(
+            vault.add(&STANDARD.encode([1u8; 32])),
+            Err(Error::Vault(_))
+        ));
+    }
+}
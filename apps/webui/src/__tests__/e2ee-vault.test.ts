@@ -0,0 +1,142 @@
+import { beforeEach, describe, expect, it, vi } from "vitest";
+
+const invoke = vi.hoisted(() => vi.fn());
+const mockUnwrapDEK = vi.hoisted(() => vi.fn());
+
+vi.mock("@tauri-apps/api/core", () => ({ invoke }));
+
+vi.mock("@mobvibe/shared", () => ({
+	initCrypto: vi.fn().mockResolvedValue(undefined),
+	base64ToUint8: (s: string) => new Uint8Array(Buffer.from(s, "base64")),
+	uint8ToBase64: (arr: Uint8Array) => Buffer.from(arr).toString("base64"),
+	deriveContentKeyPair: vi.fn(),
+	deriveAuthKeyPair: vi.fn(),
+	unwrapDEK: mockUnwrapDEK,
+	decryptPayload: vi.fn(),
+	encryptPayload: vi.fn(() => ({ t: "encrypted", c: "c2VhbGVk" })),
+	isEncryptedPayload: (payload: { t?: string }) => payload?.t === "encrypted",
+}));
+
+vi.mock("@/lib/auth", () => ({
+	isInTauri: () => true,
+}));
+
+const { e2ee, ensureSessionE2EE } = await import("@/lib/e2ee");
+
+const device = { fingerprint: "AbCdEfGh", addedAt: 1 };
+
+describe("E2EEManager in the app", () => {
+	beforeEach(async () => {
+		invoke.mockReset().mockResolvedValue(undefined);
+		await e2ee.clearSecret();
+		invoke.mockReset();
+		localStorage.clear();
+	});
+
+	it("pairs through the vault and keeps only fingerprints", async () => {
+		invoke.mockResolvedValueOnce(device);
+
+		await e2ee.addPairedSecret("c2VjcmV0");
+
+		expect(invoke).toHaveBeenCalledWith("vault_add_secret", {
+			secret: "c2VjcmV0",
+		});
+		expect(e2ee.isEnabled()).toBe(true);
+		expect(e2ee.getPairedDevices()).toEqual([device]);
+		expect(e2ee.getPairedSecrets()).toEqual([]);
+		expect(localStorage.getItem("mobvibe_e2ee_secrets")).toBeNull();
+	});
+
+	it("loads devices from an unlocked vault", async () => {
+		invoke.mockImplementation(async (command: string) => {
+			if (command === "vault_status") {
+				return { backend: "keyring", locked: false };
+			}
+			if (command === "vault_list_secrets") return [device];
+			return undefined;
+		});
+
+		await expect(e2ee.loadFromStorage()).resolves.toBe(true);
+		expect(e2ee.getPairedDevices()).toEqual([device]);
+	});
+
+	it("does not load a locked vault", async () => {
+		invoke.mockResolvedValueOnce({ backend: "file", locked: true });
+
+		await expect(e2ee.loadFromStorage()).resolves.toBe(false);
+		expect(invoke).toHaveBeenCalledTimes(1);
+	});
+
+	it("learns which session DEKs Rust holds and reports when they are ready", async () => {
+		invoke.mockResolvedValueOnce([{ sessionId: "s1", revision: 2 }]);
+		const listener = vi.fn();
+		const unsubscribe = e2ee.onDekReady(listener);
+
+		expect(e2ee.unwrapSessionDek("s1", "wrapped", 2)).toBe(false);
+		await vi.waitFor(() => expect(listener).toHaveBeenCalledWith("s1", 2));
+
+		expect(invoke).toHaveBeenCalledWith("e2ee_unwrap_session_deks", {
+			sessions: [{ sessionId: "s1", wrappedDek: "wrapped", revision: 2 }],
+		});
+		expect(mockUnwrapDEK).not.toHaveBeenCalled();
+		expect(e2ee.getSessionE2EEStatus("s1", true, 2)).toBe("ok");
+		unsubscribe();
+	});
+
+	it("waits for the vault before reporting a status to send with", async () => {
+		invoke.mockResolvedValueOnce([{ sessionId: "s2", revision: 1 }]);
+
+		await expect(ensureSessionE2EE("s2", "wrapped", 1)).resolves.toBe("ok");
+
+		invoke.mockResolvedValueOnce([]);
+		await expect(ensureSessionE2EE("s3", "wrapped", 1)).resolves.toBe(
+			"missing_key",
+		);
+		await expect(ensureSessionE2EE("s4")).resolves.toBe("none");
+	});
+
+	it("decrypts events and encrypts prompts in Rust", async () => {
+		const event = {
+			sessionId: "s5",
+			machineId: "m1",
+			revision: 1,
+			seq: 1,
+			kind: "user_message",
+			createdAt: "2026-01-01T00:00:00.000Z",
+			payload: { t: "encrypted", c: "c2VhbGVk" },
+		};
+		const decrypted = [{ event: { ...event, payload: { text: "hi" } } }];
+		invoke.mockResolvedValueOnce(decrypted);
+
+		await expect(e2ee.decryptEvents([event])).resolves.toEqual(decrypted);
+		expect(invoke).toHaveBeenCalledWith("e2ee_decrypt_events", {
+			events: [event],
+		});
+		// Nothing in the webview can open what Rust left encrypted.
+		expect(() => e2ee.decryptEvent(event)).toThrow("not available");
+
+		invoke.mockResolvedValueOnce(event.payload);
+		await expect(
+			e2ee.encryptPayloadForSession("s5", [{ type: "text" }], 1, true),
+		).resolves.toEqual(event.payload);
+		expect(invoke).toHaveBeenLastCalledWith("e2ee_encrypt_payload", {
+			sessionId: "s5",
+			payload: [{ type: "text" }],
+			revision: 1,
+			encryptionRequired: true,
+		});
+	});
+
+	it("removes devices by fingerprint", async () => {
+		invoke.mockResolvedValueOnce(device);
+		await e2ee.addPairedSecret("c2VjcmV0");
+
+		await e2ee.removePairedDevice(device.fingerprint);
+
+		expect(invoke).toHaveBeenLastCalledWith("vault_remove_secret", {
+			fingerprint: device.fingerprint,
+		});
+		expect(e2ee.getPairedDevices()).toEqual([]);
+		expect(e2ee.isEnabled()).toBe(false);
+	});
+});
@@ -431,9 +431,12 @@ describe("E2EEManager", () => {
 	});
 
 	describe("bidirectional encryption", () => {
-		it("encryptPayloadForSession returns plaintext when no DEK", () => {
+		it("encryptPayloadForSession returns plaintext when no DEK", async () => {
 			const payload = [{ type: "text", text: "hello" }];
-			const result = e2ee.encryptPayloadForSession("session-no-dek", payload);
+			const result = await e2ee.encryptPayloadForSession(
+				"session-no-dek",
+				payload,
+			);
 			expect(result).toBe(payload);
 			expect(mockEncryptPayload).not.toHaveBeenCalled();
 		});
@@ -443,7 +446,7 @@ describe("E2EEManager", () => {
 			e2ee.unwrapSessionDek("session-1", "wrapped-dek");
 
 			const payload = [{ type: "text", text: "hello" }];
-			const result = e2ee.encryptPayloadForSession("session-1", payload);
+			const result = await e2ee.encryptPayloadForSession("session-1", payload);
 
 			expect(mockEncryptPayload).toHaveBeenCalledWith(
 				payload,
@@ -459,26 +462,26 @@ describe("E2EEManager", () => {
 			await e2ee.addPairedSecret(btoa("test-secret"));
 			e2ee.unwrapSessionDek("session-1", "wrapped-revision-1", 1);
 
-			expect(() =>
+			await expect(
 				e2ee.encryptPayloadForSession(
 					"session-1",
 					[{ type: "text", text: "revision two" }],
 					2,
 					true,
 				),
-			).toThrow("matching session encryption key");
+			).rejects.toThrow("matching session encryption key");
 			expect(mockEncryptPayload).not.toHaveBeenCalled();
 		});
 
-		it("fails closed when an encrypted session has no DEK", () => {
-			expect(() =>
+		it("fails closed when an encrypted session has no DEK", async () => {
+			await expect(
 				e2ee.encryptPayloadForSession(
 					"session-no-dek",
 					[{ type: "text", text: "secret" }],
 					3,
 					true,
 				),
-			).toThrow("matching session encryption key");
+			).rejects.toThrow("matching session encryption key");
 			expect(mockEncryptPayload).not.toHaveBeenCalled();
 		});
 
@@ -490,14 +493,14 @@ describe("E2EEManager", () => {
 				1,
 			);
 
-			expect(() =>
+			await expect(
 				e2ee.encryptPayloadForSession(
 					"session-unknown-revision",
 					[{ type: "text", text: "unknown revision" }],
 					undefined,
 					true,
 				),
-			).toThrow("matching session encryption key");
+			).rejects.toThrow("matching session encryption key");
 			expect(mockEncryptPayload).not.toHaveBeenCalled();
 		});
 
@@ -506,7 +509,7 @@ describe("E2EEManager", () => {
 			e2ee.unwrapSessionDek("legacy-session", "wrapped-revision-1", 1);
 
 			const payload = [{ type: "text", text: "legacy gateway" }];
-			const result = e2ee.encryptPayloadForSession(
+			const result = await e2ee.encryptPayloadForSession(
 				"legacy-session",
 				payload,
 				undefined,
@@ -522,7 +525,10 @@ describe("E2EEManager", () => {
 			e2ee.unwrapSessionDek("session-rt", "wrapped-dek", 1);
 
 			const original = [{ type: "text", text: "round trip" }];
-			const encrypted = e2ee.encryptPayloadForSession("session-rt", original);
+			const encrypted = await e2ee.encryptPayloadForSession(
+				"session-rt",
+				original,
+			);
 
 			mockDecryptPayload.mockReturnValueOnce(original);
 			mockIsEncryptedPayload.mockReturnValueOnce(true);
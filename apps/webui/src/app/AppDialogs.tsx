@@ -2,6 +2,7 @@ import { lazy, Suspense } from "react";
 import type { MainAppController } from "@/app/use-main-app-controller";
 import { CreateSessionDialog } from "@/components/app/CreateSessionDialog";
 import { FileExplorerDialog } from "@/components/app/FileExplorerDialog";
+import { TerminalDialog } from "@/components/app/TerminalDialog";
 
 const CommandPalette = lazy(async () => {
 	const module = await import("@/components/app/CommandPalette");
@@ -14,6 +15,7 @@ type AppDialogsProps = {
 
 export function AppDialogs({ controller }: AppDialogsProps) {
 	const {
+		activeSession,
 		activeSessionId,
 		availableBackends,
 		commandPaletteOpen,
@@ -23,6 +25,7 @@ export function AppDialogs({ controller }: AppDialogsProps) {
 		filePreviewPath,
 		handleCreateSession,
 		isCreatingSession,
+		terminalOpen,
 		uiActions,
 	} = controller;
 
@@ -46,6 +49,11 @@ export function AppDialogs({ controller }: AppDialogsProps) {
 				sessionId={activeSessionId}
 				initialFilePath={filePreviewPath}
 			/>
+			<TerminalDialog
+				open={terminalOpen}
+				onOpenChange={uiActions.setTerminalOpen}
+				cwd={activeSession?.cwd}
+			/>
 			<Suspense fallback={null}>
 				<CommandPalette
 					open={commandPaletteOpen}
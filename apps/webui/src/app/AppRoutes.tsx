@@ -1,16 +1,33 @@
 import { useBetterAuthTauri } from "@daveyplate/better-auth-tauri/react";
 import { BrandLogo } from "@mobvibe/ui/brand-logo";
 import { useQueryClient } from "@tanstack/react-query";
-import { lazy, Suspense, useEffect, useRef } from "react";
+import { lazy, Suspense, useCallback, useEffect, useRef, useState } from "react";
 import { useTranslation } from "react-i18next";
 import { Navigate, Route, Routes, useNavigate } from "react-router-dom";
 import { MainApp } from "@/app/MainApp";
+import {
+	type ConfirmableDeepLink,
+	DeepLinkConfirmDialog,
+} from "@/components/app/DeepLinkConfirmDialog";
 import { useAuth } from "@/components/auth/AuthProvider";
-import { parsePairingUrl } from "@/components/settings/E2EESettings";
+import i18n from "@/i18n";
 import type { SessionsResponse } from "@/lib/api";
 import { getAuthClient, isInTauri } from "@/lib/auth";
 import { useChatStore } from "@/lib/chat-store";
+import {
+	acceptPairing,
+	type DeepLinkError,
+	type DeepLinkRoute,
+	listenDeepLinks,
+	rejectPairing,
+	sessionPath,
+} from "@/lib/deep-link";
 import { e2ee } from "@/lib/e2ee";
+import { setGatewayUrl } from "@/lib/gateway-config";
+import { useNotificationStore } from "@/lib/notification-store";
+import { isQuickPromptWindow } from "@/lib/quick-prompt";
+import { isSessionWindow } from "@/lib/session-windows";
+import { useUiStore } from "@/lib/ui-store";
 
 const SettingsPage = lazy(async () => {
 	const module = await import("@/pages/SettingsPage");
@@ -22,6 +39,11 @@ const LoginPage = lazy(async () => {
 	return { default: module.LoginPage };
 });
 
+const QuickPromptPage = lazy(async () => {
+	const module = await import("@/pages/QuickPromptPage");
+	return { default: module.QuickPromptPage };
+});
+
 const LegalPage = lazy(async () => {
 	const module = await import("@/pages/LegalPage");
 	return { default: module.LegalPage };
@@ -44,62 +66,125 @@ function TauriAuthHandler({
 	return null;
 }
 
-function TauriPairHandler() {
-	const unlistenRef = useRef<(() => void) | null>(null);
-	const queryClientRef = useRef(useQueryClient());
+function TauriDeepLinkHandler() {
+	const navigate = useNavigate();
+	const queryClient = useQueryClient();
+	const navigateRef = useRef(navigate);
+	navigateRef.current = navigate;
+	// Pairing and gateway links wait here, oldest first, for the user.
+	const [confirming, setConfirming] = useState<ConfirmableDeepLink[]>([]);
+	const answered = useRef(new WeakSet<ConfirmableDeepLink>());
+
+	const refreshSessionE2EE = useCallback(() => {
+		const cached = queryClient.getQueryData<SessionsResponse>(["sessions"]);
+		if (!cached?.sessions) return;
+		e2ee.unwrapAllSessionDeks(cached.sessions);
+		const { setSessionE2EEStatus } = useChatStore.getState();
+		for (const session of cached.sessions) {
+			setSessionE2EEStatus(
+				session.sessionId,
+				e2ee.getSessionE2EEStatus(
+					session.sessionId,
+					Boolean(session.wrappedDek),
+					session.revision,
+				),
+			);
+		}
+	}, [queryClient]);
+
+	const onAnswer = useCallback(
+		(route: ConfirmableDeepLink, accepted: boolean) => {
+			// Accepting also closes the dialog, which reads as a decline.
+			if (answered.current.has(route)) return;
+			answered.current.add(route);
+			setConfirming((queue) => queue.filter((queued) => queued !== route));
+			if (route.type === "gateway") {
+				if (accepted) {
+					void setGatewayUrl(route.url).then(() => window.location.reload());
+				}
+				return;
+			}
+			if (!accepted) {
+				void rejectPairing(route.id).catch(() => {});
+				return;
+			}
+			const { pushNotification } = useNotificationStore.getState();
+			void acceptPairing(route.id)
+				.then(() => e2ee.loadFromStorage())
+				.then(() => {
+					refreshSessionE2EE();
+					pushNotification({
+						title: i18n.t("deepLink.pairedTitle"),
+						description: route.fingerprint,
+						variant: "success",
+					});
+				})
+				.catch((error: unknown) => {
+					pushNotification({
+						title: i18n.t("deepLink.pairFailedTitle"),
+						description: error instanceof Error ? error.message : String(error),
+						variant: "error",
+					});
+				});
+		},
+		[refreshSessionE2EE],
+	);
 
 	useEffect(() => {
 		let cancelled = false;
+		let unlisten: (() => void) | null = null;
 
-		(async () => {
-			try {
-				const { onOpenUrl } = await import("@tauri-apps/plugin-deep-link");
-				const unlisten = await onOpenUrl((urls) => {
-					for (const url of urls) {
-						const secret = parsePairingUrl(url);
-						if (secret) {
-							void e2ee.setPairedSecret(secret).then(() => {
-								// Unwrap DEKs for all known sessions after pairing.
-								const cached =
-									queryClientRef.current.getQueryData<SessionsResponse>([
-										"sessions",
-									]);
-								if (cached?.sessions) {
-									e2ee.unwrapAllSessionDeks(cached.sessions);
-									const { setSessionE2EEStatus } = useChatStore.getState();
-									for (const session of cached.sessions) {
-										setSessionE2EEStatus(
-											session.sessionId,
-											e2ee.getSessionE2EEStatus(
-												session.sessionId,
-												Boolean(session.wrappedDek),
-												session.revision,
-											),
-										);
-									}
-								}
-							});
-							break;
-						}
-					}
-				});
+		const onRoute = (route: DeepLinkRoute) => {
+			switch (route.type) {
+				case "pair":
+				case "gateway":
+					setConfirming((queue) => [...queue, route]);
+					break;
+				case "session": {
+					const path = sessionPath(route);
+					if (path) navigateRef.current(path);
+					break;
+				}
+				case "newSession": {
+					const ui = useUiStore.getState();
+					ui.setDraftCwd(route.cwd ?? undefined);
+					ui.setDraftPrompt(route.prompt ?? undefined);
+					ui.setCreateDialogOpen(true);
+					navigateRef.current("/");
+					break;
+				}
+			}
+		};
+
+		const onError = ({ reason }: DeepLinkError) => {
+			useNotificationStore.getState().pushNotification({
+				title: i18n.t("deepLink.invalidTitle"),
+				description: reason,
+				variant: "error",
+			});
+		};
+
+		void listenDeepLinks(onRoute, onError)
+			.then((stop) => {
 				if (cancelled) {
-					unlisten();
+					stop?.();
 				} else {
-					unlistenRef.current = unlisten;
+					unlisten = stop;
 				}
-			} catch {
-				// Deep-link plugin is unavailable in browser builds.
-			}
-		})();
+			})
+			.catch((error) => {
+				console.warn("[deep-link] Failed to listen for links", error);
+			});
 
 		return () => {
 			cancelled = true;
-			unlistenRef.current?.();
+			unlisten?.();
 		};
 	}, []);
 
-	return null;
+	return (
+		<DeepLinkConfirmDialog route={confirming[0] ?? null} onAnswer={onAnswer} />
+	);
 }
 
 function LoadingState() {
@@ -119,6 +204,18 @@ function LoadingState() {
 	);
 }
 
+function QuickPromptSignedOut() {
+	const { t } = useTranslation();
+
+	return (
+		<div className="flex min-h-dvh items-center justify-center p-6 text-center">
+			<span className="text-muted-foreground text-sm">
+				{t("quickPrompt.signInRequired")}
+			</span>
+		</div>
+	);
+}
+
 function RoutePending() {
 	return <LoadingState />;
 }
@@ -129,16 +226,28 @@ export function AppRoutes() {
 
 	const authClient = getAuthClient();
 	const shouldSetupTauriAuth = isInTauri() && authClient !== null;
-	const shouldSetupTauriPair = isInTauri();
+	// Links navigate the main window; session windows stay on their session.
+	const shouldSetupTauriDeepLinks = isInTauri() && !isSessionWindow();
 
 	if (isLoading) {
 		return <LoadingState />;
 	}
 
+	// The quick prompt window shows only the prompt form, whatever the path.
+	if (isQuickPromptWindow()) {
+		return !isAuthEnabled || isAuthenticated ? (
+			<Suspense fallback={<RoutePending />}>
+				<QuickPromptPage />
+			</Suspense>
+		) : (
+			<QuickPromptSignedOut />
+		);
+	}
+
 	return (
 		<>
 			{shouldSetupTauriAuth && <TauriAuthHandler authClient={authClient!} />}
-			{shouldSetupTauriPair && <TauriPairHandler />}
+			{shouldSetupTauriDeepLinks && <TauriDeepLinkHandler />}
 			<Routes>
 				<Route
 					path="/privacy"
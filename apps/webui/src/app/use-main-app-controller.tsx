@@ -5,10 +5,12 @@ import {
 	useEffect,
 	useMemo,
 	useRef,
+	useState,
 } from "react";
 import { useTranslation } from "react-i18next";
-import { useSearchParams } from "react-router-dom";
+import { useNavigate, useSearchParams } from "react-router-dom";
 import { useShallow } from "zustand/react/shallow";
+import type { ChatMessageListHandle } from "@/components/app/ChatMessageList";
 import { useAuth } from "@/components/auth/AuthProvider";
 import { useMachineDiscovery } from "@/hooks/useMachineDiscovery";
 import { useMachinesQuery } from "@/hooks/useMachinesQuery";
@@ -18,23 +20,38 @@ import { useSessionList } from "@/hooks/useSessionList";
 import { useSessionMutations } from "@/hooks/useSessionMutations";
 import { useSessionQueries } from "@/hooks/useSessionQueries";
 import { useSocket } from "@/hooks/useSocket";
-import { useChatStore } from "@/lib/chat-store";
-import { bootstrapSessionE2EE } from "@/lib/e2ee";
+import { findMessageIndexForSeq, useChatStore } from "@/lib/chat-store";
+import { listenAppMenu, updateAppMenu } from "@/lib/app-menu";
+import { isInTauri } from "@/lib/auth";
+import { e2ee } from "@/lib/e2ee";
 import { createFallbackError, normalizeError } from "@/lib/error-utils";
-import { isInputFocused, registerHotkeys } from "@/lib/hotkeys";
+import {
+	type HotkeyCommand,
+	isInputFocused,
+	registerHotkeys,
+	runHotkeyCommand,
+} from "@/lib/hotkeys";
 import { getBackendCapability, useMachinesStore } from "@/lib/machines-store";
 import { ensureNotificationPermission } from "@/lib/notifications";
+import { isSessionWindow, openSessionWindow } from "@/lib/session-windows";
 import { shouldActivateSessionOnSelect } from "@/lib/session-selection";
 import { getContextLeftPercent } from "@/lib/session-usage";
 import { useUiStore } from "@/lib/ui-store";
 import { getPathBasename } from "@/lib/ui-utils";
 
 export function useMainAppController() {
-	const { t } = useTranslation();
+	const { t, i18n } = useTranslation();
 	const { isAuthenticated } = useAuth();
+	const navigate = useNavigate();
 	const [searchParams, setSearchParams] = useSearchParams();
 	const notificationSessionId = searchParams.get("sessionId");
 	const handlingNotificationSessionIdRef = useRef<string | null>(null);
+	// The event a `?seq=` link points at, shown once its session is caught up.
+	const [focusedEvent, setFocusedEvent] = useState<{
+		sessionId: string;
+		seq: number;
+	} | null>(null);
+	const chatMessageListRef = useRef<ChatMessageListHandle>(null);
 
 	// Reactive state — re-renders only when these values change
 	const { activeSessionId, appError, lastCreatedCwd } = useChatStore(
@@ -96,6 +113,7 @@ export function useMainAppController() {
 		filePreviewPath,
 		commandPaletteOpen,
 		chatSearchOpen,
+		terminalOpen,
 		draftBackendId,
 		selectedWorkspaceByMachine,
 	} = useUiStore(
@@ -105,6 +123,7 @@ export function useMainAppController() {
 			filePreviewPath: s.filePreviewPath,
 			commandPaletteOpen: s.commandPaletteOpen,
 			chatSearchOpen: s.chatSearchOpen,
+			terminalOpen: s.terminalOpen,
 			draftBackendId: s.draftBackendId,
 			selectedWorkspaceByMachine: s.selectedWorkspaceByMachine,
 		})),
@@ -119,6 +138,7 @@ export function useMainAppController() {
 			setFilePreviewPath: s.setFilePreviewPath,
 			setCommandPaletteOpen: s.setCommandPaletteOpen,
 			setChatSearchOpen: s.setChatSearchOpen,
+			setTerminalOpen: s.setTerminalOpen,
 			clearEditingSession: s.clearEditingSession,
 			setDraftTitle: s.setDraftTitle,
 			setDraftBackendId: s.setDraftBackendId,
@@ -258,14 +278,16 @@ export function useMainAppController() {
 		if (sessionsQuery.data?.sessions) {
 			syncSessionSummaries(sessionsQuery.data.sessions);
 
-			// Bootstrap session DEKs and keep runtime E2EE status in sync.
+			// Unwrap every session DEK in one batch (in the app, the vault also
+			// keeps them for search indexing) and keep E2EE status in sync.
+			e2ee.unwrapAllSessionDeks(sessionsQuery.data.sessions);
 			const { setSessionE2EEStatus } = useChatStore.getState();
 			for (const session of sessionsQuery.data.sessions) {
 				setSessionE2EEStatus(
 					session.sessionId,
-					bootstrapSessionE2EE(
+					e2ee.getSessionE2EEStatus(
 						session.sessionId,
-						session.wrappedDek,
+						Boolean(session.wrappedDek),
 						session.revision,
 					),
 				);
@@ -362,16 +384,47 @@ export function useMainAppController() {
 		}
 
 		handlingNotificationSessionIdRef.current = notificationSessionId;
+		const seq = Number(searchParams.get("seq") ?? Number.NaN);
 		startTransition(() => {
 			void activateSession(targetSession).finally(() => {
 				const nextParams = new URLSearchParams(searchParams);
 				nextParams.delete("sessionId");
+				nextParams.delete("seq");
 				setSearchParams(nextParams, { replace: true });
 				handlingNotificationSessionIdRef.current = null;
+				setFocusedEvent(
+					Number.isInteger(seq)
+						? { sessionId: notificationSessionId, seq }
+						: null,
+				);
 			});
 		});
 	}, [activateSession, notificationSessionId, searchParams, setSearchParams]);
 
+	useEffect(() => {
+		if (!focusedEvent) {
+			return;
+		}
+		if (activeSession?.sessionId !== focusedEvent.sessionId) {
+			setFocusedEvent(null);
+			return;
+		}
+		if (
+			activeSession.historySyncing ||
+			(activeSession.lastAppliedSeq ?? 0) < focusedEvent.seq
+		) {
+			return;
+		}
+		setFocusedEvent(null);
+		const index = findMessageIndexForSeq(
+			activeSession.messages,
+			focusedEvent.seq,
+		);
+		if (index >= 0) {
+			chatMessageListRef.current?.highlightMessage(index);
+		}
+	}, [activeSession, focusedEvent]);
+
 	useEffect(() => {
 		if (!createDialogOpen) {
 			return;
@@ -387,18 +440,17 @@ export function useMainAppController() {
 	]);
 
 	// --- Global hotkeys ---
-	const chatMessageListRef = useRef<{ scrollToIndex: (index: number) => void }>(
-		null,
-	);
 
 	useEffect(() => {
 		return registerHotkeys([
 			{
+				command: "palette.open",
 				key: "k",
 				mod: true,
 				handler: () => uiActions.setCommandPaletteOpen(true),
 			},
 			{
+				command: "chat.search",
 				key: "f",
 				mod: true,
 				handler: () => {
@@ -408,18 +460,73 @@ export function useMainAppController() {
 				},
 			},
 			{
+				command: "sidebar.toggle",
 				key: "b",
 				mod: true,
 				handler: () =>
 					uiActions.setMobileMenuOpen(!useUiStore.getState().mobileMenuOpen),
 			},
 			{
+				command: "session.new",
 				key: "n",
 				mod: true,
 				handler: () => handleOpenCreateDialog(),
 			},
+			{
+				command: "settings.open",
+				key: ",",
+				mod: true,
+				handler: () => navigate("/settings"),
+			},
+			// Browsers keep Ctrl+Shift+N, so only the desktop app has this one.
+			...(isInTauri()
+				? [
+						{
+							command: "session.openWindow" as const,
+							key: "n",
+							mod: true,
+							shift: true,
+							handler: () => {
+								const sessionId = useChatStore.getState().activeSessionId;
+								if (sessionId && !isSessionWindow()) {
+									void openSessionWindow(sessionId);
+								}
+							},
+						},
+					]
+				: []),
 		]);
-	}, [uiActions, handleOpenCreateDialog]);
+	}, [uiActions, handleOpenCreateDialog, navigate]);
+
+	// The desktop menu runs the same commands as the hotkeys above.
+	useEffect(() => {
+		let stop: (() => void) | undefined;
+		let cancelled = false;
+		void listenAppMenu((command) => runHotkeyCommand(command)).then(
+			(unlisten) => {
+				if (cancelled) unlisten();
+				else stop = unlisten;
+			},
+		);
+		return () => {
+			cancelled = true;
+			stop?.();
+		};
+	}, []);
+
+	useEffect(() => {
+		const disabled: HotkeyCommand[] = [];
+		if (!activeSessionId) {
+			disabled.push("chat.search");
+		}
+		if (!activeSessionId || isSessionWindow()) {
+			disabled.push("session.openWindow");
+		}
+		const sync = () => void updateAppMenu(i18n.language, disabled);
+		sync();
+		window.addEventListener("focus", sync);
+		return () => window.removeEventListener("focus", sync);
+	}, [activeSessionId, i18n.language]);
 
 	const handleScrollToMessage = useCallback((index: number) => {
 		chatMessageListRef.current?.scrollToIndex(index);
@@ -675,6 +782,7 @@ export function useMainAppController() {
 		subdirectoryLabel,
 		syncHistoryAvailable,
 		syncHistoryDisabled,
+		terminalOpen,
 		uiActions,
 		warningMessage,
 		workspaceLabel,
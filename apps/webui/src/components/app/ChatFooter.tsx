@@ -1,6 +1,7 @@
 import {
 	ArrowUp01Icon,
 	Cancel01Icon,
+	File01Icon,
 	Image01Icon,
 	StopIcon,
 } from "@hugeicons/core-free-icons";
@@ -28,7 +29,7 @@ import {
 	SelectTrigger,
 	SelectValue,
 } from "@mobvibe/ui/select";
-import { useQuery } from "@tanstack/react-query";
+import { keepPreviousData, useQuery } from "@tanstack/react-query";
 import type { ClipboardEvent, FormEvent, KeyboardEvent } from "react";
 import {
 	useCallback,
@@ -52,12 +53,23 @@ import {
 	type SessionFsResourceEntry,
 } from "@/lib/api";
 import type { ChatSession } from "@/lib/chat-store";
+import { readClipboard } from "@/lib/clipboard";
 import { filterCommandItems } from "@/lib/command-utils";
 import { createDefaultContentBlocks } from "@/lib/content-block-utils";
+import { getLocalMachineId } from "@/lib/daemon";
+import {
+	type DroppedImage,
+	type DroppedResource,
+	droppedImageToFile,
+	type FileDropEvent,
+	listenFileDrop,
+} from "@/lib/file-drop";
 import type { FuzzySearchResult } from "@/lib/fuzzy-search";
+import { searchLocalPaths } from "@/lib/local-search";
 import { useMachinesStore } from "@/lib/machines-store";
 import { normalizeImageFileForPrompt } from "@/lib/prompt-images";
 import { filterResourceItems } from "@/lib/resource-utils";
+import { STANDALONE_MACHINE_ID } from "@/lib/standalone";
 import { createEmptyChatDraft, useUiStore } from "@/lib/ui-store";
 import { cn } from "@/lib/utils";
 
@@ -99,15 +111,25 @@ const getEditorContentBlocks = (blocks: ContentBlock[]) =>
 const getImageContentBlocks = (blocks: ContentBlock[]) =>
 	blocks.filter(isImageContentBlock);
 
+const isEmbeddedResourceBlock = (
+	block: ContentBlock,
+): block is Extract<ContentBlock, { type: "resource" }> =>
+	block.type === "resource";
+
+const getEmbeddedResourceBlocks = (blocks: ContentBlock[]) =>
+	blocks.filter(isEmbeddedResourceBlock);
+
 const mergeComposerContents = (
 	editorBlocks: ContentBlock[],
 	imageBlocks: Extract<ContentBlock, { type: "image" }>[],
-): ContentBlock[] => [...editorBlocks, ...imageBlocks];
+	resourceBlocks: Extract<ContentBlock, { type: "resource" }>[] = [],
+): ContentBlock[] => [...editorBlocks, ...imageBlocks, ...resourceBlocks];
 
 const hasSendablePromptContent = (blocks: ContentBlock[]) =>
 	blocks.some(
 		(block) =>
 			block.type === "image" ||
+			block.type === "resource" ||
 			block.type === "resource_link" ||
 			(block.type === "text" && block.text.trim().length > 0),
 	);
@@ -497,6 +519,10 @@ export function ChatFooter({
 		() => getImageContentBlocks(contentBlocks),
 		[contentBlocks],
 	);
+	const resourceAttachments = useMemo(
+		() => getEmbeddedResourceBlocks(contentBlocks),
+		[contentBlocks],
+	);
 	const draftInput = storedDraft?.input;
 	const rawInput = useMemo(
 		() => draftInput ?? buildInputValueFromContents(editorContentBlocks),
@@ -511,6 +537,15 @@ export function ChatFooter({
 	const canAttachImages = Boolean(
 		activeSessionId && canMutateSession && imageCapability === true,
 	);
+	const embeddedContextCapability =
+		activeSession?.machineId && activeSession?.backendId
+			? machines[activeSession.machineId]?.backendCapabilities?.[
+					activeSession.backendId
+				]?.prompt?.embeddedContext
+			: undefined;
+	const canAttachFiles = Boolean(
+		activeSessionId && canMutateSession && embeddedContextCapability === true,
+	);
 	const hasSlashPrefix = rawInput.startsWith("/");
 	const slashInput = hasSlashPrefix ? rawInput.slice(1) : "";
 	const commandQuery = hasSlashPrefix
@@ -528,6 +563,19 @@ export function ChatFooter({
 		!commandPickerSuppressed &&
 		availableCommands.length > 0 &&
 		hasSlashPrefix;
+	const localMachineIdQuery = useQuery({
+		queryKey: ["local-machine-id"],
+		queryFn: getLocalMachineId,
+		staleTime: Number.POSITIVE_INFINITY,
+	});
+	// Sessions on this machine are searched by the desktop app directly.
+	const localResourceRoot =
+		activeSession?.cwd &&
+		activeSession.machineId &&
+		(activeSession.machineId === STANDALONE_MACHINE_ID ||
+			activeSession.machineId === localMachineIdQuery.data)
+			? activeSession.cwd
+			: null;
 	const resourcesQuery = useQuery({
 		queryKey: ["session-resources", activeSessionId],
 		queryFn: () => {
@@ -536,7 +584,7 @@ export function ChatFooter({
 			}
 			return fetchSessionFsResources({ sessionId: activeSessionId });
 		},
-		enabled: Boolean(activeSessionId && isReady),
+		enabled: Boolean(activeSessionId && isReady && !localResourceRoot),
 	});
 	const resourceEntries = resourcesQuery.data?.entries ?? [];
 	const resourceTokens = useMemo(
@@ -558,13 +606,34 @@ export function ChatFooter({
 		start: number;
 		query: string;
 	} | null>(null);
-	const resourceMatches = useMemo(
-		() =>
-			resourceTrigger
-				? filterResourceItems(resourceEntries, resourceTrigger.query)
-				: [],
-		[resourceEntries, resourceTrigger],
-	);
+	const localResourceQuery = useQuery({
+		queryKey: [
+			"local-resource-matches",
+			localResourceRoot,
+			resourceTrigger?.query,
+		],
+		queryFn: () =>
+			localResourceRoot && resourceTrigger
+				? searchLocalPaths(localResourceRoot, resourceTrigger.query)
+				: null,
+		enabled: Boolean(localResourceRoot && resourceTrigger),
+		placeholderData: keepPreviousData,
+	});
+	const localResourceMatches = localResourceQuery.data;
+	const resourceMatches = useMemo(() => {
+		if (!resourceTrigger) {
+			return [];
+		}
+		if (localResourceRoot) {
+			return localResourceMatches ?? [];
+		}
+		return filterResourceItems(resourceEntries, resourceTrigger.query);
+	}, [
+		localResourceMatches,
+		localResourceRoot,
+		resourceEntries,
+		resourceTrigger,
+	]);
 
 	const resourcePickerDisabled = !activeSessionId || !isReady;
 	const shouldShowResourcePicker =
@@ -578,6 +647,7 @@ export function ChatFooter({
 		resourceHighlight >= resourceMatches.length ? 0 : resourceHighlight;
 	const [attachmentError, setAttachmentError] = useState<string | null>(null);
 	const [isAttachingImages, setIsAttachingImages] = useState(false);
+	const [isDraggingFiles, setIsDraggingFiles] = useState(false);
 	const fileInputRef = useRef<HTMLInputElement | null>(null);
 	const previousSessionId = useRef(activeSessionId);
 
@@ -592,10 +662,14 @@ export function ChatFooter({
 			}
 			setChatDraft(activeSessionId, {
 				input: nextInputValue ?? buildInputValueFromContents(nextEditorBlocks),
-				inputContents: mergeComposerContents(nextEditorBlocks, nextImageBlocks),
+				inputContents: mergeComposerContents(
+					nextEditorBlocks,
+					nextImageBlocks,
+					resourceAttachments,
+				),
 			});
 		},
-		[activeSessionId, setChatDraft],
+		[activeSessionId, resourceAttachments, setChatDraft],
 	);
 
 	const renderEditorContents = useCallback(
@@ -790,6 +864,120 @@ export function ChatFooter({
 		[appendImageAttachments, canAttachImages],
 	);
 
+	const updateResourceAttachments = useCallback(
+		(nextResources: DroppedResource[]) => {
+			if (!activeSessionId) {
+				return;
+			}
+			setChatDraft(activeSessionId, {
+				input: rawInput,
+				inputContents: mergeComposerContents(
+					editorContentBlocks,
+					imageAttachments,
+					nextResources,
+				),
+			});
+		},
+		[
+			activeSessionId,
+			editorContentBlocks,
+			imageAttachments,
+			rawInput,
+			setChatDraft,
+		],
+	);
+
+	const handleRemoveResourceAttachment = useCallback(
+		(index: number) => {
+			updateResourceAttachments(
+				resourceAttachments.filter(
+					(_, attachmentIndex) => attachmentIndex !== index,
+				),
+			);
+			setAttachmentError(null);
+		},
+		[resourceAttachments, updateResourceAttachments],
+	);
+
+	const handleFileDrop = useCallback(
+		async (event: Extract<FileDropEvent, { type: "drop" }>) => {
+			const images = event.blocks.filter(
+				(block): block is DroppedImage => block.type === "image",
+			);
+			const resources = event.blocks.filter(
+				(block): block is DroppedResource => block.type === "resource",
+			);
+			const errors = event.errors.map(
+				(error) => `${error.name}: ${error.message}`,
+			);
+			if (images.length > 0 && !canAttachImages) {
+				errors.push(t("chat.dropImagesUnsupported"));
+			}
+			if (resources.length > 0 && !canAttachFiles) {
+				errors.push(t("chat.dropFilesUnsupported"));
+			}
+			if (resources.length > 0 && canAttachFiles) {
+				updateResourceAttachments([...resourceAttachments, ...resources]);
+			}
+			if (images.length > 0 && canAttachImages) {
+				setIsAttachingImages(true);
+				try {
+					const normalizedImages = await Promise.all(
+						images.map(async (image) => ({
+							...(await normalizeImageFileForPrompt(
+								droppedImageToFile(image),
+							)),
+							uri: image.uri ?? null,
+						})),
+					);
+					await appendImageAttachments(normalizedImages);
+				} catch (error) {
+					errors.push(
+						error instanceof Error ? error.message : "Failed to attach image",
+					);
+				} finally {
+					setIsAttachingImages(false);
+				}
+			}
+			setAttachmentError(errors.length > 0 ? errors.join("\n") : null);
+		},
+		[
+			appendImageAttachments,
+			canAttachFiles,
+			canAttachImages,
+			resourceAttachments,
+			t,
+			updateResourceAttachments,
+		],
+	);
+
+	// The listener is registered once; drops reach the latest handler.
+	const handleFileDropRef = useRef(handleFileDrop);
+	handleFileDropRef.current = handleFileDrop;
+
+	useEffect(() => {
+		let stop: (() => void) | null = null;
+		let disposed = false;
+		void listenFileDrop((event) => {
+			if (event.type === "drop") {
+				setIsDraggingFiles(false);
+				void handleFileDropRef.current(event);
+				return;
+			}
+			setIsDraggingFiles(event.type === "hover");
+		}).then((unlisten) => {
+			if (disposed) {
+				unlisten?.();
+				return;
+			}
+			stop = unlisten;
+		});
+		return () => {
+			disposed = true;
+			stop?.();
+		};
+	}, []);
+
 	const handleRemoveImageAttachment = useCallback(
 		(index: number) => {
 			updateImageAttachments(
@@ -1107,6 +1295,20 @@ export function ChatFooter({
 			}
 			event.preventDefault();
 			const text = event.clipboardData.getData("text/plain");
+			// WebViews often leave copied images out of the paste event, or
+			// carry them only inside copied HTML; ask the app for them.
+			if (
+				canAttachImages &&
+				(text === "" || event.clipboardData.getData("text/html") !== "")
+			) {
+				void readClipboard().then((contents) => {
+					if (contents && contents.images.length > 0) {
+						void handleLocalImageFiles(
+							contents.images.map(droppedImageToFile),
+						);
+					}
+				});
+			}
 			const selection = window.getSelection();
 			if (!selection || selection.rangeCount === 0) {
 				return;
@@ -1212,6 +1414,9 @@ export function ChatFooter({
 					className={cn(
 						"relative flex flex-col border border-input",
 						"focus-within:ring-1 focus-within:ring-ring/50",
+						isDraggingFiles && activeSessionId
+							? "border-dashed border-primary bg-primary/5"
+							: null,
 						!activeSessionId ? "opacity-50" : null,
 					)}
 				>
@@ -1284,8 +1489,40 @@ export function ChatFooter({
 						</AttachmentGroup>
 					) : null}
 
+					{resourceAttachments.length > 0 ? (
+						<AttachmentGroup className="border-b border-input px-2.5 py-2">
+							{resourceAttachments.map((block, index) => (
+								<Attachment key={`${block.resource.uri}-${index}`} size="sm">
+									<AttachmentMedia>
+										<HugeiconsIcon icon={File01Icon} aria-hidden="true" />
+									</AttachmentMedia>
+									<AttachmentContent>
+										<AttachmentTitle>
+											{resolveFilePathFromUri(block.resource.uri)
+												?.split("/")
+												.pop() ?? block.resource.uri}
+										</AttachmentTitle>
+										<AttachmentDescription>
+											{block.resource.mimeType ?? "text/plain"}
+										</AttachmentDescription>
+									</AttachmentContent>
+									<AttachmentActions>
+										<AttachmentAction
+											type="button"
+											aria-label="Remove"
+											title="Remove"
+											onClick={() => handleRemoveResourceAttachment(index)}
+										>
+											<HugeiconsIcon icon={Cancel01Icon} aria-hidden="true" />
+										</AttachmentAction>
+									</AttachmentActions>
+								</Attachment>
+							))}
+						</AttachmentGroup>
+					) : null}
+
 					{attachmentError ? (
-						<div className="border-b border-input px-2.5 py-2 text-[11px] text-destructive">
+						<div className="whitespace-pre-line border-b border-input px-2.5 py-2 text-[11px] text-destructive">
 							{attachmentError}
 						</div>
 					) : null}
@@ -11,6 +11,7 @@ import {
 	useImperativeHandle,
 	useMemo,
 	useRef,
+	useState,
 } from "react";
 import { useTranslation } from "react-i18next";
 import { E2EEMissingBanner } from "@/components/app/E2EEMissingBanner";
@@ -23,9 +24,12 @@ import { buildMessageIndexMap, groupMessages } from "@/lib/group-tool-calls";
 import { useUiStore } from "@/lib/ui-store";
 
 const SCROLL_THRESHOLD = 64;
+const HIGHLIGHT_MS = 2000;
 
 export type ChatMessageListHandle = {
 	scrollToIndex: (index: number) => void;
+	/** Scrolls to a message and marks it briefly, for links to an event. */
+	highlightMessage: (index: number) => void;
 };
 
 export type ChatMessageListProps = {
@@ -57,6 +61,9 @@ export const ChatMessageList = forwardRef<
 	const isPinnedRef = useRef(true);
 	const followFrameRef = useRef<number | null>(null);
 	const lastMeasuredTotalSizeRef = useRef(0);
+	const [highlightedIndex, setHighlightedIndex] = useState<number | null>(
+		null,
+	);
 	const messages = activeSession?.messages ?? [];
 	const showIndicator = !!activeSession?.sending;
 	const isThinking = showIndicator && !activeSession?.streamingMessageId;
@@ -101,15 +108,33 @@ export const ChatMessageList = forwardRef<
 		});
 	}, [totalItems, virtualizer]);
 
+	const scrollToMessage = (msgIndex: number) => {
+		isPinnedRef.current = false;
+		if (followFrameRef.current !== null) {
+			cancelAnimationFrame(followFrameRef.current);
+			followFrameRef.current = null;
+		}
+		const displayIndex =
+			msgIndexMap.get(msgIndex) ?? Math.max(0, displayItems.length - 1);
+		virtualizer.scrollToIndex(displayIndex, { align: "center" });
+		return displayIndex;
+	};
+
 	useImperativeHandle(ref, () => ({
 		scrollToIndex: (msgIndex: number) => {
-			isPinnedRef.current = false;
-			const displayIndex =
-				msgIndexMap.get(msgIndex) ?? Math.max(0, displayItems.length - 1);
-			virtualizer.scrollToIndex(displayIndex, { align: "center" });
+			scrollToMessage(msgIndex);
+		},
+		highlightMessage: (msgIndex: number) => {
+			setHighlightedIndex(scrollToMessage(msgIndex));
 		},
 	}));
 
+	useEffect(() => {
+		if (highlightedIndex === null) return;
+		const timeout = setTimeout(() => setHighlightedIndex(null), HIGHLIGHT_MS);
+		return () => clearTimeout(timeout);
+	}, [highlightedIndex]);
+
 	const handleOpenFilePreview = useCallback(
 		(path: string) => {
 			if (!activeSession?.cwd) {
@@ -263,8 +288,11 @@ export const ChatMessageList = forwardRef<
 									<div
 										key={item.key}
 										data-index={item.index}
+										data-highlighted={
+											item.index === highlightedIndex || undefined
+										}
 										ref={virtualizer.measureElement}
-										className="absolute left-0 top-0 w-full pb-3"
+										className="absolute left-0 top-0 w-full pb-3 transition-colors data-[highlighted]:bg-primary/10"
 										style={{
 											transform: `translateY(${item.start}px)`,
 										}}
@@ -3,10 +3,12 @@ import {
 	ArchiveIcon,
 	Cancel01Icon,
 	CancelCircleIcon,
+	ComputerTerminal01Icon,
 	Delete01Icon,
 	File01Icon,
 	FolderOpenIcon,
 	GitCompareIcon,
+	LayoutIcon,
 	Logout01Icon,
 	Moon02Icon,
 	PaintBoardIcon,
@@ -23,7 +25,7 @@ import {
 	DialogTitle,
 } from "@mobvibe/ui/dialog";
 import { useTheme } from "@mobvibe/ui/theme-provider";
-import { useQuery } from "@tanstack/react-query";
+import { keepPreviousData, useQuery } from "@tanstack/react-query";
 import { useVirtualizer } from "@tanstack/react-virtual";
 import { useCallback, useEffect, useMemo, useRef, useState } from "react";
 import { useTranslation } from "react-i18next";
@@ -31,10 +33,19 @@ import { useNavigate } from "react-router-dom";
 import { useShallow } from "zustand/react/shallow";
 import { GitStatusIndicator } from "@/components/app/git-status-indicator";
 import { useAuth } from "@/components/auth/AuthProvider";
+import { useDebouncedValue } from "@/hooks/useDebouncedValue";
 import { fetchSessionFsResources, fetchSessionGitStatus } from "@/lib/api";
+import { isInTauri } from "@/lib/auth";
 import { useChatStore } from "@/lib/chat-store";
 import { createFallbackError } from "@/lib/error-utils";
 import { FuzzyHighlight, fuzzySearch } from "@/lib/fuzzy-search";
+import {
+	openSearchHit,
+	type SessionSearchHit,
+	searchSessions,
+} from "@/lib/session-search";
+import { listSessionWindows, openSessionWindow } from "@/lib/session-windows";
+import { listTerminals } from "@/lib/terminal";
 import { useUiStore } from "@/lib/ui-store";
 import { cn } from "@/lib/utils";
 
@@ -95,17 +106,38 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 		setChatSearchOpen,
 		setCreateDialogOpen,
 		setMobileMenuOpen,
+		setTerminalOpen,
 	} = useUiStore(
 		useShallow((s) => ({
 			setFileExplorerOpen: s.setFileExplorerOpen,
 			setChatSearchOpen: s.setChatSearchOpen,
+			setTerminalOpen: s.setTerminalOpen,
 			setCreateDialogOpen: s.setCreateDialogOpen,
 			setMobileMenuOpen: s.setMobileMenuOpen,
 		})),
 	);
 
+	// Null outside the desktop app, where sessions cannot get own windows.
+	const { data: sessionWindows } = useQuery({
+		queryKey: ["session-windows"],
+		queryFn: listSessionWindows,
+		enabled: open,
+	});
+	const canOpenSessionWindow = Boolean(sessionWindows && activeSessionId);
+
+	// Null where shells cannot run: outside the app and on mobile.
+	const { data: terminals } = useQuery({
+		queryKey: ["terminals"],
+		queryFn: listTerminals,
+		enabled: open,
+	});
+	const canOpenTerminal = Boolean(terminals && activeSession?.cwd);
+
 	const isFileMode = query.startsWith("@");
-	const searchQuery = isFileMode ? query.slice(1) : query;
+	// Transcript search needs the app's offline session cache.
+	const canSearchSessions = isInTauri();
+	const isSessionMode = canSearchSessions && query.startsWith("#");
+	const searchQuery = isFileMode || isSessionMode ? query.slice(1) : query;
 
 	// Built-in commands
 	const builtinCommands = useMemo((): CommandItem[] => {
@@ -165,6 +197,19 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 					}
 				},
 			},
+			{
+				id: "open-session-window",
+				name: t("commandPalette.openInNewWindow"),
+				icon: LayoutIcon,
+				group: "session",
+				enabled: canOpenSessionWindow,
+				action: () => {
+					onOpenChange(false);
+					if (activeSessionId) {
+						void openSessionWindow(activeSessionId);
+					}
+				},
+			},
 			// App group
 			{
 				id: "file-explorer",
@@ -199,6 +244,14 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 				enabled: hasRemoteSessionContext,
 				action: () => setQuery("@"),
 			},
+			{
+				id: "session-search",
+				name: t("commandPalette.searchSessions"),
+				icon: Search01Icon,
+				group: "app",
+				enabled: canSearchSessions,
+				action: () => setQuery("#"),
+			},
 			{
 				id: "open-changes",
 				name: t("commandPalette.openChanges"),
@@ -210,6 +263,17 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 					setFileExplorerOpen(true);
 				},
 			},
+			{
+				id: "open-terminal",
+				name: t("commandPalette.openTerminal"),
+				icon: ComputerTerminal01Icon,
+				group: "app",
+				enabled: canOpenTerminal,
+				action: () => {
+					onOpenChange(false);
+					setTerminalOpen(true);
+				},
+			},
 			{
 				id: "toggle-sidebar",
 				name: t("commandPalette.toggleSidebar"),
@@ -278,7 +342,11 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 		setChatSearchOpen,
 		setCreateDialogOpen,
 		setMobileMenuOpen,
+		setTerminalOpen,
 		activeSessionId,
+		canOpenSessionWindow,
+		canOpenTerminal,
+		canSearchSessions,
 		hasRemoteSessionContext,
 		hasMessages,
 		isGenerating,
@@ -291,7 +359,7 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 
 	// Filter commands by query
 	const filteredCommands = useMemo(() => {
-		if (isFileMode) return [];
+		if (isFileMode || isSessionMode) return [];
 		if (!searchQuery) return builtinCommands;
 		const results = fuzzySearch({
 			items: builtinCommands,
@@ -299,7 +367,20 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 			query: searchQuery,
 		});
 		return results;
-	}, [builtinCommands, isFileMode, searchQuery]);
+	}, [builtinCommands, isFileMode, isSessionMode, searchQuery]);
+
+	// Transcript search, debounced: each query indexes and searches in Rust
+	const sessionSearchQuery = useDebouncedValue(
+		isSessionMode ? searchQuery.trim() : "",
+		200,
+	);
+	const { data: sessionHits = [] } = useQuery({
+		queryKey: ["session-search", sessionSearchQuery],
+		queryFn: () => searchSessions(sessionSearchQuery),
+		enabled: open && isSessionMode && sessionSearchQuery.length > 0,
+		placeholderData: keepPreviousData,
+	});
+	const sessionResults: SessionSearchHit[] = isSessionMode ? sessionHits : [];
 
 	// File search data
 	const resourcesQuery = useQuery({
@@ -366,7 +447,11 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 	}, [isFileMode, resourcesQuery.data, gitStatusQuery.data, searchQuery]);
 
 	// Total items for keyboard navigation
-	const totalItems = isFileMode ? fileResults.length : filteredCommands.length;
+	const totalItems = isFileMode
+		? fileResults.length
+		: isSessionMode
+			? sessionResults.length
+			: filteredCommands.length;
 
 	// Virtualizer for results list
 	const virtualizer = useVirtualizer({
@@ -412,6 +497,17 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 					setFileExplorerOpen(true);
 					useUiStore.getState().setFilePreviewPath(result.item.path);
 				}
+			} else if (isSessionMode) {
+				const hit = sessionResults[index];
+				if (hit) {
+					onOpenChange(false);
+					void openSearchHit(hit).catch((error) => {
+						console.warn(
+							"[session-search] session_search_open failed",
+							error,
+						);
+					});
+				}
 			} else {
 				const result = filteredCommands[index];
 				if (result) {
@@ -423,6 +519,8 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 		[
 			isFileMode,
 			fileResults,
+			isSessionMode,
+			sessionResults,
 			filteredCommands,
 			onOpenChange,
 			setFileExplorerOpen,
@@ -447,21 +545,29 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 					break;
 				case "Escape":
 					e.preventDefault();
-					if (isFileMode && query.length > 1) {
-						setQuery("@");
+					if ((isFileMode || isSessionMode) && query.length > 1) {
+						setQuery(query[0]);
 					} else {
 						onOpenChange(false);
 					}
 					break;
 				case "Backspace":
-					if (query === "@") {
+					if (query === "@" || (isSessionMode && query === "#")) {
 						e.preventDefault();
 						setQuery("");
 					}
 					break;
 			}
 		},
-		[totalItems, selectedIndex, executeItem, isFileMode, query, onOpenChange],
+		[
+			totalItems,
+			selectedIndex,
+			executeItem,
+			isFileMode,
+			isSessionMode,
+			query,
+			onOpenChange,
+		],
 	);
 
 	// Scroll selected item into view
@@ -537,9 +643,11 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 							</Button>
 						</DialogClose>
 					</div>
-					{isFileMode ? (
+					{isFileMode || isSessionMode ? (
 						<div className="text-muted-foreground mt-1 text-xs">
-							{t("commandPalette.searchFiles")}
+							{isFileMode
+								? t("commandPalette.searchFiles")
+								: t("commandPalette.searchSessions")}
 						</div>
 					) : null}
 				</div>
@@ -555,6 +663,45 @@ function CommandPaletteContent({ open, onOpenChange }: CommandPaletteProps) {
 						<div className="text-muted-foreground flex items-center justify-center px-4 py-8 text-sm">
 							{t("commandPalette.noResults")}
 						</div>
+					) : isSessionMode ? (
+						sessionResults.map((hit, index) => {
+							const isSelected = index === selectedIndex;
+							return (
+								<button
+									key={`${hit.sessionId}:${hit.revision}:${hit.seq}`}
+									id={`command-palette-item-${index}`}
+									type="button"
+									role="option"
+									aria-selected={isSelected}
+									className={cn(
+										"flex min-h-12 w-full items-center gap-3 px-4 py-2 text-left text-sm",
+										isSelected
+											? "bg-accent text-accent-foreground"
+											: "hover:bg-muted",
+									)}
+									onClick={() => executeItem(index)}
+									onMouseEnter={() => setSelectedIndex(index)}
+								>
+									<HugeiconsIcon
+										icon={Search01Icon}
+										strokeWidth={2}
+										className="text-muted-foreground h-4 w-4 shrink-0"
+										aria-hidden="true"
+									/>
+									<div className="min-w-0 flex-1">
+										<div className="text-muted-foreground truncate text-xs">
+											{hit.sessionTitle ??
+												t("commandPalette.untitledSession")}
+										</div>
+										<FuzzyHighlight
+											text={hit.snippet}
+											ranges={hit.highlightRanges}
+											className="block truncate text-sm"
+										/>
+									</div>
+								</button>
+							);
+						})
 					) : isFileMode ? (
 						<div
 							style={{
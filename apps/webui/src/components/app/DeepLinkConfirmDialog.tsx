@@ -0,0 +1,66 @@
+import {
+	AlertDialog,
+	AlertDialogAction,
+	AlertDialogCancel,
+	AlertDialogContent,
+	AlertDialogDescription,
+	AlertDialogFooter,
+	AlertDialogHeader,
+	AlertDialogTitle,
+} from "@mobvibe/ui/alert-dialog";
+import { useTranslation } from "react-i18next";
+import type { DeepLinkRoute } from "@/lib/deep-link";
+
+/** Routes that change who the app trusts and wait for the user. */
+export type ConfirmableDeepLink = Extract<
+	DeepLinkRoute,
+	{ type: "pair" | "gateway" }
+>;
+
+export type DeepLinkConfirmDialogProps = {
+	route: ConfirmableDeepLink | null;
+	onAnswer: (route: ConfirmableDeepLink, accepted: boolean) => void;
+};
+
+export function DeepLinkConfirmDialog({
+	route,
+	onAnswer,
+}: DeepLinkConfirmDialogProps) {
+	const { t } = useTranslation();
+	const isPair = route?.type === "pair";
+
+	return (
+		<AlertDialog
+			open={route !== null}
+			onOpenChange={(open) => {
+				if (!open && route) onAnswer(route, false);
+			}}
+		>
+			<AlertDialogContent size="sm">
+				<AlertDialogHeader>
+					<AlertDialogTitle>
+						{isPair ? t("deepLink.pairTitle") : t("deepLink.gatewayTitle")}
+					</AlertDialogTitle>
+					<AlertDialogDescription>
+						{isPair
+							? t("deepLink.pairDescription")
+							: t("deepLink.gatewayDescription")}
+					</AlertDialogDescription>
+				</AlertDialogHeader>
+				<code className="break-all bg-muted px-2 py-1 text-sm">
+					{route?.type === "pair" ? route.fingerprint : route?.host}
+				</code>
+				<AlertDialogFooter>
+					<AlertDialogCancel>{t("common.cancel")}</AlertDialogCancel>
+					<AlertDialogAction
+						onClick={() => {
+							if (route) onAnswer(route, true);
+						}}
+					>
+						{isPair ? t("deepLink.pairConfirm") : t("deepLink.gatewayConfirm")}
+					</AlertDialogAction>
+				</AlertDialogFooter>
+			</AlertDialogContent>
+		</AlertDialog>
+	);
+}
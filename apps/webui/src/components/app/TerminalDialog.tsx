@@ -0,0 +1,204 @@
+import {
+	Dialog,
+	DialogContent,
+	DialogDescription,
+	DialogTitle,
+} from "@mobvibe/ui/dialog";
+import { useEffect, useRef, useState } from "react";
+import { useTranslation } from "react-i18next";
+import {
+	closeTerminal,
+	openTerminal,
+	resizeTerminal,
+	writeTerminal,
+} from "@/lib/terminal";
+
+export type TerminalDialogProps = {
+	open: boolean;
+	onOpenChange: (open: boolean) => void;
+	/** Where the shell starts, the active session's working directory. */
+	cwd?: string;
+};
+
+/** Output kept on screen; older lines scroll away for good. */
+const MAX_OUTPUT = 200_000;
+
+// CSI and OSC sequences, and the remaining lone escapes; colors and cursor
+// movement are dropped rather than emulated.
+// biome-ignore lint/suspicious/noControlCharactersInRegex: escape sequences
+const ESCAPES = /\x1b(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1b]*(?:\x07|\x1b\\)|.)/g;
+
+const KEYS: Record<string, string> = {
+	Enter: "\r",
+	Backspace: "\x7f",
+	Tab: "\t",
+	Escape: "\x1b",
+	ArrowUp: "\x1b[A",
+	ArrowDown: "\x1b[B",
+	ArrowRight: "\x1b[C",
+	ArrowLeft: "\x1b[D",
+	Home: "\x1b[H",
+	End: "\x1b[F",
+	Delete: "\x1b[3~",
+};
+
+/** What a key press sends to the shell, or null for keys it never sees. */
+export function encodeKey(
+	event: Pick<
+		KeyboardEvent,
+		"key" | "ctrlKey" | "metaKey" | "altKey" | "isComposing"
+	>,
+): string | null {
+	if (event.isComposing || event.metaKey) return null;
+	if (event.ctrlKey && /^[a-z]$/i.test(event.key)) {
+		return String.fromCharCode(event.key.toUpperCase().charCodeAt(0) - 64);
+	}
+	const data =
+		KEYS[event.key] ?? (event.key.length === 1 ? event.key : undefined);
+	if (data === undefined) return null;
+	return event.altKey ? `\x1b${data}` : data;
+}
+
+/** Applies what the shell printed to the text on screen. */
+export function appendOutput(screen: string, data: string): string {
+	let next = screen;
+	for (const char of data.replace(ESCAPES, "")) {
+		if (char === "\b") {
+			next = next.slice(0, -1);
+		} else if (char !== "\r" && char !== "\x07") {
+			next += char;
+		}
+	}
+	return next.length > MAX_OUTPUT ? next.slice(-MAX_OUTPUT) : next;
+}
+
+/**
+ * A shell in the session's working directory, on this machine. It opens
+ * with the dialog and is closed with it.
+ */
+export function TerminalDialog({
+	open,
+	onOpenChange,
+	cwd,
+}: TerminalDialogProps) {
+	const { t } = useTranslation();
+	const [output, setOutput] = useState("");
+	const [status, setStatus] = useState<string | null>(null);
+	const terminalIdRef = useRef<number | null>(null);
+	const screenRef = useRef<HTMLPreElement>(null);
+	const cellRef = useRef<HTMLSpanElement>(null);
+
+	// Sizes the pty to the cells that fit on screen
+	const fitRef = useRef(() => {
+		const id = terminalIdRef.current;
+		const screen = screenRef.current;
+		const cell = cellRef.current?.getBoundingClientRect();
+		if (id === null || !screen || !cell?.width || !cell.height) return;
+		const cols = Math.max(1, Math.floor(screen.clientWidth / cell.width));
+		const rows = Math.max(1, Math.floor(screen.clientHeight / cell.height));
+		void resizeTerminal(id, cols, rows).catch(() => {});
+	});
+
+	useEffect(() => {
+		if (!open || !cwd) return;
+		let disposed = false;
+		setOutput("");
+		setStatus(null);
+		openTerminal(cwd, (event) => {
+			if (disposed) return;
+			if (event.kind === "output") {
+				setOutput((screen) => appendOutput(screen, event.data));
+			} else {
+				terminalIdRef.current = null;
+				setStatus(
+					event.code === null
+						? t("terminal.exitedBySignal")
+						: t("terminal.exited", { code: event.code }),
+				);
+			}
+		})
+			.then((info) => {
+				if (disposed) {
+					void closeTerminal(info.id);
+				} else {
+					terminalIdRef.current = info.id;
+					fitRef.current();
+					screenRef.current?.focus();
+				}
+			})
+			.catch((error: unknown) => {
+				if (!disposed) {
+					setStatus(t("terminal.openFailed", { error: String(error) }));
+				}
+			});
+		return () => {
+			disposed = true;
+			const id = terminalIdRef.current;
+			terminalIdRef.current = null;
+			if (id !== null) {
+				void closeTerminal(id).catch(() => {});
+			}
+		};
+	}, [open, cwd, t]);
+
+	// Keep the last line in view as output arrives
+	// biome-ignore lint/correctness/useExhaustiveDependencies: output triggers the scroll
+	useEffect(() => {
+		const screen = screenRef.current;
+		if (screen) screen.scrollTop = screen.scrollHeight;
+	}, [output]);
+
+	useEffect(() => {
+		const screen = screenRef.current;
+		if (!open || !screen || typeof ResizeObserver === "undefined") return;
+		const observer = new ResizeObserver(() => fitRef.current());
+		observer.observe(screen);
+		return () => observer.disconnect();
+	}, [open]);
+
+	const send = (data: string) => {
+		const id = terminalIdRef.current;
+		if (id !== null) {
+			void writeTerminal(id, data).catch(() => {});
+		}
+	};
+
+	return (
+		<Dialog open={open} onOpenChange={onOpenChange}>
+			<DialogContent className="flex h-[100svh] w-[100vw] max-w-none flex-col gap-3 sm:h-[70vh] sm:w-[92vw] sm:max-w-[92vw] lg:max-w-5xl">
+				<div className="grid gap-1.5 pr-8">
+					<DialogTitle>{t("terminal.title")}</DialogTitle>
+					<DialogDescription className="truncate font-mono text-xs">
+						{cwd}
+					</DialogDescription>
+				</div>
+				<pre
+					ref={screenRef}
+					role="log"
+					aria-label={t("terminal.title")}
+					// biome-ignore lint/a11y/noNoninteractiveTabindex: the screen takes keyboard input
+					tabIndex={0}
+					className="bg-muted min-h-0 flex-1 overflow-auto whitespace-pre-wrap break-all rounded-md p-3 font-mono text-xs leading-5 outline-none focus-visible:ring-2 focus-visible:ring-ring"
+					onKeyDown={(event) => {
+						const data = encodeKey(event.nativeEvent);
+						if (data === null) return;
+						event.preventDefault();
+						send(data);
+					}}
+					onPaste={(event) => {
+						event.preventDefault();
+						send(event.clipboardData.getData("text"));
+					}}
+				>
+					{output}
+					<span ref={cellRef} aria-hidden className="invisible absolute">
+						M
+					</span>
+				</pre>
+				{status ? (
+					<p className="text-muted-foreground text-xs">{status}</p>
+				) : null}
+			</DialogContent>
+		</Dialog>
+	);
+}
@@ -82,6 +82,7 @@ vi.mock("@mobvibe/ui/select", () => ({
 
 const fetchSessionFsResources = vi.hoisted(() => vi.fn());
 const normalizeImageFileForPrompt = vi.hoisted(() => vi.fn());
+const listenFileDrop = vi.hoisted(() => vi.fn());
 
 vi.mock("@/lib/api", async (importOriginal) => {
 	const actual = await importOriginal<typeof import("@/lib/api")>();
@@ -95,6 +96,14 @@ vi.mock("@/lib/prompt-images", () => ({
 	normalizeImageFileForPrompt,
 }));
 
+vi.mock("@/lib/file-drop", async (importOriginal) => {
+	const actual = await importOriginal<typeof import("@/lib/file-drop")>();
+	return {
+		...actual,
+		listenFileDrop,
+	};
+});
+
 const buildSession = (overrides: Partial<ChatSession> = {}): ChatSession =>
 	({
 		sessionId: "session-1",
@@ -236,6 +245,7 @@ describe("ChatFooter", () => {
 			entries: [],
 		});
 		normalizeImageFileForPrompt.mockResolvedValue(createImageAttachment());
+		listenFileDrop.mockResolvedValue(null);
 		HTMLElement.prototype.scrollIntoView = vi.fn();
 	});
 
@@ -688,6 +698,54 @@ describe("ChatFooter", () => {
 		});
 	});
 
+	it("attaches dropped text files as embedded resources", async () => {
+		let onDrop: ((event: unknown) => void) | undefined;
+		listenFileDrop.mockImplementation(async (onEvent) => {
+			onDrop = onEvent;
+			return vi.fn();
+		});
+		useMachinesStore.setState({
+			machines: {
+				"machine-1": {
+					machineId: "machine-1",
+					connected: true,
+					backendCapabilities: {
+						"backend-1": {
+							list: true,
+							load: true,
+							prompt: { image: true, audio: false, embeddedContext: true },
+						},
+					},
+				},
+			},
+		});
+		const session = buildSession();
+		const resource = {
+			type: "resource" as const,
+			resource: {
+				uri: "file:///home/me/notes.md",
+				mimeType: "text/markdown",
+				text: "# Notes",
+			},
+		};
+
+		renderFooter(session);
+		await waitFor(() => expect(onDrop).toBeDefined());
+		onDrop?.({
+			type: "drop",
+			blocks: [resource],
+			errors: [{ name: "app.bin", message: "Binary files are not supported" }],
+		});
+
+		expect(await screen.findByText("notes.md")).toBeInTheDocument();
+		expect(
+			screen.getByText("app.bin: Binary files are not supported"),
+		).toBeInTheDocument();
+		expect(
+			useUiStore.getState().chatDrafts[session.sessionId]?.inputContents,
+		).toEqual(expect.arrayContaining([resource]));
+	});
+
 	it("disables image attach UI when image capability is missing", () => {
 		useMachinesStore.setState({
 			machines: {
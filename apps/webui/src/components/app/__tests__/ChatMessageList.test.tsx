@@ -1,7 +1,13 @@
-import { render, screen } from "@testing-library/react";
+import { act, render, screen } from "@testing-library/react";
+import { createRef } from "react";
 import { describe, expect, it, vi } from "vitest";
-import type { ChatSession } from "@/lib/chat-store";
-import { ChatMessageList } from "../ChatMessageList";
+import type { ChatMessage, ChatSession } from "@/lib/chat-store";
+import {
+	ChatMessageList,
+	type ChatMessageListHandle,
+} from "../ChatMessageList";
+
+const scrollToIndex = vi.hoisted(() => vi.fn());
 
 vi.mock("react-i18next", () => ({
 	useTranslation: () => ({
@@ -20,11 +26,16 @@ vi.mock("react-i18next", () => ({
 }));
 
 vi.mock("@tanstack/react-virtual", () => ({
-	useVirtualizer: () => ({
-		getVirtualItems: () => [],
+	useVirtualizer: ({ count }: { count: number }) => ({
+		getVirtualItems: () =>
+			Array.from({ length: count }, (_, index) => ({
+				index,
+				key: index,
+				start: 0,
+			})),
 		getTotalSize: () => 0,
 		measureElement: vi.fn(),
-		scrollToIndex: vi.fn(),
+		scrollToIndex,
 	}),
 }));
 
@@ -101,4 +112,38 @@ describe("ChatMessageList", () => {
 			screen.queryByText("Synchronizing history…"),
 		).not.toBeInTheDocument();
 	});
+
+	it("scrolls to and marks the message a link points at", () => {
+		vi.useFakeTimers();
+		const messages = ["first", "second"].map(
+			(content, index): ChatMessage => ({
+				id: `m${index}`,
+				role: "assistant",
+				kind: "text",
+				content,
+				contentBlocks: [],
+				createdAt: "2026-01-01T00:00:00.000Z",
+				isStreaming: false,
+			}),
+		);
+		const ref = createRef<ChatMessageListHandle>();
+		const { container } = render(
+			<ChatMessageList
+				ref={ref}
+				activeSession={buildSession({ messages })}
+				onPermissionDecision={vi.fn()}
+			/>,
+		);
+
+		act(() => ref.current?.highlightMessage(1));
+
+		expect(scrollToIndex).toHaveBeenLastCalledWith(1, { align: "center" });
+		const highlighted = container.querySelectorAll("[data-highlighted]");
+		expect(highlighted).toHaveLength(1);
+		expect(highlighted[0]).toHaveAttribute("data-index", "1");
+
+		act(() => vi.advanceTimersByTime(2000));
+		expect(container.querySelector("[data-highlighted]")).toBeNull();
+		vi.useRealTimers();
+	});
 });
@@ -1,5 +1,5 @@
 import { QueryClient, QueryClientProvider } from "@tanstack/react-query";
-import { render, screen } from "@testing-library/react";
+import { render, screen, waitFor } from "@testing-library/react";
 import userEvent from "@testing-library/user-event";
 import React from "react";
 import { beforeEach, describe, expect, it, vi } from "vitest";
@@ -17,10 +17,14 @@ vi.mock("react-i18next", () => ({
 				"commandPalette.archiveSession": "Archive Session",
 				"commandPalette.clearChat": "Clear Chat Messages",
 				"commandPalette.cancelGeneration": "Cancel Generation",
+				"commandPalette.openInNewWindow": "Open in New Window",
 				"commandPalette.openFileExplorer": "Open File Explorer",
 				"commandPalette.openChanges": "Open Changes",
 				"commandPalette.searchInChat": "Search in Chat",
 				"commandPalette.searchFiles": "Search Files",
+				"commandPalette.searchSessions": "Search Sessions",
+				"commandPalette.openTerminal": "Open Terminal",
+				"commandPalette.untitledSession": "Untitled session",
 				"commandPalette.toggleSidebar": "Toggle Sidebar",
 				"commandPalette.openSettings": "Open Settings",
 				"commandPalette.signOut": "Sign Out",
@@ -80,6 +84,7 @@ const mockUiStore = vi.hoisted(() => ({
 		setCreateDialogOpen: vi.fn(),
 		setMobileMenuOpen: vi.fn(),
 		setFilePreviewPath: vi.fn(),
+		setTerminalOpen: vi.fn(),
 		getState: vi.fn(() => mockUiStore.value),
 	},
 }));
@@ -137,6 +142,19 @@ vi.mock("@/lib/api", async (importOriginal) => {
 	};
 });
 
+const isInTauri = vi.hoisted(() => vi.fn(() => false));
+vi.mock("@/lib/auth", async (importOriginal) => ({
+	...(await importOriginal<typeof import("@/lib/auth")>()),
+	isInTauri,
+}));
+
+const searchSessions = vi.hoisted(() => vi.fn());
+const openSearchHit = vi.hoisted(() => vi.fn());
+vi.mock("@/lib/session-search", () => ({ searchSessions, openSearchHit }));
+
+const listTerminals = vi.hoisted(() => vi.fn());
+vi.mock("@/lib/terminal", () => ({ listTerminals }));
+
 // Mock react-virtual
 const mockMeasure = vi.fn();
 vi.mock("@tanstack/react-virtual", () => ({
@@ -208,6 +226,10 @@ describe("CommandPalette", () => {
 		});
 		vi.clearAllMocks();
 		mockDialogOnOpenChange.value = undefined;
+		isInTauri.mockReturnValue(false);
+		searchSessions.mockResolvedValue([]);
+		openSearchHit.mockResolvedValue(undefined);
+		listTerminals.mockResolvedValue(null);
 		// Reset store mocks
 		mockChatStore.value.sessions = {};
 		mockChatStore.value.activeSessionId = undefined;
@@ -299,6 +321,44 @@ describe("CommandPalette", () => {
 		});
 	});
 
+	describe("Session Search", () => {
+		const hit = {
+			sessionId: "s1",
+			sessionTitle: "Refactor",
+			revision: 2,
+			seq: 14,
+			kind: "agent_message_chunk" as const,
+			snippet: "moved the parser",
+			highlightRanges: [[10, 16]] as Array<[number, number]>,
+		};
+
+		it("is unavailable outside the app", () => {
+			renderCommandPalette();
+			expect(
+				screen.getByRole("option", { name: "Search Sessions" }),
+			).toBeDisabled();
+		});
+
+		it("opens the session of the chosen hit", async () => {
+			isInTauri.mockReturnValue(true);
+			searchSessions.mockResolvedValue([hit]);
+			renderCommandPalette();
+			const user = userEvent.setup();
+
+			const input = screen.getByPlaceholderText("Type a command or search...");
+			await user.type(input, "#parser");
+
+			await waitFor(() =>
+				expect(screen.getByText("Refactor")).toBeInTheDocument(),
+			);
+			expect(searchSessions).toHaveBeenLastCalledWith("parser");
+			await user.type(input, "{Enter}");
+
+			expect(openSearchHit).toHaveBeenCalledWith(hit);
+			expect(mockOnOpenChange).toHaveBeenCalledWith(false);
+		});
+	});
+
 	describe("Contextual Command Availability", () => {
 		it("has no active session by default", () => {
 			// This test verifies our mock setup
@@ -485,6 +545,36 @@ describe("CommandPalette", () => {
 				screen.getByRole("option", { name: /Open Changes/i }),
 			).toBeDisabled();
 		});
+
+		it("opens a terminal in the session's directory in the app", async () => {
+			listTerminals.mockResolvedValue([]);
+			mockChatStore.value.activeSessionId = "session-1";
+			mockChatStore.value.sessions = {
+				"session-1": {
+					sessionId: "session-1",
+					title: "Local Session",
+					cwd: "/repo",
+					messages: [],
+				},
+			};
+			renderCommandPalette();
+			const user = userEvent.setup();
+
+			const command = screen.getByRole("option", { name: "Open Terminal" });
+			await waitFor(() => expect(command).toBeEnabled());
+			await user.click(command);
+
+			expect(mockUiStore.value.setTerminalOpen).toHaveBeenCalledWith(true);
+			expect(mockOnOpenChange).toHaveBeenCalledWith(false);
+		});
+
+		it("cannot open a terminal where shells cannot run", () => {
+			renderCommandPalette();
+
+			expect(
+				screen.getByRole("option", { name: "Open Terminal" }),
+			).toBeDisabled();
+		});
 	});
 
 	describe("Virtualizer Behavior", () => {
@@ -0,0 +1,136 @@
+import { fireEvent, render, screen, waitFor } from "@testing-library/react";
+import { beforeEach, describe, expect, it, vi } from "vitest";
+import type { TerminalEvent } from "@/lib/terminal";
+import { appendOutput, encodeKey, TerminalDialog } from "../TerminalDialog";
+
+const terminal = vi.hoisted(() => ({
+	openTerminal: vi.fn(),
+	writeTerminal: vi.fn(),
+	resizeTerminal: vi.fn(),
+	closeTerminal: vi.fn(),
+}));
+
+vi.mock("@/lib/terminal", () => terminal);
+
+vi.mock("react-i18next", () => ({
+	useTranslation: () => ({
+		t: (key: string, options?: Record<string, unknown>) =>
+			options ? `${key} ${JSON.stringify(options)}` : key,
+	}),
+}));
+
+vi.mock("@mobvibe/ui/dialog", () => ({
+	Dialog: ({ open, children }: { open: boolean; children: React.ReactNode }) =>
+		open ? <div>{children}</div> : null,
+	DialogContent: ({ children }: { children: React.ReactNode }) => (
+		<div>{children}</div>
+	),
+	DialogTitle: ({ children }: { children: React.ReactNode }) => (
+		<h2>{children}</h2>
+	),
+	DialogDescription: ({ children }: { children: React.ReactNode }) => (
+		<p>{children}</p>
+	),
+}));
+
+const info = { id: 7, shell: "/bin/zsh", cwd: "/repo", pid: 42 };
+
+describe("TerminalDialog", () => {
+	let emit: (event: TerminalEvent) => void = () => {};
+
+	beforeEach(() => {
+		vi.clearAllMocks();
+		terminal.openTerminal.mockImplementation(
+			async (_cwd: string, onEvent: (event: TerminalEvent) => void) => {
+				emit = onEvent;
+				return info;
+			},
+		);
+		terminal.writeTerminal.mockResolvedValue(undefined);
+		terminal.resizeTerminal.mockResolvedValue(undefined);
+		terminal.closeTerminal.mockResolvedValue(undefined);
+	});
+
+	it("runs a shell in the directory while open", async () => {
+		const { rerender } = render(
+			<TerminalDialog open onOpenChange={vi.fn()} cwd="/repo" />,
+		);
+
+		expect(terminal.openTerminal).toHaveBeenCalledWith(
+			"/repo",
+			expect.any(Function),
+		);
+		const screenLog = screen.getByRole("log");
+		await waitFor(() => expect(screenLog).toHaveFocus());
+
+		emit({ kind: "output", data: "\x1b[32m~/repo\x1b[0m $ " });
+		await waitFor(() => expect(screenLog).toHaveTextContent("~/repo $"));
+
+		fireEvent.keyDown(screenLog, { key: "l" });
+		fireEvent.keyDown(screenLog, { key: "Enter" });
+		fireEvent.keyDown(screenLog, { key: "c", ctrlKey: true });
+		expect(terminal.writeTerminal.mock.calls).toEqual([
+			[7, "l"],
+			[7, "\r"],
+			[7, "\x03"],
+		]);
+
+		rerender(
+			<TerminalDialog open={false} onOpenChange={vi.fn()} cwd="/repo" />,
+		);
+		expect(terminal.closeTerminal).toHaveBeenCalledWith(7);
+	});
+
+	it("reports how the shell ended", async () => {
+		render(<TerminalDialog open onOpenChange={vi.fn()} cwd="/repo" />);
+		await waitFor(() => expect(screen.getByRole("log")).toHaveFocus());
+
+		emit({ kind: "exit", code: 2 });
+
+		expect(
+			await screen.findByText('terminal.exited {"code":2}'),
+		).toBeInTheDocument();
+		fireEvent.keyDown(screen.getByRole("log"), { key: "a" });
+		expect(terminal.writeTerminal).not.toHaveBeenCalled();
+	});
+
+	it("shows why a shell could not start", async () => {
+		terminal.openTerminal.mockRejectedValue("Terminal error: no such dir");
+
+		render(<TerminalDialog open onOpenChange={vi.fn()} cwd="/gone" />);
+
+		expect(
+			await screen.findByText(
+				'terminal.openFailed {"error":"Terminal error: no such dir"}',
+			),
+		).toBeInTheDocument();
+	});
+});
+
+describe("terminal encoding", () => {
+	const key = (key: string, modifiers: Partial<KeyboardEvent> = {}) =>
+		encodeKey({
+			key,
+			ctrlKey: false,
+			metaKey: false,
+			altKey: false,
+			isComposing: false,
+			...modifiers,
+		});
+
+	it("encodes keys as a terminal sends them", () => {
+		expect(key("a")).toBe("a");
+		expect(key("ArrowUp")).toBe("\x1b[A");
+		expect(key("Backspace")).toBe("\x7f");
+		expect(key("d", { ctrlKey: true })).toBe("\x04");
+		expect(key("b", { altKey: true })).toBe("\x1bb");
+		expect(key("Shift")).toBeNull();
+		expect(key("c", { metaKey: true })).toBeNull();
+	});
+
+	it("drops escape sequences and applies backspaces", () => {
+		expect(appendOutput("$ ", "\x1b]0;title\x07ls\b\bpwd\r\n")).toBe(
+			"$ pwd\n",
+		);
+	});
+});
@@ -16,12 +16,28 @@ import type { Node, QueryCapture, QueryMatch } from "web-tree-sitter";
 import { Parser, Query, Language as TreeSitterLanguage } from "web-tree-sitter";
 import type { SessionFsFilePreviewResponse } from "@/lib/api";
 import { fetchSessionGitDiff } from "@/lib/api";
+import { isInTauri } from "@/lib/auth";
+import { copyOutput } from "@/lib/clipboard";
 import {
 	getGruvboxTheme,
 	normalizeCode,
 	useResolvedTheme,
 } from "@/lib/code-highlight";
 import { resolveLanguageFromPath } from "@/lib/file-preview-utils";
+import type { OutlineItem, OutlineKind, OutlineLanguage } from "@/lib/outline";
+import { extractOutline } from "@/lib/outline";
+import BASH_OUTLINE_QUERY from "@/lib/outline-queries/bash.scm?raw";
+import C_OUTLINE_QUERY from "@/lib/outline-queries/c.scm?raw";
+import CPP_OUTLINE_QUERY from "@/lib/outline-queries/cpp.scm?raw";
+import CSHARP_OUTLINE_QUERY from "@/lib/outline-queries/csharp.scm?raw";
+import GO_OUTLINE_QUERY from "@/lib/outline-queries/go.scm?raw";
+import JAVA_OUTLINE_QUERY from "@/lib/outline-queries/java.scm?raw";
+import JAVASCRIPT_OUTLINE_QUERY from "@/lib/outline-queries/javascript.scm?raw";
+import PHP_OUTLINE_QUERY from "@/lib/outline-queries/php.scm?raw";
+import PYTHON_OUTLINE_QUERY from "@/lib/outline-queries/python.scm?raw";
+import RUBY_OUTLINE_QUERY from "@/lib/outline-queries/ruby.scm?raw";
+import RUST_OUTLINE_QUERY from "@/lib/outline-queries/rust.scm?raw";
+import TYPESCRIPT_EXTRA_OUTLINE_QUERY from "@/lib/outline-queries/typescript.scm?raw";
 import { cn } from "@/lib/utils";
 
 export type CodePreviewProps = {
@@ -29,47 +45,6 @@ export type CodePreviewProps = {
 	sessionId?: string;
 };
 
-type OutlineLanguage =
-	| "bash"
-	| "c"
-	| "cpp"
-	| "csharp"
-	| "go"
-	| "java"
-	| "javascript"
-	| "php"
-	| "python"
-	| "ruby"
-	| "rust"
-	| "typescript"
-	| "tsx";
-
-type OutlineKind =
-	| "class"
-	| "method"
-	| "function"
-	| "interface"
-	| "type"
-	| "enum"
-	| "module"
-	| "constant"
-	| "property"
-	| "field"
-	| "constructor"
-	| "variable"
-	| "struct";
-
-type OutlineItem = {
-	id: string;
-	label: string;
-	kind: OutlineKind;
-	startIndex: number;
-	endIndex: number;
-	startLine: number;
-	endLine: number;
-	children: OutlineItem[];
-};
-
 type OutlineStatus = "idle" | "loading" | "ready" | "unsupported" | "error";
 
 type SymbolGitStatus = "added" | "deleted" | "modified" | "unchanged";
@@ -90,330 +65,8 @@ const OUTLINE_KIND_LABELS: Record<OutlineKind, string> = {
 	struct: "Struct",
 };
 
-const JAVASCRIPT_OUTLINE_QUERY = `
-(
-  (method_definition
-    name: [(property_identifier) (private_property_identifier)] @name) @definition.method
-  (#not-eq? @name "constructor")
-)
-
-(
-  [
-    (class
-      name: (_) @name)
-    (class_declaration
-      name: (_) @name)
-  ] @definition.class
-)
-
-(
-  [
-    (function_expression
-      name: (identifier) @name)
-    (function_declaration
-      name: (identifier) @name)
-    (generator_function
-      name: (identifier) @name)
-    (generator_function_declaration
-      name: (identifier) @name)
-  ] @definition.function
-)
-
-(
-  (lexical_declaration
-    (variable_declarator
-      name: (identifier) @name
-      value: [(arrow_function) (function_expression)])) @definition.function
-)
-
-(
-  (variable_declaration
-    (variable_declarator
-      name: (identifier) @name
-      value: [(arrow_function) (function_expression)])) @definition.function
-)
-
-(assignment_expression
-  left: [
-    (identifier) @name
-    (member_expression
-      property: (property_identifier) @name)
-  ]
-  right: [(arrow_function) (function_expression)]
-) @definition.function
-
-(pair
-  key: (property_identifier) @name
-  value: [(arrow_function) (function_expression)]) @definition.function
-`;
-
-const TYPESCRIPT_OUTLINE_QUERY = `
-${JAVASCRIPT_OUTLINE_QUERY}
-
-(interface_declaration
-  name: (type_identifier) @name) @definition.interface
-
-(type_alias_declaration
-  name: (type_identifier) @name) @definition.type
-
-(enum_declaration
-  name: (identifier) @name) @definition.enum
-
-(module
-  name: (identifier) @name) @definition.module
-`;
-
-const BASH_OUTLINE_QUERY = `
-(function_definition
-  name: (word) @name) @definition.function
-`;
-
-const C_OUTLINE_QUERY = `
-(type_definition
-  type: (enum_specifier) @definition.enum
-  declarator: (type_identifier) @name)
-
-(type_definition
-  type: (struct_specifier) @definition.struct
-  declarator: (type_identifier) @name)
-
-(struct_specifier
-  name: (type_identifier) @name
-  body: (field_declaration_list)) @definition.struct
-
-(function_definition
-  declarator: (function_declarator
-    declarator: (identifier) @name)) @definition.function
-
-(function_definition
-  declarator: (pointer_declarator
-    declarator: (function_declarator
-      declarator: (identifier) @name))) @definition.function
-`;
-
-const CPP_OUTLINE_QUERY = `
-(struct_specifier
-  name: (type_identifier) @name
-  body: (field_declaration_list)) @definition.struct
-
-(declaration
-  (struct_specifier
-    body: (field_declaration_list))
-  declarator: (identifier) @name) @definition.struct
-
-(function_declarator
-  declarator: (_) @name) @definition.function
-
-(enum_specifier
-  name: (type_identifier) @name) @definition.enum
-
-(class_specifier
-  name: (type_identifier) @name) @definition.class
-`;
-
-const CSHARP_OUTLINE_QUERY = `
-(interface_declaration
-  name: (identifier) @name) @definition.interface
-
-(class_declaration
-  name: (identifier) @name) @definition.class
-
-(struct_declaration
-  name: (identifier) @name) @definition.struct
-
-(method_declaration
-  name: (identifier) @name) @definition.method
-
-(enum_declaration
-  name: (identifier) @name) @definition.enum
-
-(constructor_declaration
-  name: (identifier) @name) @definition.constructor
-
-(property_declaration
-  name: (identifier) @name) @definition.property
-
-(field_declaration
-  (variable_declaration
-    (variable_declarator
-      (identifier) @name))) @definition.field
-`;
-
-const GO_OUTLINE_QUERY = `
-(function_declaration
-  name: (identifier) @name) @definition.function
-
-(type_declaration
-  (type_spec
-    name: (type_identifier) @name
-    type: (struct_type)) @definition.struct)
-
-(type_declaration
-  (type_spec
-    name: (type_identifier) @name
-    type: (interface_type)) @definition.interface)
-
-(method_declaration
-  receiver: (_)
-  name: (field_identifier) @name) @definition.method
-`;
-
-const JAVA_OUTLINE_QUERY = `
-(interface_declaration
-  name: (identifier) @name) @definition.interface
-
-(method_declaration
-  name: (identifier) @name) @definition.method
-
-(constructor_declaration
-  name: (identifier) @name) @definition.constructor
-
-(class_declaration
-  name: (identifier) @name) @definition.class
-
-(enum_declaration
-  name: (identifier) @name) @definition.enum
-
-(field_declaration
-  declarator: (variable_declarator
-    name: (identifier) @name)) @definition.field
-`;
-
-const PHP_OUTLINE_QUERY = `
-(function_definition
-  name: (name) @name) @definition.function
-
-(expression_statement
-  (assignment_expression
-    left: (variable_name) @name
-    right: (anonymous_function))) @definition.function
-
-(class_declaration
-  name: (name) @name) @definition.class
-
-(method_declaration
-  name: (name) @name) @definition.method
-
-(interface_declaration
-  name: (name) @name) @definition.interface
-
-(trait_declaration
-  name: (name) @name) @definition.class
-`;
-
-const PYTHON_OUTLINE_QUERY = `
-(function_definition
-  name: (identifier) @name) @definition.function
-
-(class_definition
-  name: (identifier) @name) @definition.class
-
-(assignment
-  left: (_) @name) @definition.variable
-`;
-
-const RUBY_OUTLINE_QUERY = `
-(class
-  name: [
-    (constant)
-    (scope_resolution)
-  ] @name) @definition.class
-
-(call
-  ((identifier) @scope
-    (#any-of? @scope "private" "protected" "public"))?
-  .
-  (argument_list
-    (method
-      name: (_) @name) )) @definition.method
-
-(body_statement
-  [
-    (_)
-    ((identifier) @scope
-      (#any-of? @scope "private" "protected" "public"))
-  ]*
-  .
-  (method
-    name: (_) @name)) @definition.method
-
-(body_statement
-  (method
-    name: (_) @name)) @definition.method
-
-(singleton_method
-  object: [
-    (constant)
-    (self)
-    (identifier)
-  ]
-  ([
-    "."
-    "::"
-  ])?
-  name: [
-    (operator)
-    (identifier)
-  ] @name) @definition.method
-
-(singleton_class
-  value: (_) @name) @definition.class
-
-(module
-  name: [
-    (constant)
-    (scope_resolution)
-  ] @name) @definition.module
-
-(call
-  method: (identifier) @method @name
-  (#any-of? @method
-    "describe" "it" "before" "after"
-    "namespace" "task" "multitask" "file"
-    "setup" "teardown" "should" "should_not" "should_eventually" "context")
-  arguments: (argument_list
-    [
-      (string
-        (string_content) @name)
-      (simple_symbol) @name
-      (pair
-        key: [
-          (string
-            (string_content) @name)
-          (hash_key_symbol) @name
-        ])
-      (call) @name
-    ])?) @definition.method
-`;
-
-const RUST_OUTLINE_QUERY = `
-(mod_item
-  name: (identifier) @name) @definition.module
-
-(enum_item
-  name: (type_identifier) @name) @definition.enum
-
-(struct_item
-  name: (type_identifier) @name) @definition.struct
-
-(function_item
-  name: (identifier) @name) @definition.function
-
-(function_signature_item
-  name: (identifier) @name) @definition.function
-
-(trait_item
-  name: (type_identifier) @name) @definition.interface
-
-(impl_item
-  trait: (type_identifier)?
-  type: (type_identifier) @name) @definition.class
-
-(impl_item
-  trait: (type_identifier)?
-  type: (generic_type
-    type: (type_identifier) @name)) @definition.class
-`;
+const TYPESCRIPT_OUTLINE_QUERY = `${JAVASCRIPT_OUTLINE_QUERY}
+${TYPESCRIPT_EXTRA_OUTLINE_QUERY}`;
 
 const OUTLINE_LANGUAGE_CONFIG: Record<
 	OutlineLanguage,
@@ -795,7 +448,7 @@ export function CodePreview({ payload, sessionId }: CodePreviewProps) {
 	}, [filePath, sourceContent]);
 
 	useEffect(() => {
-		if (!outlineLanguage || !canUseTreeSitter) {
+		if (!outlineLanguage || (!canUseTreeSitter && !isInTauri())) {
 			setOutlineItems([]);
 			setOutlineStatus("unsupported");
 			setActivePane("code");
@@ -807,6 +460,25 @@ export function CodePreview({ payload, sessionId }: CodePreviewProps) {
 		setOutlineStatus("loading");
 		void (async () => {
 			try {
+				// The desktop app parses natively; elsewhere the webview does
+				const nativeItems = await extractOutline(outlineLanguage, code);
+				if (cancelled) {
+					return;
+				}
+				if (nativeItems) {
+					setOutlineItems(nativeItems);
+					setOutlineStatus("ready");
+					if (nativeItems.length === 0) {
+						setActivePane("code");
+					}
+					return;
+				}
+				if (!canUseTreeSitter) {
+					setOutlineItems([]);
+					setOutlineStatus("unsupported");
+					setActivePane("code");
+					return;
+				}
 				await ensureParserReady();
 				if (cancelled) {
 					return;
@@ -918,20 +590,7 @@ export function CodePreview({ payload, sessionId }: CodePreviewProps) {
 		if (!text) {
 			return;
 		}
-		if (typeof navigator !== "undefined" && navigator.clipboard?.writeText) {
-			await navigator.clipboard.writeText(text);
-		} else if (typeof document !== "undefined") {
-			const textarea = document.createElement("textarea");
-			textarea.value = text;
-			textarea.setAttribute("readonly", "true");
-			textarea.style.position = "fixed";
-			textarea.style.opacity = "0";
-			document.body.appendChild(textarea);
-			textarea.focus();
-			textarea.select();
-			document.execCommand("copy");
-			document.body.removeChild(textarea);
-		}
+		await copyOutput({ kind: "code", code: text, language });
 		setCopiedId(item.id);
 		if (copyTimeoutRef.current) {
 			window.clearTimeout(copyTimeoutRef.current);
@@ -7,7 +7,8 @@ import {
 	MultiFileDiff,
 	type SupportedLanguages,
 } from "@pierre/diffs/react";
-import { useMemo } from "react";
+import { useMemo, useState } from "react";
+import { copyOutput } from "@/lib/clipboard";
 import { useResolvedTheme } from "@/lib/code-highlight";
 import {
 	resolveFileNameFromPath,
@@ -97,6 +98,7 @@ export const UnifiedDiffView = (props: UnifiedDiffViewProps) => {
 	const themeMode = useResolvedTheme();
 	const options = useMemo(() => buildPierreDiffOptions(themeMode), [themeMode]);
 	const label = useMemo(() => resolveFileNameFromPath(path), [path]);
+	const [copied, setCopied] = useState(false);
 	const parsedFiles = useMemo(
 		() => (diff !== undefined ? flattenPatchFiles(diff) : []),
 		[diff],
@@ -131,6 +133,16 @@ export const UnifiedDiffView = (props: UnifiedDiffViewProps) => {
 		return null;
 	}
 
+	const handleCopy = async () => {
+		await copyOutput(
+			isContentDiff
+				? { kind: "diff", path, oldText, newText: newText ?? "" }
+				: { kind: "diff", path, diff: diff ?? "" },
+		);
+		setCopied(true);
+		setTimeout(() => setCopied(false), 1200);
+	};
+
 	return (
 		<div
 			className={cn(
@@ -156,6 +168,16 @@ export const UnifiedDiffView = (props: UnifiedDiffViewProps) => {
 						{label}
 					</span>
 				)}
+				<button
+					type="button"
+					className="ml-auto rounded text-[11px] text-muted-foreground hover:text-foreground focus-visible:outline-none focus-visible:ring-2 focus-visible:ring-ring focus-visible:ring-offset-1"
+					onClick={(event) => {
+						event.preventDefault();
+						void handleCopy();
+					}}
+				>
+					{copied ? getLabel("toolCall.copied") : getLabel("toolCall.copyDiff")}
+				</button>
 			</div>
 			<div
 				className={cn(
@@ -30,6 +30,7 @@ import {
 	selectTerminalOutputSnapshot,
 	useChatStore,
 } from "@/lib/chat-store";
+import { copyOutput, writePlainText } from "@/lib/clipboard";
 import { getCodeAccentFillClass } from "@/lib/code-highlight";
 import { getContentBlocksText } from "@/lib/content-block-utils";
 import { getToolCallMetaHints } from "@/lib/tool-call-meta";
@@ -864,23 +865,17 @@ const MessageItemInner = ({
 	const isFailedUserMessage =
 		isUser && message.kind === "text" && message.failed === true;
 
-	// User message copy button state
+	// Message copy button state
 	const [copied, setCopied] = useState(false);
 
 	const handleCopy = useCallback(async () => {
-		if (message.kind !== "text" || !isUser) return;
-		const text = extractUserMessageText(
-			message as Extract<ChatMessage, { kind: "text" }>,
-		);
-		try {
-			await navigator.clipboard.writeText(text);
-		} catch {
-			const textarea = document.createElement("textarea");
-			textarea.value = text;
-			document.body.appendChild(textarea);
-			textarea.select();
-			document.execCommand("copy");
-			document.body.removeChild(textarea);
+		if (message.kind !== "text") return;
+		const textMessage = message as Extract<ChatMessage, { kind: "text" }>;
+		if (isUser) {
+			await writePlainText(extractUserMessageText(textMessage));
+		} else {
+			// Agent replies are markdown; rich editors get them rendered.
+			await copyOutput({ kind: "markdown", text: textMessage.content });
 		}
 		setCopied(true);
 		setTimeout(() => setCopied(false), 1200);
@@ -1091,7 +1086,7 @@ const MessageItemInner = ({
 			<MessageAvatar className="min-w-2 self-start bg-transparent">
 				<span className="mt-1.5 size-2 shrink-0 rounded-full bg-foreground" />
 			</MessageAvatar>
-			<MessageContent>
+			<MessageContent className="group/agent-msg">
 				<Bubble variant="ghost" className="max-w-full">
 					<BubbleContent
 						className={message.isStreaming ? "opacity-90" : "opacity-100"}
@@ -1104,6 +1099,25 @@ const MessageItemInner = ({
 						)}
 					</BubbleContent>
 				</Bubble>
+				{message.kind === "text" && !message.isStreaming ? (
+					<Button
+						type="button"
+						variant="ghost"
+						size="icon-xs"
+						className="self-start rounded-full text-muted-foreground md:opacity-0 group-hover/agent-msg:opacity-100 focus-visible:opacity-100"
+						onClick={(event) => {
+							event.stopPropagation();
+							handleCopy();
+						}}
+						aria-label={t("chat.copyResponse")}
+						title={t("chat.copyResponse")}
+					>
+						<HugeiconsIcon
+							icon={copied ? Tick02Icon : Copy01Icon}
+							aria-hidden="true"
+						/>
+					</Button>
+				) : null}
 			</MessageContent>
 		</Message>
 	);
@@ -0,0 +1,586 @@
+import {
+	AlertDialog,
+	AlertDialogAction,
+	AlertDialogCancel,
+	AlertDialogContent,
+	AlertDialogDescription,
+	AlertDialogFooter,
+	AlertDialogHeader,
+	AlertDialogTitle,
+	AlertDialogTrigger,
+} from "@mobvibe/ui/alert-dialog";
+import { Button } from "@mobvibe/ui/button";
+import { Input } from "@mobvibe/ui/input";
+import { Label } from "@mobvibe/ui/label";
+import {
+	Select,
+	SelectContent,
+	SelectItem,
+	SelectTrigger,
+	SelectValue,
+} from "@mobvibe/ui/select";
+import { Separator } from "@mobvibe/ui/separator";
+import { useCallback, useEffect, useRef, useState } from "react";
+import { useTranslation } from "react-i18next";
+import {
+	type DaemonStatus,
+	getDaemonStatus,
+	setDaemonCliPath,
+	startDaemon,
+	stopDaemon,
+	watchDaemonLogs,
+} from "@/lib/daemon";
+import {
+	getLocalGatewayStatus,
+	type LocalGatewayStatus,
+	startLocalGateway,
+	stopLocalGateway,
+} from "@/lib/local-gateway";
+import {
+	DEFAULT_QUICK_PROMPT_SHORTCUT,
+	getQuickPromptStatus,
+	type QuickPromptStatus,
+	setQuickPromptShortcut,
+} from "@/lib/quick-prompt";
+import {
+	checkForUpdate,
+	downloadUpdate,
+	getUpdaterStatus,
+	installUpdate,
+	type ReleaseChannel,
+	setUpdateChannel,
+	setUpdateManifestUrl,
+	type UpdateProgress,
+	type UpdaterStatus,
+} from "@/lib/updater";
+
+/** Lines kept in the log view; older ones scroll away. */
+const MAX_LOG_LINES = 1000;
+const STATUS_POLL_MS = 5000;
+
+const errorMessage = (error: unknown) =>
+	error instanceof Error ? error.message : String(error);
+
+/* ------------------------------------------------------------------ */
+/*  Daemon                                                             */
+/* ------------------------------------------------------------------ */
+
+function DaemonLogs() {
+	const { t } = useTranslation();
+	const [file, setFile] = useState<string | null>(null);
+	const [lines, setLines] = useState<string[]>([]);
+	const bottomRef = useRef<HTMLDivElement>(null);
+
+	useEffect(() => {
+		let stop: (() => void) | undefined;
+		let cancelled = false;
+		void watchDaemonLogs((event) => {
+			if (event.kind === "file") {
+				setFile(event.path);
+				return;
+			}
+			setLines((prev) => [...prev, ...event.lines].slice(-MAX_LOG_LINES));
+		}).then((unwatch) => {
+			if (cancelled) unwatch();
+			else stop = unwatch;
+		});
+		return () => {
+			cancelled = true;
+			stop?.();
+		};
+	}, []);
+
+	useEffect(() => {
+		if (lines.length > 0) bottomRef.current?.scrollIntoView({ block: "end" });
+	}, [lines]);
+
+	return (
+		<div className="space-y-2">
+			{file && (
+				<p className="text-muted-foreground truncate text-xs" title={file}>
+					{file}
+				</p>
+			)}
+			<div className="bg-muted/50 h-72 overflow-auto border p-2">
+				<pre className="whitespace-pre-wrap break-all font-mono text-xs">
+					{lines.length > 0 ? lines.join("\n") : t("daemon.noLogs")}
+				</pre>
+				<div ref={bottomRef} />
+			</div>
+		</div>
+	);
+}
+
+export function DaemonSettings() {
+	const { t } = useTranslation();
+	const [status, setStatus] = useState<DaemonStatus | null>(null);
+	const [cliPath, setCliPath] = useState("");
+	const [busy, setBusy] = useState(false);
+	const [error, setError] = useState<string | null>(null);
+	const [showLogs, setShowLogs] = useState(false);
+
+	const refresh = useCallback(async () => {
+		setStatus(await getDaemonStatus());
+	}, []);
+
+	useEffect(() => {
+		void refresh();
+		const timer = window.setInterval(() => void refresh(), STATUS_POLL_MS);
+		return () => window.clearInterval(timer);
+	}, [refresh]);
+
+	useEffect(() => {
+		setCliPath(status?.cliPath ?? "");
+	}, [status?.cliPath]);
+
+	const run = async (action: () => Promise<DaemonStatus>) => {
+		setBusy(true);
+		setError(null);
+		try {
+			setStatus(await action());
+		} catch (err) {
+			setError(errorMessage(err));
+			void refresh();
+		} finally {
+			setBusy(false);
+		}
+	};
+
+	if (!status) return null;
+
+	const running = status.state !== "stopped";
+
+	return (
+		<div className="space-y-4">
+			<div className="flex flex-wrap items-center gap-2">
+				<div
+					className={`h-2 w-2 rounded-full ${running ? "bg-green-500" : "bg-muted-foreground"}`}
+				/>
+				<span className="text-sm font-medium">
+					{status.state === "running" &&
+						t("daemon.running", { pid: status.pid })}
+					{status.state === "unverified" &&
+						t("daemon.unverified", { pid: status.pid })}
+					{status.state === "stopped" && t("daemon.stopped")}
+				</span>
+				<div className="ml-auto flex gap-2">
+					<Button
+						size="sm"
+						onClick={() => run(() => startDaemon())}
+						disabled={busy || running || !status.cliPath}
+					>
+						{t("daemon.start")}
+					</Button>
+					<Button
+						size="sm"
+						variant="outline"
+						onClick={() => run(stopDaemon)}
+						disabled={busy || !running || !status.cliPath}
+					>
+						{t("daemon.stop")}
+					</Button>
+				</div>
+			</div>
+
+			<p className="text-muted-foreground text-sm">
+				{t("daemon.home", { path: status.home })}
+			</p>
+
+			<div className="space-y-2">
+				<Label htmlFor="daemon-cli-path">{t("daemon.cliPath")}</Label>
+				<div className="flex gap-2">
+					<Input
+						id="daemon-cli-path"
+						value={cliPath}
+						onChange={(e) => setCliPath(e.target.value)}
+						placeholder={t("daemon.cliPathPlaceholder")}
+						className="flex-1 font-mono text-xs"
+					/>
+					<Button
+						variant="outline"
+						onClick={() => run(() => setDaemonCliPath(cliPath.trim() || null))}
+						disabled={busy}
+					>
+						{t("common.save")}
+					</Button>
+					<Button
+						variant="ghost"
+						onClick={() => run(() => setDaemonCliPath(null))}
+						disabled={busy}
+					>
+						{t("daemon.detect")}
+					</Button>
+				</div>
+				{!status.cliPath && (
+					<p className="text-muted-foreground text-sm">
+						{t("daemon.cliNotFound")}
+					</p>
+				)}
+			</div>
+
+			{error && (
+				<pre
+					role="alert"
+					className="bg-destructive/10 text-destructive whitespace-pre-wrap p-3 text-xs"
+				>
+					{error}
+				</pre>
+			)}
+
+			<Button
+				variant="outline"
+				size="sm"
+				onClick={() => setShowLogs((prev) => !prev)}
+			>
+				{showLogs ? t("daemon.hideLogs") : t("daemon.showLogs")}
+			</Button>
+			{showLogs && <DaemonLogs />}
+		</div>
+	);
+}
+
+/* ------------------------------------------------------------------ */
+/*  Embedded gateway                                                   */
+/* ------------------------------------------------------------------ */
+
+export function LocalGatewaySettings() {
+	const { t } = useTranslation();
+	const [status, setStatus] = useState<LocalGatewayStatus | null>(null);
+	const [busy, setBusy] = useState(false);
+	const [error, setError] = useState<string | null>(null);
+
+	const refresh = useCallback(async () => {
+		setStatus(await getLocalGatewayStatus());
+	}, []);
+
+	useEffect(() => {
+		void refresh();
+		const timer = window.setInterval(() => void refresh(), STATUS_POLL_MS);
+		return () => window.clearInterval(timer);
+	}, [refresh]);
+
+	const toggle = async () => {
+		setBusy(true);
+		setError(null);
+		try {
+			if (status?.running) {
+				await stopLocalGateway();
+				await refresh();
+			} else {
+				setStatus(await startLocalGateway());
+			}
+		} catch (err) {
+			setError(errorMessage(err));
+		} finally {
+			setBusy(false);
+		}
+	};
+
+	if (!status) return null;
+
+	return (
+		<div className="space-y-2">
+			<Separator />
+			<div className="flex flex-col gap-2 sm:flex-row sm:items-center sm:justify-between">
+				<div className="space-y-0.5">
+					<Label>{t("daemon.localGateway")}</Label>
+					<p className="text-muted-foreground text-sm">
+						{status.running
+							? t("daemon.localGatewayRunning", {
+									url: status.url,
+									count: status.machines.length,
+								})
+							: t("daemon.localGatewayHint")}
+					</p>
+				</div>
+				<Button
+					variant={status.running ? "outline" : "default"}
+					size="sm"
+					onClick={toggle}
+					disabled={busy}
+				>
+					{status.running
+						? t("daemon.localGatewayStop")
+						: t("daemon.localGatewayStart")}
+				</Button>
+			</div>
+			{error && <p className="text-destructive text-sm">{error}</p>}
+		</div>
+	);
+}
+
+/* ------------------------------------------------------------------ */
+/*  Quick prompt                                                       */
+/* ------------------------------------------------------------------ */
+
+export function QuickPromptSettings() {
+	const { t } = useTranslation();
+	const [status, setStatus] = useState<QuickPromptStatus | null>(null);
+	const [shortcut, setShortcut] = useState("");
+	const [busy, setBusy] = useState(false);
+	const [error, setError] = useState<string | null>(null);
+
+	useEffect(() => {
+		void getQuickPromptStatus().then((next) => {
+			setStatus(next);
+			setShortcut(next?.shortcut ?? "");
+		});
+	}, []);
+
+	const save = async (next: string | null) => {
+		setBusy(true);
+		setError(null);
+		try {
+			const updated = await setQuickPromptShortcut(next);
+			setStatus(updated);
+			setShortcut(updated.shortcut ?? "");
+		} catch (err) {
+			setError(errorMessage(err));
+		} finally {
+			setBusy(false);
+		}
+	};
+
+	if (!status) return null;
+
+	return (
+		<div className="space-y-2">
+			<Separator />
+			<div className="space-y-0.5">
+				<Label htmlFor="quick-prompt-shortcut">
+					{t("daemon.quickPrompt")}
+				</Label>
+				<p className="text-muted-foreground text-sm">
+					{status.shortcut
+						? t("daemon.quickPromptHint", {
+								default: DEFAULT_QUICK_PROMPT_SHORTCUT,
+							})
+						: t("daemon.quickPromptOff")}
+				</p>
+			</div>
+			<div className="flex gap-2">
+				<Input
+					id="quick-prompt-shortcut"
+					value={shortcut}
+					onChange={(e) => setShortcut(e.target.value)}
+					placeholder={DEFAULT_QUICK_PROMPT_SHORTCUT}
+					className="flex-1 font-mono text-xs"
+				/>
+				<Button
+					variant="outline"
+					onClick={() =>
+						save(shortcut.trim() || DEFAULT_QUICK_PROMPT_SHORTCUT)
+					}
+					disabled={busy}
+				>
+					{t("common.save")}
+				</Button>
+				<Button
+					variant="ghost"
+					onClick={() => save(null)}
+					disabled={busy || !status.shortcut}
+				>
+					{t("daemon.quickPromptDisable")}
+				</Button>
+			</div>
+			{(error ?? status.error) && (
+				<p className="text-destructive text-sm">{error ?? status.error}</p>
+			)}
+		</div>
+	);
+}
+
+/* ------------------------------------------------------------------ */
+/*  Updates                                                            */
+/* ------------------------------------------------------------------ */
+
+const formatMegabytes = (bytes: number) => (bytes / 1024 / 1024).toFixed(1);
+
+export function UpdateSettings() {
+	const { t } = useTranslation();
+	const [status, setStatus] = useState<UpdaterStatus | null>(null);
+	const [manifestUrl, setManifestUrl] = useState("");
+	const [progress, setProgress] = useState<UpdateProgress | null>(null);
+	const [busy, setBusy] = useState(false);
+	const [upToDate, setUpToDate] = useState(false);
+	const [error, setError] = useState<string | null>(null);
+
+	const refresh = useCallback(async () => {
+		const next = await getUpdaterStatus();
+		setStatus(next);
+		return next;
+	}, []);
+
+	useEffect(() => {
+		void refresh().then((next) => setManifestUrl(next?.manifestUrl ?? ""));
+	}, [refresh]);
+
+	const run = async (action: () => Promise<unknown>) => {
+		setBusy(true);
+		setError(null);
+		setUpToDate(false);
+		try {
+			await action();
+		} catch (err) {
+			setError(errorMessage(err));
+		} finally {
+			setProgress(null);
+			setBusy(false);
+		}
+	};
+
+	const changeChannel = (channel: ReleaseChannel) =>
+		run(async () => setStatus(await setUpdateChannel(channel)));
+
+	const saveManifestUrl = () =>
+		run(async () => {
+			const next = await setUpdateManifestUrl(manifestUrl.trim() || null);
+			setStatus(next);
+			setManifestUrl(next.manifestUrl ?? "");
+		});
+
+	const check = () =>
+		run(async () => {
+			const found = await checkForUpdate();
+			setUpToDate(!found);
+			await refresh();
+		});
+
+	const download = () =>
+		run(async () => {
+			await downloadUpdate(setProgress);
+			await refresh();
+		});
+
+	if (!status) return null;
+	const update = status.available;
+
+	return (
+		<div className="space-y-3">
+			<Separator />
+			<div className="flex flex-col gap-2 sm:flex-row sm:items-center sm:justify-between">
+				<div className="space-y-0.5">
+					<Label htmlFor="update-channel">{t("daemon.updates")}</Label>
+					<p className="text-muted-foreground text-sm">
+						{t("daemon.updatesVersion", { version: status.currentVersion })}
+					</p>
+				</div>
+				<Select
+					value={status.channel}
+					onValueChange={(value) => changeChannel(value as ReleaseChannel)}
+					disabled={busy}
+				>
+					<SelectTrigger id="update-channel" className="w-full sm:w-40">
+						<SelectValue />
+					</SelectTrigger>
+					<SelectContent>
+						<SelectItem value="stable">
+							{t("daemon.updatesChannelStable")}
+						</SelectItem>
+						<SelectItem value="beta">
+							{t("daemon.updatesChannelBeta")}
+						</SelectItem>
+					</SelectContent>
+				</Select>
+			</div>
+			<div className="space-y-1">
+				<Label htmlFor="update-manifest-url">
+					{t("daemon.updatesManifestUrl")}
+				</Label>
+				<div className="flex gap-2">
+					<Input
+						id="update-manifest-url"
+						value={manifestUrl}
+						onChange={(e) => setManifestUrl(e.target.value)}
+						placeholder={
+							status.defaultManifestUrl ?? "https://…/{channel}.json"
+						}
+						className="flex-1 font-mono text-xs"
+					/>
+					<Button variant="outline" onClick={saveManifestUrl} disabled={busy}>
+						{t("common.save")}
+					</Button>
+				</div>
+			</div>
+			{!status.enabled && (
+				<p className="text-muted-foreground text-sm">
+					{t("daemon.updatesDisabled")}
+				</p>
+			)}
+			{update && (
+				<div className="space-y-1">
+					<p className="text-sm">
+						{t("daemon.updatesAvailable", { version: update.version })}
+					</p>
+					{update.notes && (
+						<p className="text-muted-foreground whitespace-pre-wrap text-sm">
+							{update.notes}
+						</p>
+					)}
+					{!update.installable && (
+						<p className="text-muted-foreground text-sm">
+							{t("daemon.updatesManaged")}
+						</p>
+					)}
+				</div>
+			)}
+			{progress && (
+				<p className="text-muted-foreground text-sm">
+					{progress.total
+						? t("daemon.updatesProgress", {
+								downloaded: formatMegabytes(progress.downloaded),
+								total: formatMegabytes(progress.total),
+							})
+						: t("daemon.updatesProgressUnknown", {
+								downloaded: formatMegabytes(progress.downloaded),
+							})}
+				</p>
+			)}
+			{upToDate && (
+				<p className="text-muted-foreground text-sm">
+					{t("daemon.updatesUpToDate")}
+				</p>
+			)}
+			<div className="flex gap-2">
+				<Button
+					variant="outline"
+					onClick={check}
+					disabled={busy || !status.enabled}
+				>
+					{t("daemon.updatesCheck")}
+				</Button>
+				{update?.installable && !update.ready && (
+					<Button onClick={download} disabled={busy}>
+						{t("daemon.updatesDownload")}
+					</Button>
+				)}
+				{update?.ready && (
+					<AlertDialog>
+						<AlertDialogTrigger asChild>
+							<Button disabled={busy}>{t("daemon.updatesInstall")}</Button>
+						</AlertDialogTrigger>
+						<AlertDialogContent size="sm">
+							<AlertDialogHeader>
+								<AlertDialogTitle>
+									{t("daemon.updatesRestartTitle")}
+								</AlertDialogTitle>
+								<AlertDialogDescription>
+									{t("daemon.updatesRestartDescription", {
+										version: update.version,
+									})}
+								</AlertDialogDescription>
+							</AlertDialogHeader>
+							<AlertDialogFooter>
+								<AlertDialogCancel>{t("common.cancel")}</AlertDialogCancel>
+								<AlertDialogAction onClick={() => run(installUpdate)}>
+									{t("daemon.updatesRestart")}
+								</AlertDialogAction>
+							</AlertDialogFooter>
+						</AlertDialogContent>
+					</AlertDialog>
+				)}
+			</div>
+			{error && <p className="text-destructive text-sm">{error}</p>}
+		</div>
+	);
+}
@@ -19,14 +19,9 @@ import { useQrScanner } from "@/hooks/use-qr-scanner";
 import type { SessionsResponse } from "@/lib/api";
 import { isInTauri } from "@/lib/auth";
 import { useChatStore } from "@/lib/chat-store";
-import { e2ee } from "@/lib/e2ee";
+import { e2ee, type PairedDevice } from "@/lib/e2ee";
 import { getQrScanErrorCode } from "@/lib/qr-scan-errors";
 
-interface PairedDevice {
-	secret: string;
-	fingerprint: string;
-}
-
 function base64urlToBase64(base64url: string): string {
 	let b64 = base64url.replace(/-/g, "+").replace(/_/g, "/");
 	const pad = b64.length % 4;
@@ -129,6 +124,9 @@ export function E2EESettings() {
 	const [pairedDevices, setPairedDevices] = useState<PairedDevice[]>([]);
 	const [isSubmitting, setIsSubmitting] = useState(false);
 	const [removeTarget, setRemoveTarget] = useState<PairedDevice | null>(null);
+	const [vaultLocked, setVaultLocked] = useState(false);
+	const [keyringError, setKeyringError] = useState<string | null>(null);
+	const [passphrase, setPassphrase] = useState("");
 
 	const queryClient = useQueryClient();
 
@@ -158,19 +156,41 @@ export function E2EESettings() {
 	}, [queryClient]);
 
 	const refreshDevices = useCallback(() => {
-		const devices = e2ee.getPairedSecrets();
-		setPairedDevices(
-			devices.map((d) => ({
-				secret: d.secret,
-				fingerprint: d.fingerprint,
-			})),
-		);
+		setPairedDevices(e2ee.getPairedDevices());
 	}, []);
 
 	useEffect(() => {
 		refreshDevices();
+		void e2ee
+			.getVaultStatus()
+			.then((status) => {
+				setVaultLocked(status?.locked ?? false);
+				setKeyringError(status?.error ?? null);
+			})
+			.catch(() => {});
 	}, [refreshDevices]);
 
+	const handleUnlock = async () => {
+		setIsSubmitting(true);
+		setError(null);
+		try {
+			await e2ee.unlockVault(passphrase);
+			setPassphrase("");
+			setVaultLocked(false);
+			setKeyringError(null);
+			refreshDevices();
+			refreshSessionE2EE();
+		} catch (err) {
+			if (keyringError) {
+				setKeyringError(err instanceof Error ? err.message : String(err));
+			} else {
+				setError(t("e2ee.wrongPassphrase"));
+			}
+		} finally {
+			setIsSubmitting(false);
+		}
+	};
+
 	const handlePair = async () => {
 		if (!secret.trim()) {
 			setError(t("e2ee.enterSecret"));
@@ -223,7 +243,7 @@ export function E2EESettings() {
 	};
 
 	const handleRemove = async (device: PairedDevice) => {
-		await e2ee.removePairedSecret(device.secret);
+		await e2ee.removePairedDevice(device.fingerprint);
 		setRemoveTarget(null);
 		refreshDevices();
 		refreshSessionE2EE();
@@ -235,6 +255,49 @@ export function E2EESettings() {
 		refreshSessionE2EE();
 	};
 
+	if (vaultLocked && keyringError) {
+		return (
+			<div className="space-y-4">
+				<p className="text-muted-foreground text-sm">
+					{t("e2ee.keyringErrorHint")}
+				</p>
+				<p className="text-destructive text-sm">{keyringError}</p>
+				<Button onClick={handleUnlock} disabled={isSubmitting}>
+					{t("common.retry")}
+				</Button>
+			</div>
+		);
+	}
+
+	if (vaultLocked) {
+		return (
+			<div className="space-y-4">
+				<p className="text-muted-foreground text-sm">
+					{t("e2ee.vaultLockedHint")}
+				</p>
+				<div className="flex gap-2">
+					<Input
+						type="password"
+						value={passphrase}
+						onChange={(e) => setPassphrase(e.target.value)}
+						onKeyDown={(e) => {
+							if (e.key === "Enter") void handleUnlock();
+						}}
+						placeholder={t("e2ee.passphrasePlaceholder")}
+						className="flex-1"
+					/>
+					<Button
+						onClick={handleUnlock}
+						disabled={isSubmitting || passphrase === ""}
+					>
+						{t("e2ee.unlockVault")}
+					</Button>
+				</div>
+				{error && <p className="text-destructive text-sm">{error}</p>}
+			</div>
+		);
+	}
+
 	return (
 		<div className="space-y-4">
 			<div className="flex items-center gap-2">
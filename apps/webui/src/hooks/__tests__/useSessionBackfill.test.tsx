@@ -21,6 +21,11 @@ vi.mock("@/lib/tauri-fetch", () => ({
 	platformFetch: mockFetch,
 }));
 
+vi.mock("@/lib/session-cache", () => ({
+	readCachedEvents: () => Promise.resolve(null),
+	recordCachedPage: () => {},
+}));
+
 describe("useSessionBackfill", () => {
 	beforeEach(() => {
 		mockFetch.mockReset();
@@ -3,15 +3,21 @@ import { renderHook, waitFor } from "@testing-library/react";
 import type { ReactNode } from "react";
 import { beforeEach, describe, expect, it, vi } from "vitest";
 import * as api from "@/lib/api";
+import { readCachedSessions } from "@/lib/session-cache";
 import { useSessionQueries } from "../useSessionQueries";
 
 // Mock the API module
 vi.mock("@/lib/api", () => ({
+	ApiError: class ApiError extends Error {},
 	fetchSessions: vi.fn(),
 	fetchAcpBackends: vi.fn(),
 	discoverSessions: vi.fn(),
 }));
 
+vi.mock("@/lib/session-cache", () => ({
+	readCachedSessions: vi.fn().mockResolvedValue(null),
+}));
+
 describe("useSessionQueries", () => {
 	let queryClient: QueryClient;
 
@@ -158,6 +164,48 @@ describe("useSessionQueries", () => {
 		expect(result.current.sessionsQuery.error).toBeDefined();
 	});
 
+	it("lists cached sessions when the gateway is unreachable", async () => {
+		const cached = {
+			sessionId: "session-1",
+			title: "Cached Session",
+			backendId: "backend-1",
+			backendLabel: "Backend 1",
+			createdAt: "2025-01-01T00:00:00Z",
+			updatedAt: "2025-01-01T00:00:00Z",
+		};
+		vi.mocked(api.fetchSessions).mockRejectedValue(
+			new TypeError("Failed to fetch"),
+		);
+		vi.mocked(api.fetchAcpBackends).mockResolvedValue({ backends: [] });
+		vi.mocked(readCachedSessions).mockResolvedValueOnce([cached]);
+
+		const { result } = renderHook(() => useSessionQueries(), { wrapper });
+
+		await waitFor(() => {
+			expect(result.current.sessionsQuery.isSuccess).toBe(true);
+		});
+		expect(result.current.sessionsQuery.data).toEqual({ sessions: [cached] });
+	});
+
+	it("keeps the gateway's own errors over the cache", async () => {
+		vi.mocked(api.fetchSessions).mockRejectedValue(
+			new api.ApiError({
+				code: "AUTHORIZATION_FAILED",
+				message: "Unauthorized",
+				retryable: false,
+				scope: "request",
+			}),
+		);
+		vi.mocked(api.fetchAcpBackends).mockResolvedValue({ backends: [] });
+
+		const { result } = renderHook(() => useSessionQueries(), { wrapper });
+
+		await waitFor(() => {
+			expect(result.current.sessionsQuery.isError).toBe(true);
+		});
+		expect(readCachedSessions).not.toHaveBeenCalled();
+	});
+
 	it("should handle fetchAcpBackends errors", async () => {
 		vi.mocked(api.fetchSessions).mockResolvedValue({ sessions: [] });
 		vi.mocked(api.fetchAcpBackends).mockRejectedValue(
@@ -125,8 +125,10 @@ const dekReadyListeners = vi.hoisted(
 const decryptedPayloads = vi.hoisted(
 	() => new Map<string, Record<string, unknown>>(),
 );
+const tauri = vi.hoisted(() => ({ value: false }));
 const mockE2EE = vi.hoisted(() => ({
 	hasSessionDek: vi.fn((_sessionId: string, _revision?: number) => true),
+	decryptEvents: vi.fn(),
 	decryptEvent: vi.fn((event: { payload: unknown }) => {
 		if (
 			event.payload &&
@@ -155,6 +157,11 @@ const mockE2EE = vi.hoisted(() => ({
 	),
 }));
 
+vi.mock("@/lib/auth", async (importOriginal) => ({
+	...(await importOriginal<typeof import("@/lib/auth")>()),
+	isInTauri: () => tauri.value,
+}));
+
 vi.mock("@/lib/e2ee", () => ({
 	e2ee: mockE2EE,
 	bootstrapSessionE2EE: vi.fn(
@@ -494,6 +501,8 @@ describe("useSocket (webui)", () => {
 		mockE2EE.hasSessionDek.mockReset();
 		mockE2EE.hasSessionDek.mockReturnValue(true);
 		mockE2EE.decryptEvent.mockClear();
+		mockE2EE.decryptEvents.mockReset();
+		tauri.value = false;
 		mockE2EE.onDekReady.mockClear();
 		mockE2EE.unwrapSessionDek.mockReset();
 		mockE2EE.getSessionE2EEStatus.mockReset();
@@ -1684,6 +1693,110 @@ describe("useSocket (webui)", () => {
 		expect(store.updateSessionCursor).toHaveBeenCalledWith("session-1", 1, 1);
 	});
 
+	it("decrypts events through Rust in the app, in the order they arrived", async () => {
+		tauri.value = true;
+		const store = createStore();
+		const sessions = {
+			"session-1": buildSession({
+				sessionId: "session-1",
+				isAttached: true,
+				revision: 1,
+				lastAppliedSeq: 0,
+			}),
+		};
+		mockStoreState.sessions = { ...sessions };
+		for (const text of ["first", "second"]) {
+			decryptedPayloads.set(`cipher-${text}`, {
+				sessionId: "session-1",
+				update: {
+					sessionUpdate: "agent_message_chunk",
+					content: { type: "text", text },
+				},
+			});
+		}
+		const decrypting: Array<() => void> = [];
+		mockE2EE.decryptEvents.mockImplementation(
+			(events: Array<{ payload: unknown }>) =>
+				new Promise((resolve) => {
+					decrypting.push(() =>
+						resolve(
+							events.map((event) => ({
+								event: mockE2EE.decryptEvent(event),
+							})),
+						),
+					);
+				}),
+		);
+
+		renderHook(() =>
+			useSocket({
+				sessions,
+				appendAssistantChunk: store.appendAssistantChunk,
+				appendThoughtChunk: store.appendThoughtChunk,
+				confirmOrAppendUserMessage: store.confirmOrAppendUserMessage,
+				updateSessionMeta: store.updateSessionMeta,
+				setStreamError: store.setStreamError,
+				addPermissionRequest: store.addPermissionRequest,
+				setPermissionDecisionState: store.setPermissionDecisionState,
+				setPermissionOutcome: store.setPermissionOutcome,
+				addToolCall: store.addToolCall,
+				updateToolCall: store.updateToolCall,
+				appendTerminalOutput: store.appendTerminalOutput,
+				handleSessionsChanged: store.handleSessionsChanged,
+				markSessionAttached: store.markSessionAttached,
+				markSessionDetached: store.markSessionDetached,
+				createLocalSession: store.createLocalSession,
+				updateSessionCursor: store.updateSessionCursor,
+				resetSessionForRevision: store.resetSessionForRevision,
+			}),
+		);
+
+		for (const [seq, text] of [
+			[1, "first"],
+			[2, "second"],
+		] as const) {
+			handlers.sessionEvent?.({
+				sessionId: "session-1",
+				revision: 1,
+				seq,
+				kind: "agent_message_chunk",
+				payload: { t: "encrypted", c: `cipher-${text}` },
+			});
+		}
+
+		// The second event waits for the first to be decrypted.
+		expect(mockE2EE.decryptEvents).toHaveBeenCalledTimes(1);
+		expect(store.appendAssistantChunk).not.toHaveBeenCalled();
+		await act(async () => {
+			decrypting[0]?.();
+		});
+		await vi.waitFor(() =>
+			expect(mockE2EE.decryptEvents).toHaveBeenCalledTimes(2),
+		);
+		await act(async () => {
+			decrypting[1]?.();
+		});
+
+		await vi.waitFor(() =>
+			expect(store.appendAssistantChunk).toHaveBeenCalledTimes(2),
+		);
+		expect(store.appendAssistantChunk).toHaveBeenNthCalledWith(
+			1,
+			"session-1",
+			{ type: "text", text: "first" },
+		);
+		expect(store.appendAssistantChunk).toHaveBeenNthCalledWith(
+			2,
+			"session-1",
+			{ type: "text", text: "second" },
+		);
+		expect(store.updateSessionCursor).toHaveBeenLastCalledWith(
+			"session-1",
+			1,
+			2,
+		);
+	});
+
 	it("does not apply or advance past an encrypted event that fails decryption", () => {
 		const store = createStore();
 		setMockSessions({
@@ -2,6 +2,8 @@ import type { SessionEvent, SessionEventsResponse } from "@mobvibe/shared";
 import { useCallback, useEffect, useReducer, useRef } from "react";
 import { isInTauri } from "@/lib/auth";
 import { getAuthToken } from "@/lib/auth-token";
+import { localGatewayToken } from "@/lib/local-gateway";
+import { readCachedEvents, recordCachedPage } from "@/lib/session-cache";
 import { platformFetch } from "@/lib/tauri-fetch";
 
 const BACKFILL_REQUEST_TIMEOUT_MS = 15_000;
@@ -89,7 +91,9 @@ export function useSessionBackfill({
 				"Content-Type": "application/json",
 			};
 			const tauriEnv = isInTauri();
-			const token = authToken ?? (tauriEnv ? getAuthToken() : null);
+			const token = tauriEnv
+				? (localGatewayToken(gatewayUrl) ?? authToken ?? getAuthToken())
+				: authToken;
 			if (token) {
 				headers.Authorization = `Bearer ${token}`;
 			}
@@ -139,9 +143,56 @@ export function useSessionBackfill({
 		[gatewayUrl, authToken, pageSize],
 	);
 
+	/**
+	 * Replay events from the offline session cache (Tauri only).
+	 * Only the contiguous prefix the cache has fully synced is delivered, so
+	 * the gateway can be asked for the remaining gap alone.
+	 * Returns the seq remote backfill should resume after.
+	 */
+	const replayCachedEvents = useCallback(
+		async (
+			sessionId: string,
+			revision: number,
+			afterSeq: number,
+			signal: AbortSignal,
+		): Promise<{ afterSeq: number; count: number }> => {
+			let cursor = afterSeq;
+			let count = 0;
+			while (!signal.aborted) {
+				const page = await readCachedEvents(
+					sessionId,
+					revision,
+					cursor,
+					pageSize,
+				);
+				if (!page || page.revision !== revision || signal.aborted) {
+					break;
+				}
+				const events = page.events.filter(
+					(event) => event.seq <= page.syncedSeq,
+				);
+				if (events.length > 0) {
+					onEvents(sessionId, events);
+					count += events.length;
+				}
+				cursor = Math.max(cursor, page.syncedSeq);
+				if (
+					!page.hasMore ||
+					page.nextAfterSeq === null ||
+					page.nextAfterSeq >= page.syncedSeq
+				) {
+					break;
+				}
+			}
+			return { afterSeq: cursor, count };
+		},
+		[onEvents, pageSize],
+	);
+
 	/**
 	 * Start backfill for a session.
-	 * Will fetch events in pages until all events are retrieved.
+	 * Replays the offline cache first (Tauri), then fetches the remaining
+	 * events from the gateway in pages until all events are retrieved.
 	 */
 	const startBackfill = useCallback(
 		async (
@@ -169,6 +220,17 @@ export function useSessionBackfill({
 			let totalEvents = 0;
 
 			try {
+				if (isInTauri()) {
+					const cached = await replayCachedEvents(
+						sessionId,
+						revision,
+						currentAfterSeq,
+						abortController.signal,
+					);
+					currentAfterSeq = cached.afterSeq;
+					totalEvents += cached.count;
+				}
+
 				while (true) {
 					// Check if aborted before each fetch
 					if (abortController.signal.aborted) {
@@ -213,6 +275,9 @@ export function useSessionBackfill({
 						onEvents(sessionId, response.events);
 						totalEvents += response.events.length;
 					}
+					if (isInTauri()) {
+						recordCachedPage(currentAfterSeq, response);
+					}
 					currentAfterSeq = nextAfterSeq;
 
 					// Guard against infinite loops
@@ -238,6 +303,7 @@ export function useSessionBackfill({
 		},
 		[
 			fetchEvents,
+			replayCachedEvents,
 			onEvents,
 			onComplete,
 			onError,
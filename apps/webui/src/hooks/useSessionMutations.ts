@@ -35,6 +35,7 @@ import type {
 	StatusVariant,
 } from "@/lib/chat-store";
 import { useChatStore } from "@/lib/chat-store";
+import { createDefaultContentBlocks } from "@/lib/content-block-utils";
 import { bootstrapSessionE2EE, e2ee } from "@/lib/e2ee";
 import { createFallbackError, normalizeError } from "@/lib/error-utils";
 import { useNotificationStore } from "@/lib/notification-store";
@@ -340,6 +341,16 @@ export function useSessionMutations(
 			// Switch to real session
 			store.setActiveSessionId(data.sessionId);
 
+			const { draftPrompt, setDraftPrompt, setChatDraft } =
+				useUiStore.getState();
+			if (draftPrompt) {
+				setChatDraft(data.sessionId, {
+					input: draftPrompt,
+					inputContents: createDefaultContentBlocks(draftPrompt),
+				});
+				setDraftPrompt(undefined);
+			}
+
 			const lastCwd = _variables?.cwd ?? data.worktreeSourceCwd ?? data.cwd;
 			if (data.machineId && lastCwd) {
 				store.setLastCreatedCwd(data.machineId, lastCwd);
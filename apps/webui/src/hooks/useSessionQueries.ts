@@ -3,11 +3,13 @@ import type { AgentSessionCapabilities } from "@/lib/acp";
 import {
 	type AcpBackendSummary,
 	type AcpBackendsResponse,
+	ApiError,
 	discoverSessions,
 	fetchAcpBackends,
 	fetchSessions,
 	type SessionsResponse,
 } from "@/lib/api";
+import { readCachedSessions } from "@/lib/session-cache";
 
 export interface UseSessionQueriesReturn {
 	sessionsQuery: ReturnType<typeof useQuery<SessionsResponse>>;
@@ -141,6 +143,23 @@ export function useDiscoverSessionsMutation() {
 	});
 }
 
+/**
+ * The gateway's session list. When the gateway cannot be reached, the app
+ * lists the sessions in its offline cache instead, so their cached
+ * transcripts stay readable.
+ */
+const fetchSessionsOrCached = async (): Promise<SessionsResponse> => {
+	try {
+		return await fetchSessions();
+	} catch (error) {
+		// The gateway answered; its error stands.
+		if (error instanceof ApiError) throw error;
+		const cached = await readCachedSessions();
+		if (!cached?.length) throw error;
+		return { sessions: cached };
+	}
+};
+
 /**
  * Hook to fetch sessions and backends data.
  * Returns query objects and derived data.
@@ -150,7 +169,7 @@ export function useSessionQueries(): UseSessionQueriesReturn {
 	// Sessions — socket-driven, never refetch automatically
 	const sessionsQuery = useQuery({
 		queryKey: queryKeys.sessions,
-		queryFn: fetchSessions,
+		queryFn: fetchSessionsOrCached,
 		staleTime: Number.POSITIVE_INFINITY,
 	});
 
@@ -29,6 +29,7 @@ import {
 	type SessionsChangedPayload,
 } from "@/lib/acp";
 import { type ChatSession, useChatStore } from "@/lib/chat-store";
+import { isInTauri } from "@/lib/auth";
 import { bootstrapSessionE2EE, e2ee } from "@/lib/e2ee";
 import { createFallbackError } from "@/lib/error-utils";
 import { sanitizeInboundAcpPayload } from "@/lib/inbound-acp-meta";
@@ -72,6 +73,14 @@ type UseSocketOptions = {
 	| "resetSessionForRevision"
 >;
 
+/** Hands events of one session to the ingest path, decrypted, in order. */
+type ReceiveSessionEvents = (
+	sessionId: string,
+	events: SessionEvent[],
+	source: EventSource,
+	onIngested?: () => void,
+) => void;
+
 // Helper to get cursor from store directly (unified source of truth)
 const getCursor = (sessionId: string) => {
 	const session = useChatStore.getState().sessions[sessionId];
@@ -271,16 +280,26 @@ export function useSocket({
 	const ingestSessionEventRef = useRef<
 		((event: SessionEvent, source: EventSource) => void) | undefined
 	>(undefined);
+	const receiveSessionEventsRef = useRef<ReceiveSessionEvents | undefined>(
+		undefined,
+	);
+	// In the app Rust holds the DEKs and decrypts asynchronously; a session's
+	// events wait behind the ones decrypting before them to keep their order.
+	const decryptChainsRef = useRef(new Map<string, Promise<void>>());
 
 	// Setup backfill hook for gap recovery
 	const { startBackfill, cancelBackfill, isBackfilling } = useSessionBackfill({
 		gatewayUrl: gatewaySocket.getGatewayUrl(),
 		onEvents: (sessionId, events) => {
-			for (const rawEvent of [...events].sort((a, b) => a.seq - b.seq)) {
-				ingestSessionEventRef.current?.(rawEvent, "backfill");
-			}
-			prunePendingEventsRef.current?.(sessionId);
-			flushPendingEventsRef.current?.(sessionId);
+			receiveSessionEventsRef.current?.(
+				sessionId,
+				[...events].sort((a, b) => a.seq - b.seq),
+				"backfill",
+				() => {
+					prunePendingEventsRef.current?.(sessionId);
+					flushPendingEventsRef.current?.(sessionId);
+				},
+			);
 		},
 		onComplete: (_sessionId) => {
 			syncBackupsRef.current.delete(_sessionId);
@@ -429,6 +448,50 @@ export function useSocket({
 	) => {
 		startBackfill(sessionId, revision, afterSeq);
 	};
+	receiveSessionEventsRef.current = (
+		sessionId: string,
+		events: SessionEvent[],
+		source: EventSource,
+		onIngested?: () => void,
+	) => {
+		const ingest = (decrypted: SessionEvent[]) => {
+			for (const event of decrypted) {
+				ingestSessionEventRef.current?.(event, source);
+			}
+			onIngested?.();
+		};
+		const needsRust = () =>
+			events.some(
+				(event) =>
+					isEncryptedPayload(event.payload) &&
+					e2ee.hasSessionDek(event.sessionId, event.revision),
+			);
+		const chains = decryptChainsRef.current;
+		const previous = chains.get(sessionId);
+		if (!isInTauri() || (!previous && !needsRust())) {
+			ingest(events);
+			return;
+		}
+		// Events Rust cannot decrypt come back as received, and the ingest
+		// path buffers them as it does without a key.
+		const chain = (previous ?? Promise.resolve())
+			.then(async () =>
+				needsRust()
+					? (await e2ee.decryptEvents(events)).map(({ event }) => event)
+					: events,
+			)
+			.then(ingest)
+			.catch((error) => {
+				console.error(
+					`[E2EE] Failed to decrypt events for ${sessionId}`,
+					error,
+				);
+			})
+			.finally(() => {
+				if (chains.get(sessionId) === chain) chains.delete(sessionId);
+			});
+		chains.set(sessionId, chain);
+	};
 	ingestSessionEventRef.current = (
 		incomingEvent: SessionEvent,
 		source: EventSource,
@@ -636,20 +699,32 @@ export function useSocket({
 		}
 	};
 
+	// The app's offline cache records live events in Rust, off the gateway
+	// connection (`session_cache::init`).
 	handleSessionEventRef.current = (incomingEvent: SessionEvent) => {
-		ingestSessionEventRef.current?.(incomingEvent, "live");
+		receiveSessionEventsRef.current?.(
+			incomingEvent.sessionId,
+			[incomingEvent],
+			"live",
+		);
 	};
 
 	// Flush buffered encrypted events once a DEK becomes available
 	useEffect(() => {
 		const unsubDekReady = e2ee.onDekReady((sessionId, revision) => {
+			// In the app DEKs arrive after the session was bootstrapped.
+			const { sessions, setSessionE2EEStatus } = useChatStore.getState();
+			if (e2ee.hasSessionDek(sessionId, sessions[sessionId]?.revision)) {
+				setSessionE2EEStatus(sessionId, "ok");
+			}
 			const buffered = encryptedBufferRef.current.drain(sessionId, revision);
 			if (buffered.length === 0) return;
 
 			// Re-process each buffered event through the original ingest path.
 			for (const bufferedEvent of buffered) {
-				ingestSessionEventRef.current?.(
-					bufferedEvent.event,
+				receiveSessionEventsRef.current?.(
+					sessionId,
+					[bufferedEvent.event],
 					bufferedEvent.source,
 				);
 			}
@@ -1,12 +1,7 @@
 import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
 
-// Shared mock store used across Tauri tests
-const mockStore = {
-	get: vi.fn(),
-	set: vi.fn(),
-	save: vi.fn(),
-	delete: vi.fn(),
-};
+// Shared mock of the Tauri invoke used across Tauri tests
+const mockInvoke = vi.fn();
 
 describe("auth-token (non-Tauri)", () => {
 	beforeEach(() => {
@@ -39,14 +34,14 @@ describe("auth-token (non-Tauri)", () => {
 	});
 
 	it("loadAuthToken is a no-op in non-Tauri environment", async () => {
-		vi.doMock("@tauri-apps/plugin-store", () => ({
-			load: vi.fn(),
+		vi.doMock("@tauri-apps/api/core", () => ({
+			invoke: vi.fn(),
 		}));
 		const { loadAuthToken } = await import("../auth-token");
 		await loadAuthToken();
-		// Should not throw and not call store
-		const { load } = await import("@tauri-apps/plugin-store");
-		expect(load).not.toHaveBeenCalled();
+		// Should not throw and not call the settings store
+		const { invoke } = await import("@tauri-apps/api/core");
+		expect(invoke).not.toHaveBeenCalled();
 	});
 });
 
@@ -54,56 +49,54 @@ describe("auth-token (Tauri)", () => {
 	beforeEach(() => {
 		vi.resetModules();
 		vi.doMock("../auth", () => ({ isInTauri: () => true }));
-		vi.doMock("@tauri-apps/plugin-store", () => ({
-			load: vi.fn().mockResolvedValue(mockStore),
-		}));
-		mockStore.get.mockReset();
-		mockStore.set.mockReset();
-		mockStore.save.mockReset();
-		mockStore.delete.mockReset();
+		vi.doMock("@tauri-apps/api/core", () => ({ invoke: mockInvoke }));
+		mockInvoke.mockReset();
 	});
 
 	afterEach(() => {
 		vi.restoreAllMocks();
 	});
 
-	it("setAuthToken persists token via store", async () => {
-		mockStore.set.mockResolvedValue(undefined);
-		mockStore.save.mockResolvedValue(undefined);
+	it("setAuthToken hands the token to the native gateway", async () => {
+		mockInvoke.mockResolvedValue(undefined);
 		const { setAuthToken } = await import("../auth-token");
 
 		setAuthToken("tauri-token");
 
 		// Wait for the async persist to complete
 		await vi.waitFor(() => {
-			expect(mockStore.set).toHaveBeenCalledWith("bearerToken", "tauri-token");
+			expect(mockInvoke).toHaveBeenCalledWith("gateway_set_token", {
+				token: "tauri-token",
+			});
 		});
-		expect(mockStore.save).toHaveBeenCalled();
 	});
 
-	it("clearAuthToken deletes token from store", async () => {
-		mockStore.delete.mockResolvedValue(undefined);
-		mockStore.save.mockResolvedValue(undefined);
+	it("clearAuthToken clears the native gateway's token", async () => {
+		mockInvoke.mockResolvedValue(undefined);
 		const { clearAuthToken } = await import("../auth-token");
 
 		await clearAuthToken();
 
-		expect(mockStore.delete).toHaveBeenCalledWith("bearerToken");
-		expect(mockStore.save).toHaveBeenCalled();
+		expect(mockInvoke).toHaveBeenCalledWith("gateway_set_token", {
+			token: null,
+		});
 	});
 
-	it("loadAuthToken reads token from store into memory", async () => {
-		mockStore.get.mockResolvedValue("stored-token");
+	it("loadAuthToken reads token from the settings store into memory", async () => {
+		mockInvoke.mockResolvedValue("stored-token");
 		const { getAuthToken, loadAuthToken } = await import("../auth-token");
 
 		await loadAuthToken();
 
-		expect(mockStore.get).toHaveBeenCalledWith("bearerToken");
+		expect(mockInvoke).toHaveBeenCalledWith("settings_get", {
+			scope: "auth",
+			key: "bearerToken",
+		});
 		expect(getAuthToken()).toBe("stored-token");
 	});
 
 	it("loadAuthToken does not set cache when store returns null", async () => {
-		mockStore.get.mockResolvedValue(null);
+		mockInvoke.mockResolvedValue(null);
 		const { getAuthToken, loadAuthToken } = await import("../auth-token");
 
 		await loadAuthToken();
@@ -114,8 +107,8 @@ describe("auth-token (Tauri)", () => {
 	it("loadAuthToken silently catches store errors", async () => {
 		vi.resetModules();
 		vi.doMock("../auth", () => ({ isInTauri: () => true }));
-		vi.doMock("@tauri-apps/plugin-store", () => ({
-			load: vi.fn().mockRejectedValue(new Error("Store unavailable")),
+		vi.doMock("@tauri-apps/api/core", () => ({
+			invoke: vi.fn().mockRejectedValue(new Error("Store unavailable")),
 		}));
 
 		const { getAuthToken, loadAuthToken } = await import("../auth-token");
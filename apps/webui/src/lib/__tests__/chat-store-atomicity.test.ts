@@ -1,5 +1,5 @@
 import { beforeEach, describe, expect, it } from "vitest";
-import { useChatStore } from "../chat-store";
+import { findMessageIndexForSeq, useChatStore } from "../chat-store";
 import { type SyncStateStorage, setStorageAdapter } from "../storage-adapter";
 
 type MemoryStorage = {
@@ -86,6 +86,28 @@ describe("chat-store session event atomicity", () => {
 		expect(restored.messages[0]).toMatchObject({ content: "only once" });
 	});
 
+	it("remembers the event that wrote each message, to find it by seq", () => {
+		const store = useChatStore.getState();
+		store.applySessionEventTransaction(
+			{ sessionId: "s1", revision: 1, seq: 1 },
+			(actions) => actions.confirmOrAppendUserMessage("s1", "question"),
+		);
+		store.applySessionEventTransaction(
+			{ sessionId: "s1", revision: 1, seq: 2 },
+			(actions) => actions.appendAssistantChunk("s1", "ans"),
+		);
+		store.applySessionEventTransaction(
+			{ sessionId: "s1", revision: 1, seq: 3 },
+			(actions) => actions.appendAssistantChunk("s1", "wer"),
+		);
+
+		const { messages } = useChatStore.getState().sessions.s1;
+		expect(messages.map((message) => message.eventSeq)).toEqual([1, 2]);
+		expect(findMessageIndexForSeq(messages, 1)).toBe(0);
+		expect(findMessageIndexForSeq(messages, 3)).toBe(1);
+		expect(findMessageIndexForSeq(messages, 0)).toBe(-1);
+	});
+
 	it("rehydrates structured assistant and thought blocks", async () => {
 		const event = { sessionId: "s1", revision: 1, seq: 1 };
 		const image = {
@@ -0,0 +1,43 @@
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
+import { copyOutput, readClipboard } from "../clipboard";
+
+describe("clipboard", () => {
+	const writeText = vi.fn();
+	let clipboardDescriptor: PropertyDescriptor | undefined;
+
+	beforeEach(() => {
+		writeText.mockReset().mockResolvedValue(undefined);
+		clipboardDescriptor = Object.getOwnPropertyDescriptor(
+			navigator,
+			"clipboard",
+		);
+		Object.defineProperty(navigator, "clipboard", {
+			configurable: true,
+			value: { writeText },
+		});
+	});
+
+	afterEach(() => {
+		if (clipboardDescriptor) {
+			Object.defineProperty(navigator, "clipboard", clipboardDescriptor);
+		} else {
+			Reflect.deleteProperty(navigator, "clipboard");
+		}
+	});
+
+	it("copies the plain text of outputs outside the desktop app", async () => {
+		await copyOutput({ kind: "markdown", text: "**Done**" });
+		await copyOutput({ kind: "code", code: "let a = 1;", language: "ts" });
+		await copyOutput({ kind: "diff", path: "a.ts", diff: "-a\n+b\n" });
+
+		expect(writeText.mock.calls).toEqual([
+			["**Done**"],
+			["let a = 1;"],
+			["-a\n+b\n"],
+		]);
+	});
+
+	it("does not read the clipboard natively outside the desktop app", async () => {
+		await expect(readClipboard()).resolves.toBeNull();
+	});
+});
@@ -0,0 +1,100 @@
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
+import {
+	acceptPairing,
+	listenDeepLinks,
+	rejectPairing,
+	sessionPath,
+} from "../deep-link";
+
+const invoke = vi.hoisted(() => vi.fn());
+const listeners = vi.hoisted(
+	() => new Map<string, (event: { payload: unknown }) => void>(),
+);
+const unlisten = vi.hoisted(() => vi.fn());
+
+vi.mock("@tauri-apps/api/core", () => ({ invoke }));
+vi.mock("@tauri-apps/api/event", () => ({
+	listen: vi.fn(
+		async (event: string, handler: (event: { payload: unknown }) => void) => {
+			listeners.set(event, handler);
+			return unlisten;
+		},
+	),
+}));
+
+describe("deep-link", () => {
+	beforeEach(() => {
+		invoke.mockReset();
+		listeners.clear();
+		unlisten.mockReset();
+	});
+
+	afterEach(() => {
+		Reflect.deleteProperty(window, "__TAURI_INTERNALS__");
+	});
+
+	it("does nothing outside the desktop app", async () => {
+		await expect(listenDeepLinks(vi.fn(), vi.fn())).resolves.toBeNull();
+		expect(invoke).not.toHaveBeenCalled();
+	});
+
+	it("delivers cold-start links, then live routes and errors", async () => {
+		Object.defineProperty(window, "__TAURI_INTERNALS__", {
+			configurable: true,
+			value: {},
+		});
+		invoke.mockImplementation(async () => {
+			// Listeners are in place before the queue is drained.
+			expect(listeners.has("deep-link:route")).toBe(true);
+			return [{ type: "session", sessionId: "s1" }];
+		});
+		const onRoute = vi.fn();
+		const onError = vi.fn();
+
+		const stop = await listenDeepLinks(onRoute, onError);
+
+		expect(invoke).toHaveBeenCalledWith("deep_link_take_pending");
+		expect(onRoute).toHaveBeenCalledWith({ type: "session", sessionId: "s1" });
+
+		listeners.get("deep-link:route")?.({
+			payload: { type: "pair", id: 1, fingerprint: "AbCd" },
+		});
+		listeners.get("deep-link:error")?.({
+			payload: { reason: "Invalid link: missing secret" },
+		});
+		expect(onRoute).toHaveBeenLastCalledWith({
+			type: "pair",
+			id: 1,
+			fingerprint: "AbCd",
+		});
+		expect(onError).toHaveBeenCalledWith({
+			reason: "Invalid link: missing secret",
+		});
+
+		stop?.();
+		expect(unlisten).toHaveBeenCalledTimes(2);
+	});
+
+	it("maps session routes to the main window's session path", () => {
+		expect(sessionPath({ type: "session", sessionId: "a b", seq: 3 })).toBe(
+			"/?sessionId=a%20b&seq=3",
+		);
+		expect(sessionPath({ type: "session", sessionId: "s1" })).toBe(
+			"/?sessionId=s1",
+		);
+		expect(
+			sessionPath({ type: "gateway", url: "https://gw", host: "gw" }),
+		).toBeNull();
+	});
+
+	it("answers pairing links by id", async () => {
+		await acceptPairing(3);
+		await rejectPairing(4);
+		expect(invoke).toHaveBeenNthCalledWith(1, "deep_link_accept_pair", {
+			id: 3,
+		});
+		expect(invoke).toHaveBeenNthCalledWith(2, "deep_link_reject_pair", {
+			id: 4,
+		});
+	});
+});
@@ -1,5 +1,5 @@
 import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
-import { registerHotkeys } from "../hotkeys";
+import { registerHotkeys, runHotkeyCommand } from "../hotkeys";
 
 /**
  * Detects if we're on a Mac platform based on userAgent.
@@ -163,4 +163,43 @@ describe("registerHotkeys", () => {
 			expect(mockHandler).not.toHaveBeenCalled();
 		});
 	});
+
+	describe("Menu Commands", () => {
+		it("runs the handler registered for a command id", () => {
+			const mockHandler = vi.fn();
+			cleanup = registerHotkeys([
+				{
+					command: "sidebar.toggle",
+					key: "b",
+					mod: true,
+					handler: mockHandler,
+				},
+			]);
+
+			expect(runHotkeyCommand("sidebar.toggle")).toBe(true);
+			expect(mockHandler).toHaveBeenCalledTimes(1);
+			expect(runHotkeyCommand("palette.open")).toBe(false);
+
+			cleanup();
+			cleanup = null;
+			expect(runHotkeyCommand("sidebar.toggle")).toBe(false);
+		});
+
+		it("drops the menu echo of a shortcut that just ran", () => {
+			const mockHandler = vi.fn();
+			cleanup = registerHotkeys([
+				{
+					command: "palette.open",
+					key: "k",
+					mod: true,
+					handler: mockHandler,
+				},
+			]);
+
+			window.dispatchEvent(createModKeyEvent("k"));
+			runHotkeyCommand("palette.open");
+
+			expect(mockHandler).toHaveBeenCalledTimes(1);
+		});
+	});
 });
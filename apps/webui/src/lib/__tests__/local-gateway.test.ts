@@ -0,0 +1,56 @@
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
+
+const invoke = vi.hoisted(() => vi.fn());
+const LOCAL_GATEWAY_KEY = "__MOBVIBE_LOCAL_GATEWAY__";
+
+vi.mock("@tauri-apps/api/core", () => ({ invoke }));
+vi.mock("../auth", () => ({ isInTauri: () => true }));
+
+const status = (port: number | null) => ({
+	running: port !== null,
+	port,
+	url: port === null ? null : `http://localhost:${port}`,
+	machines: [],
+});
+
+describe("local gateway token", () => {
+	beforeEach(() => {
+		vi.resetModules();
+		invoke.mockReset();
+		Object.defineProperty(window, LOCAL_GATEWAY_KEY, {
+			configurable: true,
+			value: { token: "t0k", port: 3005 },
+		});
+	});
+
+	afterEach(() => {
+		Reflect.deleteProperty(window, LOCAL_GATEWAY_KEY);
+	});
+
+	it("is only handed to the running embedded gateway", async () => {
+		invoke.mockResolvedValue(status(3005));
+		const { localGatewayToken } = await import("../local-gateway");
+
+		expect(localGatewayToken("http://localhost:3005")).toBe("t0k");
+		expect(localGatewayToken("http://127.0.0.1:3005/")).toBe("t0k");
+		expect(localGatewayToken("http://localhost:3006")).toBeNull();
+		expect(localGatewayToken("https://gateway.example.com:3005")).toBeNull();
+	});
+
+	it("follows the server as it starts and stops", async () => {
+		invoke.mockResolvedValue(status(null));
+		const { localGatewayToken, startLocalGateway, stopLocalGateway } =
+			await import("../local-gateway");
+		await vi.waitFor(() =>
+			expect(localGatewayToken("http://localhost:3005")).toBeNull(),
+		);
+
+		invoke.mockResolvedValue(status(4000));
+		await startLocalGateway(4000);
+		expect(localGatewayToken("http://localhost:4000")).toBe("t0k");
+
+		invoke.mockResolvedValue(undefined);
+		await stopLocalGateway();
+		expect(localGatewayToken("http://localhost:4000")).toBeNull();
+	});
+});
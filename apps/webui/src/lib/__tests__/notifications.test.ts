@@ -0,0 +1,63 @@
+import { beforeEach, describe, expect, it, vi } from "vitest";
+
+const invoke = vi.hoisted(() => vi.fn());
+const sendNotification = vi.hoisted(() => vi.fn());
+const isMobilePlatform = vi.hoisted(() => vi.fn());
+
+vi.mock("@tauri-apps/api/core", () => ({ invoke }));
+vi.mock("@tauri-apps/plugin-notification", () => ({
+	isPermissionGranted: vi.fn().mockResolvedValue(true),
+	requestPermission: vi.fn(),
+	sendNotification,
+}));
+vi.mock("@/lib/auth", () => ({ isInTauri: () => true }));
+vi.mock("@/lib/platform", () => ({ isMobilePlatform }));
+vi.mock("@/lib/notification-store", () => ({
+	useNotificationStore: { getState: () => ({ pushNotification: vi.fn() }) },
+}));
+
+const { notifyPermissionRequest } = await import("../notifications");
+
+const request = {
+	sessionId: "s1",
+	requestId: "r1",
+	options: [{ optionId: "a", name: "Allow", kind: "allow_once" as const }],
+	toolCall: { toolCallId: "t1", title: "Edit main.rs" },
+};
+const sessions = { s1: { sessionId: "s1", title: "Refactor" } };
+
+describe("notifyPermissionRequest in the desktop app", () => {
+	beforeEach(() => {
+		invoke.mockReset();
+		sendNotification.mockReset();
+		isMobilePlatform.mockResolvedValue(false);
+	});
+
+	it("asks Rust for an actionable notification in its language", async () => {
+		invoke.mockResolvedValue(undefined);
+
+		notifyPermissionRequest(request, { sessions });
+
+		await vi.waitFor(() =>
+			expect(invoke).toHaveBeenCalledWith(
+				"notifications_permission_request",
+				{
+					request,
+					text: expect.objectContaining({
+						title: expect.stringContaining("Refactor"),
+						body: expect.stringContaining("Edit main.rs"),
+					}),
+				},
+			),
+		);
+		expect(sendNotification).not.toHaveBeenCalled();
+	});
+
+	it("falls back to the plugin when Rust cannot show one", async () => {
+		invoke.mockRejectedValue("Notification error: no server");
+
+		notifyPermissionRequest(request, { sessions });
+
+		await vi.waitFor(() => expect(sendNotification).toHaveBeenCalledTimes(1));
+	});
+});
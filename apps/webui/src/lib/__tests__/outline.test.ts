@@ -0,0 +1,53 @@
+import { beforeEach, describe, expect, it, vi } from "vitest";
+
+const invoke = vi.hoisted(() => vi.fn());
+const isInTauri = vi.hoisted(() => vi.fn());
+
+vi.mock("@tauri-apps/api/core", () => ({ invoke }));
+vi.mock("@/lib/auth", () => ({ isInTauri }));
+
+const { extractOutline } = await import("../outline");
+
+describe("extractOutline", () => {
+	beforeEach(() => {
+		invoke.mockReset();
+		isInTauri.mockReturnValue(true);
+	});
+
+	it("parses in the desktop app", async () => {
+		const items = [
+			{
+				id: "function-0-16",
+				label: "main",
+				kind: "function",
+				startIndex: 0,
+				endIndex: 16,
+				startLine: 1,
+				endLine: 1,
+				children: [],
+			},
+		];
+		invoke.mockResolvedValue(items);
+
+		await expect(extractOutline("rust", "fn main() {}")).resolves.toEqual(
+			items,
+		);
+		expect(invoke).toHaveBeenCalledWith("outline_extract", {
+			language: "rust",
+			content: "fn main() {}",
+		});
+	});
+
+	it("leaves parsing to the webview elsewhere", async () => {
+		isInTauri.mockReturnValue(false);
+
+		await expect(extractOutline("rust", "fn main() {}")).resolves.toBeNull();
+		expect(invoke).not.toHaveBeenCalled();
+	});
+
+	it("leaves parsing to the webview where the command is missing", async () => {
+		invoke.mockRejectedValue("Command outline_extract not found");
+
+		await expect(extractOutline("go", "package main")).resolves.toBeNull();
+	});
+});
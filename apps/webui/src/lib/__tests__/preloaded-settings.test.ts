@@ -0,0 +1,36 @@
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
+
+const PRELOAD_KEY = "__MOBVIBE_PRELOAD__";
+
+type PreloadWindow = Record<string, unknown>;
+
+describe("preloaded settings", () => {
+	beforeEach(() => {
+		vi.resetModules();
+		sessionStorage.clear();
+	});
+
+	afterEach(() => {
+		delete (window as unknown as PreloadWindow)[PRELOAD_KEY];
+	});
+
+	it("keeps only the injected scopes, so tokens never reach storage", async () => {
+		(window as unknown as PreloadWindow)[PRELOAD_KEY] = {
+			"app-state": {},
+			gateway: {},
+		};
+		const { getPreloadedEntries, updatePreloaded } = await import(
+			"../preloaded-settings"
+		);
+
+		updatePreloaded("gateway", "gatewayUrl", "https://gw");
+		updatePreloaded("auth", "bearerToken", "secret");
+		window.dispatchEvent(new Event("pagehide"));
+
+		expect(getPreloadedEntries("gateway")).toEqual({
+			gatewayUrl: "https://gw",
+		});
+		expect(getPreloadedEntries("auth")).toBeNull();
+		expect(sessionStorage.getItem(PRELOAD_KEY)).not.toContain("secret");
+	});
+});
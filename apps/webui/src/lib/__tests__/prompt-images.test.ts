@@ -1,4 +1,4 @@
-import { PROMPT_IMAGE_MAX_BYTES } from "@mobvibe/shared";
+import { PROMPT_IMAGE_MAX_BYTES, PROMPT_IMAGE_MAX_EDGE } from "@mobvibe/shared";
 import {
 	afterAll,
 	beforeAll,
@@ -10,6 +10,9 @@ import {
 } from "vitest";
 import { normalizeImageFileForPrompt } from "../prompt-images";
 
+const invoke = vi.hoisted(() => vi.fn());
+vi.mock("@tauri-apps/api/core", () => ({ invoke }));
+
 type MockImageState = { naturalWidth: number; naturalHeight: number } | "error";
 
 type CanvasBlobRequest = {
@@ -285,4 +288,45 @@ describe("prompt-images", () => {
 			`Image exceeds ${PROMPT_IMAGE_MAX_BYTES / 1024} KiB after normalization`,
 		);
 	});
+
+	it("normalizes through the app when running inside it", async () => {
+		const file = registerDataUrl(
+			new File(["jpeg"], "photo.jpg", { type: "image/jpeg" }),
+			"data:image/jpeg;base64,cGhvdG8=",
+		);
+		invoke.mockResolvedValueOnce({
+			type: "image",
+			data: "c3RyaXBwZWQ=",
+			mimeType: "image/webp",
+			width: 1200,
+			height: 1600,
+		});
+		Object.defineProperty(window, "__TAURI_INTERNALS__", {
+			configurable: true,
+			value: {},
+		});
+		try {
+			await expect(normalizeImageFileForPrompt(file)).resolves.toEqual({
+				type: "image",
+				mimeType: "image/webp",
+				data: "c3RyaXBwZWQ=",
+				uri: null,
+			});
+			expect(invoke).toHaveBeenCalledWith("prompt_image_normalize", {
+				data: "cGhvdG8=",
+				options: {
+					maxEdge: PROMPT_IMAGE_MAX_EDGE,
+					maxBytes: PROMPT_IMAGE_MAX_BYTES,
+				},
+			});
+			expect(mockContext.drawImage).not.toHaveBeenCalled();
+
+			invoke.mockRejectedValueOnce("Image error: Unrecognized image format");
+			await expect(normalizeImageFileForPrompt(file)).rejects.toThrow(
+				"Image error: Unrecognized image format",
+			);
+		} finally {
+			Reflect.deleteProperty(window, "__TAURI_INTERNALS__");
+		}
+	});
 });
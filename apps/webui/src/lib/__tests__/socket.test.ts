@@ -97,7 +97,6 @@ describe("gatewaySocket connect() auth branches", () => {
 
 	it("uses withCredentials in browser environment", async () => {
 		vi.doMock("../auth", () => ({ isInTauri: () => false }));
-		vi.doMock("../auth-token", () => ({ getAuthToken: () => null }));
 
 		const { gatewaySocket: gs } = await import("../socket");
 		gs.connect();
@@ -113,24 +112,62 @@ describe("gatewaySocket connect() auth branches", () => {
 		expect(callOpts.auth).toBeUndefined();
 	});
 
-	it("uses auth token in Tauri environment", async () => {
+	it("listens to the native gateway in Tauri environment", async () => {
+		const channels: { onmessage: (message: unknown) => void }[] = [];
+		const invoke = vi.fn(async (command: string) =>
+			command === "gateway_listen" ? 7 : undefined,
+		);
 		vi.doMock("../auth", () => ({ isInTauri: () => true }));
-		vi.doMock("../auth-token", () => ({
-			getAuthToken: () => "tauri-test-token",
+		vi.doMock("@tauri-apps/api/core", () => ({
+			invoke,
+			Channel: class {
+				onmessage: (message: unknown) => void = () => {};
+				constructor() {
+					channels.push(this);
+				}
+			},
 		}));
 
 		const { gatewaySocket: gs } = await import("../socket");
+		const onConnect = vi.fn();
+		const onDisconnect = vi.fn();
+		const onCliStatus = vi.fn();
+		gs.onConnect(onConnect);
+		gs.onDisconnect(onDisconnect);
 		gs.connect();
+		gs.onCliStatus(onCliStatus);
+		await vi.waitFor(() =>
+			expect(invoke).toHaveBeenCalledWith("gateway_listen", {
+				onEvent: channels[0],
+			}),
+		);
 
-		expect(mockIo).toHaveBeenCalledWith(
-			expect.any(String),
-			expect.objectContaining({
-				transports: ["websocket"],
-				auth: { token: "tauri-test-token" },
-				query: { bearerToken: "tauri-test-token" },
+		const [channel] = channels;
+		channel.onmessage({
+			event: "connection",
+			payload: { state: "connected", error: null },
+		});
+		channel.onmessage({ event: "cli:status", payload: { machineId: "m1" } });
+		channel.onmessage({
+			event: "connection",
+			payload: { state: "reconnecting", error: "closed" },
+		});
+		gs.subscribeToSession("s1");
+
+		expect(mockIo).not.toHaveBeenCalled();
+		expect(onConnect).toHaveBeenCalledTimes(1);
+		expect(onCliStatus).toHaveBeenCalledWith({ machineId: "m1" });
+		expect(onDisconnect).toHaveBeenCalledWith("closed");
+		expect(gs.isConnected()).toBe(false);
+		await vi.waitFor(() =>
+			expect(invoke).toHaveBeenCalledWith("gateway_subscribe_session", {
+				sessionId: "s1",
 			}),
 		);
-		const callOpts = mockIo.mock.calls[0][1];
-		expect(callOpts.withCredentials).toBeUndefined();
+
+		gs.destroy();
+		await vi.waitFor(() =>
+			expect(invoke).toHaveBeenCalledWith("gateway_unlisten", { id: 7 }),
+		);
 	});
 });
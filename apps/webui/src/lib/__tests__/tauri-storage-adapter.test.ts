@@ -0,0 +1,64 @@
+import { afterEach, beforeEach, describe, expect, it, vi } from "vitest";
+
+const mockInvoke = vi.fn();
+const PRELOAD_KEY = "__MOBVIBE_PRELOAD__";
+
+type PreloadWindow = Record<string, unknown>;
+
+describe("tauri storage adapter", () => {
+	beforeEach(() => {
+		vi.resetModules();
+		vi.doMock("@tauri-apps/api/core", () => ({ invoke: mockInvoke }));
+		mockInvoke.mockReset();
+		mockInvoke.mockResolvedValue(undefined);
+	});
+
+	afterEach(() => {
+		delete (window as unknown as PreloadWindow)[PRELOAD_KEY];
+		vi.restoreAllMocks();
+	});
+
+	it("serves preloaded state on the first read without IPC", async () => {
+		(window as unknown as PreloadWindow)[PRELOAD_KEY] = {
+			"app-state": { "chat-store": '{"state":{}}' },
+		};
+		const { getStorageAdapter } = await import("../storage-adapter");
+
+		expect(getStorageAdapter().getItem("chat-store")).toBe('{"state":{}}');
+		expect(mockInvoke).not.toHaveBeenCalled();
+	});
+
+	it("writes single keys in order and keeps the preload current", async () => {
+		(window as unknown as PreloadWindow)[PRELOAD_KEY] = { "app-state": {} };
+		const { getStorageAdapter } = await import("../storage-adapter");
+		const adapter = getStorageAdapter();
+
+		adapter.setItem("a", "1");
+		adapter.removeItem("a");
+
+		await vi.waitFor(() => {
+			expect(mockInvoke).toHaveBeenCalledTimes(2);
+		});
+		expect(mockInvoke.mock.calls.map(([command]) => command)).toEqual([
+			"settings_set",
+			"settings_delete",
+		]);
+		const { getPreloadedEntries } = await import("../preloaded-settings");
+		expect(getPreloadedEntries("app-state")).toEqual({});
+	});
+
+	it("falls back to loading from the settings store without a preload", async () => {
+		mockInvoke.mockResolvedValue({ "chat-store": "{}" });
+		const { createTauriStorageAdapter } = await import(
+			"../tauri-storage-adapter"
+		);
+		const adapter = createTauriStorageAdapter();
+
+		await vi.waitFor(() => {
+			expect(adapter.getItem("chat-store")).toBe("{}");
+		});
+		expect(mockInvoke).toHaveBeenCalledWith("settings_entries", {
+			scope: "app-state",
+		});
+	});
+});
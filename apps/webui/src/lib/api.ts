@@ -68,6 +68,7 @@ import { getAuthToken } from "./auth-token";
 import { e2ee } from "./e2ee";
 import { createFallbackError } from "./error-utils";
 import { getDefaultGatewayUrl } from "./gateway-config";
+import { localGatewayToken } from "./local-gateway";
 import { platformFetch } from "./tauri-fetch";
 
 let API_BASE_URL = getDefaultGatewayUrl();
@@ -122,11 +123,11 @@ type RequestTransport = {
 	headers: Record<string, string>;
 };
 
-const createRequestTransport = (): RequestTransport => {
+const createRequestTransport = (baseUrl: string): RequestTransport => {
 	const tauriEnv = isInTauri();
 	const headers: Record<string, string> = {};
 	if (tauriEnv) {
-		const token = getAuthToken();
+		const token = localGatewayToken(baseUrl) ?? getAuthToken();
 		if (token) {
 			headers.Authorization = `Bearer ${token}`;
 		}
@@ -341,7 +342,7 @@ const requestJson = async <ResponseType>(
 ): Promise<ResponseType> => {
 	const baseUrl = API_BASE_URL;
 	const state = ownerRoutingState;
-	const transport = createRequestTransport();
+	const transport = createRequestTransport(baseUrl);
 	const routedRequest = isOwnerRoutedPath(path);
 	const initialOwnerId = routedRequest
 		? await resolveOwner(state, transport, options?.signal)
@@ -775,7 +776,7 @@ export const sendMessage = async (payload: {
 	encryptionRequired: boolean;
 }): Promise<SendMessageResult> => {
 	const { revision, encryptionRequired, ...requestPayload } = payload;
-	const encryptedPrompt = e2ee.encryptPayloadForSession(
+	const encryptedPrompt = await e2ee.encryptPayloadForSession(
 		payload.sessionId,
 		payload.prompt,
 		revision,
@@ -0,0 +1,55 @@
+import { isInTauri } from "./auth";
+import type { HotkeyCommand } from "./hotkeys";
+
+/**
+ * The native application menu of the desktop app (`app_menu_*` commands).
+ * Its items send hotkey command ids to the focused window; each window
+ * reports its language and the commands that do not apply to it, again
+ * whenever it comes to the front.
+ */
+
+async function invokeAppMenu<T>(
+	command: string,
+	args?: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
+/**
+ * Calls `onCommand` for menu items chosen while this window is in front.
+ * Resolves to a no-op where there is no app menu.
+ */
+export async function listenAppMenu(
+	onCommand: (command: HotkeyCommand) => void,
+): Promise<() => void> {
+	if (!isInTauri()) return () => {};
+	try {
+		const { Channel } = await import("@tauri-apps/api/core");
+		const channel = new Channel<HotkeyCommand>();
+		let active = true;
+		channel.onmessage = (command) => {
+			if (active) onCommand(command);
+		};
+		await invokeAppMenu("app_menu_listen", { onCommand: channel });
+		return () => {
+			active = false;
+		};
+	} catch {
+		// Mobile builds do not register the command.
+		return () => {};
+	}
+}
+
+/** Relabels the menu for `language` and greys out `disabled` commands. */
+export async function updateAppMenu(
+	language: string,
+	disabled: HotkeyCommand[],
+): Promise<void> {
+	if (!isInTauri()) return;
+	try {
+		await invokeAppMenu("app_menu_update", { language, disabled });
+	} catch {
+		// Mobile builds do not register the command.
+	}
+}
@@ -1,5 +1,5 @@
 import { isInTauri } from "./auth";
-import { tauriStoreDelete, tauriStoreGet, tauriStoreSet } from "./tauri-store";
+import { settingsGet } from "./settings-store";
 
 let tokenCache: string | null = null;
 
@@ -8,21 +8,21 @@ export const getAuthToken = (): string | null => tokenCache;
 export const setAuthToken = (token: string): void => {
 	tokenCache = token;
 	if (isInTauri()) {
-		void persistToken(token);
+		void setGatewayToken(token);
 	}
 };
 
 export const clearAuthToken = async (): Promise<void> => {
 	tokenCache = null;
 	if (isInTauri()) {
-		await clearPersistedToken();
+		await setGatewayToken(null);
 	}
 };
 
 export const loadAuthToken = async (): Promise<void> => {
 	if (!isInTauri()) return;
 	try {
-		const token = await tauriStoreGet<string>("auth.json", "bearerToken");
+		const token = await settingsGet<string>("auth", "bearerToken");
 		if (token) {
 			tokenCache = token;
 		}
@@ -31,17 +31,14 @@ export const loadAuthToken = async (): Promise<void> => {
 	}
 };
 
-const persistToken = async (token: string): Promise<void> => {
+/**
+ * The gateway connection in Rust persists the token and reconnects with
+ * it, so the two never disagree about who is signed in.
+ */
+const setGatewayToken = async (token: string | null): Promise<void> => {
 	try {
-		await tauriStoreSet("auth.json", "bearerToken", token);
-	} catch {
-		// Store not available
-	}
-};
-
-const clearPersistedToken = async (): Promise<void> => {
-	try {
-		await tauriStoreDelete("auth.json", "bearerToken");
+		const { invoke } = await import("@tauri-apps/api/core");
+		await invoke("gateway_set_token", { token });
 	} catch {
 		// Store not available
 	}
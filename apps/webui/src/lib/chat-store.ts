@@ -186,12 +186,32 @@ export type StatusMessage = {
 	isStreaming: false;
 };
 
-export type ChatMessage =
+export type ChatMessage = (
 	| TextMessage
 	| ThoughtMessage
 	| PermissionMessage
 	| ToolCallMessage
-	| StatusMessage;
+	| StatusMessage
+) & {
+	/** Seq of the session event that first wrote the message. */
+	eventSeq?: number;
+};
+
+/**
+ * The message showing a session event: the last one written at or before
+ * its seq, as later events of a message (chunks, tool call updates) keep
+ * the seq of the event that started it. -1 when none is.
+ */
+export const findMessageIndexForSeq = (
+	messages: ChatMessage[],
+	seq: number,
+): number => {
+	for (let index = messages.length - 1; index >= 0; index -= 1) {
+		const eventSeq = messages[index].eventSeq;
+		if (eventSeq !== undefined && eventSeq <= seq) return index;
+	}
+	return -1;
+};
 
 export type TerminalOutputSnapshot = {
 	terminalId: string;
@@ -1045,6 +1065,32 @@ const partializeChatState = (state: ChatState): PersistedChatState => ({
 	lastCreatedCwd: state.lastCreatedCwd,
 });
 
+/** Stamps the messages an event wrote with its seq. */
+const stampEventSeq = (
+	state: ChatState,
+	previous: ChatMessage[],
+	event: Pick<SessionEvent, "sessionId" | "seq">,
+): ChatState => {
+	const session = state.sessions[event.sessionId];
+	if (!session || session.messages === previous) return state;
+	let stamped = false;
+	const messages = session.messages.map((message, index) => {
+		if (message.eventSeq !== undefined || message === previous[index]) {
+			return message;
+		}
+		stamped = true;
+		return { ...message, eventSeq: event.seq };
+	});
+	if (!stamped) return state;
+	return {
+		...state,
+		sessions: {
+			...state.sessions,
+			[event.sessionId]: { ...session, messages },
+		},
+	};
+};
+
 type ChatStateSetter = Parameters<StateCreator<ChatState>>[0];
 type ChatStateUpdate =
 	| ChatState
@@ -1100,6 +1146,11 @@ const withSessionEventTransactions = (
 					transactionState = state;
 					try {
 						applyEvent(actions);
+						transactionState = stampEventSeq(
+							transactionState,
+							session.messages,
+							event,
+						);
 						actions.updateSessionCursor(
 							event.sessionId,
 							event.revision,
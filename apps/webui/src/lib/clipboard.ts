@@ -0,0 +1,91 @@
+import type { ContentBlock } from "@/lib/acp";
+import { isInTauri } from "./auth";
+
+/**
+ * The system clipboard through the desktop app (`clipboard_*` commands).
+ * Paste events in some WebViews carry no image, so the composer asks Rust
+ * for what was copied; copying agent output writes HTML next to the plain
+ * text so rich editors keep the formatting. Elsewhere copying falls back
+ * to plain text.
+ */
+
+type ImageContent = Extract<ContentBlock, { type: "image" }>;
+
+export type ClipboardContents = {
+	images: ImageContent[];
+	text: string | null;
+};
+
+export type ClipboardOutput =
+	| { kind: "markdown"; text: string }
+	| { kind: "code"; code: string; language?: string }
+	| {
+			kind: "diff";
+			path: string;
+			diff?: string;
+			oldText?: string | null;
+			newText?: string;
+	  };
+
+async function invokeClipboard<T>(
+	command: string,
+	args?: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
+/** What is on the clipboard; null where it cannot be read natively. */
+export async function readClipboard(): Promise<ClipboardContents | null> {
+	if (!isInTauri()) return null;
+	try {
+		return await invokeClipboard<ClipboardContents>("clipboard_read");
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+}
+
+const plainText = (output: ClipboardOutput) => {
+	switch (output.kind) {
+		case "markdown":
+			return output.text;
+		case "code":
+			return output.code;
+		case "diff":
+			return output.diff ?? output.newText ?? "";
+	}
+};
+
+export async function writePlainText(text: string): Promise<void> {
+	if (typeof navigator !== "undefined" && navigator.clipboard?.writeText) {
+		try {
+			await navigator.clipboard.writeText(text);
+			return;
+		} catch {
+			// Fall through to the selection-based copy.
+		}
+	}
+	const textarea = document.createElement("textarea");
+	textarea.value = text;
+	textarea.setAttribute("readonly", "true");
+	textarea.style.position = "fixed";
+	textarea.style.opacity = "0";
+	document.body.appendChild(textarea);
+	textarea.select();
+	document.execCommand("copy");
+	document.body.removeChild(textarea);
+}
+
+/** Copies agent output, as text and HTML where the app can. */
+export async function copyOutput(output: ClipboardOutput): Promise<void> {
+	if (isInTauri()) {
+		try {
+			await invokeClipboard("clipboard_write_output", { output });
+			return;
+		} catch {
+			// Mobile builds do not register the command.
+		}
+	}
+	await writePlainText(plainText(output));
+}
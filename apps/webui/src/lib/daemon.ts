@@ -0,0 +1,92 @@
+import { isInTauri } from "./auth";
+
+/**
+ * The local `mobvibe-cli` daemon, managed by the desktop app (`daemon_*`
+ * commands). Start and stop run the CLI itself, so they behave exactly like
+ * `mobvibe start` and `mobvibe stop` in a terminal.
+ */
+
+/** `unverified`: a process holds the PID but its identity was not checked. */
+export type DaemonState = "running" | "stopped" | "unverified";
+
+export type DaemonStatus = {
+	state: DaemonState;
+	pid: number | null;
+	/** `MOBVIBE_HOME`. */
+	home: string;
+	/** The `mobvibe` executable in use; `null` if none was found. */
+	cliPath: string | null;
+	logFile: string | null;
+};
+
+export type DaemonLogEvent =
+	| { kind: "file"; path: string }
+	| { kind: "lines"; lines: string[] };
+
+async function invokeDaemon<T>(
+	command: string,
+	args?: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
+export async function getDaemonStatus(): Promise<DaemonStatus | null> {
+	if (!isInTauri()) return null;
+	try {
+		return await invokeDaemon<DaemonStatus>("daemon_status");
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+}
+
+/**
+ * The machine id this machine's CLI registers under, for telling which
+ * sessions run here; null outside the desktop app.
+ */
+export async function getLocalMachineId(): Promise<string | null> {
+	if (!isInTauri()) return null;
+	try {
+		return await invokeDaemon<string>("daemon_machine_id");
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+}
+
+/** Starts the daemon against the gateway the app is connected to. */
+export function startDaemon(options?: {
+	noE2ee?: boolean;
+}): Promise<DaemonStatus> {
+	return invokeDaemon("daemon_start", { noE2ee: options?.noE2ee });
+}
+
+export function stopDaemon(): Promise<DaemonStatus> {
+	return invokeDaemon("daemon_stop");
+}
+
+/** Pins the `mobvibe` executable; `null` goes back to searching `PATH`. */
+export function setDaemonCliPath(path: string | null): Promise<DaemonStatus> {
+	return invokeDaemon("daemon_set_cli_path", { path });
+}
+
+/**
+ * Streams the last `lines` log lines and then everything the daemon writes.
+ * Resolves to a function that stops the stream.
+ */
+export async function watchDaemonLogs(
+	onEvent: (event: DaemonLogEvent) => void,
+	lines?: number,
+): Promise<() => void> {
+	const { Channel } = await import("@tauri-apps/api/core");
+	const channel = new Channel<DaemonLogEvent>();
+	channel.onmessage = onEvent;
+	const id = await invokeDaemon<number>("daemon_watch_logs", {
+		lines,
+		onEvent: channel,
+	});
+	return () => {
+		void invokeDaemon("daemon_unwatch_logs", { id });
+	};
+}
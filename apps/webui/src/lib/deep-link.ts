@@ -0,0 +1,73 @@
+import { isInTauri } from "./auth";
+
+/**
+ * `mobvibe://` links, parsed and validated in Rust (`deep_link` module).
+ * The webview gets typed routes on `deep-link:route`, or a reason on
+ * `deep-link:error`. Pairing and gateway links only take effect once the
+ * user confirms them: a pairing secret stays in Rust until
+ * `acceptPairing`, and only its fingerprint reaches the webview. Tray
+ * items, notifications and search hits navigate through the same event.
+ */
+
+export type DeepLinkRoute =
+	| { type: "pair"; id: number; fingerprint: string }
+	| { type: "session"; sessionId: string; seq?: number }
+	| { type: "gateway"; url: string; host: string }
+	| { type: "newSession"; cwd: string | null; prompt: string | null };
+
+export type DeepLinkError = {
+	reason: string;
+};
+
+/**
+ * Delivers routes until the returned function is called, starting with
+ * the ones that arrived before the webview was listening (the link that
+ * cold-started the app). Resolves to null outside the app.
+ */
+export async function listenDeepLinks(
+	onRoute: (route: DeepLinkRoute) => void,
+	onError: (error: DeepLinkError) => void,
+): Promise<(() => void) | null> {
+	if (!isInTauri()) return null;
+	const { listen } = await import("@tauri-apps/api/event");
+	const { invoke } = await import("@tauri-apps/api/core");
+	const unlistenRoute = await listen<DeepLinkRoute>(
+		"deep-link:route",
+		(event) => onRoute(event.payload),
+	);
+	const unlistenError = await listen<DeepLinkError>(
+		"deep-link:error",
+		(event) => onError(event.payload),
+	);
+	// Listen first: once drained, Rust emits instead of queueing.
+	const pending = await invoke<DeepLinkRoute[]>("deep_link_take_pending");
+	for (const route of pending) {
+		onRoute(route);
+	}
+	return () => {
+		unlistenRoute();
+		unlistenError();
+	};
+}
+
+/** Pairs the secret of a `pair` route the user confirmed. */
+export async function acceptPairing(id: number): Promise<void> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	await invoke("deep_link_accept_pair", { id });
+}
+
+/** Drops the secret of a `pair` route the user declined. */
+export async function rejectPairing(id: number): Promise<void> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	await invoke("deep_link_reject_pair", { id });
+}
+
+/**
+ * The path that shows a route's session, scrolled to its event when the
+ * route names one; null for other routes.
+ */
+export function sessionPath(route: DeepLinkRoute): string | null {
+	if (route.type !== "session") return null;
+	const path = `/?sessionId=${encodeURIComponent(route.sessionId)}`;
+	return route.seq === undefined ? path : `${path}&seq=${route.seq}`;
+}
@@ -12,14 +12,59 @@ import {
 } from "@mobvibe/shared";
 import type { SessionEvent } from "@/lib/acp";
 import { isInTauri } from "@/lib/auth";
-import {
-	tauriStoreDelete,
-	tauriStoreGet,
-	tauriStoreSet,
-} from "@/lib/tauri-store";
 
 export type E2EEStatus = "none" | "ok" | "missing_key";
 
+/**
+ * A paired device as the UI sees it. In the desktop and mobile app the
+ * master secrets live in the Rust vault (`vault_*` commands) and only
+ * these fingerprints reach the webview; session DEKs are unwrapped by
+ * Rust (`e2ee_unwrap_session_deks`) and never leave it, so events are
+ * decrypted and prompts encrypted there too.
+ */
+export interface PairedDevice {
+	fingerprint: string;
+	addedAt: number;
+}
+
+export type VaultStatus = {
+	backend: "keyring" | "file";
+	locked: boolean;
+	/** Why the keyring could not be read; it stays locked until it can. */
+	error?: string;
+};
+
+type WrappedSessionDek = {
+	sessionId: string;
+	wrappedDek?: string;
+	revision?: number;
+};
+
+/** A session revision whose DEK Rust holds. */
+type UnwrappedSession = {
+	sessionId: string;
+	revision: number | null;
+};
+
+/**
+ * One event of a decrypted batch; with `error`, the event as received.
+ */
+export type DecryptedEvent = {
+	event: SessionEvent;
+	error?: string;
+};
+
+const MISSING_SESSION_KEY_MESSAGE =
+	"Cannot send without a matching session encryption key. Reload the session or pair the correct device key, then try again.";
+
+async function invokeE2EE<T>(
+	command: string,
+	args?: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
 const STORAGE_KEY = "mobvibe_e2ee_secrets";
 const LEGACY_STORAGE_KEY = "mobvibe_e2ee_master_secret";
 
@@ -31,8 +76,11 @@ interface StoredSecret {
 
 class E2EEManager {
 	private contentKeyPairs: Map<string, CryptoKeyPair> = new Map();
+	private vaultDevices: PairedDevice[] = [];
 	private sessionToSecret: Map<string, string> = new Map();
 	private sessionDeks: Map<string, Uint8Array> = new Map();
+	/** Sessions whose DEK Rust holds, in the app. */
+	private vaultSessions: Set<string> = new Set();
 	private sessionDekRevisions: Map<string, number> = new Map();
 	private dekReadyListeners: Array<
 		(sessionId: string, revision?: number) => void
@@ -67,7 +115,10 @@ class E2EEManager {
 	 * Check whether a DEK has been unwrapped for the given session.
 	 */
 	hasSessionDek(sessionId: string, revision?: number): boolean {
-		if (!this.sessionDeks.has(sessionId)) {
+		if (
+			!this.sessionDeks.has(sessionId) &&
+			!this.vaultSessions.has(sessionId)
+		) {
 			return false;
 		}
 		return (
@@ -83,21 +134,30 @@ class E2EEManager {
 	forgetSession(sessionId: string): void {
 		this.sessionDeks.get(sessionId)?.fill(0);
 		this.sessionDeks.delete(sessionId);
+		this.vaultSessions.delete(sessionId);
 		this.sessionDekRevisions.delete(sessionId);
 		this.sessionToSecret.delete(sessionId);
+		if (isInTauri()) {
+			void invokeE2EE("e2ee_forget_session", { sessionId }).catch(() => {});
+		}
 	}
 
 	/**
 	 * Attempt to unwrap DEKs for all provided sessions.
 	 * Skips sessions that already have a DEK or lack a wrappedDek.
 	 */
-	unwrapAllSessionDeks(
-		sessions: Array<{
-			sessionId: string;
-			wrappedDek?: string;
-			revision?: number;
-		}>,
-	): void {
+	unwrapAllSessionDeks(sessions: WrappedSessionDek[]): void {
+		if (isInTauri()) {
+			const missing = sessions.filter(
+				(session) =>
+					session.wrappedDek &&
+					!this.hasSessionDek(session.sessionId, session.revision),
+			);
+			if (missing.length > 0) {
+				void this.unwrapWithVault(missing);
+			}
+			return;
+		}
 		for (const session of sessions) {
 			if (!session.wrappedDek) continue;
 			if (this.hasSessionDek(session.sessionId, session.revision)) continue;
@@ -110,7 +170,50 @@ class E2EEManager {
 	}
 
 	isEnabled(): boolean {
-		return this.contentKeyPairs.size > 0;
+		return this.contentKeyPairs.size > 0 || this.vaultDevices.length > 0;
+	}
+
+	/**
+	 * Paired devices by fingerprint, the only view of them the app's
+	 * webview has.
+	 */
+	getPairedDevices(): PairedDevice[] {
+		if (isInTauri()) {
+			return [...this.vaultDevices];
+		}
+		return this.getPairedSecrets().map(({ fingerprint, addedAt }) => ({
+			fingerprint,
+			addedAt,
+		}));
+	}
+
+	async removePairedDevice(fingerprint: string): Promise<void> {
+		if (isInTauri()) {
+			await invokeE2EE("vault_remove_secret", { fingerprint });
+			this.vaultDevices = this.vaultDevices.filter(
+				(device) => device.fingerprint !== fingerprint,
+			);
+			// Rust drops the DEKs opened with this secret; re-request the rest.
+			this.clearSessionDeks();
+			return;
+		}
+		for (const secret of [...this.contentKeyPairs.keys()]) {
+			if (this.computeFingerprint(secret) === fingerprint) {
+				await this.removePairedSecret(secret);
+			}
+		}
+	}
+
+	/** Where the app keeps paired secrets; null outside the app. */
+	async getVaultStatus(): Promise<VaultStatus | null> {
+		if (!isInTauri()) return null;
+		return invokeE2EE<VaultStatus>("vault_status");
+	}
+
+	/** Unlocks a passphrase-protected vault and loads its devices. */
+	async unlockVault(passphrase: string): Promise<boolean> {
+		await invokeE2EE("vault_unlock", { passphrase });
+		return this.loadFromStorage();
 	}
 
 	getPairedSecrets(): StoredSecret[] {
@@ -126,6 +229,19 @@ class E2EEManager {
 	}
 
 	async addPairedSecret(base64Secret: string): Promise<void> {
+		if (isInTauri()) {
+			const device = await invokeE2EE<PairedDevice>("vault_add_secret", {
+				secret: base64Secret,
+			});
+			if (
+				!this.vaultDevices.some(
+					(existing) => existing.fingerprint === device.fingerprint,
+				)
+			) {
+				this.vaultDevices.push(device);
+			}
+			return;
+		}
 		if (this.contentKeyPairs.has(base64Secret)) {
 			return;
 		}
@@ -149,6 +265,17 @@ class E2EEManager {
 	}
 
 	async loadFromStorage(): Promise<boolean> {
+		if (isInTauri()) {
+			// Rust moves secrets the webview used to keep in app-state into
+			// the vault before the first render.
+			const status = await this.getVaultStatus();
+			if (!status || status.locked) {
+				return false;
+			}
+			this.vaultDevices =
+				await invokeE2EE<PairedDevice[]>("vault_list_secrets");
+			return this.vaultDevices.length > 0;
+		}
 		const stored = await this.getStoredSecrets();
 		if (!stored || stored.length === 0) {
 			const legacy = await this.getLegacyStoredSecret();
@@ -177,18 +304,32 @@ class E2EEManager {
 	}
 
 	async clearSecret(): Promise<void> {
+		if (isInTauri()) {
+			await invokeE2EE("vault_clear");
+			this.vaultDevices = [];
+		}
 		this.contentKeyPairs.clear();
 		this.sessionToSecret.clear();
-		this.sessionDeks.clear();
-		this.sessionDekRevisions.clear();
+		this.clearSessionDeks();
 		await this.removeStoredSecrets();
 	}
 
+	/**
+	 * In the app the vault unwraps asynchronously: this returns whether the
+	 * DEK is already available, and `onDekReady` fires once it arrives.
+	 */
 	unwrapSessionDek(
 		sessionId: string,
 		wrappedDek: string,
 		revision?: number,
 	): boolean {
+		if (isInTauri()) {
+			if (this.hasSessionDek(sessionId, revision)) {
+				return true;
+			}
+			void this.unwrapWithVault([{ sessionId, wrappedDek, revision }]);
+			return false;
+		}
 		const cachedSecret = this.sessionToSecret.get(sessionId);
 		if (cachedSecret) {
 			const keypair = this.contentKeyPairs.get(cachedSecret);
@@ -208,6 +349,11 @@ class E2EEManager {
 
 	decryptEvent(event: SessionEvent): SessionEvent {
 		if (!isEncryptedPayload(event.payload)) return event;
+		if (isInTauri()) {
+			// Rust decrypts in the app (`decryptEvents`); an event still
+			// encrypted here had no matching key there.
+			throw new Error("Session key is not available");
+		}
 
 		const dek = this.hasSessionDek(event.sessionId, event.revision)
 			? this.sessionDeks.get(event.sessionId)
@@ -218,6 +364,47 @@ class E2EEManager {
 		return { ...event, payload: decrypted };
 	}
 
+	/**
+	 * Decrypts a batch of events, in the app through Rust, which holds the
+	 * DEKs. Events without a matching DEK come back unchanged; each event
+	 * that fails to decrypt comes back as received with an `error`.
+	 */
+	async decryptEvents(events: SessionEvent[]): Promise<DecryptedEvent[]> {
+		if (isInTauri()) {
+			return invokeE2EE<DecryptedEvent[]>("e2ee_decrypt_events", { events });
+		}
+		return events.map((event) => {
+			try {
+				return { event: this.decryptEvent(event) };
+			} catch (error) {
+				return {
+					event,
+					error: error instanceof Error ? error.message : String(error),
+				};
+			}
+		});
+	}
+
+	/**
+	 * Unwraps a session DEK and resolves once it is known whether one is
+	 * available, for callers that must not send before it is.
+	 */
+	async ensureSessionDek(
+		sessionId: string,
+		wrappedDek?: string,
+		revision?: number,
+	): Promise<E2EEStatus> {
+		if (!wrappedDek) return "none";
+		if (isInTauri()) {
+			if (!this.hasSessionDek(sessionId, revision)) {
+				await this.unwrapWithVault([{ sessionId, wrappedDek, revision }]);
+			}
+		} else {
+			this.unwrapSessionDek(sessionId, wrappedDek, revision);
+		}
+		return this.getSessionE2EEStatus(sessionId, true, revision);
+	}
+
 	getSessionE2EEStatus(
 		sessionId: string,
 		hasWrappedDek: boolean,
@@ -227,19 +414,26 @@ class E2EEManager {
 		return this.hasSessionDek(sessionId, revision) ? "ok" : "missing_key";
 	}
 
-	encryptPayloadForSession(
+	/** In the app the payload is encrypted by Rust, which holds the DEKs. */
+	async encryptPayloadForSession(
 		sessionId: string,
 		payload: unknown,
 		revision?: number,
 		encryptionRequired?: boolean,
-	): unknown {
+	): Promise<unknown> {
+		if (isInTauri()) {
+			return invokeE2EE<unknown>("e2ee_encrypt_payload", {
+				sessionId,
+				payload,
+				revision,
+				encryptionRequired,
+			});
+		}
 		if (encryptionRequired === false) {
 			return payload;
 		}
 		if (encryptionRequired === true && revision === undefined) {
-			throw new Error(
-				"Cannot send without a matching session encryption key. Reload the session or pair the correct device key, then try again.",
-			);
+			throw new Error(MISSING_SESSION_KEY_MESSAGE);
 		}
 
 		const dek = this.hasSessionDek(sessionId, revision)
@@ -247,15 +441,60 @@ class E2EEManager {
 			: undefined;
 		if (!dek) {
 			if (encryptionRequired === true || this.sessionDeks.has(sessionId)) {
-				throw new Error(
-					"Cannot send without a matching session encryption key. Reload the session or pair the correct device key, then try again.",
-				);
+				throw new Error(MISSING_SESSION_KEY_MESSAGE);
 			}
 			return payload;
 		}
 		return encryptPayload(payload, dek);
 	}
 
+	private async unwrapWithVault(sessions: WrappedSessionDek[]): Promise<void> {
+		let unwrapped: UnwrappedSession[];
+		try {
+			unwrapped = await invokeE2EE<UnwrappedSession[]>(
+				"e2ee_unwrap_session_deks",
+				{ sessions },
+			);
+		} catch (error) {
+			console.error("[E2EE] Failed to unwrap session keys:", error);
+			return;
+		}
+		for (const { sessionId, revision } of unwrapped) {
+			const sessionRevision = revision ?? undefined;
+			if (this.hasSessionDek(sessionId, sessionRevision)) continue;
+			this.vaultSessions.add(sessionId);
+			this.setSessionDekRevision(sessionId, sessionRevision);
+			this.notifyDekReady(sessionId, sessionRevision);
+		}
+	}
+
+	private storeSessionDek(
+		sessionId: string,
+		dek: Uint8Array,
+		revision?: number,
+	): void {
+		this.sessionDeks.set(sessionId, dek);
+		this.setSessionDekRevision(sessionId, revision);
+		this.notifyDekReady(sessionId, revision);
+	}
+
+	private setSessionDekRevision(sessionId: string, revision?: number): void {
+		if (revision === undefined) {
+			this.sessionDekRevisions.delete(sessionId);
+		} else {
+			this.sessionDekRevisions.set(sessionId, revision);
+		}
+	}
+
+	private clearSessionDeks(): void {
+		for (const dek of this.sessionDeks.values()) {
+			dek.fill(0);
+		}
+		this.sessionDeks.clear();
+		this.vaultSessions.clear();
+		this.sessionDekRevisions.clear();
+	}
+
 	private tryUnwrap(
 		sessionId: string,
 		wrappedDek: string,
@@ -264,13 +503,7 @@ class E2EEManager {
 	): boolean {
 		try {
 			const dek = unwrapDEK(wrappedDek, keypair.publicKey, keypair.secretKey);
-			this.sessionDeks.set(sessionId, dek);
-			if (revision === undefined) {
-				this.sessionDekRevisions.delete(sessionId);
-			} else {
-				this.sessionDekRevisions.set(sessionId, revision);
-			}
-			this.notifyDekReady(sessionId, revision);
+			this.storeSessionDek(sessionId, dek, revision);
 			return true;
 		} catch {
 			return false;
@@ -297,16 +530,6 @@ class E2EEManager {
 	}
 
 	private async getStoredSecrets(): Promise<StoredSecret[] | null> {
-		if (isInTauri()) {
-			try {
-				return await tauriStoreGet<StoredSecret[]>(
-					"app-state.json",
-					STORAGE_KEY,
-				);
-			} catch {
-				return null;
-			}
-		}
 		const raw = localStorage.getItem(STORAGE_KEY);
 		if (!raw) return null;
 		try {
@@ -317,50 +540,18 @@ class E2EEManager {
 	}
 
 	private async getLegacyStoredSecret(): Promise<string | null> {
-		if (isInTauri()) {
-			try {
-				return await tauriStoreGet<string>(
-					"app-state.json",
-					LEGACY_STORAGE_KEY,
-				);
-			} catch {
-				return null;
-			}
-		}
 		return localStorage.getItem(LEGACY_STORAGE_KEY);
 	}
 
 	private async storeSecrets(secrets: StoredSecret[]): Promise<void> {
-		if (isInTauri()) {
-			try {
-				await tauriStoreSet("app-state.json", STORAGE_KEY, secrets);
-				return;
-			} catch {
-				// Fall through to localStorage
-			}
-		}
 		localStorage.setItem(STORAGE_KEY, JSON.stringify(secrets));
 	}
 
 	private async removeStoredSecrets(): Promise<void> {
-		if (isInTauri()) {
-			try {
-				await tauriStoreDelete("app-state.json", STORAGE_KEY);
-			} catch {
-				// Ignore
-			}
-		}
 		localStorage.removeItem(STORAGE_KEY);
 	}
 
 	private async removeLegacyStoredSecret(): Promise<void> {
-		if (isInTauri()) {
-			try {
-				await tauriStoreDelete("app-state.json", LEGACY_STORAGE_KEY);
-			} catch {
-				// Ignore
-			}
-		}
 		localStorage.removeItem(LEGACY_STORAGE_KEY);
 	}
 }
@@ -378,3 +569,13 @@ export const bootstrapSessionE2EE = (
 	e2ee.unwrapSessionDek(sessionId, wrappedDek, revision);
 	return e2ee.getSessionE2EEStatus(sessionId, true, revision);
 };
+
+/**
+ * Like `bootstrapSessionE2EE`, but waits for the vault in the app. Use it
+ * before sending, where a key still on its way would mean plaintext.
+ */
+export const ensureSessionE2EE = (
+	sessionId: string,
+	wrappedDek?: string,
+	revision?: number,
+): Promise<E2EEStatus> => e2ee.ensureSessionDek(sessionId, wrappedDek, revision);
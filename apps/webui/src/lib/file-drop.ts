@@ -0,0 +1,72 @@
+import type { ContentBlock } from "@/lib/acp";
+import { isInTauri } from "./auth";
+
+/**
+ * Files dropped from the OS onto a desktop window (`file_drop_*`
+ * commands). Rust reads them and sends prompt content blocks: images with
+ * their original bytes, to be normalized like any other prompt image, and
+ * text files as embedded resources.
+ */
+
+export type DroppedImage = Extract<ContentBlock, { type: "image" }>;
+export type DroppedResource = Extract<ContentBlock, { type: "resource" }>;
+
+export type FileDropError = {
+	name: string;
+	message: string;
+};
+
+export type FileDropEvent =
+	| { type: "hover"; count: number }
+	| { type: "leave" }
+	| {
+			type: "drop";
+			blocks: (DroppedImage | DroppedResource)[];
+			errors: FileDropError[];
+	  };
+
+async function invokeFileDrop<T>(
+	command: string,
+	args?: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
+/**
+ * Receives drops on this window until the returned function is called;
+ * resolves to null where native drops are unavailable.
+ */
+export async function listenFileDrop(
+	onEvent: (event: FileDropEvent) => void,
+): Promise<(() => void) | null> {
+	if (!isInTauri()) return null;
+	const { Channel } = await import("@tauri-apps/api/core");
+	const channel = new Channel<FileDropEvent>();
+	channel.onmessage = onEvent;
+	let id: number;
+	try {
+		id = await invokeFileDrop<number>("file_drop_listen", {
+			onEvent: channel,
+		});
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+	return () => {
+		void invokeFileDrop("file_drop_unlisten", { id });
+	};
+}
+
+/** A dropped image as a File, for the prompt image normalizer. */
+export function droppedImageToFile(image: DroppedImage): File {
+	const binary = atob(image.data);
+	const bytes = new Uint8Array(binary.length);
+	for (let index = 0; index < binary.length; index += 1) {
+		bytes[index] = binary.charCodeAt(index);
+	}
+	const name = image.uri?.split("/").pop() ?? "image";
+	return new File([bytes], decodeURIComponent(name), {
+		type: image.mimeType,
+	});
+}
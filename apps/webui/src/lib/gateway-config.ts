@@ -1,5 +1,6 @@
 import { isInTauri } from "./auth";
-import { tauriStoreGet, tauriStoreSet } from "./tauri-store";
+import { getPreloadedEntries, updatePreloaded } from "./preloaded-settings";
+import { settingsGet } from "./settings-store";
 
 /**
  * Get the gateway URL based on the current environment.
@@ -7,7 +8,7 @@ import { tauriStoreGet, tauriStoreSet } from "./tauri-store";
  * Priority:
  * 1. VITE_API_GATEWAY_URL environment variable
  * 2. VITE_GATEWAY_URL environment variable
- * 2. Stored URL from Tauri Store (for desktop/mobile apps)
+ * 2. Stored URL from the settings store (for desktop/mobile apps)
  * 3. Default based on current window location (for web)
  */
 export const getGatewayUrl = async (): Promise<string> => {
@@ -21,11 +22,12 @@ export const getGatewayUrl = async (): Promise<string> => {
 
 	// In Tauri, check for stored gateway URL
 	if (isInTauri()) {
+		const preloadedUrl = getPreloadedGatewayUrl();
+		if (preloadedUrl) {
+			return preloadedUrl;
+		}
 		try {
-			const storedUrl = await tauriStoreGet<string>(
-				"gateway.json",
-				"gatewayUrl",
-			);
+			const storedUrl = await settingsGet<string>("gateway", "gatewayUrl");
 			if (storedUrl) {
 				return storedUrl;
 			}
@@ -43,22 +45,25 @@ export const getGatewayUrl = async (): Promise<string> => {
 };
 
 /**
- * Save the gateway URL to Tauri Store (for desktop/mobile apps).
+ * Save the gateway URL (for desktop/mobile apps). Rust persists it and
+ * reconnects the gateway connection to it.
  */
 export const setGatewayUrl = async (url: string): Promise<void> => {
 	if (!isInTauri()) {
 		return;
 	}
 
+	updatePreloaded("gateway", "gatewayUrl", url);
 	try {
-		await tauriStoreSet("gateway.json", "gatewayUrl", url);
+		const { invoke } = await import("@tauri-apps/api/core");
+		await invoke("gateway_set_url", { url });
 	} catch {
 		// Store not available
 	}
 };
 
 /**
- * Get the default gateway URL without checking Tauri Store.
+ * Get the default gateway URL without checking the settings store.
  * Useful for synchronous operations where async is not possible.
  */
 export const getDefaultGatewayUrl = (): string => {
@@ -70,7 +75,7 @@ export const getDefaultGatewayUrl = (): string => {
 	}
 
 	if (isInTauri()) {
-		return "http://localhost:3005";
+		return getPreloadedGatewayUrl() ?? "http://localhost:3005";
 	}
 
 	if (typeof window === "undefined") {
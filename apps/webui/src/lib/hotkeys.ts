@@ -1,6 +1,20 @@
-type HotkeyHandler = (e: KeyboardEvent) => void;
+/**
+ * Commands the hotkeys dispatch. The desktop app menu sends the same ids
+ * (`app_menu` in the Tauri crate), so each must keep its id there in sync.
+ */
+export type HotkeyCommand =
+	| "palette.open"
+	| "chat.search"
+	| "sidebar.toggle"
+	| "session.new"
+	| "session.openWindow"
+	| "settings.open";
+
+/** Called without an event when the command comes from the app menu. */
+type HotkeyHandler = (e?: KeyboardEvent) => void;
 
 type HotkeyEntry = {
+	command?: HotkeyCommand;
 	key: string;
 	mod?: boolean;
 	shift?: boolean;
@@ -20,6 +34,32 @@ export function isInputFocused(): boolean {
 	return false;
 }
 
+/**
+ * A menu accelerator can reach the webview as a keydown as well, which
+ * already ran the command; a menu dispatch this soon after is dropped.
+ */
+const MENU_ECHO_MS = 250;
+
+const commandHandlers = new Map<HotkeyCommand, HotkeyHandler>();
+let lastKeyboardCommand: { command: HotkeyCommand; at: number } | null = null;
+
+/**
+ * Runs the handler registered for `command`, as the app menu does.
+ * Returns false when nothing handles it.
+ */
+export function runHotkeyCommand(command: HotkeyCommand): boolean {
+	const handler = commandHandlers.get(command);
+	if (!handler) return false;
+	if (
+		lastKeyboardCommand?.command === command &&
+		Date.now() - lastKeyboardCommand.at < MENU_ECHO_MS
+	) {
+		return true;
+	}
+	handler();
+	return true;
+}
+
 /**
  * Register global keyboard shortcuts.
  * Automatically normalises `mod` to Meta (macOS) / Ctrl (Windows/Linux).
@@ -44,12 +84,28 @@ export function registerHotkeys(entries: HotkeyEntry[]): () => void {
 				shiftMatch
 			) {
 				e.preventDefault();
+				if (entry.command) {
+					lastKeyboardCommand = { command: entry.command, at: Date.now() };
+				}
 				entry.handler(e);
 				return;
 			}
 		}
 	};
 
+	for (const entry of entries) {
+		if (entry.command) commandHandlers.set(entry.command, entry.handler);
+	}
 	window.addEventListener("keydown", handler);
-	return () => window.removeEventListener("keydown", handler);
+	return () => {
+		window.removeEventListener("keydown", handler);
+		for (const entry of entries) {
+			if (
+				entry.command &&
+				commandHandlers.get(entry.command) === entry.handler
+			) {
+				commandHandlers.delete(entry.command);
+			}
+		}
+	};
 }
@@ -0,0 +1,91 @@
+import { isInTauri } from "./auth";
+
+/**
+ * Gateway embedded in the desktop app. A local `mobvibe-cli` started with
+ * `MOBVIBE_GATEWAY_URL` set to `url` registers with it, so nothing but the
+ * app and the CLI has to run. Not available on mobile or the web.
+ *
+ * Its REST routes want a bearer token made fresh on every launch, which
+ * Rust injects before any page script runs (`src-tauri/src/preload.rs`).
+ */
+
+const LOCAL_GATEWAY_KEY = "__MOBVIBE_LOCAL_GATEWAY__";
+const LOOPBACK_HOSTS = new Set(["localhost", "127.0.0.1", "[::1]"]);
+
+type LocalGatewayAuth = { token: string; port: number | null };
+
+const injected =
+	typeof window === "undefined"
+		? undefined
+		: (window as unknown as Record<string, LocalGatewayAuth | undefined>)[
+				LOCAL_GATEWAY_KEY
+			];
+let runningPort = injected?.port ?? null;
+
+/**
+ * The launch token when `url` is where the embedded gateway runs, else
+ * null; it is never sent anywhere else.
+ */
+export const localGatewayToken = (url: string): string | null => {
+	if (!injected || runningPort === null) return null;
+	try {
+		const { hostname, port } = new URL(url);
+		return LOOPBACK_HOSTS.has(hostname) && Number(port) === runningPort
+			? injected.token
+			: null;
+	} catch {
+		return null;
+	}
+};
+
+/** Follows the server as it is started and stopped after the page loaded. */
+const track = (status: LocalGatewayStatus): LocalGatewayStatus => {
+	runningPort = status.running ? status.port : null;
+	return status;
+};
+
+export type LocalGatewayStatus = {
+	running: boolean;
+	port: number | null;
+	url: string | null;
+	/** Connected machines, in the `/api/machines` shape. */
+	machines: Array<{
+		id: string;
+		name: string | null;
+		hostname: string;
+		isOnline: boolean;
+	}>;
+};
+
+export async function getLocalGatewayStatus(): Promise<LocalGatewayStatus | null> {
+	if (!isInTauri()) return null;
+	try {
+		const { invoke } = await import("@tauri-apps/api/core");
+		return track(await invoke<LocalGatewayStatus>("local_gateway_status"));
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+}
+
+/** Starts the embedded gateway and switches the app to it. */
+export async function startLocalGateway(
+	port?: number,
+): Promise<LocalGatewayStatus> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return track(
+		await invoke<LocalGatewayStatus>("local_gateway_start", { port }),
+	);
+}
+
+export async function stopLocalGateway(): Promise<void> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	await invoke("local_gateway_stop");
+	runningPort = null;
+}
+
+// The injected port is the one when the window was created; a reload can
+// come after the server was started or stopped.
+if (injected) {
+	void getLocalGatewayStatus();
+}
@@ -0,0 +1,75 @@
+import type {
+	GitBlameResponse,
+	GitBranchesResponse,
+	GitFileDiffResponse,
+	GitGrepParams,
+	GitGrepResponse,
+	GitLogParams,
+	GitLogResponse,
+	GitStatusResponse,
+} from "@mobvibe/shared";
+import { isInTauri } from "./auth";
+
+/**
+ * Git inspection in the desktop app (`git_*` commands) for sessions whose
+ * working directory is on this machine. The results have the shapes of the
+ * gateway's git RPCs but need neither the gateway nor a running daemon.
+ */
+
+async function invokeGit<T>(
+	command: string,
+	args: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
+/**
+ * Changed files under `cwd`; null where native git is unavailable, in
+ * which case callers fall back to the gateway.
+ */
+export async function getLocalGitStatus(
+	cwd: string,
+): Promise<GitStatusResponse | null> {
+	if (!isInTauri()) return null;
+	try {
+		return await invokeGit<GitStatusResponse>("git_status", { cwd });
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+}
+
+export function getLocalGitFileDiff(
+	cwd: string,
+	path: string,
+): Promise<GitFileDiffResponse> {
+	return invokeGit("git_file_diff", { cwd, path });
+}
+
+export function getLocalGitLog(
+	cwd: string,
+	options?: Omit<GitLogParams, "sessionId">,
+): Promise<GitLogResponse> {
+	return invokeGit("git_log", { cwd, ...options });
+}
+
+export function getLocalGitBlame(
+	cwd: string,
+	path: string,
+	range?: { startLine?: number; endLine?: number },
+): Promise<GitBlameResponse> {
+	return invokeGit("git_blame", { cwd, path, ...range });
+}
+
+export function getLocalGitBranches(cwd: string): Promise<GitBranchesResponse> {
+	return invokeGit("git_branches", { cwd });
+}
+
+export function searchLocalGit(
+	cwd: string,
+	query: string,
+	options?: Omit<GitGrepParams, "sessionId" | "query">,
+): Promise<GitGrepResponse> {
+	return invokeGit("git_grep", { cwd, query, ...options });
+}
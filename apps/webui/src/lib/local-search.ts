@@ -0,0 +1,92 @@
+import type { GrepResult, SessionFsResourceEntry } from "@mobvibe/shared";
+import { isInTauri } from "./auth";
+import type { FuzzySearchResult } from "./fuzzy-search";
+
+/**
+ * Path and content search in the desktop app (`search_*` commands) over a
+ * workspace on this machine. Paths come from an index the app keeps per
+ * root, which follows `.gitignore` in git repositories.
+ */
+
+type PathMatch = SessionFsResourceEntry & {
+	score: number;
+	/** Ranges of `relativePath`. */
+	highlightRanges: [number, number][];
+};
+
+export type ContentSearchEvent =
+	| { kind: "matches"; results: GrepResult[] }
+	/** The last event of a search. */
+	| { kind: "done"; truncated: boolean; cancelled: boolean };
+
+async function invokeSearch<T>(
+	command: string,
+	args: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
+/**
+ * Fuzzy-matches `query` against the files under `root`, best first, in the
+ * shape `filterResourceItems` returns. Null where native search is
+ * unavailable.
+ */
+export async function searchLocalPaths(
+	root: string,
+	query: string,
+	limit?: number,
+): Promise<FuzzySearchResult<SessionFsResourceEntry>[] | null> {
+	if (!isInTauri()) return null;
+	let matches: PathMatch[];
+	try {
+		matches = await invokeSearch<PathMatch[]>("search_paths", {
+			root,
+			query,
+			limit,
+		});
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+	return matches.map(
+		({ score, highlightRanges, name, path, relativePath }) => {
+			// The picker highlights `${name} ${relativePath}`.
+			const offset = name.length + 1;
+			return {
+				item: { name, path, relativePath },
+				score,
+				highlightRanges: highlightRanges.map(
+					([start, end]) =>
+						[start + offset, end + offset] as [number, number],
+				),
+			};
+		},
+	);
+}
+
+/**
+ * Searches the contents of the files under `root` and streams matching
+ * lines to `onEvent`. `query` is a literal, case-insensitive string unless
+ * the options say otherwise. Resolves to a function that cancels the
+ * search.
+ */
+export async function searchLocalContent(
+	root: string,
+	query: string,
+	onEvent: (event: ContentSearchEvent) => void,
+	options?: { caseSensitive?: boolean; regex?: boolean; glob?: string },
+): Promise<() => void> {
+	const { Channel } = await import("@tauri-apps/api/core");
+	const channel = new Channel<ContentSearchEvent>();
+	channel.onmessage = onEvent;
+	const id = await invokeSearch<number>("search_content", {
+		root,
+		query,
+		options,
+		onEvent: channel,
+	});
+	return () => {
+		void invokeSearch("search_cancel", { id });
+	};
+}
@@ -1,6 +1,6 @@
 import type { ToastVariant } from "@mobvibe/ui/toast";
 import i18n from "@/i18n";
-import type { PermissionToolCall } from "@/lib/acp";
+import type { PermissionRequestPayload } from "@/lib/acp";
 import {
 	type ErrorDetail,
 	fetchNotificationVapidPublicKey,
@@ -9,6 +9,7 @@ import {
 } from "@/lib/api";
 import { isInTauri } from "@/lib/auth";
 import { useNotificationStore } from "@/lib/notification-store";
+import { isMobilePlatform } from "@/lib/platform";
 import { getToolCallMetaHints } from "@/lib/tool-call-meta";
 
 export type NotificationVariant = ToastVariant;
@@ -194,7 +195,7 @@ const emitWebNotification = (
 	}
 };
 
-export const pushNotification = (
+const pushInAppNotification = (
 	payload: NotificationPayload,
 	context?: NotificationContext,
 ) => {
@@ -203,6 +204,13 @@ export const pushNotification = (
 		description: payload.description,
 		variant: payload.variant ?? "info",
 	});
+};
+
+export const pushNotification = (
+	payload: NotificationPayload,
+	context?: NotificationContext,
+) => {
+	pushInAppNotification(payload, context);
 	emitWebNotification(payload, context);
 };
 
@@ -246,27 +254,70 @@ export const ensureNotificationPermission = async (options?: {
 	}
 };
 
+/**
+ * Asks Rust for the desktop notification with one button per permission
+ * option, in this webview's language. False when it could not be shown.
+ */
+const emitNativePermissionNotification = async (
+	payload: PermissionRequestPayload,
+	notification: NotificationPayload,
+	context: NotificationContext,
+): Promise<boolean> => {
+	try {
+		const { invoke } = await import("@tauri-apps/api/core");
+		await invoke("notifications_permission_request", {
+			request: payload,
+			text: {
+				title: resolveNotificationTitle(notification, context),
+				body: notification.description ?? "",
+				open: i18n.t("notifications.open"),
+				dismiss: i18n.t("notifications.dismiss"),
+				respond: i18n.t("notifications.respond"),
+			},
+		});
+		return true;
+	} catch {
+		return false;
+	}
+};
+
 export const notifyPermissionRequest = (
-	payload: {
-		sessionId: string;
-		requestId: string;
-		toolCall?: PermissionToolCall;
-	},
+	payload: PermissionRequestPayload,
 	context?: { sessions?: Record<string, SessionSummary> },
 ) => {
 	const meta = getToolCallMetaHints(payload.toolCall?._meta);
 	const toolLabel =
 		payload.toolCall?.title ?? meta.name ?? i18n.t("toolCall.toolCall");
-	pushNotification(
-		{
-			title: i18n.t("notifications.permissionRequest"),
-			description: i18n.t("notifications.permissionRequestDetail", {
-				tool: toolLabel,
-			}),
-			variant: "warning",
-		},
-		{ sessionId: payload.sessionId, sessions: context?.sessions },
-	);
+	const notification: NotificationPayload = {
+		title: i18n.t("notifications.permissionRequest"),
+		description: i18n.t("notifications.permissionRequestDetail", {
+			tool: toolLabel,
+		}),
+		variant: "warning",
+	};
+	const notificationContext = {
+		sessionId: payload.sessionId,
+		sessions: context?.sessions,
+	};
+	pushInAppNotification(notification, notificationContext);
+	if (!isInTauri()) {
+		emitWebNotification(notification, notificationContext);
+		return;
+	}
+	// Desktop builds raise an actionable native notification from Rust,
+	// and a plain one if that fails.
+	void isMobilePlatform().then(async (mobile) => {
+		const shown =
+			!mobile &&
+			(await emitNativePermissionNotification(
+				payload,
+				notification,
+				notificationContext,
+			));
+		if (!shown) {
+			emitWebNotification(notification, notificationContext);
+		}
+	});
 };
 
 export const notifySessionError = (
@@ -0,0 +1,20 @@
+(type_definition
+  type: (enum_specifier) @definition.enum
+  declarator: (type_identifier) @name)
+
+(type_definition
+  type: (struct_specifier) @definition.struct
+  declarator: (type_identifier) @name)
+
+(struct_specifier
+  name: (type_identifier) @name
+  body: (field_declaration_list)) @definition.struct
+
+(function_definition
+  declarator: (function_declarator
+    declarator: (identifier) @name)) @definition.function
+
+(function_definition
+  declarator: (pointer_declarator
+    declarator: (function_declarator
+      declarator: (identifier) @name))) @definition.function
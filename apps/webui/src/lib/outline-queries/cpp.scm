@@ -0,0 +1,17 @@
+(struct_specifier
+  name: (type_identifier) @name
+  body: (field_declaration_list)) @definition.struct
+
+(declaration
+  (struct_specifier
+    body: (field_declaration_list))
+  declarator: (identifier) @name) @definition.struct
+
+(function_declarator
+  declarator: (_) @name) @definition.function
+
+(enum_specifier
+  name: (type_identifier) @name) @definition.enum
+
+(class_specifier
+  name: (type_identifier) @name) @definition.class
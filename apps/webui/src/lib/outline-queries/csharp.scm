@@ -0,0 +1,25 @@
+(interface_declaration
+  name: (identifier) @name) @definition.interface
+
+(class_declaration
+  name: (identifier) @name) @definition.class
+
+(struct_declaration
+  name: (identifier) @name) @definition.struct
+
+(method_declaration
+  name: (identifier) @name) @definition.method
+
+(enum_declaration
+  name: (identifier) @name) @definition.enum
+
+(constructor_declaration
+  name: (identifier) @name) @definition.constructor
+
+(property_declaration
+  name: (identifier) @name) @definition.property
+
+(field_declaration
+  (variable_declaration
+    (variable_declarator
+      (identifier) @name))) @definition.field
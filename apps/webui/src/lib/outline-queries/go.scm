@@ -0,0 +1,16 @@
+(function_declaration
+  name: (identifier) @name) @definition.function
+
+(type_declaration
+  (type_spec
+    name: (type_identifier) @name
+    type: (struct_type)) @definition.struct)
+
+(type_declaration
+  (type_spec
+    name: (type_identifier) @name
+    type: (interface_type)) @definition.interface)
+
+(method_declaration
+  receiver: (_)
+  name: (field_identifier) @name) @definition.method
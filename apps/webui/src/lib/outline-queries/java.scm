@@ -0,0 +1,18 @@
+(interface_declaration
+  name: (identifier) @name) @definition.interface
+
+(method_declaration
+  name: (identifier) @name) @definition.method
+
+(constructor_declaration
+  name: (identifier) @name) @definition.constructor
+
+(class_declaration
+  name: (identifier) @name) @definition.class
+
+(enum_declaration
+  name: (identifier) @name) @definition.enum
+
+(field_declaration
+  declarator: (variable_declarator
+    name: (identifier) @name)) @definition.field
@@ -0,0 +1,19 @@
+(function_definition
+  name: (name) @name) @definition.function
+
+(expression_statement
+  (assignment_expression
+    left: (variable_name) @name
+    right: (anonymous_function))) @definition.function
+
+(class_declaration
+  name: (name) @name) @definition.class
+
+(method_declaration
+  name: (name) @name) @definition.method
+
+(interface_declaration
+  name: (name) @name) @definition.interface
+
+(trait_declaration
+  name: (name) @name) @definition.class
@@ -0,0 +1,8 @@
+(function_definition
+  name: (identifier) @name) @definition.function
+
+(class_definition
+  name: (identifier) @name) @definition.class
+
+(assignment
+  left: (_) @name) @definition.variable
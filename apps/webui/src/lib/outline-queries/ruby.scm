@@ -0,0 +1,71 @@
+(class
+  name: [
+    (constant)
+    (scope_resolution)
+  ] @name) @definition.class
+
+(call
+  ((identifier) @scope
+    (#any-of? @scope "private" "protected" "public"))?
+  .
+  (argument_list
+    (method
+      name: (_) @name) )) @definition.method
+
+(body_statement
+  [
+    (_)
+    ((identifier) @scope
+      (#any-of? @scope "private" "protected" "public"))
+  ]*
+  .
+  (method
+    name: (_) @name)) @definition.method
+
+(body_statement
+  (method
+    name: (_) @name)) @definition.method
+
+(singleton_method
+  object: [
+    (constant)
+    (self)
+    (identifier)
+  ]
+  ([
+    "."
+    "::"
+  ])?
+  name: [
+    (operator)
+    (identifier)
+  ] @name) @definition.method
+
+(singleton_class
+  value: (_) @name) @definition.class
+
+(module
+  name: [
+    (constant)
+    (scope_resolution)
+  ] @name) @definition.module
+
+(call
+  method: (identifier) @method @name
+  (#any-of? @method
+    "describe" "it" "before" "after"
+    "namespace" "task" "multitask" "file"
+    "setup" "teardown" "should" "should_not" "should_eventually" "context")
+  arguments: (argument_list
+    [
+      (string
+        (string_content) @name)
+      (simple_symbol) @name
+      (pair
+        key: [
+          (string
+            (string_content) @name)
+          (hash_key_symbol) @name
+        ])
+      (call) @name
+    ])?) @definition.method
@@ -0,0 +1,26 @@
+(mod_item
+  name: (identifier) @name) @definition.module
+
+(enum_item
+  name: (type_identifier) @name) @definition.enum
+
+(struct_item
+  name: (type_identifier) @name) @definition.struct
+
+(function_item
+  name: (identifier) @name) @definition.function
+
+(function_signature_item
+  name: (identifier) @name) @definition.function
+
+(trait_item
+  name: (type_identifier) @name) @definition.interface
+
+(impl_item
+  trait: (type_identifier)?
+  type: (type_identifier) @name) @definition.class
+
+(impl_item
+  trait: (type_identifier)?
+  type: (generic_type
+    type: (type_identifier) @name)) @definition.class
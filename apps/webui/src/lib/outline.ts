@@ -0,0 +1,71 @@
+import { isInTauri } from "./auth";
+
+/**
+ * File outlines parsed by the desktop app (`outline_extract`) with the
+ * grammars built into it, so the preview need not load WASM grammars. The
+ * queries are the `.scm` files in `./outline-queries`, shared with the
+ * web-tree-sitter fallback of `CodePreview`.
+ */
+
+export type OutlineLanguage =
+	| "bash"
+	| "c"
+	| "cpp"
+	| "csharp"
+	| "go"
+	| "java"
+	| "javascript"
+	| "php"
+	| "python"
+	| "ruby"
+	| "rust"
+	| "typescript"
+	| "tsx";
+
+export type OutlineKind =
+	| "class"
+	| "method"
+	| "function"
+	| "interface"
+	| "type"
+	| "enum"
+	| "module"
+	| "constant"
+	| "property"
+	| "field"
+	| "constructor"
+	| "variable"
+	| "struct";
+
+/** Indexes are UTF-16 offsets into the content; lines start at 1. */
+export type OutlineItem = {
+	id: string;
+	label: string;
+	kind: OutlineKind;
+	startIndex: number;
+	endIndex: number;
+	startLine: number;
+	endLine: number;
+	children: OutlineItem[];
+};
+
+/**
+ * The outline of `content`, nested by containment; null where the native
+ * parser is unavailable, in which case callers parse in the webview.
+ */
+export async function extractOutline(
+	language: OutlineLanguage,
+	content: string,
+): Promise<OutlineItem[] | null> {
+	if (!isInTauri()) return null;
+	try {
+		const { invoke } = await import("@tauri-apps/api/core");
+		return await invoke<OutlineItem[]>("outline_extract", {
+			language,
+			content,
+		});
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+}
@@ -0,0 +1,52 @@
+import type { SettingsScope } from "./settings-store";
+
+/**
+ * Settings entries the native side injects before any page script runs
+ * (`src-tauri/src/preload.rs`). They are kept in step with every write made
+ * through `settings-store.ts` and snapshotted into sessionStorage on
+ * `pagehide`; on reload the injected values win and the snapshot only fills
+ * in keys they lack. Only the injected scopes are kept, so credentials
+ * (`auth`) never reach page storage.
+ */
+
+const PRELOAD_KEY = "__MOBVIBE_PRELOAD__";
+
+type Preload = Partial<Record<SettingsScope, Record<string, unknown>>>;
+
+const getPreload = (): Preload | null => {
+	if (typeof window === "undefined") return null;
+	return (
+		(window as unknown as Record<string, Preload | undefined>)[PRELOAD_KEY] ??
+		null
+	);
+};
+
+if (getPreload() && typeof window !== "undefined") {
+	window.addEventListener("pagehide", () => {
+		try {
+			window.sessionStorage.setItem(PRELOAD_KEY, JSON.stringify(getPreload()));
+		} catch {
+			// The next load falls back to the launch values
+		}
+	});
+}
+
+/** Every preloaded entry of a scope, or null when nothing was injected. */
+export const getPreloadedEntries = (
+	scope: SettingsScope,
+): Record<string, unknown> | null => getPreload()?.[scope] ?? null;
+
+/** Keeps the preloaded entries current; `null` deletes the key. */
+export const updatePreloaded = (
+	scope: SettingsScope,
+	key: string,
+	value: unknown,
+): void => {
+	const entries = getPreload()?.[scope];
+	if (!entries) return;
+	if (value === null || value === undefined) {
+		delete entries[key];
+	} else {
+		entries[key] = value;
+	}
+};
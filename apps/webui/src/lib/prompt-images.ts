@@ -6,6 +6,7 @@ import {
 	validatePromptImageBlocks,
 } from "@mobvibe/shared";
 import type { ContentBlock } from "@/lib/acp";
+import { isInTauri } from "./auth";
 
 type ImageContent = Extract<ContentBlock, { type: "image" }>;
 
@@ -150,12 +151,59 @@ const normalizeRasterImage = async (
 	);
 };
 
+type NativeImage = {
+	type: "image";
+	data: string;
+	mimeType: PromptImageMimeType;
+	width: number;
+	height: number;
+};
+
+/**
+ * Normalizes through the app (`prompt_image_normalize`), which turns the
+ * image upright by its EXIF orientation and re-encodes it without EXIF or
+ * GPS data, on desktop and mobile alike. Resolves to null outside the
+ * app, where the canvas re-encodes instead.
+ */
+const normalizeNativeImage = async (
+	file: File,
+): Promise<ImageContent | null> => {
+	if (!isInTauri()) {
+		return null;
+	}
+	const { invoke } = await import("@tauri-apps/api/core");
+	const { data } = dataUrlToImageContent(await readFileAsDataUrl(file));
+	let image: NativeImage;
+	try {
+		image = await invoke<NativeImage>("prompt_image_normalize", {
+			data,
+			options: {
+				maxEdge: PROMPT_IMAGE_MAX_EDGE,
+				maxBytes: PROMPT_IMAGE_MAX_BYTES,
+			},
+		});
+	} catch (error) {
+		// Command errors arrive as plain strings.
+		throw error instanceof Error ? error : new Error(String(error));
+	}
+	return validateSingleImage({
+		type: "image",
+		data: image.data,
+		mimeType: image.mimeType,
+		uri: null,
+	});
+};
+
 export const normalizeImageFileForPrompt = async (
 	file: File,
 ): Promise<ImageContent> => {
 	if (!isPromptImageMimeType(file.type)) {
 		throw new Error(`Unsupported image MIME type: ${file.type || "unknown"}`);
 	}
+	const nativeImage = await normalizeNativeImage(file);
+	if (nativeImage) {
+		return nativeImage;
+	}
 	if (file.type === "image/gif") {
 		return validateSingleImage(
 			dataUrlToImageContent(await readFileAsDataUrl(file)),
@@ -0,0 +1,57 @@
+import { isInTauri } from "./auth";
+
+/**
+ * The quick prompt window of the desktop app (`quick_prompt_*` commands).
+ * A global shortcut shows it over whatever is in front; it loads the app
+ * with `?quickPrompt=1` and hides again once the prompt is sent.
+ */
+
+export type QuickPromptStatus = {
+	/** Null when the shortcut is turned off. */
+	shortcut: string | null;
+	registered: boolean;
+	/** Why the shortcut could not be registered. */
+	error: string | null;
+};
+
+export const DEFAULT_QUICK_PROMPT_SHORTCUT = "CmdOrCtrl+Shift+Space";
+
+async function invokeQuickPrompt<T>(
+	command: string,
+	args?: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
+/** Whether this page is the quick prompt window. */
+export function isQuickPromptWindow(): boolean {
+	if (typeof window === "undefined") return false;
+	return new URLSearchParams(window.location.search).has("quickPrompt");
+}
+
+/** Shortcut status; null where the quick prompt is unavailable. */
+export async function getQuickPromptStatus(): Promise<
+	QuickPromptStatus | null
+> {
+	if (!isInTauri()) return null;
+	try {
+		return await invokeQuickPrompt<QuickPromptStatus>("quick_prompt_status");
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+}
+
+/** Replaces the shortcut, or turns it off for null. */
+export async function setQuickPromptShortcut(
+	shortcut: string | null,
+): Promise<QuickPromptStatus> {
+	return invokeQuickPrompt<QuickPromptStatus>("quick_prompt_set_shortcut", {
+		shortcut,
+	});
+}
+
+export async function hideQuickPrompt(): Promise<void> {
+	await invokeQuickPrompt("quick_prompt_hide");
+}
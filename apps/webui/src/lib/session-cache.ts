@@ -0,0 +1,83 @@
+import type {
+	SessionEvent,
+	SessionEventsResponse,
+	SessionSummary,
+} from "@mobvibe/shared";
+import { isInTauri } from "./auth";
+
+/**
+ * Client for the desktop/mobile offline session cache (`session_cache_*`
+ * commands). Every helper resolves to null outside Tauri or when the cache
+ * is unavailable, so callers can always fall back to the gateway.
+ */
+
+/** A page of cached events, shaped like `GET /acp/session/events`. */
+export type CachedEventsPage = {
+	sessionId: string;
+	revision: number;
+	events: SessionEvent[];
+	nextAfterSeq: number | null;
+	hasMore: boolean;
+	/** Every event up to this seq is cached; backfill can resume after it. */
+	syncedSeq: number;
+};
+
+/** A cached session with its backfill cursor. */
+export type CachedSession = {
+	sessionId: string;
+	/** Last summary seen in `sessions:changed`, if any. */
+	summary: SessionSummary | null;
+	revision: number | null;
+	syncedSeq: number;
+	lastSeq: number | null;
+};
+
+async function invokeCache<T>(
+	command: string,
+	args: Record<string, unknown>,
+): Promise<T | null> {
+	if (!isInTauri()) return null;
+	try {
+		const { invoke } = await import("@tauri-apps/api/core");
+		return await invoke<T>(command, args);
+	} catch (error) {
+		console.warn(`[session-cache] ${command} failed`, error);
+		return null;
+	}
+}
+
+/**
+ * Summaries of the cached sessions, so the session list survives the
+ * gateway being unreachable.
+ */
+export async function readCachedSessions(): Promise<SessionSummary[] | null> {
+	const sessions = await invokeCache<CachedSession[]>(
+		"session_cache_sessions",
+		{},
+	);
+	if (!sessions) return null;
+	return sessions.flatMap((session) =>
+		session.summary ? [session.summary] : [],
+	);
+}
+
+export function readCachedEvents(
+	sessionId: string,
+	revision: number,
+	afterSeq: number,
+	limit?: number,
+): Promise<CachedEventsPage | null> {
+	return invokeCache("session_cache_events", {
+		sessionId,
+		revision,
+		afterSeq,
+		limit,
+	});
+}
+
+export function recordCachedPage(
+	afterSeq: number,
+	page: SessionEventsResponse,
+): void {
+	void invokeCache("session_cache_record_page", { afterSeq, page });
+}
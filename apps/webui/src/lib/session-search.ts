@@ -0,0 +1,45 @@
+import { isInTauri } from "./auth";
+
+/**
+ * Full-text search over the desktop/mobile offline session cache. Outside
+ * Tauri there is no cache, so searches resolve to no hits.
+ */
+
+export type SessionSearchHit = {
+	sessionId: string;
+	sessionTitle: string | null;
+	revision: number;
+	/** First event of the matching message. */
+	seq: number;
+	kind: "user_message" | "agent_message_chunk" | "tool_call";
+	snippet: string;
+	/** `[start, end)` offsets into `snippet`, like `fuzzySearch`. */
+	highlightRanges: Array<[number, number]>;
+};
+
+export async function searchSessions(
+	query: string,
+	limit?: number,
+): Promise<SessionSearchHit[]> {
+	if (!isInTauri() || !query.trim()) return [];
+	try {
+		const { invoke } = await import("@tauri-apps/api/core");
+		return await invoke<SessionSearchHit[]>("session_search", {
+			query,
+			limit,
+		});
+	} catch (error) {
+		console.warn("[session-search] session_search failed", error);
+		return [];
+	}
+}
+
+/** Opens the hit's session and jumps to its event (`deep-link:route`). */
+export async function openSearchHit(hit: SessionSearchHit): Promise<void> {
+	if (!isInTauri()) return;
+	const { invoke } = await import("@tauri-apps/api/core");
+	await invoke("session_search_open", {
+		sessionId: hit.sessionId,
+		seq: hit.seq,
+	});
+}
@@ -0,0 +1,55 @@
+import { isInTauri } from "./auth";
+
+/**
+ * Sessions in their own windows in the desktop app (`session_window_*`
+ * commands). A session window loads the app with `?sessionId=…` and
+ * `sessionWindow=1`; windows still open when the app quits come back at
+ * the next launch with their size and position.
+ */
+
+export type SessionWindowInfo = {
+	label: string;
+	sessionId: string;
+};
+
+async function invokeSessionWindow<T>(
+	command: string,
+	args?: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
+/** Whether this page is a session window rather than the main window. */
+export function isSessionWindow(): boolean {
+	if (typeof window === "undefined") return false;
+	return new URLSearchParams(window.location.search).has("sessionWindow");
+}
+
+/** Open session windows; null where they are unavailable. */
+export async function listSessionWindows(): Promise<
+	SessionWindowInfo[] | null
+> {
+	if (!isInTauri()) return null;
+	try {
+		return await invokeSessionWindow<SessionWindowInfo[]>(
+			"session_window_list",
+		);
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+}
+
+/** Opens the session in its own window, or focuses the one it has. */
+export async function openSessionWindow(
+	sessionId: string,
+): Promise<SessionWindowInfo> {
+	return invokeSessionWindow<SessionWindowInfo>("session_window_open", {
+		sessionId,
+	});
+}
+
+export async function closeSessionWindow(sessionId: string): Promise<void> {
+	await invokeSessionWindow("session_window_close", { sessionId });
+}
@@ -0,0 +1,65 @@
+import { updatePreloaded } from "./preloaded-settings";
+
+/**
+ * Client for the native SQLite settings store (`settings_*` commands).
+ * Entries are JSON values grouped into scopes that replace the old
+ * tauri-plugin-store files: `app-state`, `auth` and `gateway`.
+ */
+
+export type SettingsScope = "app-state" | "auth" | "gateway";
+
+/** A write in a `settingsApply` batch; `value: null` deletes the key. */
+export type SettingChange = { key: string; value: unknown };
+
+async function invokeSettings<T>(
+	command: string,
+	args: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
+/** Every entry of a scope. */
+export function settingsEntries(
+	scope: SettingsScope,
+): Promise<Record<string, unknown>> {
+	return invokeSettings("settings_entries", { scope });
+}
+
+/** Read a typed value from a scope. */
+export async function settingsGet<T>(
+	scope: SettingsScope,
+	key: string,
+): Promise<T | null> {
+	const value = await invokeSettings<T | null>("settings_get", { scope, key });
+	return value ?? null;
+}
+
+/** Write a single value; persisted once the promise resolves. */
+export function settingsSet<T>(
+	scope: SettingsScope,
+	key: string,
+	value: T,
+): Promise<void> {
+	updatePreloaded(scope, key, value);
+	return invokeSettings("settings_set", { scope, key, value });
+}
+
+export function settingsDelete(
+	scope: SettingsScope,
+	key: string,
+): Promise<void> {
+	updatePreloaded(scope, key, null);
+	return invokeSettings("settings_delete", { scope, key });
+}
+
+/** Write several keys in one transaction. */
+export function settingsApply(
+	scope: SettingsScope,
+	changes: SettingChange[],
+): Promise<void> {
+	for (const { key, value } of changes) {
+		updatePreloaded(scope, key, value);
+	}
+	return invokeSettings("settings_apply", { scope, changes });
+}
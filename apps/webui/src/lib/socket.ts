@@ -12,28 +12,144 @@ import type {
 	WebuiToGatewayEvents,
 } from "./acp";
 import { isInTauri } from "./auth";
-import { getAuthToken } from "./auth-token";
 import { getDefaultGatewayUrl } from "./gateway-config";
 
 type TypedSocket = Socket<GatewayToWebuiEvents, WebuiToGatewayEvents>;
 
+type ConnectionState = "idle" | "connecting" | "connected" | "reconnecting";
+
+/**
+ * What the `gateway` module in Rust pushes over `gateway_listen`: the
+ * gateway's own events, plus its connection status.
+ */
+type NativeGatewayEvent =
+	| {
+			[E in keyof GatewayToWebuiEvents]: {
+				event: E;
+				payload: Parameters<GatewayToWebuiEvents[E]>[0];
+			};
+	  }[keyof GatewayToWebuiEvents]
+	| {
+			event: "connection";
+			payload: { state: ConnectionState; error: string | null };
+	  };
+
+type Handler = (payload: never) => void;
+
 let GATEWAY_URL = getDefaultGatewayUrl();
 
+/**
+ * In the desktop and mobile apps the connection lives in Rust, which also
+ * feeds notifications, the tray and the offline cache; this webview only
+ * listens to it. The browser build connects with socket.io itself.
+ */
+class NativeGateway {
+	private listenerId: Promise<number> | null = null;
+	private handlers = new Map<string, Set<Handler>>();
+	private state: ConnectionState = "idle";
+
+	constructor(
+		private onConnected: () => void,
+		private onDisconnected: (reason: string) => void,
+	) {}
+
+	listen() {
+		if (this.listenerId) return;
+		this.listenerId = (async () => {
+			const { Channel, invoke } = await import("@tauri-apps/api/core");
+			const channel = new Channel<NativeGatewayEvent>();
+			channel.onmessage = (message) => this.dispatch(message);
+			return invoke<number>("gateway_listen", { onEvent: channel });
+		})();
+		this.listenerId.catch((error) => {
+			console.error("[webui] Failed to listen to the gateway:", error);
+			this.listenerId = null;
+		});
+	}
+
+	unlisten() {
+		const listenerId = this.listenerId;
+		this.listenerId = null;
+		this.state = "idle";
+		void listenerId?.then((id) => invokeGateway("gateway_unlisten", { id }));
+	}
+
+	isConnected() {
+		return this.state === "connected";
+	}
+
+	on(event: string, handler: Handler) {
+		let handlers = this.handlers.get(event);
+		if (!handlers) {
+			handlers = new Set();
+			this.handlers.set(event, handlers);
+		}
+		handlers.add(handler);
+		return () => {
+			handlers.delete(handler);
+		};
+	}
+
+	clear() {
+		this.handlers.clear();
+	}
+
+	private dispatch(message: NativeGatewayEvent) {
+		if (message.event === "connection") {
+			const wasConnected = this.isConnected();
+			this.state = message.payload.state;
+			if (this.isConnected() && !wasConnected) {
+				console.log("[webui] Connected to gateway");
+				this.onConnected();
+			} else if (!this.isConnected() && wasConnected) {
+				const reason = message.payload.error ?? message.payload.state;
+				console.log(`[webui] Disconnected from gateway: ${reason}`);
+				this.onDisconnected(reason);
+			}
+			return;
+		}
+		for (const handler of this.handlers.get(message.event) ?? []) {
+			(handler as (payload: unknown) => void)(message.payload);
+		}
+	}
+}
+
+async function invokeGateway(
+	command: string,
+	args?: Record<string, unknown>,
+): Promise<void> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	try {
+		await invoke(command, args);
+	} catch (error) {
+		console.error(`[webui] ${command} failed:`, error);
+	}
+}
+
 class GatewaySocket {
 	private socket: TypedSocket | null = null;
+	private native: NativeGateway | null = isInTauri()
+		? new NativeGateway(
+				() => this.onConnectCallbacks.forEach((cb) => cb()),
+				(reason) => this.onDisconnectCallbacks.forEach((cb) => cb(reason)),
+			)
+		: null;
 	private subscribedSessions = new Set<string>();
 	private onConnectCallbacks = new Set<() => void>();
+	private onDisconnectCallbacks = new Set<(reason: string) => void>();
 	private isConnecting = false;
 
-	connect(): TypedSocket {
-		// Return existing socket if it exists (even if still connecting)
+	connect() {
+		if (this.native) {
+			this.native.listen();
+			return;
+		}
+		// Keep the existing socket if it exists (even if still connecting)
 		if (this.socket) {
-			return this.socket;
+			return;
 		}
 
 		this.isConnecting = true;
-		const tauriEnv = isInTauri();
-		const authToken = tauriEnv ? getAuthToken() : null;
 		this.socket = io(`${GATEWAY_URL}/webui`, {
 			path: "/socket.io",
 			reconnection: true,
@@ -42,14 +158,7 @@ class GatewaySocket {
 			reconnectionDelayMax: 10000,
 			autoConnect: true,
 			transports: ["websocket"],
-			...(tauriEnv
-				? {
-						auth: { token: authToken },
-						// Mirror the bearer token into the Engine.IO query so the gateway
-						// can apply Fly affinity before the WebSocket upgrade completes.
-						query: authToken ? { bearerToken: authToken } : undefined,
-					}
-				: { withCredentials: true }),
+			withCredentials: true,
 		});
 
 		this.socket.on("connect", () => {
@@ -76,11 +185,13 @@ class GatewaySocket {
 		if (this.socket.connected) {
 			this.onConnectCallbacks.forEach((cb) => cb());
 		}
-
-		return this.socket;
 	}
 
 	disconnect() {
+		if (this.native) {
+			this.native.unlisten();
+			return;
+		}
 		// Don't disconnect while connecting (protects against React StrictMode race condition)
 		if (this.isConnecting) {
 			return;
@@ -96,7 +207,10 @@ class GatewaySocket {
 	destroy() {
 		this.subscribedSessions.clear();
 		this.onConnectCallbacks.clear();
+		this.onDisconnectCallbacks.clear();
 		this.isConnecting = false;
+		this.native?.clear();
+		this.native?.unlisten();
 		this.socket?.disconnect();
 		this.socket = null;
 	}
@@ -107,11 +221,20 @@ class GatewaySocket {
 
 	subscribeToSession(sessionId: string) {
 		this.subscribedSessions.add(sessionId);
+		if (this.native) {
+			// Rust keeps the subscription across reconnects.
+			void invokeGateway("gateway_subscribe_session", { sessionId });
+			return;
+		}
 		this.socket?.emit("subscribe:session", { sessionId });
 	}
 
 	unsubscribeFromSession(sessionId: string) {
 		this.subscribedSessions.delete(sessionId);
+		if (this.native) {
+			void invokeGateway("gateway_unsubscribe_session", { sessionId });
+			return;
+		}
 		this.socket?.emit("unsubscribe:session", { sessionId });
 	}
 
@@ -124,6 +247,9 @@ class GatewaySocket {
 		event: E,
 		handler: GatewayToWebuiEvents[E],
 	): () => void {
+		if (this.native) {
+			return this.native.on(event, handler as Handler);
+		}
 		this.socket?.on(event, handler as never);
 		return () => {
 			this.socket?.off(event, handler as never);
@@ -163,6 +289,9 @@ class GatewaySocket {
 	}
 
 	isConnected(): boolean {
+		if (this.native) {
+			return this.native.isConnected();
+		}
 		return this.socket?.connected ?? false;
 	}
 
@@ -173,7 +302,7 @@ class GatewaySocket {
 	onConnect(callback: () => void) {
 		this.onConnectCallbacks.add(callback);
 		// If already connected, call immediately
-		if (this.socket?.connected) {
+		if (this.isConnected()) {
 			callback();
 		}
 		return () => {
@@ -182,6 +311,12 @@ class GatewaySocket {
 	}
 
 	onDisconnect(callback: (reason: string) => void) {
+		if (this.native) {
+			this.onDisconnectCallbacks.add(callback);
+			return () => {
+				this.onDisconnectCallbacks.delete(callback);
+			};
+		}
 		const handler = (reason: string) => callback(reason);
 		this.socket?.on("disconnect", handler);
 		return () => {
@@ -190,7 +325,8 @@ class GatewaySocket {
 	}
 
 	/**
-	 * Update the gateway URL and reconnect.
+	 * Update the gateway URL and reconnect. In the app, Rust reconnects
+	 * when `gateway_set_url` is called.
 	 */
 	setGatewayUrl(url: string) {
 		GATEWAY_URL = url;
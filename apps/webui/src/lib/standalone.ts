@@ -0,0 +1,108 @@
+import type { ContentBlock, SessionEvent, StopReason } from "@mobvibe/shared";
+import { isInTauri } from "./auth";
+
+/**
+ * Standalone mode: the desktop app runs ACP agents itself (`standalone_*`
+ * commands), without `mobvibe-cli` or a gateway. Sessions produce the same
+ * `SessionEvent`s the CLI does, under the machine id
+ * {@link STANDALONE_MACHINE_ID}, and are kept in the session cache.
+ */
+
+export const STANDALONE_MACHINE_ID = "standalone";
+
+export type StandaloneSession = {
+	sessionId: string;
+	machineId: typeof STANDALONE_MACHINE_ID;
+	command: string;
+	cwd: string;
+	/** `agentInfo` from the agent's `initialize` response. */
+	agentInfo: { name: string; version?: string; title?: string } | null;
+	/** A turn is in flight. */
+	busy: boolean;
+};
+
+export type StandalonePromptResult = {
+	stopReason: StopReason;
+	usage?: Record<string, unknown>;
+};
+
+async function invokeStandalone<T>(
+	command: string,
+	args?: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
+export async function listStandaloneSessions(): Promise<
+	StandaloneSession[] | null
+> {
+	if (!isInTauri()) return null;
+	try {
+		return await invokeStandalone<StandaloneSession[]>("standalone_sessions");
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+}
+
+/** Spawns an ACP agent, e.g. `claude-code-acp`, and opens a session. */
+export function startStandaloneSession(
+	command: string,
+	cwd: string,
+	args?: string[],
+): Promise<StandaloneSession> {
+	return invokeStandalone("standalone_start", { command, args, cwd });
+}
+
+/** Resolves when the turn ends. */
+export function sendStandalonePrompt(
+	sessionId: string,
+	prompt: ContentBlock[],
+	messageId: string,
+): Promise<StandalonePromptResult> {
+	return invokeStandalone("standalone_prompt", {
+		sessionId,
+		prompt,
+		messageId,
+	});
+}
+
+export function cancelStandalonePrompt(sessionId: string): Promise<void> {
+	return invokeStandalone("standalone_cancel", { sessionId });
+}
+
+/** Answers a permission request; no `optionId` cancels it. */
+export function answerStandalonePermission(
+	sessionId: string,
+	requestId: string,
+	optionId?: string,
+): Promise<void> {
+	return invokeStandalone("standalone_permission", {
+		sessionId,
+		requestId,
+		optionId,
+	});
+}
+
+export function closeStandaloneSession(sessionId: string): Promise<void> {
+	return invokeStandalone("standalone_close", { sessionId });
+}
+
+/**
+ * Streams the events of every standalone session. Resolves to a function
+ * that stops the stream.
+ */
+export async function listenStandaloneEvents(
+	onEvent: (event: SessionEvent) => void,
+): Promise<() => void> {
+	const { Channel } = await import("@tauri-apps/api/core");
+	const channel = new Channel<SessionEvent>();
+	channel.onmessage = onEvent;
+	const id = await invokeStandalone<number>("standalone_listen", {
+		onEvent: channel,
+	});
+	return () => {
+		void invokeStandalone("standalone_unlisten", { id });
+	};
+}
@@ -1,4 +1,6 @@
 import type { StateStorage } from "zustand/middleware";
+import { getPreloadedEntries } from "./preloaded-settings";
+import { createTauriStorageAdapter } from "./tauri-storage-adapter";
 
 // Synchronous storage interface
 export type SyncStateStorage = {
@@ -64,6 +66,13 @@ export const getStorageAdapter = (): SyncStateStorage => {
 		return storageAdapter;
 	}
 
+	// Tauri injects persisted state before any script runs, so stores that
+	// hydrate at import time already get the persistent adapter
+	if (getPreloadedEntries("app-state")) {
+		storageAdapter = createTauriStorageAdapter();
+		return storageAdapter;
+	}
+
 	// Default to localStorage in browser, in-memory otherwise
 	if (
 		typeof globalThis !== "undefined" &&
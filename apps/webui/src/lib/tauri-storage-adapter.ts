@@ -1,86 +1,75 @@
+import {
+	getPreloadedEntries,
+	updatePreloaded,
+} from "@/lib/preloaded-settings";
+import { settingsDelete, settingsEntries, settingsSet } from "@/lib/settings-store";
 import type { SyncStateStorage } from "@/lib/storage-adapter";
 
-// Type for the Tauri Store instance
-type TauriStore = Awaited<
-	ReturnType<typeof import("@tauri-apps/plugin-store").load>
->;
+const SCOPE = "app-state";
 
 /**
  * Create a storage adapter for Tauri that uses an in-memory cache
- * backed by Tauri Store for persistence.
+ * backed by the native settings store for persistence.
  *
  * The adapter is synchronous (required by Zustand) but persists
- * changes to Tauri Store asynchronously.
+ * changes asynchronously. Each change is written as its own key,
+ * in the order it was made. The cache starts from the entries the
+ * native side preloaded, so stores hydrate with persisted state on
+ * the first tick; without a preload it is filled asynchronously.
  */
 export const createTauriStorageAdapter = (): SyncStateStorage => {
 	const cache = new Map<string, string>();
-	let store: TauriStore | null = null;
-	let storePromise: Promise<void> | null = null;
-	let saveTimeout: ReturnType<typeof setTimeout> | null = null;
+	let ready: Promise<void> | null = null;
+	let writes: Promise<void> = Promise.resolve();
 
-	// Initialize store asynchronously
-	const initStore = async () => {
-		if (storePromise) {
-			return storePromise;
+	const preloaded = getPreloadedEntries(SCOPE);
+	if (preloaded) {
+		for (const [key, value] of Object.entries(preloaded)) {
+			if (typeof value === "string") {
+				cache.set(key, value);
+			}
 		}
+		ready = Promise.resolve();
+	}
 
-		storePromise = (async () => {
-			try {
-				const { load } = await import("@tauri-apps/plugin-store");
-				const tauriStore = await load("app-state.json");
+	// Initialize the cache asynchronously
+	const init = async () => {
+		if (ready) {
+			return ready;
+		}
 
-				// Load all existing data into cache
-				const entries = await tauriStore.entries();
-				for (const [key, value] of entries) {
-					if (typeof value === "string") {
+		ready = (async () => {
+			try {
+				const entries = await settingsEntries(SCOPE);
+				for (const [key, value] of Object.entries(entries)) {
+					// Changes made before loading finished are newer.
+					if (typeof value === "string" && !cache.has(key)) {
 						cache.set(key, value);
 					}
 				}
-
-				store = tauriStore;
 			} catch (error) {
-				console.warn("[TauriStorage] Failed to initialize store:", error);
+				console.warn("[TauriStorage] Failed to load settings:", error);
 			}
 		})();
 
-		return storePromise;
+		return ready;
 	};
 
 	// Start initialization immediately
-	initStore();
-
-	// Debounced save to disk
-	const scheduleSave = () => {
-		if (saveTimeout) {
-			clearTimeout(saveTimeout);
-		}
+	void init();
 
-		saveTimeout = setTimeout(async () => {
-			if (store) {
-				try {
-					await store.save();
-				} catch (error) {
-					console.warn("[TauriStorage] Failed to save store:", error);
-				}
-			}
-		}, 100);
-	};
-
-	// Persist a single item asynchronously
-	const persistItem = async (key: string, value: string | null) => {
-		await initStore();
-		if (!store) return;
-
-		try {
-			if (value === null) {
-				await store.delete(key);
-			} else {
-				await store.set(key, value);
-			}
-			scheduleSave();
-		} catch (error) {
-			console.warn("[TauriStorage] Failed to persist item:", error);
-		}
+	// Persist a single item, after every earlier change
+	const persistItem = (key: string, value: string | null) => {
+		writes = writes
+			.then(init)
+			.then(() =>
+				value === null
+					? settingsDelete(SCOPE, key)
+					: settingsSet(SCOPE, key, value),
+			)
+			.catch((error) => {
+				console.warn("[TauriStorage] Failed to persist item:", error);
+			});
 	};
 
 	return {
@@ -89,11 +78,13 @@ export const createTauriStorageAdapter = (): SyncStateStorage => {
 		},
 		setItem: (name: string, value: string) => {
 			cache.set(name, value);
-			void persistItem(name, value);
+			updatePreloaded(SCOPE, name, value);
+			persistItem(name, value);
 		},
 		removeItem: (name: string) => {
 			cache.delete(name);
-			void persistItem(name, null);
+			updatePreloaded(SCOPE, name, null);
+			persistItem(name, null);
 		},
 	};
 };
@@ -103,7 +94,13 @@ export const createTauriStorageAdapter = (): SyncStateStorage => {
  * Should be called early in the app lifecycle (e.g., in main.tsx).
  */
 export const initTauriStorage = async (): Promise<void> => {
-	const { setStorageAdapter } = await import("@/lib/storage-adapter");
-	const adapter = createTauriStorageAdapter();
-	setStorageAdapter(adapter);
+	const { getStorageAdapter, setStorageAdapter } = await import(
+		"@/lib/storage-adapter"
+	);
+	if (getPreloadedEntries(SCOPE)) {
+		// Already serving the preloaded state
+		getStorageAdapter();
+		return;
+	}
+	setStorageAdapter(createTauriStorageAdapter());
 };
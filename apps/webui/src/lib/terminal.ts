@@ -0,0 +1,76 @@
+import { isInTauri } from "./auth";
+
+/**
+ * Shells on pseudo-terminals in the desktop app (`terminal_*` commands),
+ * for sessions whose working directory is on this machine. A terminal is
+ * closed when the page that opened it reloads or its window closes.
+ */
+
+export type TerminalInfo = {
+	id: number;
+	shell: string;
+	cwd: string;
+	pid: number;
+};
+
+export type TerminalEvent =
+	| { kind: "output"; data: string }
+	/** `code` is null if a signal ended the shell. */
+	| { kind: "exit"; code: number | null };
+
+async function invokeTerminal<T>(
+	command: string,
+	args?: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
+/** Terminals this page has open; null where terminals are unavailable. */
+export async function listTerminals(): Promise<TerminalInfo[] | null> {
+	if (!isInTauri()) return null;
+	try {
+		return await invokeTerminal<TerminalInfo[]>("terminal_list");
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+}
+
+/**
+ * Opens a shell in `cwd`, the user's login shell unless `shell` is given,
+ * and streams what it prints to `onEvent`.
+ */
+export async function openTerminal(
+	cwd: string,
+	onEvent: (event: TerminalEvent) => void,
+	options?: { shell?: string; cols?: number; rows?: number },
+): Promise<TerminalInfo> {
+	const { Channel } = await import("@tauri-apps/api/core");
+	const channel = new Channel<TerminalEvent>();
+	channel.onmessage = onEvent;
+	return invokeTerminal<TerminalInfo>("terminal_open", {
+		cwd,
+		shell: options?.shell,
+		cols: options?.cols,
+		rows: options?.rows,
+		onEvent: channel,
+	});
+}
+
+/** Sends input as the terminal emulator encodes it, e.g. `"\r"` for Enter. */
+export function writeTerminal(id: number, data: string): Promise<void> {
+	return invokeTerminal("terminal_write", { id, data });
+}
+
+export function resizeTerminal(
+	id: number,
+	cols: number,
+	rows: number,
+): Promise<void> {
+	return invokeTerminal("terminal_resize", { id, cols, rows });
+}
+
+export function closeTerminal(id: number): Promise<void> {
+	return invokeTerminal("terminal_close", { id });
+}
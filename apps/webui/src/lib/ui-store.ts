@@ -73,11 +73,14 @@ type UiState = {
 	filePreviewPath?: string;
 	commandPaletteOpen: boolean;
 	chatSearchOpen: boolean;
+	terminalOpen: boolean;
 	editingSessionId: string | null;
 	editingTitle: string;
 	draftTitle: string;
 	draftBackendId?: string;
 	draftCwd?: string;
+	/** Put into the composer of the next session created. */
+	draftPrompt?: string;
 	draftAdditionalDirectories: string[];
 	draftWorktreeEnabled: boolean;
 	draftWorktreeBranch: string;
@@ -94,12 +97,14 @@ type UiState = {
 	setFilePreviewPath: (path?: string) => void;
 	setCommandPaletteOpen: (open: boolean) => void;
 	setChatSearchOpen: (open: boolean) => void;
+	setTerminalOpen: (open: boolean) => void;
 	startEditingSession: (sessionId: string, title: string) => void;
 	setEditingTitle: (value: string) => void;
 	clearEditingSession: () => void;
 	setDraftTitle: (value: string) => void;
 	setDraftBackendId: (value?: string) => void;
 	setDraftCwd: (value?: string) => void;
+	setDraftPrompt: (value?: string) => void;
 	setDraftAdditionalDirectories: (value: string[]) => void;
 	setDraftWorktreeEnabled: (value: boolean) => void;
 	setDraftWorktreeBranch: (value: string) => void;
@@ -121,11 +126,13 @@ export const useUiStore = create<UiState>((set) => ({
 	filePreviewPath: undefined,
 	commandPaletteOpen: false,
 	chatSearchOpen: false,
+	terminalOpen: false,
 	editingSessionId: null,
 	editingTitle: "",
 	draftTitle: "",
 	draftBackendId: undefined,
 	draftCwd: undefined,
+	draftPrompt: undefined,
 	draftAdditionalDirectories: [],
 	draftWorktreeEnabled: false,
 	draftWorktreeBranch: "",
@@ -152,6 +159,7 @@ export const useUiStore = create<UiState>((set) => ({
 	setFilePreviewPath: (path) => set({ filePreviewPath: path }),
 	setCommandPaletteOpen: (open) => set({ commandPaletteOpen: open }),
 	setChatSearchOpen: (open) => set({ chatSearchOpen: open }),
+	setTerminalOpen: (open) => set({ terminalOpen: open }),
 	startEditingSession: (sessionId, title) =>
 		set({ editingSessionId: sessionId, editingTitle: title }),
 	setEditingTitle: (value) => set({ editingTitle: value }),
@@ -159,6 +167,7 @@ export const useUiStore = create<UiState>((set) => ({
 	setDraftTitle: (value) => set({ draftTitle: value }),
 	setDraftBackendId: (value) => set({ draftBackendId: value }),
 	setDraftCwd: (value) => set({ draftCwd: value }),
+	setDraftPrompt: (value) => set({ draftPrompt: value }),
 	setDraftAdditionalDirectories: (value) =>
 		set({ draftAdditionalDirectories: value }),
 	setDraftWorktreeEnabled: (value) => set({ draftWorktreeEnabled: value }),
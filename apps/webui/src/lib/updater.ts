@@ -0,0 +1,88 @@
+import { isInTauri } from "./auth";
+
+/**
+ * In-app updates of the desktop app (`updater_*` commands). Rust checks the
+ * channel's manifest and verifies downloads; the webview only shows what
+ * was found and asks before the app restarts into the new version.
+ */
+
+export type ReleaseChannel = "stable" | "beta";
+
+export type UpdateInfo = {
+	version: string;
+	notes: string | null;
+	pubDate: string | null;
+	channel: ReleaseChannel;
+	/** False when a package manager owns the install. */
+	installable: boolean;
+	/** Downloaded and verified; installing restarts the app. */
+	ready: boolean;
+};
+
+export type UpdaterStatus = {
+	currentVersion: string;
+	channel: ReleaseChannel;
+	manifestUrl: string | null;
+	defaultManifestUrl: string | null;
+	/** False for builds without an update key or manifest URL. */
+	enabled: boolean;
+	available: UpdateInfo | null;
+};
+
+export type UpdateProgress = {
+	downloaded: number;
+	total: number | null;
+};
+
+async function invokeUpdater<T>(
+	command: string,
+	args?: Record<string, unknown>,
+): Promise<T> {
+	const { invoke } = await import("@tauri-apps/api/core");
+	return invoke<T>(command, args);
+}
+
+/** Updater status; null where the app cannot update itself. */
+export async function getUpdaterStatus(): Promise<UpdaterStatus | null> {
+	if (!isInTauri()) return null;
+	try {
+		return await invokeUpdater<UpdaterStatus>("updater_status");
+	} catch {
+		// Mobile builds do not register the command.
+		return null;
+	}
+}
+
+export async function setUpdateChannel(
+	channel: ReleaseChannel,
+): Promise<UpdaterStatus> {
+	return invokeUpdater<UpdaterStatus>("updater_set_channel", { channel });
+}
+
+/** Overrides the manifest URL template; null restores the built-in one. */
+export async function setUpdateManifestUrl(
+	url: string | null,
+): Promise<UpdaterStatus> {
+	return invokeUpdater<UpdaterStatus>("updater_set_manifest_url", { url });
+}
+
+/** Checks now; resolves to null when the app is up to date. */
+export async function checkForUpdate(): Promise<UpdateInfo | null> {
+	return invokeUpdater<UpdateInfo | null>("updater_check");
+}
+
+export async function downloadUpdate(
+	onProgress: (progress: UpdateProgress) => void,
+): Promise<UpdateInfo> {
+	const { Channel } = await import("@tauri-apps/api/core");
+	const channel = new Channel<UpdateProgress>();
+	channel.onmessage = onProgress;
+	return invokeUpdater<UpdateInfo>("updater_download", {
+		onProgress: channel,
+	});
+}
+
+/** Installs the downloaded update and restarts; ask the user first. */
+export async function installUpdate(): Promise<void> {
+	await invokeUpdater("updater_install");
+}
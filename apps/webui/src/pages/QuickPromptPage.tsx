@@ -0,0 +1,212 @@
+import { Button } from "@mobvibe/ui/button";
+import {
+	Select,
+	SelectContent,
+	SelectItem,
+	SelectTrigger,
+	SelectValue,
+} from "@mobvibe/ui/select";
+import { Textarea } from "@mobvibe/ui/textarea";
+import { useEffect, useMemo, useRef, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { useMachinesQuery } from "@/hooks/useMachinesQuery";
+import { useSessionQueries } from "@/hooks/useSessionQueries";
+import { createSession, type SessionSummary, sendMessage } from "@/lib/api";
+import { useChatStore } from "@/lib/chat-store";
+import { ensureSessionE2EE } from "@/lib/e2ee";
+import { hideQuickPrompt } from "@/lib/quick-prompt";
+
+const NEW_SESSION = "new";
+/** Sessions offered as targets, most recently updated first. */
+const MAX_RECENT_SESSIONS = 20;
+
+const errorMessage = (error: unknown) =>
+	error instanceof Error ? error.message : String(error);
+
+export function QuickPromptPage() {
+	const { t } = useTranslation();
+	const { sessionsQuery, availableBackends } = useSessionQueries();
+	const machinesQuery = useMachinesQuery();
+	const lastCreatedCwd = useChatStore((state) => state.lastCreatedCwd);
+	const [text, setText] = useState("");
+	const [target, setTarget] = useState<string>();
+	const [machineId, setMachineId] = useState<string>();
+	const [backendId, setBackendId] = useState<string>();
+	const [sending, setSending] = useState(false);
+	const [error, setError] = useState<string | null>(null);
+	const textareaRef = useRef<HTMLTextAreaElement>(null);
+
+	const sessions = useMemo(
+		() =>
+			(sessionsQuery.data?.sessions ?? [])
+				.filter(
+					(session) => session.isAttached && session.revision !== undefined,
+				)
+				.sort((a, b) => b.updatedAt.localeCompare(a.updatedAt))
+				.slice(0, MAX_RECENT_SESSIONS),
+		[sessionsQuery.data],
+	);
+	const machines = (machinesQuery.data?.machines ?? []).filter(
+		(machine) => machine.isOnline,
+	);
+	const selectedTarget = target ?? sessions[0]?.sessionId ?? NEW_SESSION;
+	const selectedMachineId = machineId ?? machines[0]?.id;
+	const selectedBackendId = backendId ?? availableBackends[0]?.backendId;
+
+	// The window is hidden rather than closed, so focus the prompt each time
+	// it comes back and refresh the session list.
+	useEffect(() => {
+		const onFocus = () => {
+			textareaRef.current?.focus();
+			void sessionsQuery.refetch();
+		};
+		textareaRef.current?.focus();
+		window.addEventListener("focus", onFocus);
+		return () => window.removeEventListener("focus", onFocus);
+	}, [sessionsQuery.refetch]);
+
+	const resolveSession = async (): Promise<SessionSummary> => {
+		if (selectedTarget !== NEW_SESSION) {
+			const session = sessions.find(
+				(session) => session.sessionId === selectedTarget,
+			);
+			if (session) return session;
+		}
+		if (!selectedMachineId) {
+			throw new Error(t("quickPrompt.noMachine"));
+		}
+		return createSession({
+			machineId: selectedMachineId,
+			backendId: selectedBackendId,
+			cwd: lastCreatedCwd[selectedMachineId],
+		});
+	};
+
+	const send = async () => {
+		const prompt = text.trim();
+		if (!prompt || sending) return;
+		setSending(true);
+		setError(null);
+		try {
+			const session = await resolveSession();
+			if (session.revision === undefined) {
+				throw new Error(t("quickPrompt.sessionNotReady"));
+			}
+			const e2eeStatus = await ensureSessionE2EE(
+				session.sessionId,
+				session.wrappedDek,
+				session.revision,
+			);
+			await sendMessage({
+				sessionId: session.sessionId,
+				messageId: crypto.randomUUID(),
+				prompt: [{ type: "text", text: prompt }],
+				revision: session.revision,
+				encryptionRequired: e2eeStatus === "ok",
+			});
+			setText("");
+			setTarget(session.sessionId);
+			await hideQuickPrompt();
+		} catch (err) {
+			setError(errorMessage(err));
+		} finally {
+			setSending(false);
+		}
+	};
+
+	const onKeyDown = (event: React.KeyboardEvent<HTMLTextAreaElement>) => {
+		if (event.key === "Escape") {
+			event.preventDefault();
+			void hideQuickPrompt();
+		} else if (
+			event.key === "Enter" &&
+			!event.shiftKey &&
+			!event.nativeEvent.isComposing
+		) {
+			event.preventDefault();
+			void send();
+		}
+	};
+
+	return (
+		<div className="flex h-dvh flex-col gap-3 bg-background p-4">
+			<Textarea
+				ref={textareaRef}
+				value={text}
+				onChange={(event) => setText(event.target.value)}
+				onKeyDown={onKeyDown}
+				placeholder={t("quickPrompt.placeholder")}
+				className="min-h-0 flex-1 resize-none"
+				disabled={sending}
+			/>
+			<div className="flex flex-wrap items-center gap-2">
+				<Select value={selectedTarget} onValueChange={setTarget}>
+					<SelectTrigger className="min-w-0 flex-1">
+						<SelectValue />
+					</SelectTrigger>
+					<SelectContent>
+						{sessions.map((session) => (
+							<SelectItem key={session.sessionId} value={session.sessionId}>
+								{session.title || session.sessionId.slice(0, 8)}
+							</SelectItem>
+						))}
+						<SelectItem value={NEW_SESSION}>
+							{t("quickPrompt.newSession")}
+						</SelectItem>
+					</SelectContent>
+				</Select>
+				{selectedTarget === NEW_SESSION && (
+					<>
+						<Select
+							value={selectedMachineId ?? ""}
+							onValueChange={setMachineId}
+							disabled={machines.length === 0}
+						>
+							<SelectTrigger className="w-36">
+								<SelectValue placeholder={t("quickPrompt.noMachine")} />
+							</SelectTrigger>
+							<SelectContent>
+								{machines.map((machine) => (
+									<SelectItem key={machine.id} value={machine.id}>
+										{machine.hostname ?? machine.id.slice(0, 8)}
+									</SelectItem>
+								))}
+							</SelectContent>
+						</Select>
+						<Select
+							value={selectedBackendId ?? ""}
+							onValueChange={setBackendId}
+							disabled={availableBackends.length === 0}
+						>
+							<SelectTrigger className="w-36">
+								<SelectValue placeholder={t("session.backendEmpty")} />
+							</SelectTrigger>
+							<SelectContent>
+								{availableBackends.map((backend) => (
+									<SelectItem key={backend.backendId} value={backend.backendId}>
+										{backend.backendLabel || backend.backendId}
+									</SelectItem>
+								))}
+							</SelectContent>
+						</Select>
+					</>
+				)}
+				<Button
+					onClick={() => void send()}
+					disabled={sending || !text.trim()}
+				>
+					{t("quickPrompt.send")}
+				</Button>
+			</div>
+			{error ? (
+				<p role="alert" className="text-destructive text-sm">
+					{error}
+				</p>
+			) : (
+				<p className="text-muted-foreground text-xs">
+					{t("quickPrompt.hint")}
+				</p>
+			)}
+		</div>
+	);
+}
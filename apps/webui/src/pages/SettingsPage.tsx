@@ -32,27 +32,40 @@ import {
 	SidebarProvider,
 } from "@mobvibe/ui/sidebar";
 import { useTheme } from "@mobvibe/ui/theme-provider";
-import { useCallback, useState, useSyncExternalStore } from "react";
+import { useCallback, useEffect, useState, useSyncExternalStore } from "react";
 import { useTranslation } from "react-i18next";
 import { Navigate, useNavigate } from "react-router-dom";
 import { useAuth } from "@/components/auth/AuthProvider";
 import { UserMenu } from "@/components/auth/UserMenu";
 import { LegalLinks } from "@/components/legal/LegalLinks";
+import {
+	DaemonSettings,
+	LocalGatewaySettings,
+	QuickPromptSettings,
+	UpdateSettings,
+} from "@/components/settings/DaemonSettings";
 import { E2EESettings } from "@/components/settings/E2EESettings";
 import i18n, { supportedLanguages } from "@/i18n";
-import { changePassword, isAuthEnabled } from "@/lib/auth";
+import { changePassword, isAuthEnabled, isInTauri } from "@/lib/auth";
+import { isMobilePlatform } from "@/lib/platform";
 import { toThemePreference } from "@/lib/ui-config";
 
 /* ------------------------------------------------------------------ */
 /*  Types & Constants                                                  */
 /* ------------------------------------------------------------------ */
 
-type SettingsSection = "security" | "account" | "appearance";
+type SettingsSection = "security" | "account" | "appearance" | "daemon";
 
 const SETTINGS_SECTIONS = [
 	{ id: "security", labelKey: "settings.security", icon: LockKeyIcon },
 	{ id: "account", labelKey: "settings.account", icon: UserAccountIcon },
 	{ id: "appearance", labelKey: "settings.appearance", icon: PaintBoardIcon },
+	{
+		id: "daemon",
+		labelKey: "settings.daemon",
+		icon: ComputerIcon,
+		desktopOnly: true,
+	},
 ] as const;
 
 const DEFAULT_SECTION: SettingsSection = "security";
@@ -86,6 +99,18 @@ function useSettingsSection() {
 	return [section, setSection] as const;
 }
 
+/** The daemon section needs the desktop app's native commands. */
+function useIsDesktopApp() {
+	const [isDesktop, setIsDesktop] = useState(false);
+
+	useEffect(() => {
+		if (!isInTauri()) return;
+		void isMobilePlatform().then((isMobile) => setIsDesktop(!isMobile));
+	}, []);
+
+	return isDesktop;
+}
+
 /* ------------------------------------------------------------------ */
 /*  Page Root                                                          */
 /* ------------------------------------------------------------------ */
@@ -122,6 +147,10 @@ function SettingsNav({
 	onSelect: (id: SettingsSection) => void;
 }) {
 	const { t } = useTranslation();
+	const isDesktop = useIsDesktopApp();
+	const sections = SETTINGS_SECTIONS.filter(
+		(section) => isDesktop || !("desktopOnly" in section),
+	);
 
 	return (
 		<Sidebar collapsible="none" className="border-r bg-sidebar">
@@ -129,7 +158,7 @@ function SettingsNav({
 				<SidebarGroup>
 					<SidebarGroupContent>
 						<SidebarMenu>
-							{SETTINGS_SECTIONS.map((section) => (
+							{sections.map((section) => (
 								<SidebarMenuItem key={section.id}>
 									<SidebarMenuButton
 										isActive={activeSection === section.id}
@@ -160,6 +189,8 @@ function SettingsSectionContent({ section }: { section: SettingsSection }) {
 			return <AccountSection />;
 		case "appearance":
 			return <AppearanceSection />;
+		case "daemon":
+			return <DaemonSection />;
 	}
 }
 
@@ -203,6 +234,7 @@ function SettingsHeader() {
 
 function SettingsContent() {
 	const [activeSection, setActiveSection] = useSettingsSection();
+	const isDesktop = useIsDesktopApp();
 
 	return (
 		<main className="h-dvh flex flex-col bg-muted/40">
@@ -231,6 +263,12 @@ function SettingsContent() {
 					<AccountSection />
 					<Separator />
 					<AppearanceSection />
+					{isDesktop && (
+						<>
+							<Separator />
+							<DaemonSection />
+						</>
+					)}
 				</div>
 			</div>
 		</main>
@@ -424,6 +462,29 @@ function AccountSection() {
 	);
 }
 
+/* ------------------------------------------------------------------ */
+/*  Daemon Section                                                     */
+/* ------------------------------------------------------------------ */
+
+function DaemonSection() {
+	const { t } = useTranslation();
+
+	return (
+		<section className="space-y-4">
+			<div>
+				<h2 className="text-lg font-semibold">{t("settings.daemon")}</h2>
+				<p className="text-muted-foreground text-sm">
+					{t("settings.daemonDescription")}
+				</p>
+			</div>
+			<DaemonSettings />
+			<LocalGatewaySettings />
+			<QuickPromptSettings />
+			<UpdateSettings />
+		</section>
+	);
+}
+
 /* ------------------------------------------------------------------ */
 /*  Appearance Section                                                 */
 /* ------------------------------------------------------------------ */
@@ -0,0 +1,58 @@
+import { beforeAll, describe, expect, it } from "vitest";
+import {
+	base64ToUint8,
+	decryptPayload,
+	deriveAuthKeyPair,
+	deriveContentKeyPair,
+	initCrypto,
+	uint8ToBase64,
+	unwrapDEK,
+} from "../src/crypto/index.js";
+import vectors from "./fixtures/crypto-vectors.json";
+
+// The same fixture is consumed by the Tauri crate (apps/webui/src-tauri/src/e2ee),
+// so both implementations are pinned to identical bytes.
+describe("crypto test vectors", () => {
+	beforeAll(async () => {
+		await initCrypto();
+	});
+
+	it("derives the auth public key and fingerprint", () => {
+		const authKeyPair = deriveAuthKeyPair(base64ToUint8(vectors.masterSecret));
+		const authPublicKey = uint8ToBase64(authKeyPair.publicKey);
+		expect(authPublicKey).toBe(vectors.authPublicKey);
+		expect(authPublicKey.slice(0, 8)).toBe(vectors.fingerprint);
+	});
+
+	it("derives the content key pair", () => {
+		const contentKeyPair = deriveContentKeyPair(
+			base64ToUint8(vectors.masterSecret),
+		);
+		expect(uint8ToBase64(contentKeyPair.publicKey)).toBe(
+			vectors.contentPublicKey,
+		);
+		expect(uint8ToBase64(contentKeyPair.secretKey)).toBe(
+			vectors.contentSecretKey,
+		);
+	});
+
+	it("unwraps the sealed DEK", () => {
+		const contentKeyPair = deriveContentKeyPair(
+			base64ToUint8(vectors.masterSecret),
+		);
+		const dek = unwrapDEK(
+			vectors.wrappedDek,
+			contentKeyPair.publicKey,
+			contentKeyPair.secretKey,
+		);
+		expect(uint8ToBase64(dek)).toBe(vectors.dek);
+	});
+
+	it("decrypts the payload envelope", () => {
+		const payload = decryptPayload(
+			{ t: "encrypted", c: vectors.encryptedC },
+			base64ToUint8(vectors.dek),
+		);
+		expect(payload).toEqual(JSON.parse(vectors.payloadJson));
+	});
+});